@@ -4,6 +4,11 @@ import random
 import os
 import json
 import base64
+import hashlib
+import secrets
+import threading
+import time
+from datetime import datetime, timezone
 from cryptography.fernet import Fernet
 
 app = Flask(__name__)
@@ -12,9 +17,56 @@ CORS(app, resources={r"/*": {"origins": "*"}}, supports_credentials=True)
 # Chemins vers les fichiers
 PASSWORDS_FILE = os.path.join(os.path.dirname(__file__), 'passwords.json')
 EMAILFILE =os.path.join(os.path.dirname(__file__), 'usermail.json')
+# Sel et paramètres de dérivation de la clé des clients, partagés entre appareils
+VAULT_FILE = os.path.join(os.path.dirname(__file__), 'vault.json')
+# Vérificateur SRP du mot de passe maître : le serveur ne voit jamais le mot de passe
+AUTH_FILE = os.path.join(os.path.dirname(__file__), 'auth.json')
+# Journal d'un changement de mot de passe maître, rejoué s'il a été interrompu
+REKEY_FILE = os.path.join(os.path.dirname(__file__), 'rekey.json')
+# Empreintes des jetons d'appairage de l'extension Chrome, délivrés depuis l'application
+EXTENSION_FILE = os.path.join(os.path.dirname(__file__), 'extension.json')
 KEY_FILE = 'key_file.key'
 USER_MAIL = ""
 
+# Champs du schéma d'entrée v2 conservés tels quels par le serveur
+ENTRY_METADATA_FIELDS = ['schema_version', 'username', 'notes', 'tags', 'folder',
+                         'custom_fields', 'created_at', 'modified_at', 'last_used_at']
+# Types de champs personnalisés dont la valeur est chiffrée comme le mot de passe
+SECRET_FIELD_KINDS = ('hidden', 'totp')
+
+# Groupe 2048 bits de la RFC 5054, identique à celui du client (src/api/srp.rs)
+SRP_N = int(
+    "AC6BDB41324A9A9BF166DE5E1389582FAF72B6651987EE07FC3192943DB56050A37329CBB4A099ED8193E0757767A13DD52312AB4B03310DCD7F48A9DA04FD50"
+    "E8083969EDB767B0CF6095179A163AB3661A05FBD5FAAAE82918A9962F0B93B855F97993EC975EEAA80D740ADBF4FF747359D041D5C33EA71D281E446B14773B"
+    "CA97B43A23FB801676BD207A436C6481F1D2B9078717461A5B9D32E688F87748544523B524B0D57D5EA77A2775D2ECFA032CFBDBF52FB3786160279004E57AE6"
+    "AF874E7303CE53299CCC041C7BC308D82A5698F3A8D0C38271AE35F8E9DBFBB694B5C803D89F7AE435DE236D525F54759B65E372FCD68EF20FA7111F9E4AFF73",
+    16)
+SRP_G = 2
+SRP_PAD_LENGTH = 256
+# Durées de vie : échange en cours, jeton d'accès, jeton de renouvellement (secondes)
+HANDSHAKE_TTL = 60
+ACCESS_TOKEN_TTL = 900
+REFRESH_TOKEN_TTL = 12 * 3600
+# Routes accessibles sans jeton
+PUBLIC_PATHS = ('/', '/generate-password')
+# Routes de l'extension Chrome, qui n'a ni le mot de passe maître ni la clé du coffre :
+# accessibles avec son jeton d'appairage, elles ne renvoient jamais les secrets chiffrés
+# de bout en bout (voir extension_entry). Toutes les autres routes exigent un jeton d'accès
+EXTENSION_PATHS = ('/save-password', '/list-passwords', '/registered', '/getEmail', '/changeMail')
+# Routes ouvertes au jeton d'un kit de récupération, sans session : lecture de l'en-tête
+# du coffre et remplacement du mot de passe maître, rien d'autre
+RECOVERY_ROUTES = (('GET', '/vault/params'), ('POST', '/recovery/rekey'))
+# Le premier vérificateur n'est accepté que depuis la machine du serveur
+LOOPBACK_ADDRESSES = ('127.0.0.1', '::1')
+
+# Échanges SRP en cours et jetons délivrés, gardés en mémoire : un redémarrage impose une reconnexion
+HANDSHAKES = {}
+ACCESS_TOKENS = {}
+REFRESH_TOKENS = {}
+# Lecture, contrôle de révision et réécriture de passwords.json forment un seul bloc :
+# deux requêtes simultanées ne peuvent ni s'écraser ni obtenir la même révision
+PASSWORDS_LOCK = threading.Lock()
+
 #Fonctions utilitaires
 def ensure_json_file(file_path):
     """Assure que le fichier JSON existe et contient un dictionnaire vide."""
@@ -55,6 +107,171 @@ def decrypt_password(encrypted_password, key):
     encrypted_password_bytes = base64.b64decode(encrypted_password)  # Décodage de base64
     return key.decrypt(encrypted_password_bytes).decode()
 
+def now_iso():
+    """Horodatage UTC au format RFC 3339."""
+    return datetime.now(timezone.utc).isoformat()
+
+def encrypt_custom_fields(fields, key):
+    """Chiffre la valeur des champs personnalisés secrets."""
+    encrypted = []
+    for field in fields or []:
+        field = dict(field)
+        if field.get('kind') in SECRET_FIELD_KINDS and field.get('value'):
+            field['value'] = encrypt_password(field['value'], key)
+        encrypted.append(field)
+    return encrypted
+
+def decrypt_entry(entry, key):
+    """Renvoie une copie de l'entrée avec le mot de passe et les champs secrets déchiffrés."""
+    entry = dict(entry)
+    entry['service_password'] = decrypt_password(entry['service_password'], key)
+    fields = []
+    for field in entry.get('custom_fields', []):
+        field = dict(field)
+        if field.get('kind') in SECRET_FIELD_KINDS and field.get('value'):
+            field['value'] = decrypt_password(field['value'], key)
+        fields.append(field)
+    if 'custom_fields' in entry:
+        entry['custom_fields'] = fields
+    return entry
+
+def is_deleted(entry):
+    """Vrai pour une entrée supprimée, conservée comme marqueur pour la synchronisation."""
+    return bool(entry.get('deleted'))
+
+def current_revision(passwords):
+    """Révision globale du coffre : la plus haute révision d'entrée."""
+    return max((entry.get('revision', 0) for entry in passwords.values()), default=0)
+
+def build_entry(data, existing, key):
+    """Construit l'entrée chiffrée à enregistrer à partir des données reçues."""
+    service_url = data.get('service')
+    entry = {
+        'service_URL': extract_service_url(service_url),
+        'service_name': extract_service_name(service_url),
+        'service_password': encrypt_password(data.get('password'), key),
+        'email': data.get('email', '') or ''  # Garantit une chaîne vide si None
+    }
+
+    # Métadonnées du schéma v2 : celles envoyées remplacent, les autres sont conservées
+    # (l'extension Chrome n'envoie que service, mot de passe et email)
+    for field in ENTRY_METADATA_FIELDS:
+        if field in data:
+            entry[field] = data[field]
+        elif field in existing:
+            entry[field] = existing[field]
+    if 'custom_fields' in data:
+        entry['custom_fields'] = encrypt_custom_fields(data['custom_fields'], key)
+    # Marqueur du chiffrement de bout en bout : les secrets reçus sont déjà chiffrés par le client.
+    # Jamais hérité, une écriture en clair (extension Chrome) ne doit pas être marquée chiffrée
+    if data.get('encryption'):
+        entry['encryption'] = data['encryption']
+    entry['created_at'] = entry.get('created_at') or now_iso()
+    if 'modified_at' not in data or not data['modified_at']:
+        entry['modified_at'] = now_iso()
+    return entry
+
+def tombstone(existing, revision):
+    """Marqueur de suppression, sans aucun secret."""
+    return {
+        'service_URL': existing.get('service_URL', ''),
+        'service_name': existing.get('service_name', ''),
+        'deleted': True,
+        'modified_at': now_iso(),
+        'revision': revision,
+    }
+
+def sync_entry(entry, key):
+    """Entrée telle qu'envoyée aux clients synchronisés : déchiffrée, ou marqueur de suppression."""
+    return dict(entry) if is_deleted(entry) else decrypt_entry(entry, key)
+
+def extension_entry(entry, key):
+    """Entrée telle qu'envoyée à l'extension Chrome, qui n'a pas la clé du coffre.
+    Les secrets chiffrés de bout en bout sont retirés et l'entrée est marquée 'encrypted' :
+    seule l'application Mushroom peut les ouvrir. Les entrées en clair restent lisibles."""
+    if not entry.get('encryption'):
+        return decrypt_entry(entry, key)
+    entry = dict(entry)
+    entry.pop('service_password', None)
+    entry['custom_fields'] = [field for field in entry.get('custom_fields', [])
+                              if field.get('kind') not in SECRET_FIELD_KINDS]
+    entry['encrypted'] = True
+    return entry
+
+def revision_conflict(data, existing):
+    """Vrai si l'entrée a changé depuis la révision sur laquelle le client s'appuie.
+    Sans 'base_revision' (extension Chrome), le dernier écrivain l'emporte."""
+    if 'base_revision' not in data:
+        return False
+    return existing.get('revision', 0) != data['base_revision']
+
+def srp_pad(value):
+    """Nombre en octets big-endian complétés à la taille du groupe."""
+    return value.to_bytes(SRP_PAD_LENGTH, 'big')
+
+def srp_hash(*parts):
+    return hashlib.sha256(b''.join(parts)).digest()
+
+def srp_hash_int(*parts):
+    return int.from_bytes(srp_hash(*parts), 'big')
+
+def load_auth():
+    """Sel, paramètres et vérificateur enregistrés, ou None avant le premier appareil."""
+    if not os.path.exists(AUTH_FILE):
+        return None
+    with open(AUTH_FILE, 'r') as file:
+        return json.load(file)
+
+def write_json_atomic(path, data):
+    """Écrit un fichier JSON via un fichier temporaire renommé : jamais à moitié écrit."""
+    temporary = path + '.tmp'
+    with open(temporary, 'w') as file:
+        json.dump(data, file, indent=4)
+        file.flush()
+        os.fsync(file.fileno())
+    os.replace(temporary, path)
+
+def finish_rekey():
+    """Applique le changement de mot de passe journalisé : en-tête du coffre puis vérificateur."""
+    if not os.path.exists(REKEY_FILE):
+        return
+    with open(REKEY_FILE, 'r') as file:
+        pending = json.load(file)
+    write_json_atomic(VAULT_FILE, pending['vault'])
+    write_json_atomic(AUTH_FILE, pending['auth'])
+    os.remove(REKEY_FILE)
+
+def extension_token_hash(token):
+    return hashlib.sha256(token.encode()).hexdigest()
+
+def load_extension_tokens():
+    """Empreintes des jetons d'appairage en vigueur."""
+    if not os.path.exists(EXTENSION_FILE):
+        return {}
+    with open(EXTENSION_FILE, 'r') as file:
+        return json.load(file).get('tokens', {})
+
+def prune_expired(store):
+    now = time.time()
+    for key in [key for key, value in store.items() if value['expires'] < now]:
+        del store[key]
+
+def issue_tokens():
+    """Nouveau couple jeton d'accès / jeton de renouvellement."""
+    prune_expired(ACCESS_TOKENS)
+    prune_expired(REFRESH_TOKENS)
+    access_token = secrets.token_urlsafe(32)
+    refresh_token = secrets.token_urlsafe(32)
+    ACCESS_TOKENS[access_token] = {'expires': time.time() + ACCESS_TOKEN_TTL}
+    REFRESH_TOKENS[refresh_token] = {'expires': time.time() + REFRESH_TOKEN_TTL, 'access': access_token}
+    return {"access_token": access_token, "refresh_token": refresh_token, "expires_in": ACCESS_TOKEN_TTL}
+
+def bearer_token():
+    header = request.headers.get('Authorization', '')
+    if header.startswith('Bearer '):
+        return header[len('Bearer '):]
+    return None
+
 def extract_domain_name(url):
     """Extrait le nom de domaine d'une URL, juste avant '.fr' ou '.com'."""
     url = url.replace('http://', '').replace('https://', '').replace('www.', '')
@@ -103,6 +320,39 @@ def add_cors_headers(response):
     response.headers['Access-Control-Allow-Credentials'] = 'true'
     return response
 
+@app.before_request
+def require_token():
+    """Exige un jeton d'accès valide hors routes publiques ; les routes de l'extension
+    acceptent aussi son jeton d'appairage. Tant qu'aucun vérificateur n'est enregistré,
+    aucun jeton ne peut être délivré."""
+    if request.method == 'OPTIONS' or request.path in PUBLIC_PATHS or request.path.startswith('/auth/'):
+        return None
+    if has_valid_token() or (request.path in EXTENSION_PATHS and has_extension_token()):
+        return None
+    if (request.method, request.path) in RECOVERY_ROUTES and has_recovery_token():
+        return None
+    return jsonify({"error": "Authentification requise."}), 401
+
+def has_valid_token():
+    """Vrai si la requête porte un jeton d'accès délivré et non expiré."""
+    token = ACCESS_TOKENS.get(bearer_token() or '')
+    return token is not None and token['expires'] >= time.time()
+
+def has_extension_token():
+    """Vrai si la requête porte un jeton d'appairage de l'extension non révoqué."""
+    token = bearer_token()
+    return token is not None and extension_token_hash(token) in load_extension_tokens()
+
+def has_recovery_token():
+    """Vrai si la requête porte le jeton du kit de récupération inscrit dans l'en-tête du coffre."""
+    token = bearer_token()
+    if token is None or not os.path.exists(VAULT_FILE):
+        return False
+    with open(VAULT_FILE, 'r') as file:
+        recovery = (json.load(file).get('factors') or {}).get('recovery') or {}
+    digest = recovery.get('token_digest')
+    return isinstance(digest, str) and secrets.compare_digest(hashlib.sha256(token.encode()).hexdigest(), digest)
+
 @app.route('/')
 def home():
     return jsonify({"message": "Bienvenue sur l'API Mushroom Password Manager!"})
@@ -116,16 +366,12 @@ def api_generate_password():
 @app.route('/save-password', methods=['POST'])
 def api_save_password():
     data = request.json
-    print(f"Data reçue : {data}")  # Affiche les données reçues pour debug
 
     service_url = data.get('service')
     password = data.get('password')
-    email = data.get('email', '')  # Valeur par défaut vide si non fournie
 
-    if not service_url or not password:
-        return jsonify({"error": "Les champs 'service' et 'password' sont requis."}), 400
-
-    if not service_url or not password:
+    # Le mot de passe peut être vide (notes sécurisées importées), mais doit être présent
+    if not service_url or password is None:
         return jsonify({"error": "Les champs 'service' et 'password' sont requis."}), 400
 
     ensure_json_file(PASSWORDS_FILE)
@@ -134,25 +380,52 @@ def api_save_password():
     except FileNotFoundError as e:
         return jsonify({"error": str(e)}), 500
 
-    with open(PASSWORDS_FILE, 'r') as file:
-        passwords = json.load(file)
+    with PASSWORDS_LOCK:
+        with open(PASSWORDS_FILE, 'r') as file:
+            passwords = json.load(file)
 
-    service_name = extract_service_name(service_url)  # Extraire le nom du service
-    service_url_trimmed = extract_service_url(service_url)  # Extraire l'URL avec le sous-domaine
-    encrypted_password = encrypt_password(password, key)  # Encrypté et encodé en base64
+        existing = passwords.get(service_url, {})
+        # Sans jeton d'accès (extension Chrome), une entrée chiffrée de bout en bout n'est jamais remplacée
+        if existing.get('encryption') and not is_deleted(existing) and not has_valid_token():
+            return jsonify({"error": "Entrée chiffrée de bout en bout : modifiez-la depuis l'application Mushroom."}), 403
+        if revision_conflict(data, existing):
+            return jsonify({
+                "error": "L'entrée a été modifiée entre-temps.",
+                "current": sync_entry(existing, key) if existing else None,
+            }), 409
 
-    passwords[service_url] = {
-        'service_URL': service_url_trimmed,
-        'service_name': service_name,
-        'service_password': encrypted_password,
-        'email': email or ''  # Garantit une chaîne vide si None
-    }
+        entry = build_entry(data, existing, key)
+        service_name = entry['service_name']
+        entry['revision'] = current_revision(passwords) + 1
+        passwords[service_url] = entry
+        write_json_atomic(PASSWORDS_FILE, passwords)
+
+    return jsonify({
+        "message": f"Mot de passe enregistré pour le service '{service_name}'.",
+        "revision": entry['revision'],
+    })
+
+@app.route('/delete-password', methods=['POST'])
+def api_delete_password():
+    data = request.json
+    service_url = data.get('service')
 
+    if not service_url:
+        return jsonify({"error": "Le champ 'service' est requis."}), 400
 
-    with open(PASSWORDS_FILE, 'w') as file:
-        json.dump(passwords, file, indent=4)
+    ensure_json_file(PASSWORDS_FILE)
+    with PASSWORDS_LOCK:
+        with open(PASSWORDS_FILE, 'r') as file:
+            passwords = json.load(file)
 
-    return jsonify({"message": f"Mot de passe enregistré pour le service '{service_name}'."})
+        if service_url not in passwords or is_deleted(passwords[service_url]):
+            return jsonify({"error": f"Aucun mot de passe trouvé pour le service '{service_url}'."}), 404
+
+        # Marqueur sans secret : les clients synchronisés apprennent la suppression
+        passwords[service_url] = tombstone(passwords[service_url], current_revision(passwords) + 1)
+        write_json_atomic(PASSWORDS_FILE, passwords)
+
+    return jsonify({"message": f"Mot de passe supprimé pour le service '{service_url}'."})
 
 @app.route('/list-passwords', methods=['GET'])
 def api_list_passwords():
@@ -162,11 +435,10 @@ def api_list_passwords():
         with open(PASSWORDS_FILE, 'r') as file:
             passwords = json.load(file)
 
-        for saved in passwords:
-            encrypted_password = passwords[saved]['service_password']
-            decrypted_password = decrypt_password(encrypted_password, key)
-            passwords[saved]['service_password'] = decrypted_password
-            
+        # Route de l'extension : les secrets chiffrés de bout en bout n'y figurent pas
+        passwords = {saved: extension_entry(entry, key)
+                     for saved, entry in passwords.items() if not is_deleted(entry)}
+
         return jsonify(passwords)  # Retourner les données brutes pour test
 
     except Exception as e:
@@ -181,46 +453,34 @@ def api_list_passwords_rust():
         key = load_key()
         with open(PASSWORDS_FILE, 'r') as file:
             encrypted_data = json.load(file)
-            passwords = [f"{service}: (chiffré)" for service in encrypted_data.keys()]
+            passwords = [f"{service}: (chiffré)" for service, entry in encrypted_data.items()
+                         if not is_deleted(entry)]
             return jsonify(passwords)
     except Exception as e:
         return jsonify({"error": str(e)}), 500
 @app.route('/get-password', methods=['POST'])
 def api_get_password():
-    print("=== ENDPOINT GET-PASSWORD APPELÉ ===")
     data = request.json
-    print(f"Données reçues: {data}")
     service_url = data.get('service_URL')
-    print(f"Service URL demandé: {service_url}")
 
     if not service_url:
-        print("Erreur: service_URL manquant")
         return jsonify({"error": "Le champ 'service_URL' est requis."}), 400
 
     ensure_json_file(PASSWORDS_FILE)
     try:
         key = load_key()
     except FileNotFoundError as e:
-        print(f"Erreur de clé: {e}")
         return jsonify({"error": str(e)}), 500
 
     with open(PASSWORDS_FILE, 'r') as file:
         passwords = json.load(file)
-        print(f"Clés disponibles: {list(passwords.keys())}")
-
-    if service_url in passwords:
-        encrypted_password = passwords[service_url]['service_password']
-        password = decrypt_password(encrypted_password, key)
-        response_data = {
-            "service_URL": service_url, 
-            "service_name": passwords[service_url]['service_name'], 
-            "service_password": password,
-            "email": passwords[service_url].get('email', '')
-        }
-        print(f"Réponse: {response_data}")
+
+    if service_url in passwords and not is_deleted(passwords[service_url]):
+        response_data = decrypt_entry(passwords[service_url], key)
+        response_data["service_URL"] = service_url
+        response_data.setdefault("email", '')
         return jsonify(response_data)
     else:
-        print(f"Aucun mot de passe trouvé pour: {service_url}")
         return jsonify({"error": f"Aucun mot de passe trouvé pour le service '{service_url}'."}), 404
         
 @app.route('/registered', methods=['POST'])
@@ -243,22 +503,283 @@ def api_registered():
         passwords = json.load(file)
 
     for service, details in passwords.items():
-        if details['service_URL'] == service_URL:
-            decrypted_password = decrypt_password(details['service_password'], key)
-            return jsonify({
+        if details['service_URL'] == service_URL and not is_deleted(details):
+            entry = extension_entry(details, key)
+            response = {
                 "registered": True,
                 "message": f"L'URL '{service_URL}' est déjà enregistrée.",
                 "service_name": details['service_name'],
                 "service_url": details['service_URL'],
                 "email": details.get('email'),  # Ajout explicite de l'email
-                "password": decrypted_password
-            })
+            }
+            # Chiffré de bout en bout : l'extension ne peut pas remplir le mot de passe
+            if entry.get('encrypted'):
+                response["encrypted"] = True
+            else:
+                response["password"] = entry['service_password']
+            return jsonify(response)
 
     return jsonify({
         "registered": False,
         "message": f"L'URL '{service_URL}' n'est pas enregistrée."
     })
 
+@app.route('/sync/changes', methods=['GET'])
+def api_sync_changes():
+    """Entrées (ou marqueurs de suppression) modifiées après la révision 'since'."""
+    try:
+        since = int(request.args.get('since', 0))
+    except ValueError:
+        return jsonify({"error": "Le paramètre 'since' doit être un entier."}), 400
+
+    ensure_json_file(PASSWORDS_FILE)
+    try:
+        key = load_key()
+    except FileNotFoundError as e:
+        return jsonify({"error": str(e)}), 500
+
+    with open(PASSWORDS_FILE, 'r') as file:
+        passwords = json.load(file)
+
+    changes = {}
+    for service, entry in passwords.items():
+        # Une synchronisation complète inclut les entrées antérieures aux révisions
+        if since == 0 or entry.get('revision', 0) > since:
+            changes[service] = sync_entry(entry, key)
+
+    return jsonify({"revision": current_revision(passwords), "entries": changes})
+
+@app.route('/sync/push', methods=['POST'])
+def api_sync_push():
+    """Applique des modifications locales ; celles basées sur une révision dépassée sont renvoyées en conflit."""
+    data = request.json or {}
+    changes = data.get('changes')
+    if not isinstance(changes, list):
+        return jsonify({"error": "Le champ 'changes' est requis."}), 400
+
+    ensure_json_file(PASSWORDS_FILE)
+    try:
+        key = load_key()
+    except FileNotFoundError as e:
+        return jsonify({"error": str(e)}), 500
+
+    with PASSWORDS_LOCK:
+        with open(PASSWORDS_FILE, 'r') as file:
+            passwords = json.load(file)
+
+        applied = {}
+        conflicts = {}
+        for change in changes:
+            service_url = change.get('service')
+            if not service_url or (not change.get('deleted') and change.get('password') is None):
+                continue
+            existing = passwords.get(service_url, {})
+            if existing.get('revision', 0) != change.get('base_revision', 0):
+                conflicts[service_url] = sync_entry(existing, key) if existing else None
+                continue
+            revision = current_revision(passwords) + 1
+            if change.get('deleted'):
+                entry = tombstone(existing, revision)
+            else:
+                entry = build_entry(change, existing, key)
+                entry['revision'] = revision
+            passwords[service_url] = entry
+            applied[service_url] = entry['revision']
+
+        if applied:
+            write_json_atomic(PASSWORDS_FILE, passwords)
+
+    return jsonify({"revision": current_revision(passwords), "applied": applied, "conflicts": conflicts})
+
+@app.route('/vault/params', methods=['GET'])
+def api_vault_params():
+    """Paramètres de dérivation de la clé de chiffrement de bout en bout."""
+    if not os.path.exists(VAULT_FILE):
+        return jsonify({"error": "Aucun paramètre de coffre enregistré."}), 404
+    with open(VAULT_FILE, 'r') as file:
+        return jsonify(json.load(file))
+
+@app.route('/vault/params', methods=['POST'])
+def api_create_vault_params():
+    """Enregistre les paramètres choisis par le premier client ; jamais remplacés ensuite."""
+    data = request.json or {}
+    if not data.get('salt') or not isinstance(data.get('kdf'), dict):
+        return jsonify({"error": "Les champs 'salt' et 'kdf' sont requis."}), 400
+
+    if os.path.exists(VAULT_FILE):
+        with open(VAULT_FILE, 'r') as file:
+            return jsonify(json.load(file)), 409
+
+    params = {'format': data.get('format', ''), 'kdf': data['kdf'], 'salt': data['salt'], 'revision': 0}
+    if isinstance(data.get('factors'), dict):
+        params['factors'] = data['factors']
+    write_json_atomic(VAULT_FILE, params)
+    return jsonify(params), 201
+
+@app.route('/vault/params', methods=['PUT'])
+def api_update_vault_factors():
+    """Remplace les facteurs de déverrouillage exigés (fichier clé, TOTP) ; le sel et la dérivation restent.
+    Le client envoie la révision de l'en-tête qu'il modifie : si un autre appareil l'a changé
+    entre-temps, l'en-tête courant est renvoyé en conflit plutôt qu'écrasé."""
+    data = request.json or {}
+    if not isinstance(data.get('factors'), dict):
+        return jsonify({"error": "Le champ 'factors' est requis."}), 400
+    if not isinstance(data.get('revision'), int):
+        return jsonify({"error": "Le champ 'revision' est requis."}), 400
+    if not os.path.exists(VAULT_FILE):
+        return jsonify({"error": "Aucun paramètre de coffre enregistré."}), 404
+
+    with open(VAULT_FILE, 'r') as file:
+        params = json.load(file)
+    if params.get('revision', 0) != data['revision']:
+        return jsonify(params), 409
+    params['factors'] = data['factors']
+    params['revision'] = params.get('revision', 0) + 1
+    write_json_atomic(VAULT_FILE, params)
+    return jsonify(params)
+
+@app.route('/vault/rekey', methods=['POST'])
+def api_vault_rekey():
+    """Nouveau mot de passe maître : l'en-tête du coffre et le vérificateur SRP sont remplacés ensemble."""
+    return rekey_vault()
+
+@app.route('/recovery/rekey', methods=['POST'])
+def api_recovery_rekey():
+    """Nouveau mot de passe maître d'un coffre rouvert par un kit de récupération, sans session.
+    Le nouvel en-tête garde le kit : le jeton reste valable jusqu'au prochain kit."""
+    return rekey_vault()
+
+def rekey_vault():
+    data = request.json or {}
+    vault = data.get('vault')
+    auth = data.get('auth')
+    if not isinstance(vault, dict) or not vault.get('salt') or not isinstance(vault.get('kdf'), dict):
+        return jsonify({"error": "Le champ 'vault' est requis."}), 400
+    if not isinstance(auth, dict) or not auth.get('salt') or not auth.get('verifier') or not isinstance(auth.get('kdf'), dict):
+        return jsonify({"error": "Le champ 'auth' est requis."}), 400
+    if not os.path.exists(VAULT_FILE) or load_auth() is None:
+        return jsonify({"error": "Aucun coffre enregistré."}), 404
+
+    # Un autre appareil a changé le mot de passe entre-temps : son en-tête n'est pas écrasé
+    with open(VAULT_FILE, 'r') as file:
+        current = json.load(file)
+    if current.get('salt') != data.get('previous_salt'):
+        return jsonify({"error": "L'en-tête du coffre a changé entre-temps."}), 409
+
+    # Le nouvel en-tête prend la révision suivante : une modification concurrente des facteurs échoue
+    vault = dict(vault, revision=current.get('revision', 0) + 1)
+    # Le journal est écrit d'un bloc ; un arrêt avant la fin est rattrapé au démarrage
+    write_json_atomic(REKEY_FILE, {'vault': vault, 'auth': auth})
+    finish_rekey()
+
+    # Toutes les sessions refont l'échange avec le nouveau mot de passe
+    ACCESS_TOKENS.clear()
+    REFRESH_TOKENS.clear()
+    HANDSHAKES.clear()
+    return jsonify(vault)
+
+@app.route('/auth/register', methods=['POST'])
+def api_auth_register():
+    """Enregistre le vérificateur SRP du premier appareil ; jamais remplacé ensuite.
+    Seule l'application lancée sur la machine du serveur peut l'enregistrer : une page web
+    ouverte dans le navigateur (requête portant un en-tête Origin) est refusée. Pour un
+    serveur distant, le premier appareil s'enregistre à travers un tunnel SSH."""
+    if request.remote_addr not in LOOPBACK_ADDRESSES or request.headers.get('Origin'):
+        return jsonify({"error": "Le premier appareil doit s'enregistrer depuis la machine du serveur."}), 403
+    data = request.json or {}
+    if not data.get('salt') or not data.get('verifier') or not isinstance(data.get('kdf'), dict):
+        return jsonify({"error": "Les champs 'salt', 'kdf' et 'verifier' sont requis."}), 400
+    if load_auth() is not None:
+        return jsonify({"error": "Un vérificateur est déjà enregistré."}), 409
+
+    auth = {'salt': data['salt'], 'kdf': data['kdf'], 'verifier': data['verifier']}
+    with open(AUTH_FILE, 'w') as file:
+        json.dump(auth, file, indent=4)
+    return jsonify({"message": "Vérificateur enregistré."}), 201
+
+@app.route('/auth/start', methods=['POST'])
+def api_auth_start():
+    """Première étape SRP-6a : reçoit A, renvoie le sel et B."""
+    auth = load_auth()
+    if auth is None:
+        return jsonify({"error": "Aucun vérificateur enregistré."}), 404
+    data = request.json or {}
+    try:
+        client_public = int(data.get('A', ''), 16)
+    except ValueError:
+        return jsonify({"error": "Valeur 'A' invalide."}), 400
+    if client_public % SRP_N == 0:
+        return jsonify({"error": "Valeur 'A' invalide."}), 400
+
+    verifier = int(auth['verifier'], 16)
+    k = srp_hash_int(srp_pad(SRP_N), srp_pad(SRP_G))
+    b = secrets.randbits(256)
+    server_public = (k * verifier + pow(SRP_G, b, SRP_N)) % SRP_N
+
+    u = srp_hash_int(srp_pad(client_public), srp_pad(server_public))
+    secret = pow(client_public * pow(verifier, u, SRP_N) % SRP_N, b, SRP_N)
+    session_key = srp_hash(srp_pad(secret))
+    client_proof = srp_hash(srp_pad(client_public), srp_pad(server_public), session_key)
+    server_proof = srp_hash(srp_pad(client_public), client_proof, session_key)
+
+    prune_expired(HANDSHAKES)
+    session = secrets.token_urlsafe(16)
+    HANDSHAKES[session] = {'expires': time.time() + HANDSHAKE_TTL, 'M1': client_proof, 'M2': server_proof}
+    return jsonify({"session": session, "salt": auth['salt'], "kdf": auth['kdf'], "B": format(server_public, 'x')})
+
+@app.route('/auth/verify', methods=['POST'])
+def api_auth_verify():
+    """Seconde étape : vérifie la preuve du client, renvoie celle du serveur et les jetons."""
+    data = request.json or {}
+    handshake = HANDSHAKES.pop(data.get('session', ''), None)
+    if handshake is None or handshake['expires'] < time.time():
+        return jsonify({"error": "Échange d'authentification expiré."}), 401
+    try:
+        client_proof = bytes.fromhex(data.get('M1', ''))
+    except ValueError:
+        client_proof = b''
+    if not secrets.compare_digest(client_proof, handshake['M1']):
+        return jsonify({"error": "Preuve invalide."}), 401
+
+    tokens = issue_tokens()
+    tokens['M2'] = handshake['M2'].hex()
+    return jsonify(tokens)
+
+@app.route('/auth/refresh', methods=['POST'])
+def api_auth_refresh():
+    """Échange un jeton de renouvellement contre un nouveau couple de jetons."""
+    data = request.json or {}
+    refresh = REFRESH_TOKENS.pop(data.get('refresh_token', ''), None)
+    if refresh is None or refresh['expires'] < time.time():
+        return jsonify({"error": "Jeton de renouvellement invalide."}), 401
+    ACCESS_TOKENS.pop(refresh['access'], None)
+    return jsonify(issue_tokens())
+
+@app.route('/auth/logout', methods=['POST'])
+def api_auth_logout():
+    """Révoque le jeton d'accès et le jeton de renouvellement de la session."""
+    data = request.json or {}
+    ACCESS_TOKENS.pop(bearer_token() or '', None)
+    refresh = REFRESH_TOKENS.pop(data.get('refresh_token', ''), None)
+    if refresh is not None:
+        ACCESS_TOKENS.pop(refresh['access'], None)
+    return jsonify({"message": "Session fermée."})
+
+@app.route('/extension/pair', methods=['POST'])
+def api_pair_extension():
+    """Délivre un jeton d'appairage à saisir dans l'extension ; seule son empreinte est gardée."""
+    token = secrets.token_urlsafe(32)
+    tokens = load_extension_tokens()
+    tokens[extension_token_hash(token)] = {"created_at": now_iso()}
+    write_json_atomic(EXTENSION_FILE, {"tokens": tokens})
+    return jsonify({"token": token})
+
+@app.route('/extension/pair', methods=['DELETE'])
+def api_unpair_extension():
+    """Révoque tous les jetons d'appairage de l'extension."""
+    write_json_atomic(EXTENSION_FILE, {"tokens": {}})
+    return jsonify({"message": "Extension dissociée."})
+
 @app.route('/getEmail', methods=["GET"])
 def getEmail():
     ensure_json_file(EMAILFILE)
@@ -298,4 +819,5 @@ if __name__ == '__main__':
         gen_key()
     ensure_json_file(PASSWORDS_FILE)
     ensure_json_file(EMAILFILE)
+    finish_rekey()
     app.run(debug=True)
\ No newline at end of file
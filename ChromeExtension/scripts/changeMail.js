@@ -11,7 +11,7 @@ document.addEventListener("DOMContentLoaded", async () => {
 
 	try {
 		// Récupération de l'email actuel
-		const response = await fetch("http://localhost:5000/getEmail");
+		const response = await mushroomFetch("http://localhost:5000/getEmail");
 		const data = await response.json();
 
 		// Affichage de l'email et du bouton
@@ -35,6 +35,20 @@ document.addEventListener("DOMContentLoaded", async () => {
         `;
 	}
 
+	// Jeton délivré par l'application Mushroom, gardé dans le stockage de l'extension
+	const pairingForm = document.querySelector("#pairingForm");
+	const pairingInput = document.querySelector("#pairingToken");
+	const pairingMessage = document.querySelector("#pairingMessage");
+	if (await getPairingToken()) {
+		pairingMessage.textContent = "Extension appairée.";
+	}
+	pairingForm.addEventListener("submit", async (e) => {
+		e.preventDefault();
+		await setPairingToken(pairingInput.value);
+		pairingInput.value = "";
+		pairingMessage.textContent = "Jeton enregistré.";
+	});
+
 	// Reste du code existant pour la gestion du formulaire
 	const emailInput = document.querySelector('input[type="email"]');
 	const messageDiv = document.createElement("div");
@@ -46,7 +60,7 @@ document.addEventListener("DOMContentLoaded", async () => {
 		const email = emailInput.value;
 
 		try {
-			const response = await fetch("http://localhost:5000/changeMail", {
+			const response = await mushroomFetch("http://localhost:5000/changeMail", {
 				method: "POST",
 				headers: {
 					"Content-Type": "application/json",
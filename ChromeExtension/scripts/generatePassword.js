@@ -36,17 +36,24 @@ chrome.tabs.query({ active: true, currentWindow: true }, function(tabs) {
 async function saveServiceAndPasswordInJSON(donnee) {
 console.log("Début de la sauvegarde");
 try {
-console.log("Données à envoyer :", JSON.stringify(donnee));
-const response = await fetch("http://localhost:5000/save-password", {
+const response = await mushroomFetch("http://localhost:5000/save-password", {
     method: 'POST',
     headers: {
         "Content-Type": "application/json"
     },
     body: JSON.stringify(donnee)
 });
+const result = await response.json();
+// Refus du serveur, par exemple une entrée chiffrée de bout en bout (403) :
+// elle ne se modifie que depuis l'application Mushroom
+if (!response.ok) {
+    console.error("Sauvegarde refusée :", result.error);
+    alert(result.error);
+    return result;
+}
 console.log("Requête envoyée avec succès");
 changeSaveButton()
-return await response.json();
+return result;
 } catch (error) {
 console.error("Erreur lors de la sauvegarde:", error);
 }
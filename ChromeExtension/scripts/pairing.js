@@ -0,0 +1,33 @@
+// Jeton d'appairage délivré par l'application Mushroom (Paramètres > Extension Chrome).
+// Les routes de l'extension le refusent s'il manque ou a été révoqué
+const PAIRING_KEY = "mushroomPairingToken";
+
+function getPairingToken() {
+	return new Promise((resolve) => {
+		chrome.storage.local.get(PAIRING_KEY, (items) => {
+			resolve(items[PAIRING_KEY] || "");
+		});
+	});
+}
+
+function setPairingToken(token) {
+	return new Promise((resolve) => {
+		chrome.storage.local.set({ [PAIRING_KEY]: token.trim() }, resolve);
+	});
+}
+
+// fetch vers le serveur Mushroom, avec le jeton d'appairage s'il est connu
+async function mushroomFetch(url, options = {}) {
+	const token = await getPairingToken();
+	const headers = { ...(options.headers || {}) };
+	if (token) {
+		headers["Authorization"] = `Bearer ${token}`;
+	}
+	const response = await fetch(url, { ...options, headers });
+	if (response.status === 401) {
+		console.warn(
+			"Extension non appairée : saisissez le jeton de l'application dans les paramètres"
+		);
+	}
+	return response;
+}
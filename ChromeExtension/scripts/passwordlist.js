@@ -4,6 +4,9 @@ const ICONS = {
 	copy: "/ChromeExtension/assets/imgs/copy_icon.png",
 	tick: "/ChromeExtension/assets/imgs/tick_icon.png",
 };
+// Les entrées chiffrées de bout en bout arrivent sans mot de passe (champ "encrypted") :
+// seule l'application Mushroom, qui détient la clé du coffre, peut les ouvrir
+const ENCRYPTED_HINT = "Chiffré de bout en bout : ouvrez l'application Mushroom";
 
 // Fonction pour extraire le nom principal d'un service à partir d'une URL
 function extractServiceName(url) {
@@ -29,7 +32,7 @@ function extractServiceName(url) {
 // Récupérer les services et mots de passe
 async function fetchPasswords() {
 	try {
-		const response = await fetch(API_URL);
+		const response = await mushroomFetch(API_URL);
 		if (!response.ok) throw new Error("Erreur de récupération");
 
 		const passwords = await response.json();
@@ -70,9 +73,15 @@ function displayPasswords(passwords) {
 
 		const copyButton = document.createElement("button");
 		copyButton.innerHTML = `<img src="${ICONS.copy}" alt="Copier">`;
-		copyButton.addEventListener("click", () =>
-			copyPassword(password.service_password, copyButton)
-		);
+		if (password.encrypted) {
+			copyButton.disabled = true;
+			copyButton.title = ENCRYPTED_HINT;
+			serviceSpan.title = ENCRYPTED_HINT;
+		} else {
+			copyButton.addEventListener("click", () =>
+				copyPassword(password.service_password, copyButton)
+			);
+		}
 
 		listItem.appendChild(serviceSpan);
 		listItem.appendChild(copyButton);
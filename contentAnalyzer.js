@@ -56,7 +56,7 @@ function fillEmailFields(mail) {
 // Fonction pour vérifier si un mot de passe est déjà enregistré
 async function alreadyRegistered(serviceURL = window.location.href) {
 	try {
-		const response = await fetch("http://localhost:5000/registered", {
+		const response = await mushroomFetch("http://localhost:5000/registered", {
 			method: "POST",
 			headers: {
 				"Content-Type": "application/json",
@@ -71,6 +71,11 @@ async function alreadyRegistered(serviceURL = window.location.href) {
 		}
 
 		const registeredData = await response.json();
+		// Entrée chiffrée de bout en bout : le serveur n'envoie pas le mot de passe,
+		// seule l'application Mushroom peut l'ouvrir
+		if (registeredData.encrypted) {
+			return { password: false, email: registeredData.email, encrypted: true };
+		}
 		if (registeredData.registered) {
 			return {
 				password: registeredData.password,
@@ -126,7 +131,7 @@ async function savePassword(serviceURL, password) {
 		const API_URL = "http://localhost:5000/save-password";
 		const data = { service: serviceURL, password };
 
-		const response = await fetch(API_URL, {
+		const response = await mushroomFetch(API_URL, {
 			method: "POST",
 			headers: {
 				"Content-Type": "application/json",
@@ -152,7 +157,7 @@ async function savePassword(serviceURL, password) {
 async function getMailAPI() {
 	console.log("Récupération de l'email en cours");
 	try {
-		const response = await fetch("http://localhost:5000/getEmail", {
+		const response = await mushroomFetch("http://localhost:5000/getEmail", {
 			method: "GET",
 		});
 
@@ -191,6 +196,14 @@ async function main() {
 		const data = await alreadyRegistered(serviceURL);
 		let password = data.password;
 
+		// Ne pas générer de remplaçant pour une entrée déjà enregistrée mais illisible ici
+		if (data.encrypted) {
+			console.log(
+				"🔒 => Mot de passe chiffré de bout en bout : ouvrez l'application Mushroom."
+			);
+			return;
+		}
+
 		console.log("📧 Avant getMailAPI");
 		const emailResponse = await getMailAPI(); // Attend la réponse de l'API
 		console.log("📧 Email reçu:", emailResponse, typeof emailResponse);
@@ -0,0 +1,537 @@
+app-title = Mushroom password manager
+
+## Login
+
+login-subtitle = Enter your master password to access your passwords
+login-master-password = Master password
+login-profiles = Profiles…
+login-recovery-kit = Recovery kit…
+login-key-file = Key file (optional, e.g. /media/usb/mushroom.key)
+login-opening = Opening...
+login-submit = Log in
+login-lockout = Too many attempts: try again in { $wait }
+login-totp-code = TOTP code
+login-totp-submit = Confirm
+wait-seconds = { $seconds } s
+wait-minutes = { $minutes } min { $seconds } s
+
+## Password list
+
+main-no-result = No results
+main-password-too-short = Password too short
+main-new-password = Add a new password
+main-saved-passwords = Saved passwords
+main-search = Search (name, URL, username, tags, notes)
+main-refresh = Refresh
+main-import = Import…
+main-export = Export…
+main-backups = Backups…
+main-team = Team…
+main-recovery = Recovery…
+main-security = Security…
+main-activity = Activity…
+main-settings = Settings…
+main-lock = Lock
+main-conflicts = Conflicts ({ $count })
+main-entry-count = { $shown } / { $total } entries
+field-password = Password
+field-service-url = Service URL
+field-service-url-placeholder = Service URL (e.g. www.example.com)
+field-email = Email
+action-generate = Generate
+action-copy = Copy
+password-reveal = Show
+password-hide = Hide
+action-save = Save
+search-field-name = Name
+search-field-url = URL
+search-field-username = Username
+search-field-email = Email
+search-field-tags = Tags
+search-field-notes = Notes
+team-shared-badge = Shared · { $collection }
+connectivity-unknown = ● Connecting...
+connectivity-online = ● Online
+connectivity-online-pending =
+    { $count ->
+        [one] ● Online, { $count } change to send
+       *[other] ● Online, { $count } changes to send
+    }
+connectivity-offline =
+    { $count ->
+        [one] ● Offline, { $count } pending change
+       *[other] ● Offline, { $count } pending changes
+    }
+
+## Entry details
+
+detail-back = < Back
+detail-title = Service details: { $service }
+detail-totp-invalid = Invalid TOTP secret: { $error }
+detail-generate = Generate a new password
+detail-update = Update
+detail-share = Share with the team…
+detail-unshare = Remove from the collection
+totp-title = One-time code (TOTP)
+totp-copy = Copy code
+entry-username = Username
+entry-folder = Folder
+entry-folder-placeholder = Folder (e.g. Personal/Banks)
+entry-tags = Tags
+entry-tags-placeholder = Comma-separated tags
+entry-notes = Notes
+entry-custom-fields = Custom fields
+entry-field-value = Value
+entry-field-name = Name
+entry-add-field = + Add a field
+entry-timestamps = Created { $created } · Modified { $modified } · Last used: { $used }
+timestamp-unknown = unknown
+field-kind-text = Text
+field-kind-hidden = Hidden
+field-kind-url = URL
+field-kind-totp = TOTP
+entry-diff-service = service
+entry-diff-username = username
+entry-diff-email = email
+entry-diff-password = password
+entry-diff-notes = notes
+entry-diff-tags = tags
+entry-diff-folder = folder
+entry-diff-custom-fields = custom fields
+audit-detail-password = password
+audit-detail-totp = TOTP code
+audit-detail-imported =
+    { $count ->
+        [one] { $count } entry
+       *[other] { $count } entries
+    }
+audit-detail-restored-backup =
+    { $count ->
+        [one] { $count } change from an automatic backup
+       *[other] { $count } changes from an automatic backup
+    }
+audit-detail-restored-archive =
+    { $count ->
+        [one] { $count } change from an archive
+       *[other] { $count } changes from an archive
+    }
+
+## Status messages
+
+status-opening-profile = Opening profile '{ $profile }'...
+status-open-failed = Could not open the vault: { $error }
+status-connected = Logged in
+status-totp-required = A TOTP code is required to open this vault
+status-totp-incorrect = Incorrect TOTP code
+status-switched-profile = Vault locked; enter the master password of profile '{ $profile }'
+status-locked = Vault locked
+status-locked-idle = Vault locked after { $delay } of inactivity
+status-logout-failed = Vault locked, but the session could not be revoked: { $error }
+status-failures-not-recorded = { $status }; failure counter not saved: { $error }
+status-cache-wiped = { $status }; local cache wiped after { $count } failures
+status-cache-wipe-failed = { $status }; could not wipe the cache: { $error }
+status-password-copied = Password copied to the clipboard
+status-totp-copied = TOTP code copied to the clipboard
+status-totp-error = TOTP error: { $error }
+status-error = Error: { $error }
+status-saved = Saved
+status-password-updated = Password updated
+status-updating = Updating...
+status-update-error = Update failed: { $error }
+status-no-service-url = Error: service URL unavailable
+status-email-saved = Email saved
+status-email-error = Could not save the email: { $error }
+status-service-error = Error: service '{ $service }' not found. { $error }
+status-service-loaded = Service details loaded
+status-back-online = Connection to the server restored
+status-conflicts =
+    { $count ->
+        [one] { $count } sync conflict to resolve
+       *[other] { $count } sync conflicts to resolve
+    }
+status-conflicts-resolved = Conflicts resolved
+status-sync-error = Sync error: { $error }
+status-sync-unreadable =
+    { $count ->
+        [one] { $count } server entry could not be read and was skipped
+       *[other] { $count } server entries could not be read and were skipped
+    }
+status-team-unavailable = Shared collections unavailable: { $error }
+status-team-unreadable = Unreadable shared collection: { $error }
+status-shared = Entry shared in '{ $collection }'
+status-unshared = Entry removed from the collection
+status-share-failed = Could not share: { $error }
+status-backups-disabled = Automatic backups disabled: { $error }
+status-audit-failed = Activity log not updated: { $error }
+
+## Client errors
+
+api-error-network = Network error: { $detail }
+api-error-http = HTTP error: { $status }
+api-error-http-detail = HTTP error: { $status }. Details: { $detail }
+api-error-decode = JSON decoding error: { $detail }
+api-error-no-session = No open session
+api-error-no-server = This profile has no server: the Chrome extension needs one
+api-error-authentication-refused = Authentication refused: wrong master password
+api-error-verifier-rejected = The server refused to register the verifier
+api-error-missing-server-proof = The server sent no proof
+api-error-locked = Vault locked: the encryption key is not available
+api-error-service-not-found = Service '{ $service }' not found
+api-error-missing-password = Invalid response: password missing
+api-error-rekey-conflict = The master password was already changed from another device
+api-error-header-conflict = The vault security settings were changed from another device; reopen the vault and try again
+api-error-import =
+    { $count ->
+        [one] { $count } entry imported before the error: { $detail }
+       *[other] { $count } entries imported before the error: { $detail }
+    }
+decrypt-error-wrong-secret = Wrong master password or key file, or tampered data
+decrypt-error-key-file-required = This vault requires its key file
+decrypt-error-key-file-unexpected = This vault does not use a key file
+decrypt-error-no-recovery-kit = No current recovery kit matches these shares
+decrypt-error-missing-check = Vault header without a check value
+decrypt-error-unsupported-format = Unsupported encryption format '{ $format }'
+decrypt-error-unreadable = Unreadable decrypted data: { $detail }
+
+## Settings and formats
+
+backend-api = Server
+backend-local-file = Local file
+theme-system = System
+theme-light = Light
+theme-dark = Dark
+theme-high-contrast = High contrast
+delay-never = Never
+delay-minutes = { $minutes } min
+delay-seconds = { $seconds } s
+wipe-policy-never = Never wipe the cache
+wipe-policy-after = Wipe the cache after { $count } failures
+kdf-params = Argon2id { $memory } MiB, { $iterations } passes
+kdf-params-parallel = Argon2id { $memory } MiB, { $iterations } passes, { $parallelism } threads
+audit-action-viewed = Viewed
+audit-action-copied = Copied
+audit-action-created = Created
+audit-action-updated = Updated
+audit-action-deleted = Deleted
+audit-action-imported = Imported
+audit-action-exported = Exported
+audit-action-restored = Restored
+export-format-archive = Encrypted archive (.mushroom)
+export-format-json = Plain-text JSON
+export-format-csv = Plain-text CSV
+restore-mode-merge = Merge with the vault
+restore-mode-replace = Replace the vault
+restore-summary = { $added } added, { $updated } updated, { $removed } removed, { $unchanged } unchanged
+restore-summary-kept-newer = , { $count } newer in the vault kept
+restore-error =
+    { $count ->
+        [one] Restore interrupted after { $count } change: { $error }
+       *[other] Restore interrupted after { $count } changes: { $error }
+    }
+import-format-bitwarden = Bitwarden (unencrypted JSON)
+import-format-chrome = Chrome / Edge (CSV)
+import-format-firefox = Firefox (CSV)
+import-format-keepassxc = KeePassXC (CSV)
+import-format-kdbx = KeePass / KeePassXC (.kdbx database)
+import-duplicate-identical = identical duplicate
+import-duplicate-conflict = duplicate, different password
+
+## Settings
+
+settings-title = Settings
+settings-loading = Reading the vault header...
+settings-preferences = Preferences
+settings-generator-length = Length of generated passwords
+settings-length-range = Length between { $min } and { $max } characters
+settings-generator-characters = Characters of generated passwords
+settings-generator-digits = Digits
+settings-generator-symbols = Symbols
+settings-clipboard-delay = Clear the clipboard after
+settings-idle-delay = Lock after being idle for
+settings-reveal-delay = Hide a revealed password after
+settings-backend = Vault of new profiles
+settings-server-url = Default server URL
+settings-theme = Theme
+settings-language = Language
+settings-email = Default email
+settings-email-hint = Suggested for new entries and filled in by the Chrome extension
+settings-email-placeholder = Email address
+settings-save = Save
+settings-extension = Chrome extension
+settings-extension-hint = The extension only reaches the server with a pairing token. Unpairing revokes every token issued.
+settings-extension-pair = Pair the extension
+settings-extension-unpair = Unpair
+settings-extension-paired = Enter this token in the extension settings: it will not be shown again
+settings-extension-unpaired = Extension unpaired: its tokens are revoked
+settings-current-required = The current master password is required for any change to the vault header
+settings-current-password = Current master password
+settings-kdf = Key derivation
+settings-kdf-current = Current: { $kdf }
+settings-calibration-hint = Calibration measures this machine to aim for { $milliseconds } ms per unlock
+settings-calibrate = Measure this machine
+settings-calibrating = Measuring this machine...
+settings-calibration-current = Unlocks in { $milliseconds } ms on this machine
+settings-calibration-proposed = Proposed: { $kdf } ({ $milliseconds } ms)
+settings-strengthen = Strengthen
+settings-strengthened = Key derivation strengthened: { $kdf }
+settings-kdf-strong-enough = The current key derivation is already at least as costly
+settings-change-password-title = Change the master password
+settings-change-password-hint = The entry key does not change: only the vault header is re-encrypted, in a single block. Other devices ask for it the next time they reach the server.
+settings-new-password = New master password
+settings-confirm-password = Confirm the new password
+settings-change-password = Change the password
+settings-changing = Changing...
+settings-rekeying = Checking the current password and deriving the new key...
+settings-new-password-required = A new master password is required
+settings-confirmation-mismatch = The two entries of the new password differ
+settings-password-unchanged = The new password is the same as the current one
+settings-password-changed = Master password changed. Existing recovery kits and backups remain valid
+settings-cache-not-rekeyed = Password changed, but the local cache was not rewritten: { $error }
+
+## Activity
+
+activity-title = Activity
+activity-loading = Reading the log...
+activity-intact =
+    { $count ->
+        [one] Log intact: { $count } operation, chain and header verified
+       *[other] Log intact: { $count } operations, chain and header verified
+    }
+activity-tampered =
+    { $count ->
+        [0] Log tampered with: { $problem }. No operation can be trusted
+        [one] Log tampered with: { $problem }. Only the first operation can be trusted
+       *[other] Log tampered with: { $problem }. Only the first { $count } operations can be trusted
+    }
+activity-verify = Verify
+activity-verifying = Verifying...
+activity-filter = Filter by entry or operation
+activity-empty = No operation logged
+audit-tampered = Activity log tampered with, new operations are not logged: { $problem }
+audit-problem-unreadable = record #{ $record } unreadable, modified or moved
+audit-problem-broken-chain = chain broken at record #{ $record }
+audit-problem-truncated = log truncated: { $count } of { $expected } records
+audit-problem-head-mismatch = the log does not match its header
+audit-problem-missing-head = log header missing
+
+## Export and restore
+
+export-title = Export and restore
+export-section = Export the vault
+export-path = Destination file (e.g. /home/me/vault.{ $extension })
+export-plaintext-warning = Warning: passwords will be written in plain text, readable by anyone who can access the file.
+export-plaintext-acknowledge = I understand and want a plain-text export
+export-password-hint = Archive password, distinct from the master password
+export-password = Archive password
+export-password-confirmation = Confirmation
+export-password-mismatch = The passwords do not match
+export-submit = Export
+export-exporting = Exporting...
+export-done =
+    { $count ->
+        [one] { $count } entry exported to '{ $path }'
+       *[other] { $count } entries exported to '{ $path }'
+    }
+file-read-failed = Could not read '{ $path }': { $error }
+audit-detail-exported =
+    { $count ->
+        [one] { $count } entry, { $format }, to '{ $path }'
+       *[other] { $count } entries, { $format }, to '{ $path }'
+    }
+restore-section = Restore an archive
+restore-path = .mushroom archive or JSON export
+restore-password = Archive password (not needed for a JSON export)
+restore-analyze = Analyze
+restore-removed-entry = Removed: { $service } ({ $id })
+restore-submit = Restore
+restore-restoring = Restoring...
+restore-cancel = Cancel
+export-restored =
+    { $count ->
+        [one] Restore complete: { $count } change
+       *[other] Restore complete: { $count } changes
+    }
+
+## Import
+
+import-title = Import from another password manager
+import-file = Export file
+import-file-placeholder = File path (e.g. /home/me/bitwarden.json)
+import-format = Format (detected automatically if empty)
+import-database-password = Database password
+import-key-file = Key file (optional)
+import-key-file-placeholder = Key file path (e.g. /home/me/database.keyx)
+import-analyze = Analyze
+import-unknown-format = Unrecognized format, pick it from the list
+import-summary = { $format }: { $read } entries read, { $duplicates } duplicates, { $selected } selected
+import-detail-username = username: { $username }
+import-detail-folder = folder: { $folder }
+import-detail-tags = tags: { $tags }
+import-detail-custom-fields =
+    { $count ->
+        [one] { $count } custom field
+       *[other] { $count } custom fields
+    }
+import-select-all = Select all
+import-select-none = Select none
+import-change-file = Change file
+import-submit = Import the selection
+import-importing = Importing...
+import-done =
+    { $count ->
+        [one] { $count } entry imported
+       *[other] { $count } entries imported
+    }
+
+## Sync conflicts
+
+merge-title = Sync conflicts
+merge-empty = No conflict to resolve
+merge-hint = These entries were changed on this device and on the server since the last sync.
+merge-keep-local = Keep my version
+merge-keep-remote = Keep the server version
+merge-deleted-remotely = Deleted on the server, changed on this device
+merge-deleted-locally = Changed on the server, deleted on this device
+merge-local = This device
+merge-remote = Server
+merge-apply = Apply the merge
+merge-syncing = Syncing...
+
+## Profiles
+
+profiles-title = Profiles
+profiles-new = New profile
+profiles-name = Name
+profiles-server-url = Server URL (default { $url })
+profiles-vault-file = Vault file (e.g. /home/me/team.mushroom)
+profiles-email = Default email (optional)
+profiles-key-file = Key file (optional, created if missing)
+profiles-hint = The profile master password is chosen the first time it is opened; the key file is bound to it then
+profiles-create = Create the profile
+profiles-remove = Remove
+profiles-location-required = The server URL or the file path is required
+profiles-created = Profile '{ $profile }' created
+profiles-wipe-policy = Profile '{ $profile }': { $policy }
+profiles-removed = Profile removed; its vault stays on disk
+backend-describe-api = server { $url }
+backend-describe-local-file = file { $path }
+
+## Security
+
+security-title = Security
+security-factors = Unlocking: { $factors }
+factor-master-password = master password
+factor-key-file = key file
+factor-totp = TOTP code
+security-key-file-required = Key file: required at every unlock
+security-key-file-none = Key file: none
+security-key-file-hint = The key file is chosen when the vault is created: it is part of its key derivation
+security-totp-hint = The TOTP code protects opening the application on this device; it does not strengthen the vault encryption, which only depends on the master password and the key file
+security-totp = TOTP code
+security-code = 6-digit code
+security-totp-enabled = A code from your authenticator app is asked for after the password
+security-totp-disable = Disable
+security-totp-secret = Secret: { $secret }
+security-totp-enable = Enable
+security-totp-offer = Second factor without hardware: a TOTP code after the master password
+security-totp-configure = Set up
+security-totp-added = Add this secret to your authenticator app, then enter a code
+security-totp-disabled = The TOTP code is not enabled
+security-qr-failed = Could not create the QR code: { $error }
+
+## Recovery
+
+recovery-kit-title = Recovery kit
+recovery-kit-hint = A random recovery key is split into shares to entrust to different people. The threshold number of shares reopens the vault without the master password or key file, then sets a new one; below it, they reveal nothing. A new kit replaces the previous one.
+recovery-shares = Shares
+recovery-threshold = Threshold
+recovery-create = Create the kit
+recovery-created = Kit created: print it, then hand out the shares separately
+recovery-path = File to print (e.g. /media/usb/kit.txt)
+recovery-save = Save
+recovery-saved = Kit saved to '{ $path }'; delete the file once printed
+recovery-share-of = Share { $index } of { $count }
+recovery-title = Vault recovery
+recovery-enter-shares = Enter the kit shares (text under each QR code)
+recovery-share = Share { $index }
+recovery-remove-share = Remove
+recovery-add-share = Add a share
+recovery-recover = Replace the master password
+recovery-new-password = New master password, and the key file to require from now on if any
+recovery-creating = Creating the kit...
+recovery-recovering = Replacing the master password...
+recovery-kit-refused = These shares do not reopen this vault: the kit was replaced since, or belongs to another profile
+recovery-no-header = Vault without an encryption header: no kit possible
+recovery-page-title = Mushroom recovery kit — profile “{ $profile }”
+recovery-page-share = Created on { $date } — share { $index } of { $count }, { $threshold } shares together reopen the vault
+recovery-page-hint = Keep this share away from the others. The kit stays valid after a master password change; creating a new kit makes this one unusable.
+
+## Backups
+
+backup-title = Restore a backup
+backup-policy = Automatic encrypted backup every { $count } changes
+backup-policy-daily = { " " }and once a day
+backup-policy-dir = , in { $dir }
+backup-now = Back up now
+backup-working = Backing up...
+backup-created =
+    { $count ->
+        [one] Backup created ({ $count } entry)
+       *[other] Backup created ({ $count } entries)
+    }
+backup-failed = Backup failed: { $error }
+backup-state-failed = Automatic backup state not saved: { $error }
+backup-snapshot-size =
+    { $count ->
+        [one] { $count } entry · { $size } KiB
+       *[other] { $count } entries · { $size } KiB
+    }
+backup-empty = No backup yet
+backup-diff-title = Differences with the backup of { $date }
+backup-diff-summary = { $added } to recreate, { $removed } to remove, { $changed } changed, { $unchanged } identical
+backup-restore = Restore this backup
+backup-close = Close
+backup-restore-hint = The current state of the vault is backed up before any restore
+backup-restored =
+    { $count ->
+        [one] Backup restored: { $count } change
+       *[other] Backup restored: { $count } changes
+    }
+
+## Team
+
+team-title = Team
+team-no-identity = Team identity unavailable for this profile
+team-public-key = Your public key
+team-public-key-hint = Give it to the members who add you to their collections
+team-key-copied = Public key copied to the clipboard
+team-collections = Shared collections
+team-collection-summary =
+    { $count ->
+        [one] { $count } member · { $path }
+       *[other] { $count } members · { $path }
+    }
+team-detach = Detach
+team-detached = Collection detached; its file stays in place
+team-new-collection = New collection
+team-collection-name = Collection name
+team-owner-name = Your name in the team
+team-collection-path = Shared file (e.g. /mnt/team/infra.json)
+team-create = Create
+team-create-required = The name, your member name and the file are required
+team-created = Collection '{ $collection }' created
+team-attach-path = Attach an existing collection (file)
+team-attach = Attach
+team-attached = Collection '{ $collection }' attached to the profile
+team-already-attached = This collection is already attached to the profile
+team-members = Members
+team-choose-collection = Choose a collection
+team-member-me = { $member } (you)
+team-remove-member = Remove
+team-member-name = Member name
+team-member-key = Member public key (base64)
+team-add-member = Add the member
+team-member-name-required = The member name is required
+team-member-added = { $member } added to '{ $collection }'
+team-member-removed = Member removed from '{ $collection }'; the entry keys were renewed
+team-remove-hint = Removing a member renews the key of every entry in the collection
@@ -0,0 +1,525 @@
+app-title = Gestionnaire de mots de passe Mushroom
+
+## Connexion
+
+login-subtitle = Veuillez entrer votre mot de passe maître pour accéder à vos mots de passe
+login-master-password = Mot de passe maître
+login-profiles = Profils…
+login-recovery-kit = Kit de récupération…
+login-key-file = Fichier clé (facultatif, ex: /media/usb/mushroom.key)
+login-opening = Ouverture...
+login-submit = Se connecter
+login-lockout = Trop d'essais : nouvel essai possible dans { $wait }
+login-totp-code = Code TOTP
+login-totp-submit = Valider
+wait-seconds = { $seconds } s
+wait-minutes = { $minutes } min { $seconds } s
+
+## Liste des mots de passe
+
+main-no-result = Aucun résultat
+main-password-too-short = Mot de passe trop court
+main-new-password = Ajouter un nouveau mot de passe
+main-saved-passwords = Mots de passe enregistrés
+main-search = Rechercher (nom, URL, utilisateur, tags, notes)
+main-refresh = Actualiser
+main-import = Importer…
+main-export = Exporter…
+main-backups = Sauvegardes…
+main-team = Équipe…
+main-recovery = Récupération…
+main-security = Sécurité…
+main-activity = Activité…
+main-settings = Paramètres…
+main-lock = Verrouiller
+main-conflicts = Conflits ({ $count })
+main-entry-count = { $shown } / { $total } entrées
+field-password = Mot de passe
+field-service-url = URL du service
+field-service-url-placeholder = URL du service (ex: www.exemple.com)
+field-email = Email
+action-generate = Générer
+action-copy = Copier
+password-reveal = Afficher
+password-hide = Masquer
+action-save = Sauvegarder
+search-field-name = Nom
+search-field-url = URL
+search-field-username = Utilisateur
+search-field-email = Email
+search-field-tags = Tags
+search-field-notes = Notes
+team-shared-badge = Partagé · { $collection }
+connectivity-unknown = ● Connexion...
+connectivity-online = ● En ligne
+connectivity-online-pending =
+    { $count ->
+        [one] ● En ligne, { $count } modification à envoyer
+       *[other] ● En ligne, { $count } modifications à envoyer
+    }
+connectivity-offline =
+    { $count ->
+        [one] ● Hors ligne, { $count } modification en attente
+       *[other] ● Hors ligne, { $count } modifications en attente
+    }
+
+## Détails d'une entrée
+
+detail-back = < Retour
+detail-title = Détails du service: { $service }
+detail-totp-invalid = Secret TOTP invalide: { $error }
+detail-generate = Générer nouveau mot de passe
+detail-update = Mettre à jour
+detail-share = Partager avec l'équipe…
+detail-unshare = Retirer de la collection
+totp-title = Code à usage unique (TOTP)
+totp-copy = Copier le code
+entry-username = Nom d'utilisateur
+entry-folder = Dossier
+entry-folder-placeholder = Dossier (ex: Perso/Banques)
+entry-tags = Tags
+entry-tags-placeholder = Tags séparés par des virgules
+entry-notes = Notes
+entry-custom-fields = Champs personnalisés
+entry-field-value = Valeur
+entry-field-name = Nom
+entry-add-field = + Ajouter un champ
+entry-timestamps = Créée le { $created } · Modifiée le { $modified } · Dernière utilisation : { $used }
+timestamp-unknown = inconnue
+field-kind-text = Texte
+field-kind-hidden = Masqué
+field-kind-url = URL
+field-kind-totp = TOTP
+entry-diff-service = service
+entry-diff-username = identifiant
+entry-diff-email = email
+entry-diff-password = mot de passe
+entry-diff-notes = notes
+entry-diff-tags = tags
+entry-diff-folder = dossier
+entry-diff-custom-fields = champs personnalisés
+audit-detail-password = mot de passe
+audit-detail-totp = code TOTP
+audit-detail-imported =
+    { $count ->
+        [one] { $count } entrée
+       *[other] { $count } entrées
+    }
+audit-detail-restored-backup =
+    { $count ->
+        [one] { $count } modification depuis une sauvegarde automatique
+       *[other] { $count } modifications depuis une sauvegarde automatique
+    }
+audit-detail-restored-archive =
+    { $count ->
+        [one] { $count } modification depuis une archive
+       *[other] { $count } modifications depuis une archive
+    }
+
+## Messages d'état
+
+status-opening-profile = Ouverture du profil '{ $profile }'...
+status-open-failed = Ouverture impossible: { $error }
+status-connected = Connexion réussie
+status-totp-required = Code TOTP requis pour ouvrir ce coffre
+status-totp-incorrect = Code TOTP incorrect
+status-switched-profile = Coffre verrouillé ; mot de passe maître du profil '{ $profile }'
+status-locked = Coffre verrouillé
+status-locked-idle = Coffre verrouillé après { $delay } d'inactivité
+status-logout-failed = Coffre verrouillé, révocation de la session impossible: { $error }
+status-failures-not-recorded = { $status } ; compteur d'échecs non enregistré: { $error }
+status-cache-wiped = { $status } ; cache local effacé après { $count } échecs
+status-cache-wipe-failed = { $status } ; effacement du cache impossible: { $error }
+status-password-copied = Mot de passe copié dans le presse-papiers
+status-totp-copied = Code TOTP copié dans le presse-papiers
+status-totp-error = Erreur TOTP: { $error }
+status-error = Erreur: { $error }
+status-saved = Sauvegarde réussie
+status-password-updated = Mot de passe mis à jour avec succès
+status-updating = Mise à jour en cours...
+status-update-error = Erreur de mise à jour: { $error }
+status-no-service-url = Erreur: URL du service non disponible
+status-email-saved = Email sauvegardé!
+status-email-error = Erreur sauvegarde email: { $error }
+status-service-error = Erreur: Service '{ $service }' non trouvé. { $error }
+status-service-loaded = Informations du service chargées
+status-back-online = Connexion au serveur rétablie
+status-conflicts = { $count } conflit(s) de synchronisation à résoudre
+status-conflicts-resolved = Conflits résolus
+status-sync-error = Erreur de synchronisation: { $error }
+status-sync-unreadable = { $count } entrée(s) du serveur illisible(s), ignorée(s)
+status-team-unavailable = Collections partagées indisponibles: { $error }
+status-team-unreadable = Collection partagée illisible: { $error }
+status-shared = Entrée partagée dans '{ $collection }'
+status-unshared = Entrée retirée de la collection
+status-share-failed = Partage impossible: { $error }
+status-backups-disabled = Sauvegardes automatiques désactivées: { $error }
+status-audit-failed = Journal d'activité non mis à jour: { $error }
+
+## Erreurs du client
+
+api-error-network = Erreur réseau: { $detail }
+api-error-http = Erreur HTTP: { $status }
+api-error-http-detail = Erreur HTTP: { $status }. Détails: { $detail }
+api-error-decode = Erreur de décodage JSON: { $detail }
+api-error-no-session = Aucune session ouverte
+api-error-no-server = Ce profil n'a pas de serveur : l'extension Chrome en a besoin
+api-error-authentication-refused = Authentification refusée : mot de passe maître incorrect
+api-error-verifier-rejected = Enregistrement du vérificateur refusé par le serveur
+api-error-missing-server-proof = Preuve du serveur absente
+api-error-locked = Coffre verrouillé : la clé de chiffrement n'est pas disponible
+api-error-service-not-found = Service '{ $service }' non trouvé
+api-error-missing-password = Format de réponse invalide : mot de passe absent
+api-error-rekey-conflict = Le mot de passe maître a déjà été changé depuis un autre appareil
+api-error-header-conflict = Les réglages de sécurité du coffre ont été changés depuis un autre appareil ; rouvrez le coffre puis réessayez
+api-error-import = { $count } entrée(s) importée(s) avant l'erreur: { $detail }
+decrypt-error-wrong-secret = Mot de passe maître ou fichier clé incorrect, ou données altérées
+decrypt-error-key-file-required = Ce coffre exige son fichier clé
+decrypt-error-key-file-unexpected = Ce coffre n'utilise pas de fichier clé
+decrypt-error-no-recovery-kit = Aucun kit de récupération en cours ne correspond à ces parts
+decrypt-error-missing-check = En-tête du coffre sans valeur de contrôle
+decrypt-error-unsupported-format = Format de chiffrement '{ $format }' non pris en charge
+decrypt-error-unreadable = Données déchiffrées illisibles: { $detail }
+
+## Réglages et formats
+
+backend-api = Serveur
+backend-local-file = Fichier local
+theme-system = Système
+theme-light = Clair
+theme-dark = Sombre
+theme-high-contrast = Contraste élevé
+delay-never = Jamais
+delay-minutes = { $minutes } min
+delay-seconds = { $seconds } s
+wipe-policy-never = Jamais effacer le cache
+wipe-policy-after = Effacer le cache après { $count } échecs
+kdf-params = Argon2id { $memory } Mio, { $iterations } passes
+kdf-params-parallel = Argon2id { $memory } Mio, { $iterations } passes, { $parallelism } fils
+audit-action-viewed = Consultation
+audit-action-copied = Copie
+audit-action-created = Création
+audit-action-updated = Modification
+audit-action-deleted = Suppression
+audit-action-imported = Import
+audit-action-exported = Export
+audit-action-restored = Restauration
+export-format-archive = Archive chiffrée (.mushroom)
+export-format-json = JSON en clair
+export-format-csv = CSV en clair
+restore-mode-merge = Fusionner avec le coffre
+restore-mode-replace = Remplacer le coffre
+restore-summary = { $added } ajoutée(s), { $updated } mise(s) à jour, { $removed } supprimée(s), { $unchanged } inchangée(s)
+restore-summary-kept-newer = , { $count } plus récente(s) dans le coffre conservée(s)
+restore-error =
+    { $count ->
+        [one] Restauration interrompue après { $count } modification: { $error }
+       *[other] Restauration interrompue après { $count } modifications: { $error }
+    }
+import-format-bitwarden = Bitwarden (JSON non chiffré)
+import-format-chrome = Chrome / Edge (CSV)
+import-format-firefox = Firefox (CSV)
+import-format-keepassxc = KeePassXC (CSV)
+import-format-kdbx = KeePass / KeePassXC (base .kdbx)
+import-duplicate-identical = doublon identique
+import-duplicate-conflict = doublon, mot de passe différent
+
+## Paramètres
+
+settings-title = Paramètres
+settings-loading = Lecture de l'en-tête du coffre...
+settings-preferences = Préférences
+settings-generator-length = Longueur des mots de passe générés
+settings-length-range = Longueur entre { $min } et { $max } caractères
+settings-generator-characters = Caractères des mots de passe générés
+settings-generator-digits = Chiffres
+settings-generator-symbols = Symboles
+settings-clipboard-delay = Vider le presse-papiers après
+settings-idle-delay = Verrouiller après une inactivité de
+settings-reveal-delay = Masquer le mot de passe affiché après
+settings-backend = Coffre des nouveaux profils
+settings-server-url = URL du serveur par défaut
+settings-theme = Thème
+settings-language = Langue
+settings-email = Email par défaut
+settings-email-hint = Proposé pour les nouvelles entrées et rempli par l'extension Chrome
+settings-email-placeholder = Adresse e-mail
+settings-save = Enregistrer
+settings-extension = Extension Chrome
+settings-extension-hint = L'extension n'accède au serveur qu'avec un jeton d'appairage. Dissocier révoque tous les jetons délivrés.
+settings-extension-pair = Appairer l'extension
+settings-extension-unpair = Dissocier
+settings-extension-paired = Saisissez ce jeton dans les paramètres de l'extension : il ne sera plus affiché
+settings-extension-unpaired = Extension dissociée : ses jetons sont révoqués
+settings-current-required = Le mot de passe maître actuel est demandé pour toute modification de l'en-tête du coffre
+settings-current-password = Mot de passe maître actuel
+settings-kdf = Dérivation de la clé
+settings-kdf-current = Actuelle : { $kdf }
+settings-calibration-hint = La calibration mesure cette machine pour viser { $milliseconds } ms par ouverture
+settings-calibrate = Mesurer cette machine
+settings-calibrating = Mesure de cette machine...
+settings-calibration-current = Ouverture en { $milliseconds } ms sur cette machine
+settings-calibration-proposed = Proposée : { $kdf } ({ $milliseconds } ms)
+settings-strengthen = Renforcer
+settings-strengthened = Dérivation renforcée : { $kdf }
+settings-kdf-strong-enough = La dérivation actuelle est déjà au moins aussi coûteuse
+settings-change-password-title = Changer le mot de passe maître
+settings-change-password-hint = La clé des entrées ne change pas : seul l'en-tête du coffre est rechiffré, d'un seul bloc. Les autres appareils le demandent à leur prochaine connexion au serveur.
+settings-new-password = Nouveau mot de passe maître
+settings-confirm-password = Confirmation du nouveau mot de passe
+settings-change-password = Changer le mot de passe
+settings-changing = Changement...
+settings-rekeying = Vérification du mot de passe actuel et dérivation de la nouvelle clé...
+settings-new-password-required = Nouveau mot de passe maître requis
+settings-confirmation-mismatch = Les deux saisies du nouveau mot de passe diffèrent
+settings-password-unchanged = Le nouveau mot de passe est identique à l'actuel
+settings-password-changed = Mot de passe maître changé. Les kits de récupération et les sauvegardes existants restent valables
+settings-cache-not-rekeyed = Mot de passe changé, mais cache local non réécrit: { $error }
+
+## Activité
+
+activity-title = Activité
+activity-loading = Lecture du journal...
+activity-intact =
+    { $count ->
+        [one] Journal intact : { $count } opération, chaînage et en-tête vérifiés
+       *[other] Journal intact : { $count } opérations, chaînage et en-tête vérifiés
+    }
+activity-tampered =
+    { $count ->
+        [0] Journal altéré : { $problem }. Aucune opération n'est sûre
+        [one] Journal altéré : { $problem }. Seule la première opération est sûre
+       *[other] Journal altéré : { $problem }. Seules les { $count } premières opérations sont sûres
+    }
+activity-verify = Vérifier
+activity-verifying = Vérification...
+activity-filter = Filtrer par entrée ou opération
+activity-empty = Aucune opération journalisée
+audit-tampered = Journal d'activité altéré, nouvelles opérations non journalisées: { $problem }
+audit-problem-unreadable = enregistrement n°{ $record } illisible, modifié ou déplacé
+audit-problem-broken-chain = chaînage rompu à l'enregistrement n°{ $record }
+audit-problem-truncated = journal tronqué : { $count } enregistrement(s) sur { $expected }
+audit-problem-head-mismatch = le journal ne correspond pas à son en-tête
+audit-problem-missing-head = en-tête du journal absent
+
+## Export et restauration
+
+export-title = Exporter et restaurer
+export-section = Exporter le coffre
+export-path = Fichier de destination (ex: /home/moi/coffre.{ $extension })
+export-plaintext-warning = Attention : les mots de passe seront écrits en clair, lisibles par quiconque accède au fichier.
+export-plaintext-acknowledge = Je comprends et veux exporter en clair
+export-password-hint = Mot de passe de l'archive, distinct du mot de passe maître
+export-password = Mot de passe de l'archive
+export-password-confirmation = Confirmation
+export-password-mismatch = Les mots de passe ne correspondent pas
+export-submit = Exporter
+export-exporting = Export en cours...
+export-done =
+    { $count ->
+        [one] { $count } entrée exportée dans '{ $path }'
+       *[other] { $count } entrées exportées dans '{ $path }'
+    }
+file-read-failed = Lecture de '{ $path }' impossible: { $error }
+audit-detail-exported =
+    { $count ->
+        [one] { $count } entrée, { $format }, vers '{ $path }'
+       *[other] { $count } entrées, { $format }, vers '{ $path }'
+    }
+restore-section = Restaurer une archive
+restore-path = Archive .mushroom ou export JSON
+restore-password = Mot de passe de l'archive (inutile pour un export JSON)
+restore-analyze = Analyser
+restore-removed-entry = Supprimée : { $service } ({ $id })
+restore-submit = Restaurer
+restore-restoring = Restauration en cours...
+restore-cancel = Annuler
+export-restored =
+    { $count ->
+        [one] Restauration terminée : { $count } modification
+       *[other] Restauration terminée : { $count } modifications
+    }
+
+## Import
+
+import-title = Importer depuis un autre gestionnaire
+import-file = Fichier d'export
+import-file-placeholder = Chemin du fichier (ex: /home/moi/bitwarden.json)
+import-format = Format (détecté automatiquement si vide)
+import-database-password = Mot de passe de la base
+import-key-file = Fichier clé (facultatif)
+import-key-file-placeholder = Chemin du fichier clé (ex: /home/moi/base.keyx)
+import-analyze = Analyser
+import-unknown-format = Format non reconnu, choisissez-le dans la liste
+import-summary = { $format } : { $read } entrée(s) lue(s), { $duplicates } doublon(s), { $selected } sélectionnée(s)
+import-detail-username = utilisateur: { $username }
+import-detail-folder = dossier: { $folder }
+import-detail-tags = tags: { $tags }
+import-detail-custom-fields =
+    { $count ->
+        [one] { $count } champ personnalisé
+       *[other] { $count } champs personnalisés
+    }
+import-select-all = Tout sélectionner
+import-select-none = Tout désélectionner
+import-change-file = Changer de fichier
+import-submit = Importer la sélection
+import-importing = Import en cours...
+import-done =
+    { $count ->
+        [one] { $count } entrée importée
+       *[other] { $count } entrées importées
+    }
+
+## Conflits de synchronisation
+
+merge-title = Conflits de synchronisation
+merge-empty = Aucun conflit à résoudre
+merge-hint = Ces entrées ont été modifiées sur cet appareil et sur le serveur depuis la dernière synchronisation.
+merge-keep-local = Garder ma version
+merge-keep-remote = Garder la version du serveur
+merge-deleted-remotely = Supprimée sur le serveur, modifiée sur cet appareil
+merge-deleted-locally = Modifiée sur le serveur, supprimée sur cet appareil
+merge-local = Cet appareil
+merge-remote = Serveur
+merge-apply = Appliquer la fusion
+merge-syncing = Synchronisation...
+
+## Profils
+
+profiles-title = Profils
+profiles-new = Nouveau profil
+profiles-name = Nom
+profiles-server-url = URL du serveur (par défaut { $url })
+profiles-vault-file = Fichier du coffre (ex: /home/moi/equipe.mushroom)
+profiles-email = Email par défaut (facultatif)
+profiles-key-file = Fichier clé (facultatif, créé s'il n'existe pas)
+profiles-hint = Le mot de passe maître du profil est choisi à sa première ouverture ; le fichier clé y est alors associé
+profiles-create = Créer le profil
+profiles-remove = Supprimer
+profiles-location-required = L'URL du serveur ou le chemin du fichier est requis
+profiles-created = Profil '{ $profile }' créé
+profiles-wipe-policy = Profil '{ $profile }' : { $policy }
+profiles-removed = Profil supprimé ; son coffre reste sur le disque
+backend-describe-api = serveur { $url }
+backend-describe-local-file = fichier { $path }
+
+## Sécurité
+
+security-title = Sécurité
+security-factors = Déverrouillage : { $factors }
+factor-master-password = mot de passe maître
+factor-key-file = fichier clé
+factor-totp = code TOTP
+security-key-file-required = Fichier clé : exigé à chaque ouverture
+security-key-file-none = Fichier clé : aucun
+security-key-file-hint = Le fichier clé se choisit à la création du coffre : il entre dans la dérivation de sa clé
+security-totp-hint = Le code TOTP protège l'ouverture de l'application sur cet appareil ; il ne renforce pas le chiffrement du coffre, qui ne dépend que du mot de passe maître et du fichier clé
+security-totp = Code TOTP
+security-code = Code à 6 chiffres
+security-totp-enabled = Un code de votre application d'authentification est demandé après le mot de passe
+security-totp-disable = Désactiver
+security-totp-secret = Secret : { $secret }
+security-totp-enable = Activer
+security-totp-offer = Second facteur sans matériel : un code TOTP après le mot de passe maître
+security-totp-configure = Configurer
+security-totp-added = Ajoutez ce secret à votre application d'authentification puis saisissez un code
+security-totp-disabled = Le code TOTP n'est pas activé
+security-qr-failed = QR code impossible: { $error }
+
+## Récupération
+
+recovery-kit-title = Kit de récupération
+recovery-kit-hint = Une clé de récupération aléatoire est découpée en parts à confier à des personnes différentes. Le seuil de parts réunies rouvre le coffre sans le mot de passe maître ni le fichier clé, puis en fixe un nouveau ; en dessous, elles ne révèlent rien. Un nouveau kit remplace le précédent.
+recovery-shares = Parts
+recovery-threshold = Seuil
+recovery-create = Créer le kit
+recovery-created = Kit créé : imprimez-le puis distribuez les parts séparément
+recovery-path = Fichier à imprimer (ex: /media/usb/kit.txt)
+recovery-save = Enregistrer
+recovery-saved = Kit enregistré dans '{ $path }' ; supprimez le fichier une fois imprimé
+recovery-share-of = Part { $index } sur { $count }
+recovery-title = Récupération du coffre
+recovery-enter-shares = Saisissez les parts du kit (texte sous chaque QR code)
+recovery-share = Part { $index }
+recovery-remove-share = Retirer
+recovery-add-share = Ajouter une part
+recovery-recover = Remplacer le mot de passe maître
+recovery-new-password = Nouveau mot de passe maître, et fichier clé à exiger désormais le cas échéant
+recovery-creating = Création du kit...
+recovery-recovering = Remplacement du mot de passe maître...
+recovery-kit-refused = Ces parts ne rouvrent pas ce coffre : kit remplacé depuis, ou d'un autre profil
+recovery-no-header = Coffre sans en-tête de chiffrement : aucun kit possible
+recovery-page-title = Kit de récupération Mushroom — profil « { $profile } »
+recovery-page-share = Créé le { $date } — part { $index } sur { $count }, { $threshold } parts réunies rouvrent le coffre
+recovery-page-hint = Conservez cette part à l'écart des autres. Le kit reste valable après un changement du mot de passe maître ; créer un nouveau kit rend celui-ci inutilisable.
+
+## Sauvegardes
+
+backup-title = Restaurer une sauvegarde
+backup-policy = Sauvegarde chiffrée automatique toutes les { $count } modifications
+backup-policy-daily = { " " }et une fois par jour
+backup-policy-dir = , dans { $dir }
+backup-now = Sauvegarder maintenant
+backup-working = Sauvegarde en cours...
+backup-created =
+    { $count ->
+        [one] Sauvegarde créée ({ $count } entrée)
+       *[other] Sauvegarde créée ({ $count } entrées)
+    }
+backup-failed = Échec de la sauvegarde: { $error }
+backup-state-failed = État des sauvegardes automatiques non enregistré: { $error }
+backup-snapshot-size =
+    { $count ->
+        [one] { $count } entrée · { $size } Kio
+       *[other] { $count } entrées · { $size } Kio
+    }
+backup-empty = Aucune sauvegarde pour l'instant
+backup-diff-title = Différences avec la sauvegarde du { $date }
+backup-diff-summary = { $added } à recréer, { $removed } à supprimer, { $changed } modifiée(s), { $unchanged } identique(s)
+backup-restore = Restaurer cette sauvegarde
+backup-close = Fermer
+backup-restore-hint = L'état actuel du coffre est sauvegardé avant toute restauration
+backup-restored =
+    { $count ->
+        [one] Sauvegarde restaurée : { $count } modification
+       *[other] Sauvegarde restaurée : { $count } modifications
+    }
+
+## Équipe
+
+team-title = Équipe
+team-no-identity = Identité d'équipe indisponible pour ce profil
+team-public-key = Votre clé publique
+team-public-key-hint = À transmettre aux membres qui vous ajoutent à leurs collections
+team-key-copied = Clé publique copiée dans le presse-papiers
+team-collections = Collections partagées
+team-collection-summary =
+    { $count ->
+        [one] { $count } membre · { $path }
+       *[other] { $count } membres · { $path }
+    }
+team-detach = Détacher
+team-detached = Collection détachée ; son fichier reste en place
+team-new-collection = Nouvelle collection
+team-collection-name = Nom de la collection
+team-owner-name = Votre nom dans l'équipe
+team-collection-path = Fichier partagé (ex: /mnt/equipe/infra.json)
+team-create = Créer
+team-create-required = Le nom, votre nom de membre et le fichier sont requis
+team-created = Collection '{ $collection }' créée
+team-attach-path = Rattacher une collection existante (fichier)
+team-attach = Rattacher
+team-attached = Collection '{ $collection }' rattachée au profil
+team-already-attached = Cette collection est déjà rattachée au profil
+team-members = Membres
+team-choose-collection = Choisir une collection
+team-member-me = { $member } (vous)
+team-remove-member = Retirer
+team-member-name = Nom du membre
+team-member-key = Clé publique du membre (base64)
+team-add-member = Ajouter le membre
+team-member-name-required = Le nom du membre est requis
+team-member-added = { $member } ajouté à '{ $collection }'
+team-member-removed = Membre retiré de '{ $collection }' ; les clés des entrées ont été renouvelées
+team-remove-hint = Retirer un membre renouvelle la clé de chaque entrée de la collection
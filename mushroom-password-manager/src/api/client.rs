@@ -1,234 +1,405 @@
-use reqwest::Client;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use serde_json::json;
 use serde::Deserialize;
-use regex::Regex;
 use std::collections::HashMap;
+use std::sync::Arc;
 
-const API_BASE_URL: &str = "http://localhost:5000";
+use crate::api::error::ApiError;
+use crate::api::local::LocalStore;
+use crate::api::session::{self, Session};
+use crate::profile::Backend;
+use crate::vault::crypto::{DecryptError, KEY_LENGTH};
+use crate::vault::e2e::{self, EntryCipher, VaultParams};
+use crate::vault::entry::{service_name_from_url, Entry};
+use crate::vault::factors::UnlockFactors;
+use crate::vault::secure::SecretKey;
 
-#[derive(Deserialize, Debug)]
-pub struct PasswordResponse {
-    pub password: String,
-}
+pub const DEFAULT_API_URL: &str = "http://localhost:5000";
 
 #[derive(Deserialize, Debug)]
 pub struct EmailResponse {
-    pub email: String,
+    // null tant qu'aucun email n'a été enregistré
+    #[serde(default)]
+    pub email: Option<String>,
 }
 
-#[derive(Deserialize, Debug)]
-struct SavedPassword {
-    service_URL: String,
-    service_name: String,
-    service_password: String,
-    #[serde(default)] // Permet une valeur par défaut si absent
-    email: Option<String>, // Rend le champ optionnel
+// Version serveur d'une entrée et sa révision ; `entry` vaut None si elle a été supprimée
+#[derive(Debug, Clone)]
+pub struct RemoteEntry {
+    pub id: String,
+    pub revision: u64,
+    pub entry: Option<Entry>,
+    // Secrets stockés en clair sur le serveur : l'entrée doit être renvoyée chiffrée
+    pub legacy: bool,
 }
 
+// Modifications du serveur postérieures à une révision
+#[derive(Debug, Clone)]
+pub struct ChangeSet {
+    pub revision: u64,
+    pub entries: Vec<RemoteEntry>,
+    // Entrées illisibles (format ou déchiffrement), écartées avec leur erreur
+    pub unreadable: Vec<(String, String)>,
+}
+
+// Modification locale à envoyer, fondée sur la révision `base_revision` ; None pour une suppression
+#[derive(Debug, Clone)]
+pub struct LocalChange {
+    pub id: String,
+    pub base_revision: u64,
+    pub entry: Option<Entry>,
+}
+
+// Réponse à un envoi : révisions des modifications acceptées, version serveur des refusées
+#[derive(Debug, Clone, Default)]
+pub struct PushOutcome {
+    pub applied: HashMap<String, u64>,
+    pub conflicts: Vec<RemoteEntry>,
+    pub unreadable: Vec<(String, String)>,
+}
+
+#[derive(Deserialize)]
+struct PairingResponse {
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct ChangeSetResponse {
+    revision: u64,
+    entries: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct PushResponse {
+    applied: HashMap<String, u64>,
+    conflicts: HashMap<String, serde_json::Value>,
+}
+
+
 
 #[derive(Clone, Debug)]
 pub struct ApiClient {
     pub client: Client,
+    base_url: String,
+    // Coffre d'un profil sans serveur, à la place des requêtes HTTP
+    local: Option<Arc<LocalStore>>,
+    // Clé du coffre, une fois dérivée du mot de passe maître ; sans elle les secrets circulent en clair
+    cipher: Option<Arc<EntryCipher>>,
+    // Session authentifiée ; chaque requête porte son jeton d'accès
+    session: Option<Arc<Session>>,
+    // Jeton d'un kit de récupération, à la place d'une session : il n'ouvre que la lecture
+    // de l'en-tête du coffre et le remplacement du mot de passe maître
+    recovery: Option<String>,
 }
 
 impl ApiClient {
     pub fn new() -> Self {
         ApiClient {
             client: Client::new(),
+            base_url: DEFAULT_API_URL.to_string(),
+            local: None,
+            cipher: None,
+            session: None,
+            recovery: None,
         }
     }
 
-    // Fonction pour extraire le nom du service à partir d'une URL
-    pub fn extract_service_name(&self, url: &str) -> String {
-        // Simplifier l'URL en supprimant http/https/www
-        let url = url.replace("https://", "")
-                    .replace("http://", "")
-                    .replace("www.", "");
-        
-        // Prendre uniquement la partie avant le premier '/'
-        let domain_part = url.split('/').next().unwrap_or("");
-        
-        // Simplifié pour ne pas exiger un point
-        let parts: Vec<&str> = domain_part.split('.').collect();
-        
-        if parts.len() >= 2 {
-            // Format "subdomain.domain" (comme "compte.auchan")
-            format!("{}.{}", parts[0], parts[1])
-        } else if parts.len() == 1 {
-            // Juste un domaine simple
-            parts[0].to_string()
-        } else {
-            // Fallback
-            domain_part.to_string()
+    // Client du coffre d'un profil ; un fichier local est ouvert (et le mot de passe vérifié) ici
+    pub async fn open(backend: &Backend, password: &str) -> Result<Self, ApiError> {
+        let mut client = ApiClient::new();
+        match backend {
+            Backend::Api { url } => {
+                client.base_url = url.trim_end_matches('/').to_string();
+                client.login(password);
+            }
+            Backend::LocalFile { path } => {
+                let (path, password) = (path.clone(), password.to_string());
+                let store = tokio::task::spawn_blocking(move || LocalStore::open(path, &password))
+                    .await
+                    .map_err(|e| e.to_string())??;
+                client.local = Some(Arc::new(store));
+            }
         }
+        Ok(client)
     }
-    
-    pub async fn generate_password(&self) -> Result<String, String> {
-        match self.client
-            .get(&format!("{}/generate-password", API_BASE_URL))
-            .send()
-            .await {
-            Ok(response) => {
-                if response.status().is_success() {
-                    match response.json::<PasswordResponse>().await {
-                        Ok(data) => Ok(data.password),
-                        Err(e) => Err(format!("Erreur de décodage JSON: {}", e)),
-                    }
-                } else {
-                    Err(format!("Erreur HTTP lors de la génération: {}", response.status()))
-                }
+
+    // Client d'un coffre rouvert par un kit de récupération, sans mot de passe maître
+    pub async fn recover(backend: &Backend, recovery_key: &SecretKey<KEY_LENGTH>) -> Result<Self, ApiError> {
+        let mut client = ApiClient::new();
+        match backend {
+            Backend::Api { url } => {
+                client.base_url = url.trim_end_matches('/').to_string();
+                client.recovery = Some(e2e::recovery_token(recovery_key));
+            }
+            Backend::LocalFile { path } => {
+                let (path, recovery_key) = (path.clone(), recovery_key.clone());
+                let store = tokio::task::spawn_blocking(move || LocalStore::recover(path, &recovery_key))
+                    .await
+                    .map_err(|e| e.to_string())??;
+                client.local = Some(Arc::new(store));
             }
-            Err(e) => Err(format!("Erreur réseau: {}", e)),
         }
+        Ok(client)
     }
 
-    pub async fn save_password(&self, service_url: &str, password: &str, email: &str) -> Result<(), String> {
-        // Extraction du nom de service
-        let service_name = self.extract_service_name(service_url);
-        
-        let body = json!({
-            "service": service_url,
-            "service_name": service_name,  // Ajout du nom de service extrait
-            "password": password,
-            "email": email
-        });
+    pub fn set_cipher(&mut self, cipher: Arc<EntryCipher>) {
+        self.cipher = Some(cipher);
+    }
+
+    pub fn cipher(&self) -> Option<Arc<EntryCipher>> {
+        self.cipher.clone()
+    }
+
+    // Ouvre une session pour le mot de passe maître ; l'échange a lieu à la première requête
+    pub fn login(&mut self, password: &str) {
+        self.session = Some(Arc::new(Session::new(&self.base_url, password)));
+    }
+
+    // Après un changement du mot de passe maître, le prochain échange se fait avec le nouveau
+    pub fn change_session_password(&mut self, password: &str) {
+        if self.session.is_some() {
+            self.login(password);
+        }
+    }
+
+    // Établit la session sans attendre une première requête (mot de passe refusé, serveur absent)
+    pub async fn authenticate(&self) -> Result<(), ApiError> {
+        match &self.session {
+            Some(session) => session.bearer(&self.client).await.map(|_| ()),
+            None if self.local.is_some() => Ok(()),
+            None => Err(ApiError::NoSession),
+        }
+    }
+
+    // Révoque le jeton de la session côté serveur
+    pub async fn logout(&self) -> Result<(), ApiError> {
+        match &self.session {
+            Some(session) => session.revoke(&self.client).await,
+            None => Ok(()),
+        }
+    }
 
-        let response = self.client
-            .post(&format!("{}/save-password", API_BASE_URL))
-            .json(&body)
+    // Joint le jeton d'accès à la requête ; un jeton refusé (expiré, révoqué)
+    // est abandonné et la requête rejouée une fois avec un nouveau jeton
+    async fn send(&self, request: impl Fn(&Client) -> RequestBuilder) -> Result<Response, ApiError> {
+        if let Some(token) = &self.recovery {
+            let response = request(&self.client)
+                .bearer_auth(token)
+                .send()
+                .await
+                .map_err(|e| ApiError::Network(e.to_string()))?;
+            // Jeton inconnu du serveur : kit remplacé depuis, ou d'un autre coffre
+            if response.status() == StatusCode::UNAUTHORIZED {
+                return Err(ApiError::Decrypt(DecryptError::NoRecoveryKit));
+            }
+            return Ok(response);
+        }
+        let Some(session) = &self.session else {
+            return request(&self.client).send().await.map_err(|e| ApiError::Network(e.to_string()));
+        };
+        let token = session.bearer(&self.client).await?;
+        let response = request(&self.client)
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+        session.invalidate().await;
+        let token = session.bearer(&self.client).await?;
+        request(&self.client)
+            .bearer_auth(token)
             .send()
             .await
-            .map_err(|e| format!("Erreur réseau: {}", e))?;
+            .map_err(|e| ApiError::Network(e.to_string()))
+    }
 
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(format!("Erreur HTTP: {}", response.status()))
+    // Entrée reçue du serveur, déchiffrée si ses secrets le sont
+    fn decode_entry(&self, id: &str, value: serde_json::Value) -> Result<Entry, ApiError> {
+        let entry = Entry::from_value(id, value)?;
+        match &self.cipher {
+            Some(cipher) => Ok(cipher.decrypt_entry(entry)?),
+            None => Ok(entry),
         }
     }
 
-    // Ajout d'une méthode pour récupérer toutes les données des mots de passe
-    async fn get_data(&self) -> Result<HashMap<String, SavedPassword>, String> {
-        match self.client
-            .get(&format!("{}/list-passwords", API_BASE_URL))
-            .send()
+    fn remote_entry(&self, id: &str, value: serde_json::Value) -> Result<RemoteEntry, ApiError> {
+        let revision = value.get("revision").and_then(|revision| revision.as_u64()).unwrap_or(0);
+        let deleted = value.get("deleted").and_then(|deleted| deleted.as_bool()).unwrap_or(false);
+        let legacy = self.cipher.is_some() && value.get("encryption").is_none_or(|marker| marker.is_null());
+        let entry = if deleted || value.is_null() { None } else { Some(self.decode_entry(id, value)?) };
+        Ok(RemoteEntry { id: id.to_string(), revision, entry, legacy: legacy && !deleted })
+    }
+
+    // Une entrée illisible ne doit pas bloquer la synchronisation des autres
+    fn remote_entries(&self, values: HashMap<String, serde_json::Value>) -> (Vec<RemoteEntry>, Vec<(String, String)>) {
+        let mut entries = Vec::with_capacity(values.len());
+        let mut unreadable = Vec::new();
+        for (id, value) in values {
+            match self.remote_entry(&id, value) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => {
+                    log::warn!(service_url = id.as_str(), error:% = e; "Entrée du serveur illisible");
+                    unreadable.push((id, e.to_string()));
+                }
+            }
+        }
+        (entries, unreadable)
+    }
+
+    // Fonction pour extraire le nom du service à partir d'une URL
+    pub fn extract_service_name(&self, url: &str) -> String {
+        service_name_from_url(url)
+    }
+    
+    // Entrée complète telle qu'attendue par le serveur, qui indexe par "service" et chiffre "password" ;
+    // les secrets partent chiffrés avec la clé du coffre, jamais en clair
+    fn entry_body(&self, entry: &Entry) -> Result<serde_json::Value, ApiError> {
+        let cipher = self.cipher.as_ref()
+            .ok_or(ApiError::Locked)?;
+        let entry = &cipher.encrypt_entry(entry)?;
+        let mut body = serde_json::to_value(entry)
+            .map_err(|e| ApiError::Decode(e.to_string()))?;
+        body["service"] = json!(entry.key());
+        body["password"] = json!(entry.service_password);
+        Ok(body)
+    }
+
+    async fn post_entry(&self, entry: &Entry) -> Result<(), ApiError> {
+        let body = self.entry_body(entry)?;
+        if let Some(store) = &self.local {
+            return store.save_entry(entry.key(), body).map(|_| ()).map_err(ApiError::from);
+        }
+
+        match self.send(|client| client
+            .post(format!("{}/save-password", self.base_url))
+            .json(&body))
             .await {
             Ok(response) => {
                 if response.status().is_success() {
-                    match response.json::<HashMap<String, SavedPassword>>().await {
-                        Ok(data) => Ok(data),
-                        Err(e) => Err(format!("Erreur de décodage JSON: {}", e)),
-                    }
+                    Ok(())
                 } else {
-                    Err(format!("Erreur HTTP: {}", response.status()))
+                    let status = response.status();
+                    match response.text().await {
+                        Ok(text) => Err(ApiError::Http { status, detail: Some(text) }),
+                        Err(_) => Err(ApiError::http(status)),
+                    }
                 }
             }
-            Err(e) => Err(format!("Erreur réseau: {}", e)),
+            Err(e) => Err(e),
         }
     }
 
-    pub async fn get_saved_passwords(&self) -> Result<Vec<String>, String> {
-        match self.client
-            .get(&format!("{}/list-passwords", API_BASE_URL))
-            .send()
+    // Ajout d'une méthode pour récupérer toutes les données des mots de passe
+    async fn get_data(&self) -> Result<HashMap<String, Entry>, ApiError> {
+        if let Some(store) = &self.local {
+            return store
+                .entries()?
+                .into_iter()
+                .map(|(key, value)| self.decode_entry(&key, value).map(|entry| (key, entry)))
+                .collect();
+        }
+        // Synchronisation complète plutôt que /list-passwords : cette route de l'extension
+        // ne renvoie pas les secrets chiffrés de bout en bout
+        match self.send(|client| client
+            .get(format!("{}/sync/changes", self.base_url))
+            .query(&[("since", 0)]))
             .await {
             Ok(response) => {
                 if response.status().is_success() {
-                    // D'abord, récupérons la réponse sous forme d'objet JSON générique
-                    match response.json::<serde_json::Value>().await {
-                        Ok(json_value) => {
-                            // Maintenant, transformons cet objet en une liste de chaînes
-                            let mut passwords = Vec::new();
-                            
-                            // Si c'est un objet (map), nous extrayons les clés
-                            if let serde_json::Value::Object(map) = json_value {
-                                for (service_url, details) in map {
-                                    // On peut aussi extraire le nom du service ici si besoin
-                                    let service_name = self.extract_service_name(&service_url);
-                                    passwords.push(format!("{}", service_name));
-                                }
-                                Ok(passwords)
-                            } else {
-                                // Si par hasard c'est déjà une liste, essayons de l'utiliser directement
-                                match serde_json::from_value::<Vec<String>>(json_value) {
-                                    Ok(list) => Ok(list),
-                                    Err(_) => Err("Format de réponse inattendu".to_string())
-                                }
-                            }
-                        },
-                        Err(e) => Err(format!("Erreur de décodage JSON: {}", e)),
+                    match response.json::<ChangeSetResponse>().await {
+                        Ok(data) => data
+                            .entries
+                            .into_iter()
+                            .filter(|(_, value)| !value.get("deleted").and_then(|deleted| deleted.as_bool()).unwrap_or(false))
+                            .map(|(key, value)| self.decode_entry(&key, value).map(|entry| (key, entry)))
+                            .collect(),
+                        Err(e) => Err(ApiError::Decode(e.to_string())),
                     }
                 } else {
-                    Err(format!("Erreur HTTP: {}", response.status()))
+                    Err(ApiError::http(response.status()))
                 }
             }
-            Err(e) => Err(format!("Erreur réseau: {}", e)),
+            Err(e) => Err(e),
         }
     }
 
-    pub async fn get_email(&self) -> Result<String, String> {
-        match self.client
-            .get(&format!("{}/getEmail", API_BASE_URL))
-            .send()
+    pub async fn get_saved_passwords(&self) -> Result<Vec<Entry>, ApiError> {
+        let data = self.get_data().await?;
+
+        let mut entries: Vec<Entry> = data.into_values().collect();
+
+        // Ordre stable pour la navigation au clavier
+        entries.sort_by_key(|entry| (entry.service_name.to_lowercase(), entry.username.to_lowercase()));
+        Ok(entries)
+    }
+
+    pub async fn get_email(&self) -> Result<String, ApiError> {
+        if let Some(store) = &self.local {
+            return store.email().map_err(ApiError::from);
+        }
+        match self.send(|client| client
+            .get(format!("{}/getEmail", self.base_url)))
             .await {
             Ok(response) => {
                 if response.status().is_success() {
                     match response.json::<EmailResponse>().await {
-                        Ok(email_data) => Ok(email_data.email),
-                        Err(e) => Err(format!("Erreur de décodage JSON pour l'email: {}", e)),
+                        Ok(email_data) => Ok(email_data.email.unwrap_or_default()),
+                        Err(e) => Err(ApiError::Decode(e.to_string())),
                     }
                 } else {
-                    Err(format!("Erreur HTTP lors de la récupération de l'email: {}", response.status()))
+                    Err(ApiError::http(response.status()))
                 }
             }
-            Err(e) => Err(format!("Erreur réseau lors de la récupération de l'email: {}", e)),
+            Err(e) => Err(e),
         }
     }
 
-    pub async fn save_email(&self, email: &str) -> Result<(), String> {
+    pub async fn save_email(&self, email: &str) -> Result<(), ApiError> {
+        if let Some(store) = &self.local {
+            return store.set_email(email).map_err(ApiError::from);
+        }
         let body = json!({
             "email": email,
         });
 
-        let response = self.client
-            .post(&format!("{}/changeMail", API_BASE_URL))
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| format!("Erreur réseau: {}", e))?;
+        let response = self.send(|client| client
+            .post(format!("{}/changeMail", self.base_url))
+            .json(&body))
+            .await?;
 
         if response.status().is_success() {
             Ok(())
         } else {
-            Err(format!("Erreur HTTP: {}", response.status()))
+            Err(ApiError::http(response.status()))
         }
     }
 
     // Changé de async fn privée à pub async fn publique
-    pub async fn get_password_details(&self, display_name: &str) -> Result<(String, String, String), String> {
-        println!("Recherche du service: {}", display_name);
+    // Accepte la clé de l'entrée côté serveur ou, à défaut, son nom affiché
+    pub async fn get_password_details(&self, display_name: &str) -> Result<Entry, ApiError> {
+        log::debug!(service = display_name; "Recherche du service");
         
         // Récupérer les données
         let data = self.get_data().await.map_err(|e| {
-            println!("Erreur get_data: {}", e);
+            log::warn!(error:% = e; "Liste des services indisponible");
             e.to_string()
         })?;
-        
-        // Afficher toutes les entrées pour le débogage
-        println!("Services disponibles:");
-        for (key, details) in &data {
-            println!("  - URL: {}, Nom: {}", key, details.service_name);
-        }
+        log::trace!(count = data.len(); "Services disponibles");
         
         // Trouver l'URL correspondante
-        let mut found_service_url = None;
+        let mut found_service_url = data.contains_key(display_name).then(|| display_name.to_string());
         
-        for (key, details) in &data {
-            // D'abord, chercher une correspondance exacte
-            if details.service_name == display_name {
-                found_service_url = Some(key.clone());
-                println!("Correspondance exacte trouvée: {}", key);
-                break;
+        if found_service_url.is_none() {
+            for (key, details) in &data {
+                // D'abord, chercher une correspondance exacte
+                if details.service_name == display_name {
+                    found_service_url = Some(key.clone());
+                    log::debug!(service_url = key.as_str(); "Correspondance exacte trouvée");
+                    break;
+                }
             }
         }
         
@@ -238,7 +409,7 @@ impl ApiClient {
                 if details.service_name.contains(display_name) || 
                    display_name.contains(&details.service_name) {
                     found_service_url = Some(key.clone());
-                    println!("Correspondance partielle trouvée: {}", key);
+                    log::debug!(service_url = key.as_str(); "Correspondance partielle trouvée");
                     break;
                 }
             }
@@ -247,100 +418,358 @@ impl ApiClient {
         let service_url = match found_service_url {
             Some(url) => url,
             None => {
-                println!("Aucun service trouvé pour: {}", display_name);
-                return Err(format!("Service '{}' non trouvé", display_name));
+                log::info!(service = display_name; "Aucun service trouvé");
+                return Err(ApiError::ServiceNotFound(display_name.to_string()));
             }
         };
         
-        println!("URL de service trouvée: {}", service_url);
+
+        // Le coffre local a déjà rendu les entrées complètes
+        if self.local.is_some() {
+            return data.get(&service_url).cloned().ok_or_else(|| ApiError::ServiceNotFound(display_name.to_string()));
+        }
         
         // Faire la requête à l'API pour obtenir le mot de passe
         let body = json!({
             "service_URL": service_url
         });
         
-        println!("Envoi de la requête à /get-password: {:?}", body);
+        log::debug!(service_url = service_url.as_str(); "Requête /get-password");
         
-        match self.client
-            .post(&format!("{}/get-password", API_BASE_URL))
-            .json(&body)
-            .send()
+        match self.send(|client| client
+            .post(format!("{}/get-password", self.base_url))
+            .json(&body))
             .await {
             Ok(response) => {
                 let status = response.status();
-                println!("Statut de la réponse: {}", status);
+                log::debug!(status = status.as_u16(); "Réponse de /get-password");
                 
                 if status.is_success() {
                     match response.text().await {
+                        // Le corps contient les secrets de l'entrée : il n'est jamais journalisé
                         Ok(text) => {
-                            println!("Réponse brute: {}", text);
-                            
                             match serde_json::from_str::<serde_json::Value>(&text) {
                                 Ok(json_value) => {
-                                    // Extraction du mot de passe et de l'email
-                                    if let Some(password) = json_value.get("service_password") {
-                                        if let Some(password_str) = password.as_str() {
-                                            let email = json_value.get("email")
-                                                .and_then(|e| e.as_str())
-                                                .unwrap_or("");
-                                            
-                                            println!("Mot de passe et email extraits avec succès");
-                                            return Ok((
-                                                service_url,
-                                                password_str.to_string(),
-                                                email.to_string(),
-                                            ));
-                                        }
+                                    // Le mot de passe est obligatoire, le reste a des valeurs par défaut
+                                    if json_value.get("service_password").and_then(|p| p.as_str()).is_none() {
+                                        return Err(ApiError::MissingPassword);
                                     }
-                                    
-                                    Err(format!("Format de réponse invalide: {}", text))
+
+                                    self.decode_entry(&service_url, json_value)
                                 },
                                 Err(e) => {
-                                    Err(format!("Erreur de décodage JSON: {}. Réponse: {}", e, text))
+                                    Err(ApiError::Decode(e.to_string()))
                                 }
                             }
                         },
-                        Err(e) => Err(format!("Erreur lors de la lecture de la réponse: {}", e)),
+                        Err(e) => Err(ApiError::Network(e.to_string())),
                     }
                 } else {
                     match response.text().await {
-                        Ok(text) => Err(format!("Erreur HTTP {}: {}", status, text)),
-                        Err(_) => Err(format!("Erreur HTTP {}", status)),
+                        Ok(text) => Err(ApiError::Http { status, detail: Some(text) }),
+                        Err(_) => Err(ApiError::http(status)),
                     }
                 }
             },
             Err(e) => {
-                println!("Erreur lors de l'envoi de la requête: {}", e);
-                Err(format!("Erreur réseau: {}", e))
+                log::warn!(error:% = e; "Échec de la requête /get-password");
+                Err(e)
             },
         }
     }
 
-    // Fonction pour mettre à jour un mot de passe existant
-    pub async fn update_password(&self, service_url: &str, password: &str, email: &str) -> Result<(), String> {
+    // Enregistre des entrées importées telles quelles (horodatages d'origine conservés)
+    pub async fn import_entries(&self, entries: &[Entry]) -> Result<usize, ApiError> {
+        for (count, entry) in entries.iter().enumerate() {
+            self.post_entry(entry)
+                .await
+                .map_err(|e| ApiError::Import { imported: count, source: Box::new(e) })?;
+        }
+        Ok(entries.len())
+    }
+
+    // Supprime l'entrée de clé `id` côté serveur
+    pub async fn delete_password(&self, id: &str) -> Result<(), ApiError> {
+        if let Some(store) = &self.local {
+            return store.delete(id).map_err(ApiError::from);
+        }
         let body = json!({
-            "service": service_url,
-            "password": password,
-            "email": email
+            "service": id,
         });
-    
-        match self.client
-            .post(&format!("{}/save-password", API_BASE_URL))
-            .json(&body)
-            .send()
-            .await {
-            Ok(response) => {
-                if response.status().is_success() {
-                    Ok(())
-                } else {
-                    let status = response.status();
-                    match response.text().await {
-                        Ok(text) => Err(format!("Erreur HTTP: {}. Détails: {}", status, text)),
-                        Err(_) => Err(format!("Erreur HTTP: {}", status)),
-                    }
-                }
+
+        let response = self.send(|client| client
+            .post(format!("{}/delete-password", self.base_url))
+            .json(&body))
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(ApiError::http(response.status()))
+        }
+    }
+
+    // Entrées modifiées ou supprimées côté serveur depuis la révision `since` (0 : tout le coffre)
+    pub async fn pull_changes(&self, since: u64) -> Result<ChangeSet, ApiError> {
+        if let Some(store) = &self.local {
+            let (revision, entries) = store.changes(since)?;
+            let (entries, unreadable) = self.remote_entries(entries);
+            return Ok(ChangeSet { revision, entries, unreadable });
+        }
+        let response = self.send(|client| client
+            .get(format!("{}/sync/changes", self.base_url))
+            .query(&[("since", since)]))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(ApiError::http(response.status()));
+        }
+        let data = response
+            .json::<ChangeSetResponse>()
+            .await
+            .map_err(|e| ApiError::Decode(e.to_string()))?;
+        let (entries, unreadable) = self.remote_entries(data.entries);
+        Ok(ChangeSet { revision: data.revision, entries, unreadable })
+    }
+
+    // Envoie des modifications locales ; le serveur refuse celles dont l'entrée a changé depuis
+    pub async fn push_changes(&self, changes: &[LocalChange]) -> Result<PushOutcome, ApiError> {
+        let mut bodies = Vec::with_capacity(changes.len());
+        for change in changes {
+            let mut body = match &change.entry {
+                Some(entry) => self.entry_body(entry)?,
+                None => json!({ "deleted": true }),
+            };
+            body["service"] = json!(change.id);
+            body["base_revision"] = json!(change.base_revision);
+            bodies.push(body);
+        }
+        if let Some(store) = &self.local {
+            let (applied, conflicts) = store.push(bodies)?;
+            let (conflicts, unreadable) = self.remote_entries(conflicts);
+            return Ok(PushOutcome { applied, conflicts, unreadable });
+        }
+
+        let response = self.send(|client| client
+            .post(format!("{}/sync/push", self.base_url))
+            .json(&json!({ "changes": bodies })))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(ApiError::http(response.status()));
+        }
+        let data = response
+            .json::<PushResponse>()
+            .await
+            .map_err(|e| ApiError::Decode(e.to_string()))?;
+        let (conflicts, unreadable) = self.remote_entries(data.conflicts);
+        Ok(PushOutcome { applied: data.applied, conflicts, unreadable })
+    }
+
+    // Paramètres de la clé du coffre ; None si aucun appareil ne les a encore créés
+    pub async fn get_vault_params(&self) -> Result<Option<VaultParams>, ApiError> {
+        if let Some(store) = &self.local {
+            return store.vault_params().map_err(ApiError::from);
+        }
+        let response = self.send(|client| client
+            .get(format!("{}/vault/params", self.base_url)))
+            .await?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => response
+                .json::<VaultParams>()
+                .await
+                .map(Some)
+                .map_err(|e| ApiError::Decode(e.to_string())),
+            status => Err(ApiError::http(status)),
+        }
+    }
+
+    // Le serveur garde les premiers paramètres enregistrés et renvoie ceux qui font foi
+    pub async fn create_vault_params(&self, params: &VaultParams) -> Result<VaultParams, ApiError> {
+        if let Some(store) = &self.local {
+            return store.create_vault_params(params).map_err(ApiError::from);
+        }
+        let response = self.send(|client| client
+            .post(format!("{}/vault/params", self.base_url))
+            .json(params))
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() && status != StatusCode::CONFLICT {
+            return Err(ApiError::http(status));
+        }
+        response
+            .json::<VaultParams>()
+            .await
+            .map_err(|e| ApiError::Decode(e.to_string()))
+    }
+
+    // Remplace les facteurs de déverrouillage de l'en-tête `previous` ; le sel et Argon2id ne changent pas.
+    // Refusé si l'en-tête a changé depuis sa lecture
+    pub async fn update_vault_factors(&self, previous: &VaultParams, factors: &UnlockFactors) -> Result<VaultParams, ApiError> {
+        if let Some(store) = &self.local {
+            let params = store.update_vault_factors(previous.revision, factors)?;
+            // Un nouveau kit doit aussi rouvrir le fichier du coffre
+            if let Some(recovery_key) = self.recovery_key(factors)? {
+                store.seal_recovery(&recovery_key)?;
             }
-            Err(e) => Err(format!("Erreur réseau: {}", e)),
+            return Ok(params);
+        }
+        let response = self.send(|client| client
+            .put(format!("{}/vault/params", self.base_url))
+            .json(&serde_json::json!({ "factors": factors, "revision": previous.revision })))
+            .await?;
+
+        match response.status() {
+            StatusCode::CONFLICT => return Err(ApiError::HeaderConflict),
+            status if !status.is_success() => return Err(ApiError::http(status)),
+            _ => {}
         }
+        response
+            .json::<VaultParams>()
+            .await
+            .map_err(|e| ApiError::Decode(e.to_string()))
     }
-}
\ No newline at end of file
+
+    // Clé du kit de récupération en cours, pour sceller le fichier du coffre
+    fn recovery_key(&self, factors: &UnlockFactors) -> Result<Option<SecretKey<KEY_LENGTH>>, ApiError> {
+        match (&self.cipher, &factors.recovery) {
+            (Some(cipher), Some(_)) => Ok(cipher.recovery_key(factors)?),
+            _ => Ok(None),
+        }
+    }
+
+    // Enregistre l'en-tête du nouveau mot de passe maître. Le serveur remplace d'un bloc
+    // l'en-tête et le vérificateur SRP, si l'en-tête `previous` est toujours le sien,
+    // et renvoie l'en-tête enregistré avec sa nouvelle révision. Rouvert par un kit,
+    // le client présente le jeton du kit à la place d'une session
+    pub async fn rekey(&self, previous: &VaultParams, params: &VaultParams, password: &str) -> Result<VaultParams, ApiError> {
+        let (params, password) = (params.clone(), password.to_string());
+        if let Some(store) = &self.local {
+            let (store, recovery_key) = (store.clone(), self.recovery_key(&params.factors)?);
+            return Ok(tokio::task::spawn_blocking(move || store.rekey(&params, &password, recovery_key.as_ref()))
+                .await
+                .map_err(|e| e.to_string())??);
+        }
+        let route = if self.recovery.is_some() { "recovery/rekey" } else { "vault/rekey" };
+        let kdf = params.kdf;
+        let auth = tokio::task::spawn_blocking(move || session::verifier_record(&password, kdf))
+            .await
+            .map_err(|e| e.to_string())??;
+        let body = serde_json::json!({
+            "previous_salt": BASE64.encode(&previous.salt),
+            "vault": params,
+            "auth": auth,
+        });
+        let response = self.send(|client| client
+            .post(format!("{}/{}", self.base_url, route))
+            .json(&body))
+            .await?;
+
+        match response.status() {
+            StatusCode::CONFLICT => Err(ApiError::RekeyConflict),
+            status if status.is_success() => response
+                .json::<VaultParams>()
+                .await
+                .map_err(|e| ApiError::Decode(e.to_string())),
+            status => Err(ApiError::http(status)),
+        }
+    }
+
+    // Nouveau jeton d'appairage de l'extension Chrome, à saisir dans ses paramètres ;
+    // il n'ouvre que les routes de l'extension
+    pub async fn pair_extension(&self) -> Result<String, ApiError> {
+        if self.local.is_some() {
+            return Err(ApiError::NoServer);
+        }
+        let response = self.send(|client| client
+            .post(format!("{}/extension/pair", self.base_url)))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(ApiError::http(response.status()));
+        }
+        response
+            .json::<PairingResponse>()
+            .await
+            .map(|pairing| pairing.token)
+            .map_err(|e| ApiError::Decode(e.to_string()))
+    }
+
+    // Révoque tous les jetons d'appairage de l'extension
+    pub async fn unpair_extension(&self) -> Result<(), ApiError> {
+        if self.local.is_some() {
+            return Err(ApiError::NoServer);
+        }
+        let response = self.send(|client| client
+            .delete(format!("{}/extension/pair", self.base_url)))
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(ApiError::http(response.status()))
+        }
+    }
+
+    // Fonction pour mettre à jour une entrée existante (même clé côté serveur)
+    pub async fn update_password(&self, entry: &Entry) -> Result<(), ApiError> {
+        self.post_entry(entry).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FAST;
+
+    fn client() -> ApiClient {
+        let mut client = ApiClient::new();
+        let params = e2e::initial_params(FAST, "motdepasse", None).unwrap();
+        client.set_cipher(Arc::new(EntryCipher::derive("motdepasse", &params, None).unwrap()));
+        client
+    }
+
+    #[test]
+    fn skips_unreadable_remote_entries() {
+        let client = client();
+        let mut entry = Entry::new("a.example.com", "A");
+        entry.service_password = "secret".into();
+        let mut good = client.entry_body(&entry).unwrap();
+        good["revision"] = json!(3);
+        let mut corrupted = good.clone();
+        corrupted["password"] = json!("e2e1:AAAA");
+        let legacy = json!({ "service_URL": "c.example.com", "password": "clair", "revision": 5 });
+
+        let values = HashMap::from([
+            (String::from("a.example.com"), good),
+            (String::from("b.example.com"), corrupted),
+            (String::from("c.example.com"), legacy),
+            (String::from("d.example.com"), json!({ "deleted": true, "revision": 6 })),
+        ]);
+        let (mut entries, unreadable) = client.remote_entries(values);
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+
+        assert_eq!(unreadable.len(), 1);
+        assert_eq!(unreadable[0].0, "b.example.com");
+        assert_eq!(entries.len(), 3);
+        assert_eq!(&*entries[0].entry.as_ref().unwrap().service_password, "secret");
+        assert_eq!(entries[0].revision, 3);
+        assert!(!entries[0].legacy);
+        assert!(entries[1].legacy);
+        assert!(entries[2].entry.is_none());
+        assert!(!entries[2].legacy);
+    }
+
+    #[test]
+    fn entry_body_requires_the_vault_key() {
+        let entry = Entry::new("a.example.com", "A");
+        assert!(matches!(ApiClient::new().entry_body(&entry), Err(ApiError::Locked)));
+
+        let body = client().entry_body(&entry).unwrap();
+        assert_eq!(body["service"], json!("a.example.com"));
+        assert_eq!(body["encryption"], json!(crate::vault::e2e::E2E_FORMAT));
+    }
+}
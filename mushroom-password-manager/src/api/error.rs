@@ -0,0 +1,98 @@
+use fluent_bundle::FluentValue;
+use reqwest::StatusCode;
+use std::fmt;
+
+use crate::i18n;
+use crate::vault::crypto::DecryptError;
+
+// Erreurs du client : leur texte est traduit au moment de l'affichage, dans la langue
+// choisie à cet instant
+#[derive(Debug, Clone)]
+pub enum ApiError {
+    Network(String),
+    Http { status: StatusCode, detail: Option<String> },
+    Decode(String),
+    NoSession,
+    // Profil sans serveur : fonction propre au serveur (extension Chrome)
+    NoServer,
+    // Échange SRP : mot de passe refusé, vérificateur non enregistré, serveur sans preuve
+    AuthenticationRefused,
+    VerifierRejected,
+    MissingServerProof,
+    // Déchiffrement du coffre, du cache ou de l'en-tête refusé
+    Decrypt(DecryptError),
+    // Clé du coffre absente : rien ne part en clair
+    Locked,
+    ServiceNotFound(String),
+    MissingPassword,
+    RekeyConflict,
+    // En-tête du coffre modifié depuis un autre appareil depuis sa lecture
+    HeaderConflict,
+    // Import interrompu après `imported` entrées
+    Import { imported: usize, source: Box<ApiError> },
+    // Erreurs des couches sous-jacentes (coffre local, session), déjà rédigées
+    Other(String),
+}
+
+impl ApiError {
+    pub fn http(status: StatusCode) -> Self {
+        ApiError::Http { status, detail: None }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            ApiError::Network(detail) => i18n::tr_args("api-error-network", &[("detail", detail.as_str().into())]),
+            ApiError::Http { status, detail: None } => {
+                i18n::tr_args("api-error-http", &[("status", status.to_string().into())])
+            }
+            ApiError::Http { status, detail: Some(detail) } => i18n::tr_args(
+                "api-error-http-detail",
+                &[("status", status.to_string().into()), ("detail", detail.as_str().into())],
+            ),
+            ApiError::Decode(detail) => i18n::tr_args("api-error-decode", &[("detail", detail.as_str().into())]),
+            ApiError::NoSession => i18n::tr("api-error-no-session"),
+            ApiError::NoServer => i18n::tr("api-error-no-server"),
+            ApiError::AuthenticationRefused => i18n::tr("api-error-authentication-refused"),
+            ApiError::VerifierRejected => i18n::tr("api-error-verifier-rejected"),
+            ApiError::MissingServerProof => i18n::tr("api-error-missing-server-proof"),
+            ApiError::Locked => i18n::tr("api-error-locked"),
+            ApiError::ServiceNotFound(service) => {
+                i18n::tr_args("api-error-service-not-found", &[("service", service.as_str().into())])
+            }
+            ApiError::MissingPassword => i18n::tr("api-error-missing-password"),
+            ApiError::RekeyConflict => i18n::tr("api-error-rekey-conflict"),
+            ApiError::HeaderConflict => i18n::tr("api-error-header-conflict"),
+            ApiError::Import { imported, source } => i18n::tr_args(
+                "api-error-import",
+                &[("count", FluentValue::from(*imported)), ("detail", source.to_string().into())],
+            ),
+            ApiError::Decrypt(error) => error.to_string(),
+            ApiError::Other(message) => message.clone(),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        ApiError::Other(message)
+    }
+}
+
+impl From<DecryptError> for ApiError {
+    fn from(error: DecryptError) -> Self {
+        match error {
+            DecryptError::Other(message) => ApiError::Other(message),
+            error => ApiError::Decrypt(error),
+        }
+    }
+}
+
+// Les appelants qui remontent des `String` traduisent l'erreur à ce moment-là
+impl From<ApiError> for String {
+    fn from(error: ApiError) -> Self {
+        error.to_string()
+    }
+}
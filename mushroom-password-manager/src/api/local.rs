@@ -0,0 +1,397 @@
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::api::error::ApiError;
+use crate::config::GeneratorSettings;
+use crate::vault::cache::{self, CacheKey};
+use crate::vault::crypto::{DecryptError, KEY_LENGTH};
+use crate::vault::e2e::VaultParams;
+use crate::vault::factors::UnlockFactors;
+use crate::vault::secure::SecretKey;
+
+// Révisions des modifications appliquées, version enregistrée des modifications refusées
+type PushOutcome = (HashMap<String, u64>, HashMap<String, Value>);
+
+// Champs propres à la requête, retirés de l'entrée enregistrée
+const REQUEST_FIELDS: [&str; 3] = ["service", "password", "base_revision"];
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct LocalState {
+    entries: BTreeMap<String, Value>,
+    #[serde(default)]
+    email: String,
+    #[serde(default)]
+    vault: Option<VaultParams>,
+}
+
+impl LocalState {
+    fn revision(&self) -> u64 {
+        self.entries.values().map(revision_of).max().unwrap_or(0)
+    }
+}
+
+// Coffre d'un profil sans serveur. Les entrées, déjà chiffrées de bout en bout,
+// sont gardées comme le serveur les garde (révisions, marqueurs de suppression)
+// dans un fichier lui-même chiffré avec le mot de passe maître
+pub struct LocalStore {
+    path: PathBuf,
+    key: Mutex<CacheKey>,
+    state: Mutex<LocalState>,
+}
+
+impl std::fmt::Debug for LocalStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalStore").field("path", &self.path).finish()
+    }
+}
+
+fn revision_of(entry: &Value) -> u64 {
+    entry.get("revision").and_then(Value::as_u64).unwrap_or(0)
+}
+
+fn is_deleted(entry: &Value) -> bool {
+    entry.get("deleted").and_then(Value::as_bool).unwrap_or(false)
+}
+
+impl LocalStore {
+    // Argon2id : à appeler hors de la boucle de l'interface. Un mot de passe
+    // incorrect est détecté au déchiffrement ; le fichier est créé au premier enregistrement
+    pub fn open(path: PathBuf, password: &str) -> Result<Self, DecryptError> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Création du dossier '{}' impossible: {}", dir.display(), e))?;
+        }
+        let key = CacheKey::derive(&path, password)?;
+        let state = cache::load(&path, &key)
+            .map_err(|e| e.context(format!("Ouverture du coffre '{}' impossible", path.display())))?
+            .unwrap_or_default();
+        Ok(LocalStore { path, key: Mutex::new(key), state: Mutex::new(state) })
+    }
+
+    // Fichier rouvert par la clé d'un kit de récupération, sans mot de passe maître
+    pub fn recover(path: PathBuf, recovery_key: &SecretKey<KEY_LENGTH>) -> Result<Self, DecryptError> {
+        let key = CacheKey::recover(&path, recovery_key)?;
+        let state = cache::load(&path, &key)
+            .map_err(|e| e.context(format!("Ouverture du coffre '{}' impossible", path.display())))?
+            .unwrap_or_default();
+        Ok(LocalStore { path, key: Mutex::new(key), state: Mutex::new(state) })
+    }
+
+    // Scelle la clé du fichier pour la clé de récupération du kit en cours
+    pub fn seal_recovery(&self, recovery_key: &SecretKey<KEY_LENGTH>) -> Result<(), String> {
+        let changed = self
+            .key
+            .lock()
+            .map_err(|_| String::from("Coffre local indisponible"))?
+            .seal_recovery(recovery_key)?;
+        if changed {
+            self.update(|_| ())?;
+        }
+        Ok(())
+    }
+
+    fn update<T>(&self, change: impl FnOnce(&mut LocalState) -> T) -> Result<T, String> {
+        let mut state = self.state.lock().map_err(|_| String::from("Coffre local indisponible"))?;
+        let result = change(&mut state);
+        let key = self.key.lock().map_err(|_| String::from("Coffre local indisponible"))?;
+        cache::save(&self.path, &key, &*state)?;
+        Ok(result)
+    }
+
+    fn read<T>(&self, read: impl FnOnce(&LocalState) -> T) -> Result<T, String> {
+        let state = self.state.lock().map_err(|_| String::from("Coffre local indisponible"))?;
+        Ok(read(&state))
+    }
+
+    // Entrées visibles, sans les marqueurs de suppression
+    pub fn entries(&self) -> Result<HashMap<String, Value>, String> {
+        self.read(|state| {
+            state
+                .entries
+                .iter()
+                .filter(|(_, entry)| !is_deleted(entry))
+                .map(|(id, entry)| (id.clone(), entry.clone()))
+                .collect()
+        })
+    }
+
+    // Enregistre une entrée ; le dernier écrivain l'emporte, comme sur le serveur sans révision de base
+    pub fn save_entry(&self, id: &str, body: Value) -> Result<u64, String> {
+        self.update(|state| {
+            let revision = state.revision() + 1;
+            state.entries.insert(id.to_string(), stored_entry(body, revision));
+            revision
+        })
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), String> {
+        self.update(|state| {
+            if state.entries.get(id).is_some_and(|entry| !is_deleted(entry)) {
+                let revision = state.revision() + 1;
+                state.entries.insert(id.to_string(), tombstone(revision));
+            }
+        })
+    }
+
+    // Entrées modifiées après `since`, marqueurs de suppression compris
+    pub fn changes(&self, since: u64) -> Result<(u64, HashMap<String, Value>), String> {
+        self.read(|state| {
+            let entries = state
+                .entries
+                .iter()
+                .filter(|(_, entry)| since == 0 || revision_of(entry) > since)
+                .map(|(id, entry)| (id.clone(), entry.clone()))
+                .collect();
+            (state.revision(), entries)
+        })
+    }
+
+    // Applique des modifications avec les règles de /sync/push ; un conflit sur
+    // une entrée absente est renvoyé avec Null
+    pub fn push(&self, changes: Vec<Value>) -> Result<PushOutcome, String> {
+        self.update(|state| {
+            let mut applied = HashMap::new();
+            let mut conflicts = HashMap::new();
+            for change in changes {
+                let Some(id) = change.get("service").and_then(Value::as_str).map(str::to_string) else {
+                    continue;
+                };
+                let deleted = is_deleted(&change);
+                if !deleted && change.get("password").is_none() {
+                    continue;
+                }
+                let existing = state.entries.get(&id);
+                let base_revision = change.get("base_revision").and_then(Value::as_u64).unwrap_or(0);
+                if existing.map(revision_of).unwrap_or(0) != base_revision {
+                    conflicts.insert(id, existing.cloned().unwrap_or(Value::Null));
+                    continue;
+                }
+                let revision = state.revision() + 1;
+                let entry = if deleted { tombstone(revision) } else { stored_entry(change, revision) };
+                state.entries.insert(id.clone(), entry);
+                applied.insert(id, revision);
+            }
+            (applied, conflicts)
+        })
+    }
+
+    pub fn email(&self) -> Result<String, String> {
+        self.read(|state| state.email.clone())
+    }
+
+    pub fn set_email(&self, email: &str) -> Result<(), String> {
+        self.update(|state| state.email = email.to_string())
+    }
+
+    pub fn vault_params(&self) -> Result<Option<VaultParams>, String> {
+        self.read(|state| state.vault.clone())
+    }
+
+    pub fn update_vault_factors(&self, revision: u64, factors: &UnlockFactors) -> Result<VaultParams, ApiError> {
+        self.update(|state| {
+            let params = state.vault.as_mut().ok_or_else(|| ApiError::from(String::from("Coffre sans en-tête")))?;
+            if params.revision != revision {
+                return Err(ApiError::HeaderConflict);
+            }
+            params.factors = factors.clone();
+            params.revision += 1;
+            Ok(params.clone())
+        })?
+    }
+
+    // Nouveau mot de passe maître : l'en-tête et la clé du fichier changent dans la même
+    // écriture (fichier temporaire renommé), sans état intermédiaire sur le disque. La
+    // nouvelle clé du fichier est scellée pour le kit de récupération en cours
+    pub fn rekey(
+        &self,
+        params: &VaultParams,
+        password: &str,
+        recovery_key: Option<&SecretKey<KEY_LENGTH>>,
+    ) -> Result<VaultParams, String> {
+        let mut key = CacheKey::generate(password, params.kdf)?;
+        if let Some(recovery_key) = recovery_key {
+            key.seal_recovery(recovery_key)?;
+        }
+        let mut state = self.state.lock().map_err(|_| String::from("Coffre local indisponible"))?;
+        let revision = state.vault.as_ref().map_or(0, |vault| vault.revision + 1);
+        let stored = VaultParams { revision, ..params.clone() };
+        let previous = state.vault.replace(stored.clone());
+        if let Err(e) = cache::save(&self.path, &key, &*state) {
+            state.vault = previous;
+            return Err(e);
+        }
+        *self.key.lock().map_err(|_| String::from("Coffre local indisponible"))? = key;
+        Ok(stored)
+    }
+
+    // Les premiers paramètres enregistrés font foi
+    pub fn create_vault_params(&self, params: &VaultParams) -> Result<VaultParams, String> {
+        self.update(|state| state.vault.get_or_insert_with(|| params.clone()).clone())
+    }
+}
+
+fn stored_entry(mut body: Value, revision: u64) -> Value {
+    if let Some(fields) = body.as_object_mut() {
+        for field in REQUEST_FIELDS {
+            fields.remove(field);
+        }
+        fields.insert(String::from("revision"), json!(revision));
+    }
+    body
+}
+
+fn tombstone(revision: u64) -> Value {
+    json!({ "deleted": true, "revision": revision, "modified_at": chrono::Utc::now().to_rfc3339() })
+}
+
+// Générés sur cette machine selon les préférences ; les symboles sont ceux de l'ancien
+// générateur du serveur
+pub fn generate_password(settings: &GeneratorSettings) -> String {
+    let symbols = if settings.symbols { "/*-+=:;.,?!'()[]{}|&%$#@^~_" } else { "" };
+    let digits = if settings.digits { "0123456789" } else { "" };
+    let alphabet: Vec<char> = symbols
+        .chars()
+        .chain('a'..='z')
+        .chain('A'..='Z')
+        .chain(digits.chars())
+        .collect();
+    let mut rng = rand::thread_rng();
+    (0..settings.length).filter_map(|_| alphabet.choose(&mut rng)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{FAST, TestDir};
+
+    // Coffre vide aux paramètres Argon2 réduits ; `LocalStore::open` reprend ensuite ceux du fichier
+    fn store(dir: &TestDir) -> LocalStore {
+        let path = dir.0.join("vault.mushroom");
+        let key = CacheKey::generate("motdepasse", FAST).unwrap();
+        LocalStore { path, key: Mutex::new(key), state: Mutex::new(LocalState::default()) }
+    }
+
+    fn change(id: &str, base_revision: u64) -> Value {
+        json!({ "service": id, "password": "chiffré", "base_revision": base_revision })
+    }
+
+    #[test]
+    fn entries_are_revisioned_and_persisted() {
+        let dir = TestDir::new("persist");
+        let store = store(&dir);
+        assert_eq!(store.save_entry("a.example", json!({ "service": "a.example", "password": "x" })).unwrap(), 1);
+        assert_eq!(store.save_entry("b.example", json!({ "password": "y" })).unwrap(), 2);
+        store.delete("a.example").unwrap();
+        // Supprimer une entrée absente ou déjà supprimée ne crée pas de révision
+        store.delete("a.example").unwrap();
+        store.delete("absent.example").unwrap();
+
+        let reopened = LocalStore::open(store.path.clone(), "motdepasse").unwrap();
+        let entries = reopened.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        // Les champs propres à la requête ne sont pas enregistrés
+        assert_eq!(entries["b.example"], json!({ "revision": 2 }));
+
+        let (revision, changes) = reopened.changes(2).unwrap();
+        assert_eq!(revision, 3);
+        assert_eq!(changes.len(), 1);
+        assert!(is_deleted(&changes["a.example"]));
+        assert_eq!(reopened.changes(0).unwrap().1.len(), 2);
+
+        assert!(LocalStore::open(store.path.clone(), "autre").is_err());
+    }
+
+    #[test]
+    fn email_is_persisted() {
+        let dir = TestDir::new("email");
+        let store = store(&dir);
+        assert_eq!(store.email().unwrap(), "");
+        store.set_email("moi@example.com").unwrap();
+        let reopened = LocalStore::open(store.path.clone(), "motdepasse").unwrap();
+        assert_eq!(reopened.email().unwrap(), "moi@example.com");
+    }
+
+    #[test]
+    fn push_applies_matching_revisions_and_reports_conflicts() {
+        let dir = TestDir::new("push");
+        let store = store(&dir);
+        let (applied, conflicts) = store.push(vec![change("a.example", 0), change("b.example", 0)]).unwrap();
+        assert_eq!(applied.len(), 2);
+        assert!(conflicts.is_empty());
+        let revision_a = applied["a.example"];
+
+        let (applied, conflicts) = store
+            .push(vec![
+                change("a.example", revision_a),
+                change("b.example", 0),
+                change("c.example", 7),
+                json!({ "service": "d.example" }),
+                json!({ "service": "a.example", "deleted": true, "base_revision": 99 }),
+            ])
+            .unwrap();
+        assert_eq!(applied.keys().collect::<Vec<_>>(), vec!["a.example"]);
+        assert_eq!(revision_of(&conflicts["b.example"]), 2);
+        assert_eq!(conflicts["c.example"], Value::Null);
+        // Une modification sans mot de passe est ignorée
+        assert!(!conflicts.contains_key("d.example"));
+        assert_eq!(revision_of(&conflicts["a.example"]), applied["a.example"]);
+    }
+
+    #[test]
+    fn vault_header_updates_check_the_revision() {
+        let dir = TestDir::new("header");
+        let store = store(&dir);
+        assert!(matches!(store.update_vault_factors(0, &UnlockFactors::default()), Err(ApiError::Other(_))));
+
+        let params = VaultParams::generate(FAST);
+        let created = store.create_vault_params(&params).unwrap();
+        assert_eq!(created.revision, 0);
+        // Les premiers paramètres enregistrés font foi
+        let other = VaultParams::generate(FAST);
+        assert_eq!(store.create_vault_params(&other).unwrap().salt, params.salt);
+
+        let updated = store.update_vault_factors(0, &UnlockFactors::default()).unwrap();
+        assert_eq!(updated.revision, 1);
+        assert!(matches!(store.update_vault_factors(0, &UnlockFactors::default()), Err(ApiError::HeaderConflict)));
+
+        let rekeyed = store.rekey(&other, "nouveau", None).unwrap();
+        assert_eq!(rekeyed.revision, 2);
+        assert_eq!(store.vault_params().unwrap().unwrap().salt, other.salt);
+        let reopened = LocalStore::open(store.path.clone(), "nouveau").unwrap();
+        assert_eq!(reopened.vault_params().unwrap().unwrap().revision, 2);
+    }
+
+    #[test]
+    fn recovery_key_reopens_the_store_after_a_password_change() {
+        let dir = TestDir::new("local-recovery");
+        let store = store(&dir);
+        store.set_email("a@example.com").unwrap();
+        let recovery_key = SecretKey::new(crate::vault::crypto::random_bytes::<KEY_LENGTH>());
+        assert!(matches!(LocalStore::recover(store.path.clone(), &recovery_key), Err(DecryptError::NoRecoveryKit)));
+
+        store.seal_recovery(&recovery_key).unwrap();
+        let recovered = LocalStore::recover(store.path.clone(), &recovery_key).unwrap();
+        assert_eq!(recovered.email().unwrap(), "a@example.com");
+
+        store.create_vault_params(&VaultParams::generate(FAST)).unwrap();
+        store.rekey(&VaultParams::generate(FAST), "nouveau", Some(&recovery_key)).unwrap();
+        let recovered = LocalStore::recover(store.path.clone(), &recovery_key).unwrap();
+        assert_eq!(recovered.email().unwrap(), "a@example.com");
+        assert!(LocalStore::open(store.path.clone(), "nouveau").is_ok());
+    }
+
+    #[test]
+    fn generated_passwords_follow_the_settings() {
+        let settings = GeneratorSettings { length: 40, digits: true, symbols: false };
+        let password = generate_password(&settings);
+        assert_eq!(password.chars().count(), 40);
+        assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+
+        let settings = GeneratorSettings { length: 12, digits: false, symbols: true };
+        assert!(!generate_password(&settings).chars().any(|c| c.is_ascii_digit()));
+    }
+}
@@ -1 +1,6 @@
-pub mod client;
\ No newline at end of file
+pub mod client;
+pub mod error;
+pub mod local;
+pub mod session;
+pub mod srp;
+pub mod sync;
\ No newline at end of file
@@ -0,0 +1,298 @@
+use num_bigint::BigUint;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use serde_json::json;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::api::error::ApiError;
+use crate::api::srp::{self, ClientHandshake};
+use crate::vault::crypto::{self, KdfParams};
+use crate::vault::secure::SecretString;
+
+// Marge avant expiration à partir de laquelle le jeton est renouvelé
+const REFRESH_MARGIN: Duration = Duration::from_secs(30);
+
+#[derive(Deserialize)]
+struct StartResponse {
+    session: String,
+    salt: String,
+    kdf: KdfParams,
+    #[serde(rename = "B")]
+    server_public: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    #[serde(rename = "M2", default)]
+    server_proof: Option<String>,
+    access_token: String,
+    refresh_token: String,
+    expires_in: u64,
+}
+
+struct Tokens {
+    access: String,
+    refresh: String,
+    expires_at: Instant,
+}
+
+// Secret SRP dérivé du mot de passe, gardé pour le sel qui l'a produit
+struct PrivateKey {
+    salt: Vec<u8>,
+    params: KdfParams,
+    x: BigUint,
+}
+
+// Session authentifiée auprès du serveur : échange SRP-6a (le mot de passe maître
+// ne circule jamais), jeton d'accès de courte durée renouvelé avant expiration.
+// Le mot de passe n'est gardé que jusqu'au premier échange réussi : les suivants
+// réutilisent x, dérivé pour le sel du serveur
+pub struct Session {
+    base_url: String,
+    password: Mutex<Option<SecretString>>,
+    private_key: Mutex<Option<PrivateKey>>,
+    tokens: Mutex<Option<Tokens>>,
+}
+
+impl std::fmt::Debug for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Session")
+    }
+}
+
+impl Session {
+    pub fn new(base_url: &str, password: &str) -> Self {
+        Session {
+            base_url: base_url.to_string(),
+            password: Mutex::new(Some(password.into())),
+            private_key: Mutex::new(None),
+            tokens: Mutex::new(None),
+        }
+    }
+
+    // Jeton d'accès valide : celui en cours, renouvelé, ou issu d'un nouvel échange
+    pub async fn bearer(&self, http: &Client) -> Result<String, ApiError> {
+        let mut tokens = self.tokens.lock().await;
+        if let Some(current) = tokens.as_ref() {
+            if current.expires_at > Instant::now() + REFRESH_MARGIN {
+                return Ok(current.access.clone());
+            }
+            let refresh_token = current.refresh.clone();
+            // Un renouvellement refusé (serveur redémarré, jeton révoqué) mène à un nouvel échange
+            if let Ok(renewed) = self.refresh(http, &refresh_token).await {
+                let access = renewed.access.clone();
+                *tokens = Some(renewed);
+                return Ok(access);
+            }
+        }
+        let fresh = self.login(http).await?;
+        let access = fresh.access.clone();
+        *tokens = Some(fresh);
+        Ok(access)
+    }
+
+    // Jeton refusé par le serveur : le prochain appel refait l'échange
+    pub async fn invalidate(&self) {
+        *self.tokens.lock().await = None;
+    }
+
+    // Révoque les jetons côté serveur, au verrouillage de l'interface
+    pub async fn revoke(&self, http: &Client) -> Result<(), ApiError> {
+        let Some(tokens) = self.tokens.lock().await.take() else {
+            return Ok(());
+        };
+        let response = http
+            .post(format!("{}/auth/logout", self.base_url))
+            .bearer_auth(&tokens.access)
+            .json(&json!({ "refresh_token": tokens.refresh }))
+            .send()
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+        if response.status().is_success() || response.status() == StatusCode::UNAUTHORIZED {
+            Ok(())
+        } else {
+            Err(ApiError::http(response.status()))
+        }
+    }
+
+    async fn login(&self, http: &Client) -> Result<Tokens, ApiError> {
+        let handshake = ClientHandshake::start();
+        let start = match self.start(http, &handshake).await? {
+            Some(start) => start,
+            // Premier appareil : le vérificateur est créé à partir du mot de passe maître
+            None => {
+                self.register(http).await?;
+                self.start(http, &handshake)
+                    .await?
+                    .ok_or(ApiError::VerifierRejected)?
+            }
+        };
+
+        let salt = hex_decode(&start.salt)?;
+        let x = self.private_key(&salt, &start.kdf).await?;
+        let proof = handshake.finish(&x, &srp::from_hex(&start.server_public)?)?;
+
+        let response = http
+            .post(format!("{}/auth/verify", self.base_url))
+            .json(&json!({ "session": start.session, "M1": hex_encode(&proof.proof) }))
+            .send()
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            return Err(ApiError::AuthenticationRefused);
+        }
+        let tokens = token_response(response).await?;
+        let server_proof = tokens.server_proof.as_deref().ok_or(ApiError::MissingServerProof)?;
+        proof.verify_server(&hex_decode(server_proof)?)?;
+        *self.password.lock().await = None;
+        Ok(tokens_from(tokens))
+    }
+
+    // None si aucun vérificateur n'est encore enregistré
+    async fn start(&self, http: &Client, handshake: &ClientHandshake) -> Result<Option<StartResponse>, ApiError> {
+        let response = http
+            .post(format!("{}/auth/start", self.base_url))
+            .json(&json!({ "A": srp::to_hex(handshake.public()) }))
+            .send()
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => response
+                .json::<StartResponse>()
+                .await
+                .map(Some)
+                .map_err(|e| ApiError::Decode(e.to_string())),
+            status => Err(ApiError::http(status)),
+        }
+    }
+
+    async fn register(&self, http: &Client) -> Result<(), ApiError> {
+        let salt = crypto::random_bytes::<{ crypto::SALT_LENGTH }>().to_vec();
+        let params = KdfParams::default();
+        let x = self.private_key(&salt, &params).await?;
+
+        let response = http
+            .post(format!("{}/auth/register", self.base_url))
+            .json(&json!({
+                "salt": hex_encode(&salt),
+                "kdf": params,
+                "verifier": srp::to_hex(&srp::verifier(&x)),
+            }))
+            .send()
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+        // Conflit : un autre appareil a enregistré son vérificateur entre-temps
+        if response.status().is_success() || response.status() == StatusCode::CONFLICT {
+            Ok(())
+        } else {
+            Err(ApiError::http(response.status()))
+        }
+    }
+
+    async fn refresh(&self, http: &Client, refresh_token: &str) -> Result<Tokens, ApiError> {
+        let response = http
+            .post(format!("{}/auth/refresh", self.base_url))
+            .json(&json!({ "refresh_token": refresh_token }))
+            .send()
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+        Ok(tokens_from(token_response(response).await?))
+    }
+
+    // Argon2id une seule fois par sel, hors de la boucle de l'interface. Sel changé après
+    // l'oubli du mot de passe (changement depuis un autre appareil) : il faut le ressaisir
+    async fn private_key(&self, salt: &[u8], params: &KdfParams) -> Result<BigUint, ApiError> {
+        let mut cached = self.private_key.lock().await;
+        if let Some(key) = cached.as_ref().filter(|key| key.salt == salt && key.params == *params) {
+            return Ok(key.x.clone());
+        }
+        let password = self.password.lock().await.clone().ok_or(ApiError::AuthenticationRefused)?;
+        let (salt, params) = (salt.to_vec(), *params);
+        let key = tokio::task::spawn_blocking(move || {
+            srp::private_key(&password, &salt, &params).map(|x| PrivateKey { salt, params, x })
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        let x = key.x.clone();
+        *cached = Some(key);
+        Ok(x)
+    }
+}
+
+// Sel, paramètres et vérificateur SRP d'un nouveau mot de passe, tels que le serveur les garde.
+// Argon2id : à appeler hors de la boucle de l'interface
+pub fn verifier_record(password: &str, params: KdfParams) -> Result<serde_json::Value, String> {
+    let salt = crypto::random_bytes::<{ crypto::SALT_LENGTH }>().to_vec();
+    let x = srp::private_key(password, &salt, &params)?;
+    Ok(json!({
+        "salt": hex_encode(&salt),
+        "kdf": params,
+        "verifier": srp::to_hex(&srp::verifier(&x)),
+    }))
+}
+
+async fn token_response(response: reqwest::Response) -> Result<TokenResponse, ApiError> {
+    if !response.status().is_success() {
+        return Err(ApiError::http(response.status()));
+    }
+    response
+        .json::<TokenResponse>()
+        .await
+        .map_err(|e| ApiError::Decode(e.to_string()))
+}
+
+fn tokens_from(response: TokenResponse) -> Tokens {
+    Tokens {
+        access: response.access_token,
+        refresh: response.refresh_token,
+        expires_at: Instant::now() + Duration::from_secs(response.expires_in),
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn hex_decode(text: &str) -> Result<Vec<u8>, String> {
+    // Hors ASCII, un découpage par paires d'octets tomberait au milieu d'un caractère
+    if !text.is_ascii() || !text.len().is_multiple_of(2) {
+        return Err(String::from("Valeur hexadécimale invalide"));
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&text[index..index + 2], 16).map_err(|_| String::from("Valeur hexadécimale invalide")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FAST;
+
+    #[test]
+    fn hex_decode_rejects_invalid_text() {
+        assert_eq!(hex_decode("00ff10").unwrap(), vec![0x00, 0xff, 0x10]);
+        assert_eq!(hex_encode(&hex_decode("0aB1").unwrap()), "0ab1");
+        assert!(hex_decode("abc").is_err());
+        assert!(hex_decode("zz").is_err());
+        // 'é' occupe deux octets : sans le contrôle, la découpe paniquerait
+        assert!(hex_decode("aé").is_err());
+        assert!(hex_decode("éa0").is_err());
+    }
+
+    #[tokio::test]
+    async fn private_key_is_cached_once_the_password_is_dropped() {
+        let session = Session::new("http://localhost:5000", "motdepasse");
+        let x = session.private_key(b"sel-de-test-a", &FAST).await.unwrap();
+
+        // Après le premier échange réussi, seul x reste en mémoire
+        *session.password.lock().await = None;
+        assert_eq!(session.private_key(b"sel-de-test-a", &FAST).await.unwrap(), x);
+        assert!(matches!(
+            session.private_key(b"sel-de-test-b", &FAST).await,
+            Err(ApiError::AuthenticationRefused)
+        ));
+    }
+}
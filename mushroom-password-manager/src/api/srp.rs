@@ -0,0 +1,193 @@
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::vault::crypto::{self, KdfParams};
+
+// Groupe 2048 bits de la RFC 5054, générateur 2
+const N_HEX: &str = concat!(
+    "AC6BDB41324A9A9BF166DE5E1389582FAF72B6651987EE07FC3192943DB56050A37329CBB4A099ED8193E0757767A13DD52312AB4B03310DCD7F48A9DA04FD50",
+    "E8083969EDB767B0CF6095179A163AB3661A05FBD5FAAAE82918A9962F0B93B855F97993EC975EEAA80D740ADBF4FF747359D041D5C33EA71D281E446B14773B",
+    "CA97B43A23FB801676BD207A436C6481F1D2B9078717461A5B9D32E688F87748544523B524B0D57D5EA77A2775D2ECFA032CFBDBF52FB3786160279004E57AE6",
+    "AF874E7303CE53299CCC041C7BC308D82A5698F3A8D0C38271AE35F8E9DBFBB694B5C803D89F7AE435DE236D525F54759B65E372FCD68EF20FA7111F9E4AFF73",
+);
+const GENERATOR: u32 = 2;
+const PAD_LENGTH: usize = 256;
+
+fn modulus() -> BigUint {
+    BigUint::parse_bytes(N_HEX.as_bytes(), 16).expect("constante du groupe SRP invalide")
+}
+
+// Nombre en octets big-endian complétés à la taille du groupe
+fn pad(value: &BigUint) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut padded = vec![0u8; PAD_LENGTH.saturating_sub(bytes.len())];
+    padded.extend(bytes);
+    padded
+}
+
+fn hash(parts: &[&[u8]]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().to_vec()
+}
+
+fn hash_int(parts: &[&[u8]]) -> BigUint {
+    BigUint::from_bytes_be(&hash(parts))
+}
+
+pub fn to_hex(value: &BigUint) -> String {
+    value.to_str_radix(16)
+}
+
+pub fn from_hex(text: &str) -> Result<BigUint, String> {
+    BigUint::parse_bytes(text.as_bytes(), 16).ok_or_else(|| String::from("Nombre SRP invalide"))
+}
+
+// Secret privé x dérivé du mot de passe maître ; Argon2id rend coûteuse
+// une attaque par dictionnaire sur le vérificateur stocké par le serveur
+pub fn private_key(password: &str, salt: &[u8], params: &KdfParams) -> Result<BigUint, String> {
+    let stretched = crypto::derive_key(password, salt, params)?;
+    Ok(hash_int(&[salt, &stretched]))
+}
+
+// Vérificateur v = g^x enregistré par le serveur à la place du mot de passe
+pub fn verifier(x: &BigUint) -> BigUint {
+    BigUint::from(GENERATOR).modpow(x, &modulus())
+}
+
+// Côté client d'un échange SRP-6a
+pub struct ClientHandshake {
+    a: BigUint,
+    public: BigUint,
+}
+
+// Résultat de l'échange : preuve à envoyer et preuve attendue du serveur
+pub struct ClientProof {
+    pub proof: Vec<u8>,
+    expected_server_proof: Vec<u8>,
+}
+
+impl ClientProof {
+    pub fn verify_server(&self, server_proof: &[u8]) -> Result<(), String> {
+        if server_proof == self.expected_server_proof.as_slice() {
+            Ok(())
+        } else {
+            Err(String::from("Le serveur n'a pas prouvé connaître le vérificateur"))
+        }
+    }
+}
+
+impl ClientHandshake {
+    pub fn start() -> Self {
+        let a = BigUint::from_bytes_be(&crypto::random_bytes::<32>());
+        let public = BigUint::from(GENERATOR).modpow(&a, &modulus());
+        ClientHandshake { a, public }
+    }
+
+    pub fn public(&self) -> &BigUint {
+        &self.public
+    }
+
+    pub fn finish(&self, x: &BigUint, server_public: &BigUint) -> Result<ClientProof, String> {
+        let n = modulus();
+        let g = BigUint::from(GENERATOR);
+        if (server_public % &n) == BigUint::ZERO {
+            return Err(String::from("Valeur publique du serveur invalide"));
+        }
+
+        let (a_pad, b_pad) = (pad(&self.public), pad(server_public));
+        let u = hash_int(&[&a_pad, &b_pad]);
+        if u == BigUint::ZERO {
+            return Err(String::from("Échange SRP invalide"));
+        }
+        let k = hash_int(&[&pad(&n), &pad(&g)]);
+
+        // S = (B - k·g^x)^(a + u·x) mod N
+        let kgx = (k * g.modpow(x, &n)) % &n;
+        let base = ((server_public % &n) + &n - kgx) % &n;
+        let secret = base.modpow(&(&self.a + u * x), &n);
+        let session_key = hash(&[&pad(&secret)]);
+
+        let proof = hash(&[&a_pad, &b_pad, &session_key]);
+        let expected_server_proof = hash(&[&a_pad, &proof, &session_key]);
+        Ok(ClientProof { proof, expected_server_proof })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FAST;
+
+    // Côté serveur de l'échange, comme API/mushroom_API.py : renvoie B et les preuves M1, M2
+    fn server(verifier: &BigUint, client_public: &BigUint) -> (BigUint, Vec<u8>, Vec<u8>) {
+        let n = modulus();
+        let g = BigUint::from(GENERATOR);
+        let k = hash_int(&[&pad(&n), &pad(&g)]);
+        let b = BigUint::from_bytes_be(&crypto::random_bytes::<32>());
+        let server_public = (k * verifier + g.modpow(&b, &n)) % &n;
+
+        let (a_pad, b_pad) = (pad(client_public), pad(&server_public));
+        let u = hash_int(&[&a_pad, &b_pad]);
+        let secret = (client_public * verifier.modpow(&u, &n) % &n).modpow(&b, &n);
+        let session_key = hash(&[&pad(&secret)]);
+        let client_proof = hash(&[&a_pad, &b_pad, &session_key]);
+        let server_proof = hash(&[&a_pad, &client_proof, &session_key]);
+        (server_public, client_proof, server_proof)
+    }
+
+    #[test]
+    fn handshake_agrees_with_the_server() {
+        let salt = b"sel-de-test-srp";
+        let x = private_key("motdepasse", salt, &FAST).unwrap();
+        let verifier = verifier(&x);
+
+        let handshake = ClientHandshake::start();
+        let (server_public, expected_proof, server_proof) = server(&verifier, handshake.public());
+        let proof = handshake.finish(&x, &server_public).unwrap();
+
+        assert_eq!(proof.proof, expected_proof);
+        assert!(proof.verify_server(&server_proof).is_ok());
+        assert!(proof.verify_server(&[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn wrong_password_gives_a_wrong_proof() {
+        let salt = b"sel-de-test-srp";
+        let verifier = verifier(&private_key("motdepasse", salt, &FAST).unwrap());
+        let wrong = private_key("mauvais", salt, &FAST).unwrap();
+
+        let handshake = ClientHandshake::start();
+        let (server_public, expected_proof, server_proof) = server(&verifier, handshake.public());
+        let proof = handshake.finish(&wrong, &server_public).unwrap();
+
+        assert_ne!(proof.proof, expected_proof);
+        assert!(proof.verify_server(&server_proof).is_err());
+    }
+
+    #[test]
+    fn rejects_a_zero_server_public_value() {
+        let x = private_key("motdepasse", b"sel-de-test-srp", &FAST).unwrap();
+        let handshake = ClientHandshake::start();
+        assert!(handshake.finish(&x, &BigUint::ZERO).is_err());
+        assert!(handshake.finish(&x, &modulus()).is_err());
+    }
+
+    #[test]
+    fn private_key_depends_on_salt_and_password() {
+        let x = private_key("motdepasse", b"sel-de-test-a", &FAST).unwrap();
+        assert_eq!(x, private_key("motdepasse", b"sel-de-test-a", &FAST).unwrap());
+        assert_ne!(x, private_key("motdepasse", b"sel-de-test-b", &FAST).unwrap());
+        assert_ne!(x, private_key("autre", b"sel-de-test-a", &FAST).unwrap());
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let value = BigUint::from(0xdead_beef_u32);
+        assert_eq!(to_hex(&value), "deadbeef");
+        assert_eq!(from_hex("DEADBEEF").unwrap(), value);
+        assert!(from_hex("zz").is_err());
+    }
+}
@@ -0,0 +1,628 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::api::client::{ApiClient, LocalChange, RemoteEntry};
+use crate::vault::cache::{self, CacheKey};
+use crate::vault::crypto::{DecryptError, KEY_LENGTH};
+use crate::vault::e2e::{self, EntryCipher, VaultParams};
+use crate::vault::factors::KeyFile;
+use crate::vault::secure::{SecretKey, SecretString};
+use crate::vault::entry::{self, Entry};
+
+// Entrée suivie localement : dernière version connue, révision serveur
+// sur laquelle elle s'appuie et modification locale restant à envoyer
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct TrackedEntry {
+    // None : supprimée localement, en attente d'envoi
+    entry: Option<Entry>,
+    base_revision: u64,
+    dirty: bool,
+}
+
+// État persistant de la synchronisation, chiffré dans le cache local.
+// Les entrées `dirty` et l'email en attente forment la file d'écritures
+// rejouée dès que le serveur répond
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct SyncState {
+    last_revision: u64,
+    entries: BTreeMap<String, TrackedEntry>,
+    // Dernier email par défaut connu, servi hors ligne
+    #[serde(default)]
+    email: String,
+    #[serde(default)]
+    pending_email: Option<String>,
+    // Paramètres de la clé du coffre, pour l'ouvrir hors ligne
+    #[serde(default)]
+    vault: Option<VaultParams>,
+    // Entrées du serveur qui n'ont pas pu être lues, avec leur erreur ; la dernière
+    // version lisible est conservée jusqu'à ce qu'une révision lisible arrive
+    #[serde(default)]
+    unreadable: BTreeMap<String, String>,
+}
+
+// État de la connexion au serveur lors de la dernière synchronisation
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Connectivity {
+    #[default]
+    Unknown,
+    Online,
+    // Serveur injoignable : lectures depuis le cache, écritures en attente
+    Offline(String),
+}
+
+// Entrée modifiée des deux côtés depuis la dernière synchronisation
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub id: String,
+    // None : supprimée de ce côté
+    pub local: Option<Entry>,
+    pub remote: Option<Entry>,
+    pub remote_revision: u64,
+}
+
+impl Conflict {
+    pub fn name(&self) -> &str {
+        self.local
+            .as_ref()
+            .or(self.remote.as_ref())
+            .map(|entry| entry.service_name.as_str())
+            .unwrap_or(&self.id)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Resolution {
+    KeepLocal,
+    KeepRemote,
+    // Fusion champ par champ choisie dans l'interface
+    Merged(Box<Entry>),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    pub entries: Vec<Entry>,
+    pub conflicts: Vec<Conflict>,
+    pub connectivity: Connectivity,
+    // Écritures locales pas encore acceptées par le serveur
+    pub pending: usize,
+    // Entrées du serveur ignorées car illisibles
+    pub unreadable: usize,
+}
+
+pub struct SyncEngine {
+    path: PathBuf,
+    key: CacheKey,
+    state: SyncState,
+    // Les conflits ne sont pas persistés : un envoi refusé les fait réapparaître
+    conflicts: BTreeMap<String, Conflict>,
+}
+
+impl SyncEngine {
+    // Dérive la clé du cache (Argon2id, à appeler hors de la boucle de l'interface)
+    // et recharge l'état de la dernière session
+    pub fn open(dir: &Path, password: &str) -> Result<Self, DecryptError> {
+        let path = cache::cache_path(dir)?;
+        let key = CacheKey::derive(&path, password)?;
+        SyncEngine::load(path, key)
+    }
+
+    // Cache rouvert par la clé d'un kit de récupération, sans mot de passe maître
+    pub fn recover(dir: &Path, recovery_key: &SecretKey<KEY_LENGTH>) -> Result<Self, DecryptError> {
+        let path = cache::cache_path(dir)?;
+        let key = CacheKey::recover(&path, recovery_key)?;
+        SyncEngine::load(path, key)
+    }
+
+    fn load(path: PathBuf, key: CacheKey) -> Result<Self, DecryptError> {
+        let mut state: SyncState = cache::load(&path, &key)?.unwrap_or_default();
+        // L'identifiant n'est pas sérialisé avec l'entrée
+        for (id, tracked) in state.entries.iter_mut() {
+            if let Some(entry) = &mut tracked.entry {
+                entry.id = id.clone();
+            }
+        }
+        Ok(SyncEngine { path, key, state, conflicts: BTreeMap::new() })
+    }
+
+    // Scelle la clé du cache pour le kit inscrit dans l'en-tête connu, y compris un kit
+    // créé depuis un autre appareil : le kit rouvre alors aussi ce cache
+    fn seal_recovery(&mut self, cipher: &EntryCipher) -> Result<(), String> {
+        let Some(params) = &self.state.vault else {
+            return Ok(());
+        };
+        if let Some(recovery_key) = cipher.recovery_key(&params.factors)? {
+            if self.key.seal_recovery(&recovery_key)? {
+                self.save()?;
+            }
+        }
+        Ok(())
+    }
+
+    // Paramètres de la clé du coffre : ceux du cache, sinon ceux du serveur
+    async fn vault_params(
+        &mut self,
+        client: &ApiClient,
+        password: &str,
+        key_file: Option<&KeyFile>,
+    ) -> Result<VaultParams, String> {
+        if let Some(params) = &self.state.vault {
+            return Ok(params.clone());
+        }
+        let params = e2e::vault_params(client, password, key_file)
+            .await
+            .map_err(|e| format!("Première ouverture du coffre impossible sans le serveur: {}", e))?;
+        self.state.vault = Some(params.clone());
+        self.save()?;
+        Ok(params)
+    }
+
+    fn save(&self) -> Result<(), String> {
+        cache::save(&self.path, &self.key, &self.state)
+    }
+
+    // Entrées visibles, dans l'ordre de la liste principale
+    pub fn entries(&self) -> Vec<Entry> {
+        let mut entries: Vec<Entry> = self
+            .state
+            .entries
+            .values()
+            .filter_map(|tracked| tracked.entry.clone())
+            .collect();
+        entries.sort_by_key(|entry| (entry.service_name.to_lowercase(), entry.username.to_lowercase()));
+        entries
+    }
+
+    pub fn pending(&self) -> usize {
+        let entries = self.state.entries.values().filter(|tracked| tracked.dirty).count();
+        entries + usize::from(self.state.pending_email.is_some())
+    }
+
+    pub fn record_email(&mut self, email: String) -> Result<(), String> {
+        self.state.email = email.clone();
+        self.state.pending_email = Some(email);
+        self.save()
+    }
+
+    // Email du serveur, ou celui du cache si le serveur ne répond pas
+    pub async fn email(&mut self, client: &ApiClient) -> Result<String, String> {
+        if self.state.pending_email.is_none() {
+            if let Ok(email) = client.get_email().await {
+                if email != self.state.email {
+                    self.state.email = email;
+                    self.save()?;
+                }
+            }
+        }
+        Ok(self.state.email.clone())
+    }
+
+    pub fn record_edit(&mut self, mut entry: Entry) -> Result<(), String> {
+        if entry.id.is_empty() {
+            entry.id = entry.service_url.clone();
+        }
+        let tracked = self.state.entries.entry(entry.id.clone()).or_insert(TrackedEntry {
+            entry: None,
+            base_revision: 0,
+            dirty: false,
+        });
+        tracked.entry = Some(entry);
+        tracked.dirty = true;
+        self.save()
+    }
+
+    // Récupère les modifications du serveur puis envoie les modifications locales ;
+    // un serveur injoignable n'est pas une erreur, seul l'enregistrement du cache peut échouer
+    pub async fn sync(&mut self, client: &ApiClient) -> Result<SyncReport, String> {
+        let connectivity = match self.exchange(client).await {
+            Ok(()) => Connectivity::Online,
+            Err(e) => Connectivity::Offline(e),
+        };
+        self.save()?;
+
+        Ok(SyncReport {
+            entries: self.entries(),
+            conflicts: self.conflicts.values().cloned().collect(),
+            connectivity,
+            pending: self.pending(),
+            unreadable: self.state.unreadable.len(),
+        })
+    }
+
+    async fn exchange(&mut self, client: &ApiClient) -> Result<(), String> {
+        let changes = client.pull_changes(self.state.last_revision).await?;
+        for remote in changes.entries {
+            self.apply_remote(remote);
+        }
+        self.state.unreadable.extend(changes.unreadable);
+
+        let pending: Vec<LocalChange> = self
+            .state
+            .entries
+            .iter()
+            .filter(|(id, tracked)| tracked.dirty && !self.conflicts.contains_key(*id))
+            .map(|(id, tracked)| LocalChange {
+                id: id.clone(),
+                base_revision: tracked.base_revision,
+                entry: tracked.entry.clone(),
+            })
+            .collect();
+
+        if !pending.is_empty() {
+            let outcome = client.push_changes(&pending).await?;
+            for (id, revision) in outcome.applied {
+                self.mark_pushed(&id, revision);
+            }
+            for remote in outcome.conflicts {
+                self.state.unreadable.remove(&remote.id);
+                self.record_conflict(remote);
+            }
+            self.state.unreadable.extend(outcome.unreadable);
+        }
+
+        // Nos propres envois reviendront au prochain tirage, sans effet sur des entrées propres
+        self.state.last_revision = self.state.last_revision.max(changes.revision);
+
+        if let Some(email) = self.state.pending_email.clone() {
+            client.save_email(&email).await?;
+            self.state.pending_email = None;
+        }
+
+        // Le serveur vient d'authentifier la session : son en-tête fait foi. Facteurs et
+        // mot de passe changés depuis un autre appareil sont ainsi exigés aussi hors ligne
+        if let Some(remote) = client.get_vault_params().await? {
+            self.state.vault = Some(remote);
+        }
+        Ok(())
+    }
+
+    fn apply_remote(&mut self, remote: RemoteEntry) {
+        self.state.unreadable.remove(&remote.id);
+        match self.state.entries.get_mut(&remote.id) {
+            Some(tracked) if tracked.dirty => {
+                if same_content(&tracked.entry, &remote.entry) {
+                    tracked.base_revision = remote.revision;
+                    tracked.dirty = remote.legacy;
+                } else if remote.revision > tracked.base_revision {
+                    self.record_conflict(remote);
+                }
+            }
+            _ => match remote.entry {
+                Some(entry) => {
+                    self.state.entries.insert(
+                        remote.id,
+                        // Une entrée aux secrets en clair sur le serveur repart chiffrée au prochain envoi
+                        TrackedEntry { entry: Some(entry), base_revision: remote.revision, dirty: remote.legacy },
+                    );
+                }
+                None => {
+                    self.state.entries.remove(&remote.id);
+                }
+            },
+        }
+    }
+
+    fn mark_pushed(&mut self, id: &str, revision: u64) {
+        let deleted = match self.state.entries.get_mut(id) {
+            Some(tracked) => {
+                tracked.base_revision = revision;
+                tracked.dirty = false;
+                tracked.entry.is_none()
+            }
+            None => false,
+        };
+        if deleted {
+            self.state.entries.remove(id);
+        }
+    }
+
+    fn record_conflict(&mut self, remote: RemoteEntry) {
+        let local = self.state.entries.get(&remote.id).and_then(|tracked| tracked.entry.clone());
+        self.conflicts.insert(
+            remote.id.clone(),
+            Conflict { id: remote.id, local, remote: remote.entry, remote_revision: remote.revision },
+        );
+    }
+
+    // Applique le choix de l'utilisateur ; la version retenue part au prochain envoi
+    pub fn resolve(&mut self, id: &str, resolution: Resolution) -> Result<(), String> {
+        let Some(conflict) = self.conflicts.remove(id) else {
+            return Ok(());
+        };
+        let (entry, dirty) = match resolution {
+            Resolution::KeepLocal => (conflict.local, true),
+            Resolution::KeepRemote => (conflict.remote, false),
+            Resolution::Merged(entry) => (Some(*entry), true),
+        };
+
+        if entry.is_none() && !dirty {
+            self.state.entries.remove(id);
+        } else {
+            self.state.entries.insert(
+                id.to_string(),
+                TrackedEntry { entry, base_revision: conflict.remote_revision, dirty },
+            );
+        }
+        self.save()
+    }
+}
+
+// Deux versions identiques ne sont pas en conflit, quelles que soient leurs dates
+fn same_content(local: &Option<Entry>, remote: &Option<Entry>) -> bool {
+    match (local, remote) {
+        (Some(local), Some(remote)) => entry::changed_fields(local, remote).is_empty(),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+// Moteur partagé entre l'interface et les tâches de synchronisation
+#[derive(Clone)]
+pub struct SyncHandle {
+    engine: Arc<Mutex<SyncEngine>>,
+    cipher: Arc<EntryCipher>,
+}
+
+impl std::fmt::Debug for SyncHandle {
+    // Les clés du cache et du coffre ne doivent jamais apparaître dans les journaux
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SyncHandle")
+    }
+}
+
+impl SyncHandle {
+    // Ouvre le cache local du profil puis dérive la clé de chiffrement de bout en bout du coffre
+    pub async fn open(
+        dir: PathBuf,
+        password: SecretString,
+        key_file: Option<KeyFile>,
+        client: ApiClient,
+    ) -> Result<Self, DecryptError> {
+        let cache_password = password.clone();
+        let mut engine = tokio::task::spawn_blocking(move || SyncEngine::open(&dir, &cache_password))
+            .await
+            .map_err(|e| e.to_string())??;
+        let params = engine.vault_params(&client, &password, key_file.as_ref()).await?;
+        let cipher = e2e::derive(&password, params, key_file).await?;
+        if let Err(e) = engine.seal_recovery(&cipher) {
+            log::warn!(error:% = e; "Cache local non scellé pour le kit de récupération");
+        }
+        Ok(SyncHandle { engine: Arc::new(Mutex::new(engine)), cipher: Arc::new(cipher) })
+    }
+
+    // Cache rouvert par un kit de récupération, avec la clé du coffre que ce kit a rouverte
+    pub fn recover(dir: &Path, recovery_key: &SecretKey<KEY_LENGTH>, cipher: Arc<EntryCipher>) -> Result<Self, DecryptError> {
+        let engine = SyncEngine::recover(dir, recovery_key)?;
+        Ok(SyncHandle { engine: Arc::new(Mutex::new(engine)), cipher })
+    }
+
+    // En-tête du coffre gardé dans le cache
+    pub async fn vault_params(&self) -> Option<VaultParams> {
+        self.engine.lock().await.state.vault.clone()
+    }
+
+    // En-tête renvoyé par le serveur après un changement des facteurs, avec sa nouvelle révision
+    pub async fn set_vault_params(&self, params: VaultParams) -> Result<(), String> {
+        let mut engine = self.engine.lock().await;
+        if engine.state.vault.is_some() {
+            engine.state.vault = Some(params);
+        }
+        engine.save()?;
+        engine.seal_recovery(&self.cipher)
+    }
+
+    // Nouveau mot de passe maître : le cache est réécrit avec une clé dérivée de lui
+    // et garde le nouvel en-tête du coffre, pour l'ouvrir hors ligne
+    pub async fn rekey(&self, password: &str, params: VaultParams) -> Result<(), String> {
+        let (password, kdf) = (password.to_string(), params.kdf);
+        let recovery_key = self.cipher.recovery_key(&params.factors)?;
+        let key = tokio::task::spawn_blocking(move || {
+            let mut key = CacheKey::generate(&password, kdf)?;
+            if let Some(recovery_key) = &recovery_key {
+                key.seal_recovery(recovery_key)?;
+            }
+            Ok::<_, String>(key)
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        let mut engine = self.engine.lock().await;
+        let previous = engine.state.vault.replace(params);
+        if let Err(e) = cache::save(&engine.path, &key, &engine.state) {
+            engine.state.vault = previous;
+            return Err(e);
+        }
+        engine.key = key;
+        Ok(())
+    }
+
+    pub fn cipher(&self) -> Arc<EntryCipher> {
+        self.cipher.clone()
+    }
+
+    pub async fn sync(&self, client: &ApiClient) -> Result<SyncReport, String> {
+        let mut engine = self.engine.lock().await;
+        let report = engine.sync(client).await?;
+        // L'en-tête vient d'être relu : un kit créé ailleurs scelle aussi ce cache
+        if let Err(e) = engine.seal_recovery(&self.cipher) {
+            log::warn!(error:% = e; "Cache local non scellé pour le kit de récupération");
+        }
+        Ok(report)
+    }
+
+    pub async fn record_edit(&self, entry: Entry) -> Result<(), String> {
+        self.engine.lock().await.record_edit(entry)
+    }
+
+    pub async fn record_email(&self, email: String) -> Result<(), String> {
+        self.engine.lock().await.record_email(email)
+    }
+
+    pub async fn email(&self, client: &ApiClient) -> Result<String, String> {
+        self.engine.lock().await.email(client).await
+    }
+
+    pub async fn resolve(&self, id: &str, resolution: Resolution) -> Result<(), String> {
+        self.engine.lock().await.resolve(id, resolution)
+    }
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestDir, FAST};
+
+    // Moteur sur un cache jetable, sans dérivation Argon2 coûteuse
+    struct TestEngine {
+        engine: SyncEngine,
+        _dir: TestDir,
+    }
+
+    impl TestEngine {
+        fn new(name: &str) -> Self {
+            let dir = TestDir::new(name);
+            let path = cache::cache_path(&dir.0).unwrap();
+            let key = CacheKey::generate("motdepasse", FAST).unwrap();
+            let engine = SyncEngine { path, key, state: SyncState::default(), conflicts: BTreeMap::new() };
+            TestEngine { engine, _dir: dir }
+        }
+    }
+
+    fn entry(id: &str, password: &str) -> Entry {
+        let mut entry = Entry::new(id, id);
+        entry.service_password = password.into();
+        entry
+    }
+
+    fn remote(id: &str, revision: u64, password: Option<&str>) -> RemoteEntry {
+        RemoteEntry { id: id.to_string(), revision, entry: password.map(|p| entry(id, p)), legacy: false }
+    }
+
+    #[test]
+    fn applies_remote_additions_and_deletions() {
+        let mut test = TestEngine::new("remote");
+        let engine = &mut test.engine;
+        engine.apply_remote(remote("b.example.com", 1, Some("un")));
+        engine.apply_remote(remote("a.example.com", 2, Some("deux")));
+        let names: Vec<String> = engine.entries().into_iter().map(|entry| entry.service_name).collect();
+        assert_eq!(names, vec!["a.example.com", "b.example.com"]);
+        assert_eq!(engine.pending(), 0);
+
+        engine.apply_remote(remote("a.example.com", 3, None));
+        assert_eq!(engine.entries().len(), 1);
+
+        // Secrets en clair sur le serveur : l'entrée repartira chiffrée
+        let mut legacy = remote("c.example.com", 4, Some("clair"));
+        legacy.legacy = true;
+        engine.apply_remote(legacy);
+        assert_eq!(engine.pending(), 1);
+    }
+
+    #[test]
+    fn identical_remote_version_clears_local_edit() {
+        let mut test = TestEngine::new("identical");
+        let engine = &mut test.engine;
+        engine.record_edit(entry("a.example.com", "nouveau")).unwrap();
+        assert_eq!(engine.pending(), 1);
+
+        engine.apply_remote(remote("a.example.com", 5, Some("nouveau")));
+        assert_eq!(engine.pending(), 0);
+        assert_eq!(engine.state.entries["a.example.com"].base_revision, 5);
+        assert!(engine.conflicts.is_empty());
+    }
+
+    #[test]
+    fn concurrent_edits_become_conflicts() {
+        let mut test = TestEngine::new("conflict");
+        let engine = &mut test.engine;
+        engine.apply_remote(remote("a.example.com", 1, Some("initial")));
+        engine.record_edit(entry("a.example.com", "local")).unwrap();
+
+        // Une révision déjà connue ne crée pas de conflit
+        engine.apply_remote(remote("a.example.com", 1, Some("initial")));
+        assert!(engine.conflicts.is_empty());
+
+        engine.apply_remote(remote("a.example.com", 2, Some("distant")));
+        let conflict = &engine.conflicts["a.example.com"];
+        assert_eq!(&*conflict.local.as_ref().unwrap().service_password, "local");
+        assert_eq!(&*conflict.remote.as_ref().unwrap().service_password, "distant");
+        assert_eq!(conflict.name(), "a.example.com");
+
+        engine.resolve("a.example.com", Resolution::KeepLocal).unwrap();
+        let tracked = &engine.state.entries["a.example.com"];
+        assert!(tracked.dirty);
+        assert_eq!(tracked.base_revision, 2);
+        assert!(engine.conflicts.is_empty());
+    }
+
+    #[test]
+    fn keeping_a_remote_deletion_removes_the_entry() {
+        let mut test = TestEngine::new("deletion");
+        let engine = &mut test.engine;
+        engine.apply_remote(remote("a.example.com", 1, Some("initial")));
+        engine.record_edit(entry("a.example.com", "local")).unwrap();
+        engine.apply_remote(remote("a.example.com", 2, None));
+
+        engine.resolve("a.example.com", Resolution::KeepRemote).unwrap();
+        assert!(engine.entries().is_empty());
+        assert_eq!(engine.pending(), 0);
+    }
+
+    #[test]
+    fn pushed_deletions_are_forgotten() {
+        let mut test = TestEngine::new("pushed");
+        let engine = &mut test.engine;
+        engine.apply_remote(remote("a.example.com", 1, Some("initial")));
+        engine.state.entries.get_mut("a.example.com").unwrap().entry = None;
+        engine.state.entries.get_mut("a.example.com").unwrap().dirty = true;
+        engine.record_edit(entry("b.example.com", "b")).unwrap();
+
+        engine.mark_pushed("a.example.com", 3);
+        engine.mark_pushed("b.example.com", 4);
+        assert!(!engine.state.entries.contains_key("a.example.com"));
+        assert_eq!(engine.state.entries["b.example.com"].base_revision, 4);
+        assert_eq!(engine.pending(), 0);
+    }
+
+    #[test]
+    fn unreadable_entries_are_tracked_until_a_readable_revision_arrives() {
+        let mut test = TestEngine::new("unreadable");
+        let engine = &mut test.engine;
+        engine.apply_remote(remote("a.example.com", 1, Some("lisible")));
+        engine
+            .state
+            .unreadable
+            .extend([(String::from("a.example.com"), String::from("déchiffrement impossible"))]);
+        // La dernière version lisible reste affichée
+        assert_eq!(engine.entries().len(), 1);
+        assert_eq!(engine.state.unreadable.len(), 1);
+
+        engine.apply_remote(remote("a.example.com", 2, Some("corrigée")));
+        assert!(engine.state.unreadable.is_empty());
+    }
+
+    #[test]
+    fn state_persists_in_the_cache() {
+        let mut test = TestEngine::new("persist");
+        test.engine.record_edit(entry("a.example.com", "secret")).unwrap();
+        let state: SyncState = cache::load(&test.engine.path, &test.engine.key).unwrap().unwrap();
+        assert!(state.entries["a.example.com"].dirty);
+        assert!(state.unreadable.is_empty());
+    }
+
+    #[tokio::test]
+    async fn queued_email_is_served_until_pushed() {
+        let mut test = TestEngine::new("email");
+        test.engine.record_email(String::from("moi@example.com")).unwrap();
+        assert_eq!(test.engine.pending(), 1);
+        // L'email en attente fait foi, sans interroger le serveur
+        let email = test.engine.email(&ApiClient::new()).await.unwrap();
+        assert_eq!(email, "moi@example.com");
+        let state: SyncState = cache::load(&test.engine.path, &test.engine.key).unwrap().unwrap();
+        assert_eq!(state.pending_email.as_deref(), Some("moi@example.com"));
+    }
+}
@@ -0,0 +1,355 @@
+pub mod rotation;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::export::{self, archive, ExportDocument};
+use crate::vault::e2e::EntryCipher;
+use crate::vault::entry::{self, Entry, EntryField};
+
+const SNAPSHOT_PREFIX: &str = "mushroom-";
+const SNAPSHOT_EXTENSION: &str = "mushroom";
+const STATE_FILE: &str = "state.json";
+
+// Fréquence des sauvegardes automatiques et nombre d'instantanés conservés
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackupPolicy {
+    // Un instantané toutes les N écritures dans le coffre (0 : désactivé)
+    pub every_n_writes: u32,
+    // Un instantané par jour d'utilisation au minimum
+    pub daily: bool,
+    pub keep_recent: usize,
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+    pub keep_monthly: usize,
+}
+
+impl Default for BackupPolicy {
+    fn default() -> Self {
+        BackupPolicy {
+            every_n_writes: 10,
+            daily: true,
+            keep_recent: 5,
+            keep_daily: 7,
+            keep_weekly: 4,
+            keep_monthly: 12,
+        }
+    }
+}
+
+// Instantané chiffré du coffre ; date et nombre d'entrées sont lus dans l'en-tête
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    pub created_at: DateTime<Utc>,
+    pub entry_count: usize,
+    pub size: u64,
+}
+
+// Sauvegardes du profil dont `data_dir` est le dossier de données
+pub fn backup_dir(data_dir: &Path) -> Result<PathBuf, String> {
+    let dir = data_dir.join("backups");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Création du dossier de sauvegarde '{}' impossible: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+// Instantanés du dossier, du plus récent au plus ancien
+pub fn list_snapshots(dir: &Path) -> Result<Vec<Snapshot>, String> {
+    let read_dir = std::fs::read_dir(dir).map_err(|e| format!("Lecture de '{}' impossible: {}", dir.display(), e))?;
+
+    let mut snapshots: Vec<Snapshot> = read_dir
+        .filter_map(|item| item.ok())
+        .map(|item| item.path())
+        .filter(|path| is_snapshot_path(path))
+        .filter_map(|path| {
+            let data = std::fs::read(&path).ok()?;
+            let header = archive::read_header(&data).ok()?;
+            Some(Snapshot {
+                created_at: header.created_at,
+                entry_count: header.entry_count,
+                size: data.len() as u64,
+                path,
+            })
+        })
+        .collect();
+
+    snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.created_at));
+    Ok(snapshots)
+}
+
+fn is_snapshot_path(path: &Path) -> bool {
+    let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+    name.starts_with(SNAPSHOT_PREFIX) && path.extension().and_then(|ext| ext.to_str()) == Some(SNAPSHOT_EXTENSION)
+}
+
+// Écrit un instantané chiffré par la clé du coffre, puis applique la rotation
+pub fn create_snapshot(dir: &Path, entries: &[Entry], cipher: &EntryCipher, policy: &BackupPolicy) -> Result<Snapshot, String> {
+    let document = ExportDocument::new(entries);
+    let data = archive::write_with_key(&document, cipher.snapshot_key().bytes())?;
+
+    let name = format!(
+        "{}{}.{}",
+        SNAPSHOT_PREFIX,
+        document.exported_at.format("%Y%m%d-%H%M%S%.3f"),
+        SNAPSHOT_EXTENSION
+    );
+    let path = dir.join(name);
+    // Écriture dans un fichier temporaire puis renommage : pas d'instantané à moitié écrit
+    let temporary = path.with_extension("tmp");
+    export::write_private(&temporary, &data)?;
+    std::fs::rename(&temporary, &path).map_err(|e| format!("Écriture de '{}' impossible: {}", path.display(), e))?;
+
+    rotate(dir, policy)?;
+
+    Ok(Snapshot {
+        path,
+        created_at: document.exported_at,
+        entry_count: entries.len(),
+        size: data.len() as u64,
+    })
+}
+
+// Supprime les instantanés que la politique ne retient plus
+pub fn rotate(dir: &Path, policy: &BackupPolicy) -> Result<usize, String> {
+    let snapshots = list_snapshots(dir)?;
+    let dates: Vec<DateTime<Utc>> = snapshots.iter().map(|snapshot| snapshot.created_at).collect();
+    let keep = rotation::retained(&dates, policy);
+
+    let mut removed = 0;
+    for (snapshot, keep) in snapshots.iter().zip(keep) {
+        if !keep {
+            std::fs::remove_file(&snapshot.path)
+                .map_err(|e| format!("Suppression de '{}' impossible: {}", snapshot.path.display(), e))?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+pub fn load_snapshot(snapshot: &Snapshot, cipher: &EntryCipher) -> Result<Vec<Entry>, String> {
+    let data = std::fs::read(&snapshot.path)
+        .map_err(|e| format!("Lecture de '{}' impossible: {}", snapshot.path.display(), e))?;
+    archive::read_with_key(&data, cipher.snapshot_key().bytes())?.into_entries()
+}
+
+// État du déclenchement automatique, conservé entre deux lancements
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct SchedulerState {
+    writes_since_snapshot: u32,
+    last_snapshot: Option<DateTime<Utc>>,
+}
+
+pub struct Scheduler {
+    dir: PathBuf,
+    policy: BackupPolicy,
+    state: SchedulerState,
+}
+
+impl Scheduler {
+    pub fn load(dir: PathBuf, policy: BackupPolicy) -> Self {
+        let mut state: SchedulerState = std::fs::read(dir.join(STATE_FILE))
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default();
+        // Sans état, le dernier instantané présent fait foi
+        if state.last_snapshot.is_none() {
+            state.last_snapshot = list_snapshots(&dir)
+                .ok()
+                .and_then(|snapshots| snapshots.first().map(|snapshot| snapshot.created_at));
+        }
+        Scheduler { dir, policy, state }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn policy(&self) -> &BackupPolicy {
+        &self.policy
+    }
+
+    // Compte des écritures ; vrai quand un instantané est dû
+    pub fn record_writes(&mut self, count: usize) -> Result<bool, String> {
+        self.state.writes_since_snapshot = self.state.writes_since_snapshot.saturating_add(count as u32);
+        self.save()?;
+        Ok(self.policy.every_n_writes > 0 && self.state.writes_since_snapshot >= self.policy.every_n_writes)
+    }
+
+    pub fn daily_due(&self, now: DateTime<Utc>) -> bool {
+        self.policy.daily
+            && self
+                .state
+                .last_snapshot
+                .is_none_or(|last| now - last >= Duration::days(1))
+    }
+
+    pub fn snapshot_taken(&mut self, at: DateTime<Utc>) -> Result<(), String> {
+        self.state.writes_since_snapshot = 0;
+        self.state.last_snapshot = Some(at);
+        self.save()
+    }
+
+    // Fichier temporaire renommé : un arrêt pendant l'écriture ne laisse pas un état tronqué
+    fn save(&self) -> Result<(), String> {
+        let data = serde_json::to_vec_pretty(&self.state).map_err(|e| format!("Erreur d'encodage JSON: {}", e))?;
+        let path = self.dir.join(STATE_FILE);
+        let temporary = path.with_extension("tmp");
+        export::write_private(&temporary, &data)?;
+        std::fs::rename(&temporary, &path).map_err(|e| format!("Écriture de '{}' impossible: {}", path.display(), e))
+    }
+}
+
+// Différences entre un instantané et le coffre actuel
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotDiff {
+    // Présentes seulement dans l'instantané : recréées par la restauration
+    pub only_in_snapshot: Vec<Entry>,
+    // Présentes seulement dans le coffre : supprimées par la restauration
+    pub only_in_vault: Vec<Entry>,
+    // Version de l'instantané et champs qui diffèrent de la version actuelle
+    pub changed: Vec<(Entry, Vec<EntryField>)>,
+    pub unchanged: usize,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.only_in_snapshot.is_empty() && self.only_in_vault.is_empty() && self.changed.is_empty()
+    }
+}
+
+pub fn diff(snapshot: &[Entry], current: &[Entry]) -> SnapshotDiff {
+    let current_by_id: HashMap<&str, &Entry> = current.iter().map(|entry| (entry.id.as_str(), entry)).collect();
+    let snapshot_ids: std::collections::HashSet<&str> = snapshot.iter().map(|entry| entry.id.as_str()).collect();
+    let mut result = SnapshotDiff::default();
+
+    for entry in snapshot {
+        match current_by_id.get(entry.id.as_str()) {
+            None => result.only_in_snapshot.push(entry.clone()),
+            Some(existing) => {
+                let fields = entry::changed_fields(entry, existing);
+                if fields.is_empty() {
+                    result.unchanged += 1;
+                } else {
+                    result.changed.push((entry.clone(), fields));
+                }
+            }
+        }
+    }
+
+    result.only_in_vault = current
+        .iter()
+        .filter(|entry| !snapshot_ids.contains(entry.id.as_str()))
+        .cloned()
+        .collect();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::e2e::{self, VaultParams};
+    use crate::test_support::{FAST, TestDir};
+
+    // Dossier temporaire propre à chaque test, supprimé à la fin
+
+    fn entry(url: &str, password: &str) -> Entry {
+        let mut entry = Entry::new(url, url);
+        entry.service_password = password.into();
+        entry
+    }
+
+    fn vault(password: &str) -> (EntryCipher, VaultParams) {
+        let params = e2e::initial_params(FAST, password, None).unwrap();
+        (EntryCipher::derive(password, &params, None).unwrap(), params)
+    }
+
+    #[test]
+    fn snapshots_survive_a_master_password_change() {
+        let dir = TestDir::new("rekey");
+        let (cipher, params) = vault("ancien");
+        let entries = vec![entry("forge.example.com", "hunter2")];
+        let snapshot = create_snapshot(&dir.0, &entries, &cipher, &BackupPolicy::default()).unwrap();
+        assert_eq!(list_snapshots(&dir.0).unwrap(), vec![snapshot.clone()]);
+
+        let rekeyed = cipher.rekey(&params, "nouveau", None, FAST).unwrap();
+        let unlocked = EntryCipher::derive("nouveau", &rekeyed, None).unwrap();
+        let restored = load_snapshot(&snapshot, &unlocked).unwrap();
+        assert_eq!(restored, entries);
+    }
+
+    #[test]
+    fn snapshots_of_another_vault_are_unreadable() {
+        let dir = TestDir::new("foreign");
+        let (cipher, _) = vault("motdepasse");
+        let snapshot = create_snapshot(&dir.0, &[entry("a.example.com", "x")], &cipher, &BackupPolicy::default()).unwrap();
+        let (other, _) = vault("motdepasse");
+        assert!(load_snapshot(&snapshot, &other).is_err());
+    }
+
+    #[test]
+    fn creating_a_snapshot_rotates_old_ones() {
+        let dir = TestDir::new("rotate");
+        let (cipher, _) = vault("motdepasse");
+        let policy = BackupPolicy { keep_recent: 1, keep_daily: 0, keep_weekly: 0, keep_monthly: 0, ..BackupPolicy::default() };
+        create_snapshot(&dir.0, &[], &cipher, &policy).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let latest = create_snapshot(&dir.0, &[entry("a.example.com", "x")], &cipher, &policy).unwrap();
+        std::fs::write(dir.0.join("notes.txt"), b"ignore").unwrap();
+
+        let snapshots = list_snapshots(&dir.0).unwrap();
+        assert_eq!(snapshots, vec![latest]);
+        assert_eq!(snapshots[0].entry_count, 1);
+    }
+
+    #[test]
+    fn scheduler_counts_writes_and_days() {
+        let dir = TestDir::new("scheduler");
+        let policy = BackupPolicy { every_n_writes: 3, ..BackupPolicy::default() };
+        let mut scheduler = Scheduler::load(dir.0.clone(), policy);
+        let now = Utc::now();
+        assert!(scheduler.daily_due(now));
+        assert!(!scheduler.record_writes(2).unwrap());
+        assert!(scheduler.record_writes(1).unwrap());
+
+        scheduler.snapshot_taken(now).unwrap();
+        assert!(!scheduler.record_writes(1).unwrap());
+        assert!(!scheduler.daily_due(now + Duration::hours(23)));
+        assert!(scheduler.daily_due(now + Duration::days(1)));
+
+        // L'état est relu au lancement suivant
+        let reloaded = Scheduler::load(dir.0.clone(), policy);
+        assert_eq!(reloaded.state.writes_since_snapshot, 1);
+        assert!(!dir.0.join(STATE_FILE).with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn scheduler_reports_state_write_failures() {
+        let dir = TestDir::new("scheduler-failure");
+        let mut scheduler = Scheduler::load(dir.0.join("absent"), BackupPolicy::default());
+        assert!(scheduler.record_writes(1).is_err());
+        assert!(scheduler.snapshot_taken(Utc::now()).is_err());
+    }
+
+    #[test]
+    fn diff_compares_snapshot_and_vault() {
+        let kept = entry("kept.example.com", "pareil");
+        let changed = entry("changed.example.com", "ancien");
+        let mut current_changed = changed.clone();
+        current_changed.service_password = "nouveau".into();
+        let removed = entry("removed.example.com", "x");
+        let added = entry("added.example.com", "y");
+
+        let result = diff(&[kept.clone(), changed.clone(), removed.clone()], &[kept, current_changed, added.clone()]);
+        assert_eq!(result.only_in_snapshot, vec![removed]);
+        assert_eq!(result.only_in_vault, vec![added]);
+        assert_eq!(result.changed.len(), 1);
+        assert_eq!(result.changed[0].0, changed);
+        assert_eq!(result.changed[0].1, vec![EntryField::Password]);
+        assert_eq!(result.unchanged, 1);
+        assert!(!result.is_empty());
+        assert!(diff(&[], &[]).is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Datelike, Local, Utc};
+
+use crate::backup::BackupPolicy;
+
+// Rotation grand-père/père/fils : les plus récents sont gardés tels quels, puis
+// le plus récent de chaque jour (fils), de chaque semaine (pères) et de chaque mois
+// (grands-pères), dans la limite de la politique. `dates` va du plus récent au plus ancien.
+pub fn retained(dates: &[DateTime<Utc>], policy: &BackupPolicy) -> Vec<bool> {
+    let mut keep = vec![false; dates.len()];
+    for kept in keep.iter_mut().take(policy.keep_recent) {
+        *kept = true;
+    }
+
+    // Les jours et les semaines suivent le fuseau de l'utilisateur
+    let local: Vec<DateTime<Local>> = dates.iter().map(|date| date.with_timezone(&Local)).collect();
+    keep_newest_per_period(&local, policy.keep_daily, |date| date.date_naive(), &mut keep);
+    keep_newest_per_period(
+        &local,
+        policy.keep_weekly,
+        |date| {
+            let week = date.iso_week();
+            (week.year(), week.week())
+        },
+        &mut keep,
+    );
+    keep_newest_per_period(&local, policy.keep_monthly, |date| (date.year(), date.month()), &mut keep);
+
+    keep
+}
+
+fn keep_newest_per_period<K: PartialEq>(
+    dates: &[DateTime<Local>],
+    periods: usize,
+    period_of: impl Fn(&DateTime<Local>) -> K,
+    keep: &mut [bool],
+) {
+    let mut current = None;
+    let mut seen = 0;
+
+    for (index, date) in dates.iter().enumerate() {
+        let period = period_of(date);
+        if current.as_ref() != Some(&period) {
+            if seen == periods {
+                break;
+            }
+            seen += 1;
+            keep[index] = true;
+            current = Some(period);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn policy(keep_recent: usize, keep_daily: usize, keep_weekly: usize, keep_monthly: usize) -> BackupPolicy {
+        BackupPolicy { keep_recent, keep_daily, keep_weekly, keep_monthly, ..BackupPolicy::default() }
+    }
+
+    // Midi UTC : les jours restent distincts quel que soit le fuseau de la machine
+    fn base() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 30, 12, 0, 0).unwrap()
+    }
+
+    fn kept(keep: &[bool]) -> Vec<usize> {
+        keep.iter().enumerate().filter(|(_, kept)| **kept).map(|(index, _)| index).collect()
+    }
+
+    #[test]
+    fn keeps_most_recent_snapshots() {
+        let dates: Vec<_> = (0..4).map(|i| base() - Duration::minutes(i)).collect();
+        assert_eq!(retained(&dates, &policy(2, 0, 0, 0)), vec![true, true, false, false]);
+        assert!(retained(&[], &policy(2, 7, 4, 12)).is_empty());
+    }
+
+    #[test]
+    fn keeps_newest_snapshot_of_each_day() {
+        let dates = vec![
+            base(),
+            base() - Duration::hours(1),
+            base() - Duration::days(1),
+            base() - Duration::days(1) - Duration::hours(1),
+            base() - Duration::days(2),
+        ];
+        assert_eq!(kept(&retained(&dates, &policy(0, 2, 0, 0))), vec![0, 2]);
+    }
+
+    #[test]
+    fn keeps_newest_snapshot_of_each_week_and_month() {
+        // Un instantané par jour sur 70 jours, du 30 juin (dimanche) en arrière
+        let dates: Vec<_> = (0..70).map(|i| base() - Duration::days(i)).collect();
+        // Semaines ISO : 24-30 juin puis 17-23 juin ; mois : juin, mai (31), avril (30)
+        assert_eq!(kept(&retained(&dates, &policy(0, 0, 2, 0))), vec![0, 7]);
+        assert_eq!(kept(&retained(&dates, &policy(0, 0, 0, 3))), vec![0, 30, 61]);
+        assert_eq!(kept(&retained(&dates, &policy(1, 3, 2, 3))), vec![0, 1, 2, 7, 30, 61]);
+    }
+}
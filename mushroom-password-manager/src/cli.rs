@@ -0,0 +1,311 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::api::client::ApiClient;
+use crate::export::{self, ExportFormat, RestoreMode};
+use crate::import::{self, Credentials, ImportFormat, ImportPreview};
+use crate::lockout::LoginLimiter;
+use crate::profile::ProfileStore;
+use crate::vault::e2e;
+use crate::vault::factors::KeyFile;
+
+const USAGE: &str = "Utilisation :
+  mushroom-password-manager                  lance l'interface graphique
+  mushroom-password-manager import <fichier> [options]
+  mushroom-password-manager export <fichier> [--format <archive|json|csv>] [--yes]
+  mushroom-password-manager restore <fichier> [--replace] [--dry-run] [--yes]
+
+Options d'import :
+  --format <bitwarden|chrome|edge|firefox|keepassxc|kdbx>   format du fichier (détecté sinon)
+  --key-file <fichier>      fichier clé d'une base KeePass (.kdbx)
+  --dry-run                 affiche l'aperçu sans rien importer
+  --include-duplicates      importe aussi les doublons dont le mot de passe diffère
+  --yes                     n'attend pas de confirmation
+
+Export : archive chiffrée par un mot de passe propre à l'export (par défaut),
+ou JSON/CSV en clair après confirmation.
+Restauration : fusionne l'archive (ou un export JSON) avec le coffre,
+ou le remplace entièrement avec --replace.";
+
+// Commandes en ligne de commande ; None si l'interface graphique doit être lancée
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = args.first()?;
+
+    let result = match command.as_str() {
+        "import" => run_import(&args[1..]),
+        "export" => run_export(&args[1..]),
+        "restore" => run_restore(&args[1..]),
+        "-h" | "--help" | "help" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(format!("Commande inconnue: {}\n\n{}", other, USAGE)),
+    };
+
+    Some(match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Erreur: {}", e);
+            1
+        }
+    })
+}
+
+struct ImportOptions {
+    path: PathBuf,
+    format: Option<ImportFormat>,
+    key_file: Option<PathBuf>,
+    dry_run: bool,
+    include_duplicates: bool,
+    assume_yes: bool,
+}
+
+fn parse_import_options(args: &[String]) -> Result<ImportOptions, String> {
+    let mut path = None;
+    let mut format = None;
+    let mut key_file = None;
+    let mut dry_run = false;
+    let mut include_duplicates = false;
+    let mut assume_yes = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                let name = args.next().ok_or("--format attend une valeur")?;
+                format = Some(ImportFormat::from_name(name).ok_or_else(|| format!("Format inconnu: {}", name))?);
+            }
+            "--key-file" => key_file = Some(PathBuf::from(args.next().ok_or("--key-file attend un fichier")?)),
+            "--dry-run" => dry_run = true,
+            "--include-duplicates" => include_duplicates = true,
+            "--yes" | "-y" => assume_yes = true,
+            option if option.starts_with("--") => return Err(format!("Option inconnue: {}", option)),
+            file => path = Some(PathBuf::from(file)),
+        }
+    }
+
+    Ok(ImportOptions {
+        path: path.ok_or_else(|| format!("Fichier à importer manquant\n\n{}", USAGE))?,
+        format,
+        key_file,
+        dry_run,
+        include_duplicates,
+        assume_yes,
+    })
+}
+
+fn run_import(args: &[String]) -> Result<(), String> {
+    let options = parse_import_options(args)?;
+    let data = std::fs::read(&options.path)
+        .map_err(|e| format!("Lecture de '{}' impossible: {}", options.path.display(), e))?;
+    let format = options
+        .format
+        .or_else(|| ImportFormat::detect(&options.path, &data))
+        .ok_or("Format non reconnu, précisez-le avec --format")?;
+
+    let mut credentials = Credentials::default();
+    if format.needs_password() {
+        if let Some(path) = &options.key_file {
+            credentials.key_file =
+                Some(std::fs::read(path).map_err(|e| format!("Lecture de '{}' impossible: {}", path.display(), e))?);
+        }
+        credentials.password = read_password("Mot de passe de la base : ")?;
+    }
+    let parsed = import::parse(format, &data, &credentials)?;
+
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    let client = connect(&runtime)?;
+    let existing = runtime.block_on(client.get_saved_passwords())?;
+
+    let mut preview = import::preview(format, parsed, &existing);
+    if options.include_duplicates {
+        for candidate in &mut preview.candidates {
+            candidate.selected |= matches!(candidate.duplicate, Some((import::Duplicate::Conflict, _)));
+        }
+    }
+
+    print_preview(&preview);
+
+    if options.dry_run || preview.selected_count() == 0 {
+        return Ok(());
+    }
+
+    if !options.assume_yes && !confirm(&format!("Importer {} entrée(s) ? [o/N] ", preview.selected_count()))? {
+        println!("Import annulé");
+        return Ok(());
+    }
+
+    let count = runtime.block_on(client.import_entries(&preview.selected_entries()))?;
+    println!("{} entrée(s) importée(s)", count);
+    Ok(())
+}
+
+fn run_export(args: &[String]) -> Result<(), String> {
+    let mut path = None;
+    let mut format = None;
+    let mut assume_yes = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                let name = args.next().ok_or("--format attend une valeur")?;
+                format = Some(ExportFormat::from_name(name).ok_or_else(|| format!("Format inconnu: {}", name))?);
+            }
+            "--yes" | "-y" => assume_yes = true,
+            option if option.starts_with("--") => return Err(format!("Option inconnue: {}", option)),
+            file => path = Some(PathBuf::from(file)),
+        }
+    }
+    let path = path.ok_or_else(|| format!("Fichier de destination manquant\n\n{}", USAGE))?;
+    let format = format.unwrap_or(ExportFormat::Archive);
+
+    if format.is_plaintext()
+        && !assume_yes
+        && !confirm("Les mots de passe seront lisibles par quiconque accède au fichier. Continuer ? [o/N] ")?
+    {
+        println!("Export annulé");
+        return Ok(());
+    }
+
+    let password = if format.is_plaintext() {
+        None
+    } else {
+        let password = read_password("Mot de passe de l'archive : ")?;
+        if read_password("Confirmez le mot de passe : ")? != password {
+            return Err(String::from("Les mots de passe ne correspondent pas"));
+        }
+        Some(password)
+    };
+
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    let entries = runtime.block_on(connect(&runtime)?.get_saved_passwords())?;
+    let data = export::export(format, &entries, password.as_deref())?;
+    export::write_private(&path, &data)?;
+
+    println!("{} entrée(s) exportée(s) dans '{}'", entries.len(), path.display());
+    Ok(())
+}
+
+fn run_restore(args: &[String]) -> Result<(), String> {
+    let mut path = None;
+    let mut mode = RestoreMode::Merge;
+    let mut dry_run = false;
+    let mut assume_yes = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--replace" => mode = RestoreMode::Replace,
+            "--dry-run" => dry_run = true,
+            "--yes" | "-y" => assume_yes = true,
+            option if option.starts_with("--") => return Err(format!("Option inconnue: {}", option)),
+            file => path = Some(PathBuf::from(file)),
+        }
+    }
+    let path = path.ok_or_else(|| format!("Fichier à restaurer manquant\n\n{}", USAGE))?;
+    let data = std::fs::read(&path).map_err(|e| format!("Lecture de '{}' impossible: {}", path.display(), e))?;
+
+    let password = if export::archive::is_archive(&data) {
+        read_password("Mot de passe de l'archive : ")?
+    } else {
+        String::new()
+    };
+    let restored = export::read_export(&data, &password)?;
+
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    let client = connect(&runtime)?;
+    let existing = runtime.block_on(client.get_saved_passwords())?;
+    let plan = export::plan_restore(restored, &existing, mode);
+
+    println!("{} : {}", mode, plan.summary());
+    for entry in &plan.removed {
+        println!("  - {} ({})", entry.service_name, entry.id);
+    }
+
+    if dry_run || plan.is_empty() {
+        return Ok(());
+    }
+    if !assume_yes && !confirm("Appliquer ces modifications ? [o/N] ")? {
+        println!("Restauration annulée");
+        return Ok(());
+    }
+
+    runtime
+        .block_on(export::apply_restore(&client, &plan))
+        .map_err(|e| e.to_string())?;
+    println!("Restauration terminée");
+    Ok(())
+}
+
+fn print_preview(preview: &ImportPreview) {
+    println!(
+        "{} : {} entrée(s) lue(s), {} doublon(s), {} sélectionnée(s)",
+        preview.format,
+        preview.candidates.len(),
+        preview.duplicate_count(),
+        preview.selected_count(),
+    );
+
+    for candidate in &preview.candidates {
+        let entry = &candidate.entry;
+        let flag = match &candidate.duplicate {
+            Some((duplicate, existing)) => format!("  [{} : {}]", duplicate, existing),
+            None => String::new(),
+        };
+        println!(
+            "  [{}] {} | {} | {} | {}{}",
+            if candidate.selected { "x" } else { " " },
+            entry.service_name,
+            entry.service_url,
+            entry.username,
+            entry.folder,
+            flag,
+        );
+    }
+
+    for warning in &preview.warnings {
+        println!("  ! {}", warning);
+    }
+}
+
+// Client du profil courant, authentifié, avec la clé du coffre dérivée du mot de passe maître
+fn connect(runtime: &tokio::runtime::Runtime) -> Result<ApiClient, String> {
+    let profiles = ProfileStore::load()?;
+    let profile = profiles.current();
+    let password = read_password(&format!("Mot de passe maître ({}) : ", profile.name))?;
+    let key_file = profile.key_file.as_deref().map(KeyFile::read).transpose()?;
+    let mut client = runtime.block_on(ApiClient::open(&profile.backend, &password))?;
+    runtime.block_on(client.authenticate())?;
+    let (cipher, params) = runtime.block_on(e2e::unlock(&client, &password, key_file))?;
+    if let Some(totp) = cipher.second_factor(&params.factors)? {
+        let code = read_password("Code TOTP : ")?;
+        // Le dernier pas accepté est partagé avec l'interface : un code déjà utilisé est refusé
+        let mut limiter = LoginLimiter::open(&profile.data_dir()?);
+        let step = e2e::verify_second_factor(&totp, &code, limiter.now(), limiter.second_factor_step())
+            .ok_or_else(|| String::from("Code TOTP incorrect"))?;
+        limiter.record_second_factor(step)?;
+    }
+    client.set_cipher(Arc::new(cipher));
+    Ok(client)
+}
+
+fn confirm(prompt: &str) -> Result<bool, String> {
+    print!("{}", prompt);
+    io::stdout().flush().map_err(|e| e.to_string())?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer).map_err(|e| e.to_string())?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "o" | "oui" | "y" | "yes"))
+}
+
+// Saisie masquée au terminal ; lue sur l'entrée standard si elle est redirigée
+fn read_password(prompt: &str) -> Result<String, String> {
+    if io::stdin().is_terminal() {
+        return rpassword::prompt_password(prompt).map_err(|e| e.to_string());
+    }
+
+    let mut password = String::new();
+    io::stdin().lock().read_line(&mut password).map_err(|e| e.to_string())?;
+    Ok(password.trim_end_matches(['\r', '\n']).to_string())
+}
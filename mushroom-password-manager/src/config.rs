@@ -0,0 +1,308 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::api::client::DEFAULT_API_URL;
+use crate::i18n;
+use crate::paths;
+
+const CONFIG_FILE: &str = "config.toml";
+
+// Préférences de l'application, communes à tous les profils, dans le dossier de configuration
+// (~/.config/mushroom-password-manager/config.toml sous Linux). Toute section absente du
+// fichier prend ses valeurs par défaut
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct AppConfig {
+    pub generator: GeneratorSettings,
+    pub clipboard: ClipboardSettings,
+    pub lock: LockSettings,
+    pub display: DisplaySettings,
+    pub backend: BackendSettings,
+    pub appearance: AppearanceSettings,
+    pub logging: LoggingSettings,
+}
+
+// Mots de passe générés : lettres minuscules et majuscules, plus les classes choisies
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct GeneratorSettings {
+    pub length: usize,
+    pub digits: bool,
+    pub symbols: bool,
+}
+
+impl GeneratorSettings {
+    pub const MIN_LENGTH: usize = 8;
+    pub const MAX_LENGTH: usize = 128;
+}
+
+// Mêmes réglages que l'ancien générateur du serveur
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        GeneratorSettings { length: 25, digits: false, symbols: true }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct ClipboardSettings {
+    // Le presse-papiers est vidé après ce délai
+    pub clear_after: Delay,
+}
+
+impl Default for ClipboardSettings {
+    fn default() -> Self {
+        ClipboardSettings { clear_after: Delay(30) }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct LockSettings {
+    // Verrouillage après ce délai sans action de l'utilisateur
+    pub idle: Delay,
+}
+
+impl Default for LockSettings {
+    fn default() -> Self {
+        LockSettings { idle: Delay(5 * 60) }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct DisplaySettings {
+    // Un mot de passe affiché en clair est masqué de nouveau après ce délai
+    pub hide_password_after: Delay,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        DisplaySettings { hide_password_after: Delay(10) }
+    }
+}
+
+// Coffre proposé par défaut pour un nouveau profil ; l'URL sert aussi quand le champ reste vide
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct BackendSettings {
+    pub kind: BackendKind,
+    pub server_url: String,
+}
+
+impl Default for BackendSettings {
+    fn default() -> Self {
+        BackendSettings { kind: BackendKind::Api, server_url: DEFAULT_API_URL.to_string() }
+    }
+}
+
+// Type de coffre d'un nouveau profil
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendKind {
+    #[default]
+    Api,
+    LocalFile,
+}
+
+impl BackendKind {
+    pub const ALL: [BackendKind; 2] = [BackendKind::Api, BackendKind::LocalFile];
+}
+
+impl fmt::Display for BackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let id = match self {
+            BackendKind::Api => "backend-api",
+            BackendKind::LocalFile => "backend-local-file",
+        };
+        write!(f, "{}", i18n::tr(id))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct AppearanceSettings {
+    pub theme: ThemeChoice,
+    pub language: Language,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeChoice {
+    // Suit l'indication de l'environnement, clair à défaut
+    #[default]
+    System,
+    Light,
+    Dark,
+    HighContrast,
+}
+
+impl ThemeChoice {
+    pub const ALL: [ThemeChoice; 4] = [ThemeChoice::System, ThemeChoice::Light, ThemeChoice::Dark, ThemeChoice::HighContrast];
+}
+
+impl fmt::Display for ThemeChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let id = match self {
+            ThemeChoice::System => "theme-system",
+            ThemeChoice::Light => "theme-light",
+            ThemeChoice::Dark => "theme-dark",
+            ThemeChoice::HighContrast => "theme-high-contrast",
+        };
+        write!(f, "{}", i18n::tr(id))
+    }
+}
+
+// Langue de l'interface
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "fr")]
+    French,
+    #[serde(rename = "en")]
+    English,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::French, Language::English];
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Language::French => "Français",
+            Language::English => "English",
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct LoggingSettings {
+    // Filtre du journal de l'application (ex: "info", "mushroom_password_manager::api=debug")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+}
+
+// Délai en secondes ; 0 désactive l'action
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(transparent)]
+pub struct Delay(pub u64);
+
+impl Delay {
+    pub const NEVER: Delay = Delay(0);
+    pub const CLIPBOARD: [Delay; 6] = [Delay(10), Delay(20), Delay(30), Delay(60), Delay(120), Delay::NEVER];
+    pub const REVEAL: [Delay; 6] = [Delay(5), Delay(10), Delay(20), Delay(30), Delay(60), Delay::NEVER];
+    pub const IDLE: [Delay; 7] = [Delay(60), Delay(5 * 60), Delay(10 * 60), Delay(15 * 60), Delay(30 * 60), Delay(60 * 60), Delay::NEVER];
+
+    pub fn duration(&self) -> Option<std::time::Duration> {
+        (self.0 > 0).then(|| std::time::Duration::from_secs(self.0))
+    }
+}
+
+impl fmt::Display for Delay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self.0 {
+            0 => i18n::tr("delay-never"),
+            seconds if seconds % 60 == 0 => i18n::tr_args("delay-minutes", &[("minutes", (seconds / 60).into())]),
+            seconds => i18n::tr_args("delay-seconds", &[("seconds", seconds.into())]),
+        };
+        f.write_str(&text)
+    }
+}
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(paths::config_dir()?.join(CONFIG_FILE))
+}
+
+impl AppConfig {
+    pub fn load() -> Result<Self, String> {
+        Self::load_from(&config_path()?)
+    }
+
+    fn load_from(path: &Path) -> Result<Self, String> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(AppConfig::default()),
+            Err(e) => return Err(format!("Lecture de '{}' impossible: {}", path.display(), e)),
+        };
+        let mut config: AppConfig =
+            toml::from_str(&text).map_err(|e| format!("Configuration '{}' illisible: {}", path.display(), e))?;
+        // Fichier modifié à la main : une longueur hors bornes donnerait des mots de passe vides ou démesurés
+        config.generator.length = config.generator.length.clamp(GeneratorSettings::MIN_LENGTH, GeneratorSettings::MAX_LENGTH);
+        Ok(config)
+    }
+
+    // Réécrit le fichier via un fichier temporaire renommé
+    pub fn save(&self) -> Result<(), String> {
+        let path = config_path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Création du dossier '{}' impossible: {}", dir.display(), e))?;
+        }
+        let text = toml::to_string_pretty(self).map_err(|e| format!("Erreur d'encodage TOML: {}", e))?;
+        let temporary = path.with_extension("tmp");
+        std::fs::write(&temporary, text)
+            .and_then(|_| std::fs::rename(&temporary, &path))
+            .map_err(|e| format!("Écriture de '{}' impossible: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestDir;
+
+    #[test]
+    fn missing_file_gives_defaults() {
+        let dir = TestDir::new("missing");
+        assert_eq!(AppConfig::load_from(&dir.0.join(CONFIG_FILE)).unwrap(), AppConfig::default());
+    }
+
+    #[test]
+    fn missing_sections_and_fields_keep_their_defaults() {
+        let dir = TestDir::new("partial");
+        let path = dir.0.join(CONFIG_FILE);
+        std::fs::write(&path, "[generator]\nlength = 40\n\n[appearance]\nlanguage = \"en\"\ntheme = \"high_contrast\"\n").unwrap();
+        let config = AppConfig::load_from(&path).unwrap();
+        assert_eq!(config.generator, GeneratorSettings { length: 40, ..GeneratorSettings::default() });
+        assert_eq!(config.appearance, AppearanceSettings { theme: ThemeChoice::HighContrast, language: Language::English });
+        assert_eq!(config.clipboard, ClipboardSettings::default());
+        assert_eq!(config.backend, BackendSettings::default());
+
+        std::fs::write(&path, "[generator]\nlength = \"long\"\n").unwrap();
+        assert!(AppConfig::load_from(&path).is_err());
+    }
+
+    #[test]
+    fn out_of_range_generator_lengths_are_clamped() {
+        let dir = TestDir::new("config-length");
+        let path = dir.0.join(CONFIG_FILE);
+        for (length, expected) in [(0, GeneratorSettings::MIN_LENGTH), (100_000, GeneratorSettings::MAX_LENGTH), (40, 40)] {
+            std::fs::write(&path, format!("[generator]\nlength = {}\n", length)).unwrap();
+            assert_eq!(AppConfig::load_from(&path).unwrap().generator.length, expected);
+        }
+    }
+
+    #[test]
+    fn configuration_round_trips_through_toml() {
+        let mut config = AppConfig::default();
+        config.clipboard.clear_after = Delay::NEVER;
+        config.backend.kind = BackendKind::LocalFile;
+        config.logging.level = Some(String::from("debug"));
+        let text = toml::to_string_pretty(&config).unwrap();
+        assert!(text.contains("clear_after = 0"));
+        assert!(text.contains("kind = \"local_file\""));
+        assert_eq!(toml::from_str::<AppConfig>(&text).unwrap(), config);
+    }
+
+    #[test]
+    fn delays_convert_and_display() {
+        assert_eq!(Delay::NEVER.duration(), None);
+        assert_eq!(Delay(90).duration(), Some(std::time::Duration::from_secs(90)));
+        assert_eq!(Delay::NEVER.to_string(), "Jamais");
+        assert_eq!(Delay(300).to_string(), "5 min");
+        assert_eq!(Delay(45).to_string(), "45 s");
+    }
+}
@@ -0,0 +1,259 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::export::ExportDocument;
+use crate::vault::crypto::{self, base64_bytes, KdfParams, KEY_LENGTH};
+use crate::vault::entry::ENTRY_SCHEMA_VERSION;
+
+pub const ARCHIVE_FORMAT: &str = "mushroom-archive";
+pub const ARCHIVE_VERSION: u32 = 1;
+
+const KDF_ALGORITHM: &str = "argon2id";
+const CIPHER_ALGORITHM: &str = "xchacha20-poly1305";
+const CHECKSUM_ALGORITHM: &str = "sha256";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArchiveKdf {
+    pub algorithm: String,
+    #[serde(flatten)]
+    pub params: KdfParams,
+    #[serde(with = "base64_bytes")]
+    pub salt: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArchiveCipher {
+    pub algorithm: String,
+    #[serde(with = "base64_bytes")]
+    pub nonce: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArchiveChecksum {
+    pub algorithm: String,
+    pub value: String,
+}
+
+// En-tête lisible sans mot de passe ; il est authentifié avec le contenu chiffré
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArchiveHeader {
+    pub format: String,
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub entry_schema_version: u32,
+    pub entry_count: usize,
+    // Absent : archive scellée directement par une clé aléatoire, sans mot de passe
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kdf: Option<ArchiveKdf>,
+    pub cipher: ArchiveCipher,
+    // Empreinte du contenu en clair, vérifiée après déchiffrement
+    pub checksum: ArchiveChecksum,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ArchiveFile {
+    #[serde(flatten)]
+    header: ArchiveHeader,
+    #[serde(with = "base64_bytes")]
+    payload: Vec<u8>,
+}
+
+impl ArchiveHeader {
+    // Données associées du chiffrement : toute modification de l'en-tête est détectée
+    fn associated_data(&self) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(self).map_err(|e| format!("Erreur d'encodage JSON: {}", e))
+    }
+}
+
+pub fn is_archive(data: &[u8]) -> bool {
+    read_header(data).is_ok()
+}
+
+// Chiffre le document avec une clé dérivée du mot de passe d'export,
+// indépendante de celle du coffre
+pub fn write(document: &ExportDocument, password: &str, params: &KdfParams) -> Result<Vec<u8>, String> {
+    if password.is_empty() {
+        return Err(String::from("Le mot de passe de l'archive ne peut pas être vide"));
+    }
+    let salt = crypto::random_bytes::<{ crypto::SALT_LENGTH }>();
+    let key = Zeroizing::new(crypto::derive_key(password, &salt, params)?);
+    let kdf = ArchiveKdf {
+        algorithm: String::from(KDF_ALGORITHM),
+        params: *params,
+        salt: salt.to_vec(),
+    };
+    seal(document, &key, Some(kdf))
+}
+
+// Chiffre le document directement avec `key`, déjà aléatoire : rien à dériver
+pub fn write_with_key(document: &ExportDocument, key: &[u8; KEY_LENGTH]) -> Result<Vec<u8>, String> {
+    seal(document, key, None)
+}
+
+fn seal(document: &ExportDocument, key: &[u8; KEY_LENGTH], kdf: Option<ArchiveKdf>) -> Result<Vec<u8>, String> {
+    let plaintext = Zeroizing::new(serde_json::to_vec(document).map_err(|e| format!("Erreur d'encodage JSON: {}", e))?);
+    let nonce = crypto::random_bytes::<{ crypto::NONCE_LENGTH }>();
+
+    let header = ArchiveHeader {
+        format: String::from(ARCHIVE_FORMAT),
+        version: ARCHIVE_VERSION,
+        created_at: document.exported_at,
+        entry_schema_version: ENTRY_SCHEMA_VERSION,
+        entry_count: document.entries.len(),
+        kdf,
+        cipher: ArchiveCipher {
+            algorithm: String::from(CIPHER_ALGORITHM),
+            nonce: nonce.to_vec(),
+        },
+        checksum: ArchiveChecksum {
+            algorithm: String::from(CHECKSUM_ALGORITHM),
+            value: crypto::sha256_hex(&plaintext),
+        },
+    };
+
+    let payload = crypto::seal(key, &nonce, &header.associated_data()?, &plaintext)?;
+    serde_json::to_vec_pretty(&ArchiveFile { header, payload }).map_err(|e| format!("Erreur d'encodage JSON: {}", e))
+}
+
+// En-tête seul : date et nombre d'entrées sans avoir à déchiffrer
+pub fn read_header(data: &[u8]) -> Result<ArchiveHeader, String> {
+    let file: ArchiveFile = serde_json::from_slice(data).map_err(|_| String::from("Ce fichier n'est pas une archive Mushroom"))?;
+    let header = file.header;
+    if header.format != ARCHIVE_FORMAT {
+        return Err(String::from("Ce fichier n'est pas une archive Mushroom"));
+    }
+    if header.version > ARCHIVE_VERSION {
+        return Err(format!("Archive au format v{} : mettez l'application à jour", header.version));
+    }
+    let kdf = header.kdf.as_ref().map_or(KDF_ALGORITHM, |kdf| kdf.algorithm.as_str());
+    if kdf != KDF_ALGORITHM || header.cipher.algorithm != CIPHER_ALGORITHM {
+        return Err(format!("Algorithmes d'archive non supportés: {} / {}", kdf, header.cipher.algorithm));
+    }
+    // Une archive forgée ne doit pas imposer une dérivation démesurée
+    if let Some(kdf) = &header.kdf {
+        kdf.params.check_limits()?;
+    }
+    Ok(header)
+}
+
+pub fn read(data: &[u8], password: &str) -> Result<ExportDocument, String> {
+    let file = read_file(data)?;
+    let Some(kdf) = &file.header.kdf else {
+        return Err(String::from("Cette archive est une sauvegarde automatique : restaurez-la depuis ses sauvegardes"));
+    };
+    let key = Zeroizing::new(crypto::derive_key(password, &kdf.salt, &kdf.params)?);
+    open(&file, &key)
+}
+
+// Archive scellée par `write_with_key`
+pub fn read_with_key(data: &[u8], key: &[u8; KEY_LENGTH]) -> Result<ExportDocument, String> {
+    let file = read_file(data)?;
+    if file.header.kdf.is_some() {
+        return Err(String::from("Cette archive est protégée par un mot de passe"));
+    }
+    open(&file, key)
+}
+
+fn read_file(data: &[u8]) -> Result<ArchiveFile, String> {
+    read_header(data)?;
+    serde_json::from_slice(data).map_err(|e| format!("Archive illisible: {}", e))
+}
+
+fn open(file: &ArchiveFile, key: &[u8; KEY_LENGTH]) -> Result<ExportDocument, String> {
+    let header = &file.header;
+    let plaintext = Zeroizing::new(crypto::open(key, &header.cipher.nonce, &header.associated_data()?, &file.payload)?);
+
+    if header.checksum.algorithm != CHECKSUM_ALGORITHM || crypto::sha256_hex(&plaintext) != header.checksum.value {
+        return Err(String::from("Archive corrompue : somme de contrôle invalide"));
+    }
+
+    let document: ExportDocument =
+        serde_json::from_slice(&plaintext).map_err(|e| format!("Contenu de l'archive illisible: {}", e))?;
+    if document.entries.len() != header.entry_count {
+        return Err(String::from("Archive corrompue : nombre d'entrées incohérent"));
+    }
+    Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::entry::Entry;
+    use crate::test_support::FAST;
+
+    // Paramètres Argon2 minimaux : les tests n'ont pas à payer le coût réel
+
+    fn document() -> ExportDocument {
+        let mut entry = Entry::new("forge.example.com", "Forge");
+        entry.service_password = "hunter2".into();
+        ExportDocument::new(&[entry])
+    }
+
+    #[test]
+    fn archive_round_trips() {
+        let data = write(&document(), "phrase de passe", &FAST).unwrap();
+        assert!(is_archive(&data));
+
+        let header = read_header(&data).unwrap();
+        assert_eq!(header.entry_count, 1);
+        assert_eq!(header.kdf.unwrap().params, FAST);
+
+        let restored = read(&data, "phrase de passe").unwrap();
+        assert_eq!(&*restored.entries[0].entry.service_password, "hunter2");
+        assert_eq!(restored.entries[0].id, "forge.example.com");
+    }
+
+    #[test]
+    fn rejects_empty_and_wrong_passwords() {
+        assert!(write(&document(), "", &FAST).is_err());
+        let data = write(&document(), "phrase de passe", &FAST).unwrap();
+        assert!(read(&data, "autre phrase").is_err());
+    }
+
+    #[test]
+    fn detects_header_tampering() {
+        let data = write(&document(), "phrase de passe", &FAST).unwrap();
+        let mut file: serde_json::Value = serde_json::from_slice(&data).unwrap();
+        file["entry_count"] = serde_json::json!(2);
+        let tampered = serde_json::to_vec(&file).unwrap();
+        // L'en-tête est authentifié : le déchiffrement échoue
+        assert!(read(&tampered, "phrase de passe").is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_derivation_costs() {
+        let data = write(&document(), "phrase de passe", &FAST).unwrap();
+        let mut file: serde_json::Value = serde_json::from_slice(&data).unwrap();
+        file["kdf"]["memory_kib"] = serde_json::json!(u32::MAX);
+        let crafted = serde_json::to_vec(&file).unwrap();
+        assert!(read_header(&crafted).is_err());
+        assert!(read(&crafted, "phrase de passe").is_err());
+    }
+
+    #[test]
+    fn key_sealed_archives_need_their_key() {
+        let key = crypto::random_bytes::<KEY_LENGTH>();
+        let data = write_with_key(&document(), &key).unwrap();
+        assert!(read_header(&data).unwrap().kdf.is_none());
+        assert_eq!(&*read_with_key(&data, &key).unwrap().entries[0].entry.service_password, "hunter2");
+
+        assert!(read_with_key(&data, &crypto::random_bytes::<KEY_LENGTH>()).is_err());
+        // Pas de mot de passe qui ouvrirait une archive scellée par une clé, ni l'inverse
+        assert!(read(&data, "phrase de passe").is_err());
+        let protected = write(&document(), "phrase de passe", &FAST).unwrap();
+        assert!(read_with_key(&protected, &key).is_err());
+    }
+
+    #[test]
+    fn rejects_foreign_files_and_newer_versions() {
+        assert!(!is_archive(b"{\"format\": \"autre\"}"));
+        assert!(!is_archive(b"name,url"));
+
+        let data = write(&document(), "phrase de passe", &FAST).unwrap();
+        let mut file: serde_json::Value = serde_json::from_slice(&data).unwrap();
+        file["version"] = serde_json::json!(ARCHIVE_VERSION + 1);
+        let newer = serde_json::to_vec(&file).unwrap();
+        assert!(read_header(&newer).unwrap_err().contains("mettez l'application à jour"));
+    }
+}
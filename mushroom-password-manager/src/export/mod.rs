@@ -0,0 +1,401 @@
+pub mod archive;
+pub mod plain;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+use crate::api::client::ApiClient;
+use crate::api::error::ApiError;
+use crate::i18n;
+use crate::vault::crypto::KdfParams;
+use crate::vault::entry::{Entry, ENTRY_SCHEMA_VERSION};
+
+pub const EXPORT_FORMAT: &str = "mushroom-export";
+pub const EXPORT_VERSION: u32 = 1;
+
+// Formats de sortie ; seule l'archive est chiffrée
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Archive,
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Archive, ExportFormat::Json, ExportFormat::Csv];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "archive" | "mushroom" => Some(ExportFormat::Archive),
+            "json" => Some(ExportFormat::Json),
+            "csv" => Some(ExportFormat::Csv),
+            _ => None,
+        }
+    }
+
+    // Les mots de passe sont lisibles par quiconque accède au fichier
+    pub fn is_plaintext(&self) -> bool {
+        !matches!(self, ExportFormat::Archive)
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Archive => "mushroom",
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let id = match self {
+            ExportFormat::Archive => "export-format-archive",
+            ExportFormat::Json => "export-format-json",
+            ExportFormat::Csv => "export-format-csv",
+        };
+        write!(f, "{}", i18n::tr(id))
+    }
+}
+
+// Entrée exportée avec sa clé côté serveur, que le schéma d'entrée ne sérialise pas
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExportedEntry {
+    pub id: String,
+    #[serde(flatten)]
+    pub entry: Entry,
+}
+
+// Contenu d'un export : chiffré dans une archive, tel quel en JSON
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExportDocument {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub entry_schema_version: u32,
+    pub entries: Vec<ExportedEntry>,
+}
+
+impl ExportDocument {
+    pub fn new(entries: &[Entry]) -> Self {
+        ExportDocument {
+            format: String::from(EXPORT_FORMAT),
+            version: EXPORT_VERSION,
+            exported_at: Utc::now(),
+            entry_schema_version: ENTRY_SCHEMA_VERSION,
+            entries: entries
+                .iter()
+                .map(|entry| ExportedEntry {
+                    id: entry.id.clone(),
+                    entry: entry.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    // Entrées migrées vers le schéma courant
+    pub fn into_entries(self) -> Result<Vec<Entry>, String> {
+        if self.entry_schema_version > ENTRY_SCHEMA_VERSION {
+            return Err(format!(
+                "Export au schéma d'entrée v{} : mettez l'application à jour",
+                self.entry_schema_version
+            ));
+        }
+        Ok(self
+            .entries
+            .into_iter()
+            .map(|exported| {
+                let mut entry = exported.entry;
+                entry.id = if exported.id.is_empty() { entry.service_url.clone() } else { exported.id };
+                entry.migrate();
+                entry
+            })
+            .collect())
+    }
+}
+
+pub fn export(format: ExportFormat, entries: &[Entry], password: Option<&str>) -> Result<Vec<u8>, String> {
+    let document = ExportDocument::new(entries);
+    match format {
+        ExportFormat::Archive => {
+            let password = password.ok_or("Mot de passe de l'archive manquant")?;
+            archive::write(&document, password, &KdfParams::default())
+        }
+        ExportFormat::Json => plain::write_json(&document),
+        ExportFormat::Csv => plain::write_csv(entries),
+    }
+}
+
+// Relit une archive chiffrée ou un export JSON en clair
+pub fn read_export(data: &[u8], password: &str) -> Result<Vec<Entry>, String> {
+    let document = if archive::is_archive(data) {
+        archive::read(data, password)?
+    } else {
+        plain::read_json(data)?
+    };
+    document.into_entries()
+}
+
+// Écrit le fichier lisible par son seul propriétaire
+pub fn write_private(path: &Path, data: &[u8]) -> Result<(), String> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(data))
+        .map_err(|e| format!("Écriture de '{}' impossible: {}", path.display(), e))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreMode {
+    // Ajoute les entrées absentes et met à jour celles de l'export plus récentes
+    Merge,
+    // Le coffre devient identique à l'export
+    Replace,
+}
+
+impl RestoreMode {
+    pub const ALL: [RestoreMode; 2] = [RestoreMode::Merge, RestoreMode::Replace];
+}
+
+impl fmt::Display for RestoreMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let id = match self {
+            RestoreMode::Merge => "restore-mode-merge",
+            RestoreMode::Replace => "restore-mode-replace",
+        };
+        write!(f, "{}", i18n::tr(id))
+    }
+}
+
+// Modifications à appliquer au coffre, affichées avant confirmation
+#[derive(Debug, Clone, Default)]
+pub struct RestorePlan {
+    pub added: Vec<Entry>,
+    pub updated: Vec<Entry>,
+    pub removed: Vec<Entry>,
+    pub unchanged: usize,
+    // Entrées du coffre plus récentes que celles de l'export, conservées
+    pub kept_newer: usize,
+}
+
+impl RestorePlan {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = i18n::tr_args(
+            "restore-summary",
+            &[
+                ("added", self.added.len().into()),
+                ("updated", self.updated.len().into()),
+                ("removed", self.removed.len().into()),
+                ("unchanged", self.unchanged.into()),
+            ],
+        );
+        if self.kept_newer > 0 {
+            summary.push_str(&i18n::tr_args("restore-summary-kept-newer", &[("count", self.kept_newer.into())]));
+        }
+        summary
+    }
+}
+
+pub fn plan_restore(restored: Vec<Entry>, existing: &[Entry], mode: RestoreMode) -> RestorePlan {
+    let current: HashMap<&str, &Entry> = existing.iter().map(|entry| (entry.id.as_str(), entry)).collect();
+    let restored_ids: HashSet<String> = restored.iter().map(|entry| entry.id.clone()).collect();
+    let mut plan = RestorePlan::default();
+
+    for entry in restored {
+        match current.get(entry.id.as_str()) {
+            None => plan.added.push(entry),
+            Some(existing) if **existing == entry => plan.unchanged += 1,
+            // En fusion, la version la plus récente l'emporte
+            Some(existing) if mode == RestoreMode::Merge && existing.modified_at > entry.modified_at => {
+                plan.kept_newer += 1
+            }
+            Some(_) => plan.updated.push(entry),
+        }
+    }
+
+    if mode == RestoreMode::Replace {
+        plan.removed = existing
+            .iter()
+            .filter(|entry| !restored_ids.contains(&entry.id))
+            .cloned()
+            .collect();
+    }
+
+    plan
+}
+
+// Modifications écrites par une restauration, dont les entrées supprimées, pour le journal d'activité
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RestoreReport {
+    pub applied: usize,
+    pub removed: Vec<String>,
+}
+
+// Échec d'une restauration : les `applied` premières modifications sont déjà écrites,
+// dont la suppression des entrées `removed`
+#[derive(Debug, Clone)]
+pub struct RestoreError {
+    pub applied: usize,
+    pub removed: Vec<String>,
+    pub error: String,
+}
+
+impl fmt::Display for RestoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = i18n::tr_args("restore-error", &[("count", self.applied.into()), ("error", self.error.as_str().into())]);
+        f.write_str(&text)
+    }
+}
+
+pub async fn apply_restore(client: &ApiClient, plan: &RestorePlan) -> Result<RestoreReport, RestoreError> {
+    let mut applied = 0;
+    for entries in [&plan.added, &plan.updated] {
+        match client.import_entries(entries).await {
+            Ok(count) => applied += count,
+            Err(ApiError::Import { imported, source }) => {
+                return Err(RestoreError { applied: applied + imported, removed: Vec::new(), error: source.to_string() })
+            }
+            Err(e) => return Err(RestoreError { applied, removed: Vec::new(), error: e.to_string() }),
+        }
+    }
+    let mut removed = Vec::with_capacity(plan.removed.len());
+    for entry in &plan.removed {
+        if let Err(e) = client.delete_password(&entry.id).await {
+            return Err(RestoreError { applied, removed, error: e.to_string() });
+        }
+        removed.push(entry.id.clone());
+        applied += 1;
+    }
+    Ok(RestoreReport { applied, removed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn entry(url: &str, password: &str) -> Entry {
+        let mut entry = Entry::new(url, url);
+        entry.service_password = password.into();
+        entry
+    }
+
+    #[test]
+    fn parses_format_names() {
+        assert_eq!(ExportFormat::from_name("MUSHROOM"), Some(ExportFormat::Archive));
+        assert_eq!(ExportFormat::from_name("csv"), Some(ExportFormat::Csv));
+        assert_eq!(ExportFormat::from_name("xml"), None);
+        assert!(!ExportFormat::Archive.is_plaintext());
+        assert!(ExportFormat::Json.is_plaintext());
+    }
+
+    #[test]
+    fn document_restores_ids_and_migrates_entries() {
+        let mut legacy = entry("mail.example.com", "secret");
+        legacy.schema_version = 1;
+        legacy.email = String::from("alice@example.com");
+        legacy.id = String::from("clé-serveur");
+        let mut document = ExportDocument::new(&[legacy]);
+        // Ancien export sans clé : l'URL sert d'identifiant
+        document.entries.push(ExportedEntry { id: String::new(), entry: entry("forge.example.com", "x") });
+
+        let entries = document.into_entries().unwrap();
+        assert_eq!(entries[0].id, "clé-serveur");
+        assert_eq!(entries[0].username, "alice@example.com");
+        assert_eq!(entries[0].schema_version, ENTRY_SCHEMA_VERSION);
+        assert_eq!(entries[1].id, "forge.example.com");
+    }
+
+    #[test]
+    fn rejects_newer_entry_schema() {
+        let mut document = ExportDocument::new(&[]);
+        document.entry_schema_version = ENTRY_SCHEMA_VERSION + 1;
+        assert!(document.into_entries().is_err());
+    }
+
+    #[test]
+    fn json_export_round_trips() {
+        let entries = vec![entry("a.example.com", "un"), entry("b.example.com", "deux")];
+        let data = export(ExportFormat::Json, &entries, None).unwrap();
+        let restored = read_export(&data, "").unwrap();
+        assert_eq!(restored, entries);
+    }
+
+    #[test]
+    fn archive_export_requires_a_password() {
+        assert!(export(ExportFormat::Archive, &[], None).is_err());
+    }
+
+    #[test]
+    fn merge_plan_keeps_newer_vault_entries() {
+        let unchanged = entry("same.example.com", "pareil");
+        let mut newer_in_vault = entry("newer.example.com", "coffre");
+        let mut older_in_export = newer_in_vault.clone();
+        older_in_export.service_password = "export".into();
+        older_in_export.modified_at = newer_in_vault.modified_at.map(|t| t - Duration::days(1));
+        newer_in_vault.modified_at = older_in_export.modified_at.map(|t| t + Duration::days(2));
+        let mut updated = entry("updated.example.com", "nouveau");
+        let mut stale = updated.clone();
+        stale.service_password = "ancien".into();
+        stale.modified_at = updated.modified_at.map(|t| t - Duration::days(1));
+        updated.modified_at = stale.modified_at.map(|t| t + Duration::days(2));
+        let only_in_vault = entry("vault.example.com", "local");
+        let added = entry("added.example.com", "ajout");
+
+        let existing = vec![unchanged.clone(), newer_in_vault, stale, only_in_vault];
+        let restored = vec![unchanged, older_in_export, updated.clone(), added.clone()];
+
+        let plan = plan_restore(restored, &existing, RestoreMode::Merge);
+        assert_eq!(plan.added, vec![added]);
+        assert_eq!(plan.updated, vec![updated]);
+        assert!(plan.removed.is_empty());
+        assert_eq!((plan.unchanged, plan.kept_newer), (1, 1));
+        assert_eq!(
+            plan.summary(),
+            "1 ajoutée(s), 1 mise(s) à jour, 0 supprimée(s), 1 inchangée(s), 1 plus récente(s) dans le coffre conservée(s)"
+        );
+    }
+
+    #[test]
+    fn replace_plan_overwrites_and_removes() {
+        let mut vault_version = entry("newer.example.com", "coffre");
+        let mut export_version = vault_version.clone();
+        export_version.service_password = "export".into();
+        vault_version.modified_at = export_version.modified_at.map(|t| t + Duration::days(1));
+        let only_in_vault = entry("vault.example.com", "local");
+
+        let plan = plan_restore(
+            vec![export_version.clone()],
+            &[vault_version, only_in_vault.clone()],
+            RestoreMode::Replace,
+        );
+        assert_eq!(plan.updated, vec![export_version]);
+        assert_eq!(plan.removed, vec![only_in_vault]);
+        assert_eq!(plan.kept_newer, 0);
+        assert!(!plan.is_empty());
+
+        let empty = plan_restore(Vec::new(), &[], RestoreMode::Replace);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn restore_error_reports_applied_changes() {
+        let error = RestoreError { applied: 3, removed: Vec::new(), error: String::from("Erreur réseau") };
+        assert_eq!(error.to_string(), "Restauration interrompue après 3 modifications: Erreur réseau");
+    }
+}
@@ -0,0 +1,95 @@
+use crate::export::{ExportDocument, EXPORT_FORMAT, EXPORT_VERSION};
+use crate::vault::entry::{Entry, FieldKind};
+
+pub fn write_json(document: &ExportDocument) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(document).map_err(|e| format!("Erreur d'encodage JSON: {}", e))
+}
+
+pub fn read_json(data: &[u8]) -> Result<ExportDocument, String> {
+    let document: ExportDocument = serde_json::from_slice(data)
+        .map_err(|_| String::from("Ce fichier n'est ni une archive ni un export JSON Mushroom"))?;
+    if document.format != EXPORT_FORMAT {
+        return Err(String::from("Ce fichier n'est ni une archive ni un export JSON Mushroom"));
+    }
+    if document.version > EXPORT_VERSION {
+        return Err(format!("Export au format v{} : mettez l'application à jour", document.version));
+    }
+    Ok(document)
+}
+
+// Colonnes proches de celles de KeePassXC pour être relues par d'autres gestionnaires
+const CSV_HEADER: [&str; 11] = [
+    "name", "url", "username", "email", "password", "notes", "folder", "tags", "totp", "created", "modified",
+];
+
+pub fn write_csv(entries: &[Entry]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(CSV_HEADER).map_err(|e| e.to_string())?;
+
+    for entry in entries {
+        let totp = entry
+            .custom_fields
+            .iter()
+            .find(|field| field.kind == FieldKind::Totp)
+            .map(|field| field.value.as_str())
+            .unwrap_or("");
+        let timestamp = |value: Option<chrono::DateTime<chrono::Utc>>| value.map(|t| t.to_rfc3339()).unwrap_or_default();
+
+        writer
+            .write_record([
+                entry.service_name.as_str(),
+                entry.service_url.as_str(),
+                entry.username.as_str(),
+                entry.email.as_str(),
+                entry.service_password.as_str(),
+                entry.notes.as_str(),
+                entry.folder.as_str(),
+                entry.tags_text().as_str(),
+                totp,
+                timestamp(entry.created_at).as_str(),
+                timestamp(entry.modified_at).as_str(),
+            ])
+            .map_err(|e| e.to_string())?;
+    }
+
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::entry::CustomField;
+
+    #[test]
+    fn rejects_foreign_json() {
+        assert!(read_json(b"{\"format\": \"autre\", \"version\": 1}").is_err());
+        assert!(read_json(b"pas du json").is_err());
+
+        let mut document = ExportDocument::new(&[]);
+        document.version = EXPORT_VERSION + 1;
+        assert!(read_json(&write_json(&document).unwrap()).is_err());
+    }
+
+    #[test]
+    fn writes_csv_with_totp_and_tags() {
+        let mut entry = Entry::new("https://forge.example.com", "Forge, inc");
+        entry.service_password = "p\"ss".into();
+        entry.tags = vec![String::from("dev"), String::from("perso")];
+        entry.created_at = None;
+        entry.modified_at = None;
+        entry.custom_fields.push(CustomField {
+            name: String::from("otp"),
+            value: "otpauth://totp/Forge?secret=JBSWY3DPEHPK3PXP".into(),
+            kind: FieldKind::Totp,
+        });
+
+        let csv = String::from_utf8(write_csv(&[entry]).unwrap()).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("name,url,username,email,password,notes,folder,tags,totp,created,modified"));
+        assert_eq!(
+            lines.next(),
+            Some("\"Forge, inc\",https://forge.example.com,,,\"p\"\"ss\",,,\"dev, perso\",otpauth://totp/Forge?secret=JBSWY3DPEHPK3PXP,,")
+        );
+        assert_eq!(lines.next(), None);
+    }
+}
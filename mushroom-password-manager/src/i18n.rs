@@ -0,0 +1,164 @@
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+use unic_langid::{langid, LanguageIdentifier};
+
+use crate::config::Language;
+
+// Catalogues intégrés au binaire, un par langue ; le français sert de repli
+const FRENCH: &str = include_str!("../locales/fr.ftl");
+const ENGLISH: &str = include_str!("../locales/en.ftl");
+
+// Langue de l'interface, changée depuis les paramètres sans redémarrer
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+fn index(language: Language) -> usize {
+    match language {
+        Language::French => 0,
+        Language::English => 1,
+    }
+}
+
+fn bundle(locale: LanguageIdentifier, source: &str) -> FluentBundle<FluentResource> {
+    let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, errors)| {
+        log::error!(locale:% = locale; "Catalogue de traductions invalide: {:?}", errors);
+        resource
+    });
+    let mut bundle = FluentBundle::new_concurrent(vec![locale]);
+    // Sans marques d'isolation Unicode, que les polices de l'interface affichent en carrés
+    bundle.set_use_isolating(false);
+    if let Err(errors) = bundle.add_resource(resource) {
+        log::error!("Messages en double dans le catalogue: {:?}", errors);
+    }
+    bundle
+}
+
+fn bundles() -> &'static [FluentBundle<FluentResource>; 2] {
+    static BUNDLES: OnceLock<[FluentBundle<FluentResource>; 2]> = OnceLock::new();
+    BUNDLES.get_or_init(|| [bundle(langid!("fr"), FRENCH), bundle(langid!("en"), ENGLISH)])
+}
+
+pub fn set_language(language: Language) {
+    CURRENT.store(index(language) as u8, Ordering::Relaxed);
+}
+
+pub fn tr(id: &str) -> String {
+    tr_args(id, &[])
+}
+
+// Message `id` dans la langue courante, avec ses paramètres ($nom dans le catalogue)
+pub fn tr_args(id: &str, args: &[(&str, FluentValue)]) -> String {
+    let bundles = bundles();
+    let current = &bundles[CURRENT.load(Ordering::Relaxed) as usize];
+    let found = [current, &bundles[0]]
+        .into_iter()
+        .find_map(|bundle| bundle.get_message(id).and_then(|message| message.value()).map(|pattern| (bundle, pattern)));
+    let Some((bundle, pattern)) = found else {
+        log::warn!(id = id; "Message absent des catalogues");
+        return id.to_string();
+    };
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, Some(&fluent_args), &mut errors);
+    if !errors.is_empty() {
+        log::warn!(id = id; "Message mal formé: {:?}", errors);
+    }
+    text.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::entry::EntryField;
+
+    // Identifiants des messages d'un catalogue
+    fn message_ids(source: &str) -> Vec<&str> {
+        source
+            .lines()
+            .filter_map(|line| line.split_once(" ="))
+            .map(|(id, _)| id)
+            .filter(|id| id.starts_with(|c: char| c.is_ascii_lowercase()) && !id.contains(' '))
+            .collect()
+    }
+
+    #[test]
+    fn catalogs_define_the_same_messages() {
+        let french = message_ids(FRENCH);
+        let english = message_ids(ENGLISH);
+        assert!(french.len() > 100);
+        for id in &french {
+            assert!(english.contains(id), "'{}' absent du catalogue anglais", id);
+        }
+        for id in &english {
+            assert!(french.contains(id), "'{}' absent du catalogue français", id);
+        }
+        // Chaque message se formate sans erreur dans les deux langues
+        for (index, bundle) in bundles().iter().enumerate() {
+            let ids = if index == 0 { &french } else { &english };
+            for id in ids {
+                assert!(bundle.get_message(id).and_then(|message| message.value()).is_some(), "'{}' invalide", id);
+            }
+        }
+    }
+
+    #[test]
+    fn messages_take_arguments_and_unknown_ids_fall_back() {
+        let english = &bundles()[index(Language::English)];
+        let pattern = english.get_message("status-audit-failed").and_then(|message| message.value()).unwrap();
+        let mut args = FluentArgs::new();
+        args.set("error", "disque plein");
+        let mut errors = Vec::new();
+        assert_eq!(english.format_pattern(pattern, Some(&args), &mut errors), "Activity log not updated: disque plein");
+        assert!(errors.is_empty());
+
+        assert_eq!(tr("message-inexistant"), "message-inexistant");
+    }
+
+    // Identifiants passés en littéral à `tr` ou `tr_args` dans les sources sous `dir`
+    fn source_ids(dir: &std::path::Path, ids: &mut Vec<String>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                source_ids(&path, ids);
+                continue;
+            }
+            if path.extension().is_none_or(|extension| extension != "rs") {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            for call in ["tr(", "tr_args("] {
+                for (start, _) in source.match_indices(call) {
+                    let rest = source[start + call.len()..].trim_start();
+                    if let Some(literal) = rest.strip_prefix('"') {
+                        let id = &literal[..literal.find('"').unwrap()];
+                        if id.starts_with(|c: char| c.is_ascii_lowercase()) && !id.contains(' ') {
+                            ids.push(id.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn messages_used_in_the_sources_exist() {
+        let mut ids = Vec::new();
+        source_ids(&std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), &mut ids);
+        assert!(ids.len() > 100);
+        let french = message_ids(FRENCH);
+        for id in ids.iter().filter(|id| id.as_str() != "message-inexistant") {
+            assert!(french.contains(&id.as_str()), "'{}' absent des catalogues", id);
+        }
+    }
+
+    #[test]
+    fn entry_field_labels_come_from_the_catalogs() {
+        for field in EntryField::ALL {
+            assert!(!field.label().starts_with("entry-diff-"), "{:?} sans traduction", field);
+        }
+    }
+}
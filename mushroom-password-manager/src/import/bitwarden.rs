@@ -0,0 +1,236 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::import::{imported_entry, parse_timestamp, ParsedImport};
+use crate::vault::entry::{CustomField, FieldKind};
+
+// Sous-ensemble du format d'export JSON non chiffré de Bitwarden
+#[derive(Deserialize)]
+struct Export {
+    #[serde(default)]
+    encrypted: bool,
+    #[serde(default)]
+    folders: Vec<Folder>,
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+#[derive(Deserialize)]
+struct Folder {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Item {
+    #[serde(rename = "type")]
+    kind: u8,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    notes: Option<String>,
+    #[serde(default)]
+    folder_id: Option<String>,
+    #[serde(default)]
+    favorite: bool,
+    #[serde(default)]
+    fields: Vec<Field>,
+    #[serde(default)]
+    login: Option<Login>,
+    #[serde(default)]
+    creation_date: Option<String>,
+    #[serde(default)]
+    revision_date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Field {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    value: Option<String>,
+    #[serde(rename = "type", default)]
+    kind: u8,
+}
+
+#[derive(Deserialize, Default)]
+struct Login {
+    #[serde(default)]
+    uris: Option<Vec<LoginUri>>,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+    #[serde(default)]
+    totp: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LoginUri {
+    #[serde(default)]
+    uri: Option<String>,
+}
+
+// Types d'éléments Bitwarden
+const ITEM_LOGIN: u8 = 1;
+const ITEM_SECURE_NOTE: u8 = 2;
+
+// Types de champs personnalisés Bitwarden
+const FIELD_TEXT: u8 = 0;
+const FIELD_HIDDEN: u8 = 1;
+const FIELD_BOOLEAN: u8 = 2;
+
+pub fn parse(text: &str) -> Result<ParsedImport, String> {
+    let export: Export = serde_json::from_str(text)
+        .map_err(|e| format!("Export Bitwarden invalide: {}", e))?;
+
+    if export.encrypted {
+        return Err(String::from(
+            "Export Bitwarden chiffré : exportez au format JSON non chiffré",
+        ));
+    }
+
+    let folders: HashMap<String, String> = export
+        .folders
+        .into_iter()
+        .map(|folder| (folder.id, folder.name))
+        .collect();
+
+    let mut parsed = ParsedImport::default();
+    for item in export.items {
+        if item.kind != ITEM_LOGIN && item.kind != ITEM_SECURE_NOTE {
+            parsed
+                .warnings
+                .push(format!("Élément '{}' ignoré (cartes et identités non prises en charge)", item.name));
+            continue;
+        }
+
+        let login = item.login.unwrap_or_default();
+        let uris: Vec<String> = login
+            .uris
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|uri| uri.uri)
+            .filter(|uri| !uri.trim().is_empty())
+            .collect();
+
+        let mut entry = imported_entry(
+            &item.name,
+            uris.first().map(String::as_str).unwrap_or(""),
+            login.username.as_deref().unwrap_or(""),
+            login.password.as_deref().unwrap_or(""),
+        );
+        entry.notes = item.notes.unwrap_or_default().into();
+        entry.folder = item
+            .folder_id
+            .and_then(|id| folders.get(&id).cloned())
+            .unwrap_or_default();
+        if item.favorite {
+            entry.tags.push(String::from("favori"));
+        }
+
+        // Les URI supplémentaires deviennent des champs URL
+        for (index, uri) in uris.iter().enumerate().skip(1) {
+            entry.custom_fields.push(CustomField {
+                name: format!("URL {}", index + 1),
+                value: uri.as_str().into(),
+                kind: FieldKind::Url,
+            });
+        }
+
+        if let Some(totp) = login.totp.filter(|totp| !totp.trim().is_empty()) {
+            entry.custom_fields.push(CustomField {
+                name: String::from("TOTP"),
+                value: totp.into(),
+                kind: FieldKind::Totp,
+            });
+        }
+
+        for field in item.fields {
+            let kind = match field.kind {
+                FIELD_TEXT | FIELD_BOOLEAN => FieldKind::Text,
+                FIELD_HIDDEN => FieldKind::Hidden,
+                // Les champs liés pointent vers d'autres champs de l'élément
+                _ => continue,
+            };
+            entry.custom_fields.push(CustomField {
+                name: field.name.unwrap_or_default(),
+                value: field.value.unwrap_or_default().into(),
+                kind,
+            });
+        }
+
+        if let Some(created) = item.creation_date.as_deref().and_then(parse_timestamp) {
+            entry.created_at = Some(created);
+        }
+        if let Some(modified) = item.revision_date.as_deref().and_then(parse_timestamp) {
+            entry.modified_at = Some(modified);
+        }
+
+        parsed.entries.push(entry);
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+        "encrypted": false,
+        "folders": [{ "id": "f1", "name": "Perso" }],
+        "items": [
+            {
+                "type": 1, "name": "Banque", "notes": "note", "folderId": "f1", "favorite": true,
+                "login": {
+                    "uris": [{ "uri": "https://banque.fr" }, { "uri": "https://m.banque.fr" }, { "uri": " " }],
+                    "username": "alice", "password": "secret", "totp": "GEZDGNBV"
+                },
+                "fields": [
+                    { "name": "PIN", "value": "1234", "type": 1 },
+                    { "name": "Client", "value": "true", "type": 2 },
+                    { "name": "Lien", "value": null, "type": 3 }
+                ],
+                "creationDate": "2023-01-02T03:04:05.000Z",
+                "revisionDate": "2024-01-02T03:04:05.000Z"
+            },
+            { "type": 2, "name": "Note seule", "notes": "texte" },
+            { "type": 3, "name": "Carte" }
+        ]
+    }"#;
+
+    #[test]
+    fn parses_logins_and_secure_notes() {
+        let parsed = parse(EXPORT).unwrap();
+        assert_eq!(parsed.entries.len(), 2);
+        assert_eq!(parsed.warnings.len(), 1);
+
+        let banque = &parsed.entries[0];
+        assert_eq!(banque.service_name, "banque.fr");
+        assert_eq!(banque.folder, "Perso");
+        assert_eq!(banque.tags, vec!["favori"]);
+        let fields: Vec<(&str, FieldKind)> =
+            banque.custom_fields.iter().map(|field| (field.name.as_str(), field.kind)).collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("URL 2", FieldKind::Url),
+                ("TOTP", FieldKind::Totp),
+                ("PIN", FieldKind::Hidden),
+                ("Client", FieldKind::Text),
+            ]
+        );
+        assert_eq!(banque.created_at, parse_timestamp("2023-01-02T03:04:05Z"));
+
+        let note = &parsed.entries[1];
+        assert_eq!(note.service_name, "Note seule");
+        assert_eq!(&*note.notes, "texte");
+    }
+
+    #[test]
+    fn encrypted_exports_are_refused() {
+        assert!(parse(r#"{ "encrypted": true, "items": [] }"#).is_err());
+        assert!(parse("pas du json").is_err());
+    }
+}
@@ -0,0 +1,222 @@
+use csv::StringRecord;
+use std::collections::HashMap;
+
+use crate::import::{imported_entry, parse_timestamp, ParsedImport};
+use crate::vault::entry::{CustomField, FieldKind};
+
+// Accès aux colonnes par nom, quel que soit leur ordre dans l'export
+struct Columns {
+    indices: HashMap<String, usize>,
+}
+
+impl Columns {
+    fn new(headers: &StringRecord) -> Self {
+        Columns {
+            indices: headers
+                .iter()
+                .enumerate()
+                .map(|(index, name)| (name.trim().to_ascii_lowercase(), index))
+                .collect(),
+        }
+    }
+
+    fn require(&self, names: &[&str]) -> Result<(), String> {
+        match names.iter().find(|name| !self.indices.contains_key(**name)) {
+            Some(missing) => Err(format!("Colonne '{}' absente de l'export", missing)),
+            None => Ok(()),
+        }
+    }
+
+    fn get<'r>(&self, record: &'r StringRecord, name: &str) -> &'r str {
+        self.indices
+            .get(name)
+            .and_then(|index| record.get(*index))
+            .unwrap_or("")
+    }
+}
+
+fn read_records(text: &str) -> Result<(Columns, Vec<StringRecord>), String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("En-tête CSV illisible: {}", e))?
+        .clone();
+
+    let records = reader
+        .records()
+        .enumerate()
+        .map(|(line, record)| record.map_err(|e| format!("Ligne {} illisible: {}", line + 2, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((Columns::new(&headers), records))
+}
+
+// Chrome et Edge : name,url,username,password[,note]
+pub fn parse_chrome(text: &str) -> Result<ParsedImport, String> {
+    let (columns, records) = read_records(text)?;
+    columns.require(&["name", "url", "username", "password"])?;
+
+    let mut parsed = ParsedImport::default();
+    for record in &records {
+        let mut entry = imported_entry(
+            columns.get(record, "name"),
+            columns.get(record, "url"),
+            columns.get(record, "username"),
+            columns.get(record, "password"),
+        );
+        entry.notes = columns.get(record, "note").into();
+        parsed.entries.push(entry);
+    }
+    Ok(parsed)
+}
+
+// Firefox : url,username,password,httpRealm,formActionOrigin,guid,timeCreated,timeLastUsed,timePasswordChanged
+pub fn parse_firefox(text: &str) -> Result<ParsedImport, String> {
+    let (columns, records) = read_records(text)?;
+    columns.require(&["url", "username", "password"])?;
+
+    let mut parsed = ParsedImport::default();
+    for record in &records {
+        let url = columns.get(record, "url");
+        // Les identifiants enregistrés pour des URL internes (chrome://...) ne servent à rien ici
+        if url.starts_with("chrome://") || url.starts_with("about:") {
+            parsed.warnings.push(format!("Entrée interne de Firefox ignorée: {}", url));
+            continue;
+        }
+
+        let mut entry = imported_entry("", url, columns.get(record, "username"), columns.get(record, "password"));
+        if let Some(created) = parse_timestamp(columns.get(record, "timecreated")) {
+            entry.created_at = Some(created);
+        }
+        entry.modified_at = parse_timestamp(columns.get(record, "timepasswordchanged")).or(entry.created_at);
+        entry.last_used_at = parse_timestamp(columns.get(record, "timelastused"));
+
+        let realm = columns.get(record, "httprealm");
+        if !realm.is_empty() {
+            entry.custom_fields.push(CustomField {
+                name: String::from("Domaine HTTP"),
+                value: realm.into(),
+                kind: FieldKind::Text,
+            });
+        }
+        parsed.entries.push(entry);
+    }
+    Ok(parsed)
+}
+
+// KeePassXC : Group,Title,Username,Password,URL,Notes,TOTP,Icon,Last Modified,Created
+pub fn parse_keepassxc(text: &str) -> Result<ParsedImport, String> {
+    let (columns, records) = read_records(text)?;
+    columns.require(&["group", "title", "username", "password", "url"])?;
+
+    let mut parsed = ParsedImport::default();
+    for record in &records {
+        let mut entry = imported_entry(
+            columns.get(record, "title"),
+            columns.get(record, "url"),
+            columns.get(record, "username"),
+            columns.get(record, "password"),
+        );
+        entry.notes = columns.get(record, "notes").into();
+        entry.folder = group_to_folder(columns.get(record, "group"));
+
+        let totp = columns.get(record, "totp");
+        if !totp.is_empty() {
+            entry.custom_fields.push(CustomField {
+                name: String::from("TOTP"),
+                value: totp.into(),
+                kind: FieldKind::Totp,
+            });
+        }
+
+        if let Some(created) = parse_timestamp(columns.get(record, "created")) {
+            entry.created_at = Some(created);
+        }
+        if let Some(modified) = parse_timestamp(columns.get(record, "last modified")) {
+            entry.modified_at = Some(modified);
+        }
+        parsed.entries.push(entry);
+    }
+    Ok(parsed)
+}
+
+// "Root/Perso/Banques" -> "Perso/Banques" : le groupe racine est le nom de la base
+pub(crate) fn group_to_folder(group: &str) -> String {
+    let segments: Vec<&str> = group
+        .split('/')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    segments.iter().skip(1).copied().collect::<Vec<_>>().join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_chrome_exports() {
+        let parsed = parse_chrome(
+            "name,url,username,password,note\n\
+             Banque,https://www.banque.fr/login,alice,\"p,ss\"\"word\",ligne 1\n\
+             Wi-Fi,,,cle,\n",
+        )
+        .unwrap();
+        assert_eq!(parsed.entries.len(), 2);
+        let banque = &parsed.entries[0];
+        assert_eq!(banque.service_name, "banque.fr");
+        assert_eq!(banque.username, "alice");
+        assert_eq!(&*banque.service_password, "p,ss\"word");
+        assert_eq!(&*banque.notes, "ligne 1");
+        // Sans URL, le nom de l'export est gardé
+        assert_eq!(parsed.entries[1].service_name, "Wi-Fi");
+    }
+
+    #[test]
+    fn missing_columns_are_reported() {
+        let error = parse_chrome("name,url,password\nA,https://a.fr,x\n").unwrap_err();
+        assert!(error.contains("username"), "{}", error);
+    }
+
+    #[test]
+    fn parses_firefox_exports_and_skips_internal_entries() {
+        let parsed = parse_firefox(
+            "\"url\",\"username\",\"password\",\"httpRealm\",\"formActionOrigin\",\"guid\",\"timeCreated\",\"timeLastUsed\",\"timePasswordChanged\"\n\
+             \"https://exemple.fr\",\"alice\",\"secret\",\"Intranet\",\"\",\"{1}\",\"1704164645000\",\"1704164646000\",\"\"\n\
+             \"chrome://FirefoxAccounts\",\"\",\"jeton\",\"\",\"\",\"{2}\",\"\",\"\",\"\"\n",
+        )
+        .unwrap();
+        assert_eq!(parsed.entries.len(), 1);
+        assert_eq!(parsed.warnings.len(), 1);
+        let entry = &parsed.entries[0];
+        assert_eq!(entry.created_at, parse_timestamp("1704164645000"));
+        // Sans date de changement du mot de passe, la création en tient lieu
+        assert_eq!(entry.modified_at, entry.created_at);
+        assert_eq!(entry.last_used_at, parse_timestamp("1704164646000"));
+        assert_eq!(entry.custom_fields[0].name, "Domaine HTTP");
+        assert_eq!(&*entry.custom_fields[0].value, "Intranet");
+    }
+
+    #[test]
+    fn parses_keepassxc_exports() {
+        let parsed = parse_keepassxc(
+            "\"Group\",\"Title\",\"Username\",\"Password\",\"URL\",\"Notes\",\"TOTP\",\"Icon\",\"Last Modified\",\"Created\"\n\
+             \"Root/Perso/Banques\",\"Banque\",\"alice\",\"secret\",\"https://banque.fr\",\"note\",\"otpauth://totp/x?secret=GEZDGNBV\",\"0\",\"2024-01-02T03:04:05Z\",\"2023-01-02T03:04:05Z\"\n",
+        )
+        .unwrap();
+        let entry = &parsed.entries[0];
+        assert_eq!(entry.folder, "Perso/Banques");
+        assert_eq!(entry.custom_fields[0].kind, FieldKind::Totp);
+        assert_eq!(entry.created_at, parse_timestamp("2023-01-02T03:04:05Z"));
+        assert_eq!(entry.modified_at, parse_timestamp("2024-01-02T03:04:05Z"));
+    }
+
+    #[test]
+    fn root_group_is_dropped_from_folders() {
+        assert_eq!(group_to_folder("Root"), "");
+        assert_eq!(group_to_folder(" Root / Perso // Banques "), "Perso/Banques");
+    }
+}
@@ -0,0 +1,491 @@
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+
+// Signatures et version d'un fichier KDBX
+const SIGNATURE_1: u32 = 0x9AA2_D903;
+const SIGNATURE_2: u32 = 0xB54B_FB67;
+const SUPPORTED_MAJOR_VERSION: u16 = 4;
+
+// Identifiants des champs de l'en-tête externe
+const HEADER_END: u8 = 0;
+const HEADER_CIPHER_ID: u8 = 2;
+const HEADER_COMPRESSION: u8 = 3;
+const HEADER_MASTER_SEED: u8 = 4;
+const HEADER_ENCRYPTION_IV: u8 = 7;
+const HEADER_KDF_PARAMETERS: u8 = 11;
+
+// Identifiants des champs de l'en-tête interne
+const INNER_HEADER_END: u8 = 0;
+const INNER_STREAM_ID: u8 = 1;
+const INNER_STREAM_KEY: u8 = 2;
+
+const CIPHER_AES256: [u8; 16] = [
+    0x31, 0xc1, 0xf2, 0xe6, 0xbf, 0x71, 0x43, 0x50, 0xbe, 0x58, 0x05, 0x21, 0x6a, 0xfc, 0x5a, 0xff,
+];
+const CIPHER_CHACHA20: [u8; 16] = [
+    0xd6, 0x03, 0x8a, 0x2b, 0x8b, 0x6f, 0x4c, 0xb5, 0xa5, 0x24, 0x33, 0x9a, 0x31, 0xdb, 0xb5, 0x9a,
+];
+
+// Lecture séquentielle d'un tampon en little-endian
+pub struct Cursor<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Cursor { data, position: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn bytes(&mut self, length: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| String::from("Fichier KDBX tronqué"))?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cipher {
+    Aes256,
+    ChaCha20,
+}
+
+// Valeurs typées d'un VariantDictionary (paramètres de dérivation de clé)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Variant {
+    U32(u32),
+    U64(u64),
+    Bool(bool),
+    I32(i32),
+    I64(i64),
+    String(String),
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VariantDictionary {
+    values: HashMap<String, Variant>,
+}
+
+impl VariantDictionary {
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        let mut cursor = Cursor::new(data);
+        let version = cursor.u16()?;
+        if version >> 8 != 1 {
+            return Err(format!("Version de dictionnaire KDF non supportée: {:#06x}", version));
+        }
+
+        let mut values = HashMap::new();
+        loop {
+            let kind = cursor.u8()?;
+            if kind == 0 {
+                break;
+            }
+            let key_length = cursor.u32()? as usize;
+            let key = String::from_utf8_lossy(cursor.bytes(key_length)?).into_owned();
+            let value_length = cursor.u32()? as usize;
+            let value = cursor.bytes(value_length)?;
+
+            let fixed = |length: usize| -> Result<&[u8], String> {
+                if value.len() == length {
+                    Ok(value)
+                } else {
+                    Err(format!("Taille invalide pour le paramètre KDF '{}'", key))
+                }
+            };
+            let variant = match kind {
+                0x04 => Variant::U32(u32::from_le_bytes(fixed(4)?.try_into().unwrap())),
+                0x05 => Variant::U64(u64::from_le_bytes(fixed(8)?.try_into().unwrap())),
+                0x08 => Variant::Bool(fixed(1)?[0] != 0),
+                0x0C => Variant::I32(i32::from_le_bytes(fixed(4)?.try_into().unwrap())),
+                0x0D => Variant::I64(i64::from_le_bytes(fixed(8)?.try_into().unwrap())),
+                0x18 => Variant::String(String::from_utf8_lossy(value).into_owned()),
+                0x42 => Variant::Bytes(value.to_vec()),
+                other => return Err(format!("Type de paramètre KDF inconnu: {:#04x}", other)),
+            };
+            values.insert(key, variant);
+        }
+
+        Ok(VariantDictionary { values })
+    }
+
+    pub fn bytes(&self, key: &str) -> Result<&[u8], String> {
+        match self.values.get(key) {
+            Some(Variant::Bytes(bytes)) => Ok(bytes),
+            _ => Err(format!("Paramètre KDF '{}' manquant", key)),
+        }
+    }
+
+    pub fn u32(&self, key: &str) -> Result<u32, String> {
+        match self.values.get(key) {
+            Some(Variant::U32(value)) => Ok(*value),
+            _ => Err(format!("Paramètre KDF '{}' manquant", key)),
+        }
+    }
+
+    pub fn u64(&self, key: &str) -> Result<u64, String> {
+        match self.values.get(key) {
+            Some(Variant::U64(value)) => Ok(*value),
+            _ => Err(format!("Paramètre KDF '{}' manquant", key)),
+        }
+    }
+}
+
+// En-tête externe (non chiffré) d'une base KDBX 4
+pub struct OuterHeader {
+    pub cipher: Cipher,
+    pub compressed: bool,
+    pub master_seed: Vec<u8>,
+    pub encryption_iv: Vec<u8>,
+    pub kdf_parameters: VariantDictionary,
+}
+
+// Lit l'en-tête, vérifie son empreinte SHA-256 et renvoie les octets bruts
+// (nécessaires au contrôle HMAC) ainsi que la position du début des données
+pub fn read_outer_header(data: &[u8]) -> Result<(OuterHeader, &[u8], usize), String> {
+    let mut cursor = Cursor::new(data);
+    if cursor.u32()? != SIGNATURE_1 || cursor.u32()? != SIGNATURE_2 {
+        return Err(String::from("Ce fichier n'est pas une base KeePass (KDBX)"));
+    }
+    let _minor = cursor.u16()?;
+    let major = cursor.u16()?;
+    if major != SUPPORTED_MAJOR_VERSION {
+        return Err(format!("Version KDBX {} non supportée (seule la version 4 l'est)", major));
+    }
+
+    let mut cipher = None;
+    let mut compressed = false;
+    let mut master_seed = None;
+    let mut encryption_iv = None;
+    let mut kdf_parameters = None;
+
+    loop {
+        let id = cursor.u8()?;
+        let length = cursor.u32()? as usize;
+        let value = cursor.bytes(length)?;
+        match id {
+            HEADER_END => break,
+            HEADER_CIPHER_ID => {
+                cipher = Some(match value {
+                    v if v == CIPHER_AES256 => Cipher::Aes256,
+                    v if v == CIPHER_CHACHA20 => Cipher::ChaCha20,
+                    _ => return Err(String::from("Algorithme de chiffrement KDBX non supporté (Twofish ?)")),
+                });
+            }
+            HEADER_COMPRESSION => {
+                let flag = u32::from_le_bytes(value.try_into().map_err(|_| "Drapeau de compression invalide")?);
+                compressed = flag == 1;
+            }
+            HEADER_MASTER_SEED => master_seed = Some(value.to_vec()),
+            HEADER_ENCRYPTION_IV => encryption_iv = Some(value.to_vec()),
+            HEADER_KDF_PARAMETERS => kdf_parameters = Some(VariantDictionary::parse(value)?),
+            // Données publiques et champs inconnus : ignorés
+            _ => {}
+        }
+    }
+
+    let header_end = cursor.position();
+    let raw_header = &data[..header_end];
+    let expected_hash = cursor.bytes(32)?;
+    if Sha256::digest(raw_header).as_slice() != expected_hash {
+        return Err(String::from("En-tête KDBX corrompu (empreinte SHA-256 invalide)"));
+    }
+
+    let header = OuterHeader {
+        cipher: cipher.ok_or("Algorithme de chiffrement absent de l'en-tête")?,
+        compressed,
+        master_seed: master_seed.ok_or("Graine maîtresse absente de l'en-tête")?,
+        encryption_iv: encryption_iv.ok_or("Vecteur d'initialisation absent de l'en-tête")?,
+        kdf_parameters: kdf_parameters.ok_or("Paramètres KDF absents de l'en-tête")?,
+    };
+
+    Ok((header, raw_header, cursor.position()))
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn block_hmac_key(index: u64, hmac_key: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha512::new();
+    hasher.update(index.to_le_bytes());
+    hasher.update(hmac_key);
+    hasher.finalize().to_vec()
+}
+
+// Vérifie le HMAC de l'en-tête : un échec signifie presque toujours une clé incorrecte
+pub fn verify_header_hmac(raw_header: &[u8], expected: &[u8], hmac_key: &[u8]) -> Result<(), String> {
+    let mut mac = HmacSha256::new_from_slice(&block_hmac_key(u64::MAX, hmac_key)).expect("clé HMAC");
+    mac.update(raw_header);
+    mac.verify_slice(expected)
+        .map_err(|_| String::from("Mot de passe ou fichier clé incorrect"))
+}
+
+// Flux de blocs authentifiés qui suit l'en-tête : [HMAC][taille][données], terminé par un bloc vide
+pub fn read_hmac_blocks(data: &[u8], hmac_key: &[u8]) -> Result<Vec<u8>, String> {
+    let mut cursor = Cursor::new(data);
+    let mut payload = Vec::new();
+
+    for index in 0u64.. {
+        let expected = cursor.bytes(32)?;
+        let size_bytes = cursor.bytes(4)?;
+        let size = i32::from_le_bytes(size_bytes.try_into().unwrap());
+        if size < 0 {
+            return Err(String::from("Taille de bloc KDBX invalide"));
+        }
+        let block = cursor.bytes(size as usize)?;
+
+        let mut mac = HmacSha256::new_from_slice(&block_hmac_key(index, hmac_key)).expect("clé HMAC");
+        mac.update(&index.to_le_bytes());
+        mac.update(size_bytes);
+        mac.update(block);
+        mac.verify_slice(expected)
+            .map_err(|_| format!("Bloc KDBX {} corrompu (HMAC invalide)", index))?;
+
+        if size == 0 {
+            break;
+        }
+        payload.extend_from_slice(block);
+    }
+
+    Ok(payload)
+}
+
+// En-tête interne (après déchiffrement) : clé du flux de protection des valeurs
+pub struct InnerHeader {
+    pub stream_id: u32,
+    pub stream_key: Vec<u8>,
+}
+
+pub fn read_inner_header(data: &[u8]) -> Result<(InnerHeader, usize), String> {
+    let mut cursor = Cursor::new(data);
+    let mut stream_id = None;
+    let mut stream_key = None;
+
+    loop {
+        let id = cursor.u8()?;
+        let length = cursor.u32()? as usize;
+        let value = cursor.bytes(length)?;
+        match id {
+            INNER_HEADER_END => break,
+            INNER_STREAM_ID => {
+                stream_id = Some(u32::from_le_bytes(value.try_into().map_err(|_| "Identifiant de flux invalide")?));
+            }
+            INNER_STREAM_KEY => stream_key = Some(value.to_vec()),
+            // Les pièces jointes binaires ne sont pas importées
+            _ => {}
+        }
+    }
+
+    Ok((
+        InnerHeader {
+            stream_id: stream_id.ok_or("Flux de protection absent de l'en-tête interne")?,
+            stream_key: stream_key.ok_or("Clé du flux de protection absente")?,
+        },
+        cursor.position(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(id: u8, value: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![id];
+        bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(value);
+        bytes
+    }
+
+    fn kdf_parameters() -> Vec<u8> {
+        let mut bytes = 0x0100u16.to_le_bytes().to_vec();
+        for (kind, key, value) in [
+            (0x42u8, "$UUID", vec![7u8; 16]),
+            (0x05, "R", 6000u64.to_le_bytes().to_vec()),
+            (0x04, "P", 2u32.to_le_bytes().to_vec()),
+        ] {
+            bytes.push(kind);
+            bytes.extend_from_slice(&(key.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(key.as_bytes());
+            bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&value);
+        }
+        bytes.push(0);
+        bytes
+    }
+
+    fn outer_header(major: u16) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&SIGNATURE_1.to_le_bytes());
+        bytes.extend_from_slice(&SIGNATURE_2.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&major.to_le_bytes());
+        bytes.extend(field(HEADER_CIPHER_ID, &CIPHER_CHACHA20));
+        bytes.extend(field(HEADER_COMPRESSION, &1u32.to_le_bytes()));
+        bytes.extend(field(HEADER_MASTER_SEED, &[1u8; 32]));
+        bytes.extend(field(HEADER_ENCRYPTION_IV, &[2u8; 12]));
+        bytes.extend(field(HEADER_KDF_PARAMETERS, &kdf_parameters()));
+        bytes.extend(field(HEADER_END, b"\r\n\r\n"));
+        bytes
+    }
+
+    fn with_hash(header: Vec<u8>) -> Vec<u8> {
+        let mut bytes = header.clone();
+        bytes.extend_from_slice(&Sha256::digest(&header));
+        bytes
+    }
+
+    fn block(index: u64, data: &[u8], hmac_key: &[u8]) -> Vec<u8> {
+        let size = (data.len() as i32).to_le_bytes();
+        let mut mac = HmacSha256::new_from_slice(&block_hmac_key(index, hmac_key)).unwrap();
+        mac.update(&index.to_le_bytes());
+        mac.update(&size);
+        mac.update(data);
+        let mut bytes = mac.finalize().into_bytes().to_vec();
+        bytes.extend_from_slice(&size);
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn cursor_reads_little_endian_and_reports_truncation() {
+        let mut cursor = Cursor::new(&[0x01, 0x34, 0x12, 0x78, 0x56, 0x34, 0x12, 0xFF]);
+        assert_eq!(cursor.u8().unwrap(), 0x01);
+        assert_eq!(cursor.u16().unwrap(), 0x1234);
+        assert_eq!(cursor.u32().unwrap(), 0x1234_5678);
+        assert_eq!(cursor.position(), 7);
+        assert!(cursor.u16().is_err());
+        // Une longueur démesurée ne doit pas provoquer de débordement
+        assert!(cursor.bytes(usize::MAX).is_err());
+        assert_eq!(cursor.bytes(1).unwrap(), &[0xFF]);
+    }
+
+    #[test]
+    fn variant_dictionary_reads_typed_values() {
+        let dictionary = VariantDictionary::parse(&kdf_parameters()).unwrap();
+        assert_eq!(dictionary.bytes("$UUID").unwrap(), &[7u8; 16]);
+        assert_eq!(dictionary.u64("R").unwrap(), 6000);
+        assert_eq!(dictionary.u32("P").unwrap(), 2);
+        // Un type différent de celui attendu est traité comme une absence
+        assert!(dictionary.u32("R").is_err());
+        assert!(dictionary.bytes("S").is_err());
+    }
+
+    #[test]
+    fn variant_dictionary_rejects_unknown_version_and_bad_sizes() {
+        assert!(VariantDictionary::parse(&0x0200u16.to_le_bytes()).is_err());
+
+        let mut bad_size = 0x0100u16.to_le_bytes().to_vec();
+        bad_size.push(0x04);
+        bad_size.extend_from_slice(&1u32.to_le_bytes());
+        bad_size.push(b'P');
+        bad_size.extend_from_slice(&2u32.to_le_bytes());
+        bad_size.extend_from_slice(&[0, 0]);
+        bad_size.push(0);
+        assert!(VariantDictionary::parse(&bad_size).is_err());
+    }
+
+    #[test]
+    fn reads_outer_header_fields() {
+        let data = with_hash(outer_header(4));
+        let (header, raw_header, position) = read_outer_header(&data).unwrap();
+        assert_eq!(header.cipher, Cipher::ChaCha20);
+        assert!(header.compressed);
+        assert_eq!(header.master_seed, vec![1u8; 32]);
+        assert_eq!(header.encryption_iv, vec![2u8; 12]);
+        assert_eq!(header.kdf_parameters.u64("R").unwrap(), 6000);
+        assert_eq!(raw_header.len() + 32, data.len());
+        assert_eq!(position, data.len());
+    }
+
+    #[test]
+    fn rejects_bad_signature_version_and_hash() {
+        let mut wrong_signature = with_hash(outer_header(4));
+        wrong_signature[0] ^= 0xFF;
+        assert!(read_outer_header(&wrong_signature).is_err());
+
+        let kdbx3 = with_hash(outer_header(3));
+        assert!(read_outer_header(&kdbx3).err().unwrap().contains("Version KDBX 3"));
+
+        let mut corrupted = with_hash(outer_header(4));
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0x01;
+        assert!(read_outer_header(&corrupted).err().unwrap().contains("SHA-256"));
+
+        let truncated = outer_header(4);
+        assert!(read_outer_header(&truncated[..truncated.len() - 10]).is_err());
+    }
+
+    #[test]
+    fn verifies_header_hmac() {
+        let hmac_key = [9u8; 64];
+        let header = outer_header(4);
+        let mut mac = HmacSha256::new_from_slice(&block_hmac_key(u64::MAX, &hmac_key)).unwrap();
+        mac.update(&header);
+        let expected = mac.finalize().into_bytes();
+
+        assert!(verify_header_hmac(&header, &expected, &hmac_key).is_ok());
+        assert!(verify_header_hmac(&header, &expected, &[8u8; 64]).is_err());
+    }
+
+    #[test]
+    fn hmac_blocks_round_trip_and_detect_tampering() {
+        let hmac_key = [3u8; 64];
+        let mut stream = block(0, b"premier ", &hmac_key);
+        stream.extend(block(1, b"second", &hmac_key));
+        stream.extend(block(2, b"", &hmac_key));
+        assert_eq!(read_hmac_blocks(&stream, &hmac_key).unwrap(), b"premier second");
+
+        let mut tampered = stream.clone();
+        tampered[36] ^= 0x01;
+        assert!(read_hmac_blocks(&tampered, &hmac_key).unwrap_err().contains("Bloc KDBX 0"));
+
+        // Blocs permutés : l'index entre dans le HMAC
+        let mut reordered = block(1, b"second", &hmac_key);
+        reordered.extend(block(0, b"premier ", &hmac_key));
+        reordered.extend(block(2, b"", &hmac_key));
+        assert!(read_hmac_blocks(&reordered, &hmac_key).is_err());
+
+        // Flux tronqué avant le bloc final vide
+        let truncated = block(0, b"premier ", &hmac_key);
+        assert!(read_hmac_blocks(&truncated, &hmac_key).is_err());
+    }
+
+    #[test]
+    fn reads_inner_header_and_skips_binaries() {
+        let mut data = field(INNER_STREAM_ID, &3u32.to_le_bytes());
+        data.extend(field(3, b"\x01piece jointe"));
+        data.extend(field(INNER_STREAM_KEY, &[5u8; 64]));
+        data.extend(field(INNER_HEADER_END, b""));
+        let end = data.len();
+        data.extend_from_slice(b"<KeePassFile/>");
+
+        let (inner, position) = read_inner_header(&data).unwrap();
+        assert_eq!(inner.stream_id, 3);
+        assert_eq!(inner.stream_key, vec![5u8; 64]);
+        assert_eq!(position, end);
+
+        let missing_key = [field(INNER_STREAM_ID, &3u32.to_le_bytes()), field(INNER_HEADER_END, b"")].concat();
+        assert!(read_inner_header(&missing_key).is_err());
+    }
+}
@@ -0,0 +1,308 @@
+use aes::cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit};
+use aes::Aes256;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::import::kdbx::format::{OuterHeader, VariantDictionary};
+
+const KDF_AES: [u8; 16] = [
+    0xc9, 0xd9, 0xf3, 0x9a, 0x62, 0x8a, 0x44, 0x60, 0xbf, 0x74, 0x0d, 0x08, 0xc1, 0x8a, 0x4f, 0xea,
+];
+const KDF_ARGON2D: [u8; 16] = [
+    0xef, 0x63, 0x6d, 0xdf, 0x8c, 0x29, 0x44, 0x4b, 0x91, 0xf7, 0xa9, 0xa4, 0x03, 0xe3, 0x0a, 0x0c,
+];
+const KDF_ARGON2ID: [u8; 16] = [
+    0x9e, 0x29, 0x8b, 0x19, 0x56, 0xdb, 0x47, 0x73, 0xb2, 0x3d, 0xfc, 0x3e, 0xc6, 0xf0, 0xa1, 0xe6,
+];
+
+// Clé composite : SHA-256 de la concaténation des empreintes de chaque facteur
+pub fn composite_key(password: &str, key_file: Option<&[u8]>) -> Result<[u8; 32], String> {
+    let mut hasher = Sha256::new();
+    if !password.is_empty() || key_file.is_none() {
+        hasher.update(Sha256::digest(password.as_bytes()));
+    }
+    if let Some(key_file) = key_file {
+        hasher.update(key_file_key(key_file)?);
+    }
+    Ok(hasher.finalize().into())
+}
+
+// Formats de fichier clé reconnus par KeePass : XML (v1 base64, v2 hexadécimal),
+// 32 octets bruts, 64 caractères hexadécimaux, ou n'importe quel fichier (haché)
+fn key_file_key(data: &[u8]) -> Result<[u8; 32], String> {
+    if let Ok(text) = std::str::from_utf8(data) {
+        if text.trim_start().starts_with("<?xml") || text.contains("<KeyFile>") {
+            if let Some(key) = xml_key_file(text)? {
+                return Ok(key);
+            }
+        }
+        let trimmed = text.trim();
+        if trimmed.len() == 64 {
+            if let Some(bytes) = decode_hex(trimmed) {
+                return Ok(bytes.try_into().unwrap());
+            }
+        }
+    }
+    if data.len() == 32 {
+        return Ok(data.try_into().unwrap());
+    }
+    Ok(Sha256::digest(data).into())
+}
+
+fn xml_key_file(text: &str) -> Result<Option<[u8; 32]>, String> {
+    let document = roxmltree::Document::parse(text).map_err(|e| format!("Fichier clé XML invalide: {}", e))?;
+    let version = document
+        .descendants()
+        .find(|node| node.has_tag_name("Version"))
+        .and_then(|node| node.text())
+        .unwrap_or("1.0");
+    let data = match document.descendants().find(|node| node.has_tag_name("Data")) {
+        Some(node) => node.text().unwrap_or("").trim().to_string(),
+        None => return Ok(None),
+    };
+
+    let bytes = if version.starts_with('2') {
+        let hex: String = data.chars().filter(|c| !c.is_whitespace()).collect();
+        decode_hex(&hex).ok_or("Fichier clé XML v2 invalide")?
+    } else {
+        BASE64.decode(data).map_err(|_| "Fichier clé XML v1 invalide")?
+    };
+
+    bytes
+        .try_into()
+        .map(Some)
+        .map_err(|_| String::from("Fichier clé XML : la clé doit faire 32 octets"))
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+// Fonction de dérivation de clé déclarée dans l'en-tête
+pub enum Kdf {
+    Aes { seed: Vec<u8>, rounds: u64 },
+    Argon2 {
+        algorithm: argon2::Algorithm,
+        salt: Vec<u8>,
+        memory_kib: u32,
+        iterations: u32,
+        parallelism: u32,
+        version: argon2::Version,
+    },
+}
+
+impl Kdf {
+    pub fn from_parameters(parameters: &VariantDictionary) -> Result<Self, String> {
+        let uuid = parameters.bytes("$UUID")?;
+        if uuid == KDF_AES {
+            return Ok(Kdf::Aes {
+                seed: parameters.bytes("S")?.to_vec(),
+                rounds: parameters.u64("R")?,
+            });
+        }
+
+        let algorithm = if uuid == KDF_ARGON2D {
+            argon2::Algorithm::Argon2d
+        } else if uuid == KDF_ARGON2ID {
+            argon2::Algorithm::Argon2id
+        } else {
+            return Err(String::from("Fonction de dérivation de clé KDBX inconnue"));
+        };
+
+        let version = match parameters.u32("V")? {
+            0x10 => argon2::Version::V0x10,
+            0x13 => argon2::Version::V0x13,
+            other => return Err(format!("Version d'Argon2 non supportée: {:#x}", other)),
+        };
+        let too_large = |name: &str| format!("Paramètre Argon2 '{}' trop grand", name);
+
+        Ok(Kdf::Argon2 {
+            algorithm,
+            salt: parameters.bytes("S")?.to_vec(),
+            memory_kib: u32::try_from(parameters.u64("M")? / 1024).map_err(|_| too_large("M"))?,
+            iterations: u32::try_from(parameters.u64("I")?).map_err(|_| too_large("I"))?,
+            parallelism: parameters.u32("P")?,
+            version,
+        })
+    }
+
+    pub fn transform(&self, key: &[u8; 32]) -> Result<[u8; 32], String> {
+        match self {
+            Kdf::Aes { seed, rounds } => {
+                let cipher = Aes256::new_from_slice(seed).map_err(|_| "Graine AES-KDF invalide")?;
+                let mut blocks = [
+                    GenericArray::clone_from_slice(&key[..16]),
+                    GenericArray::clone_from_slice(&key[16..]),
+                ];
+                for _ in 0..*rounds {
+                    cipher.encrypt_blocks(&mut blocks);
+                }
+                let mut transformed = Sha256::new();
+                transformed.update(blocks[0]);
+                transformed.update(blocks[1]);
+                Ok(transformed.finalize().into())
+            }
+            Kdf::Argon2 { algorithm, salt, memory_kib, iterations, parallelism, version } => {
+                let params = argon2::Params::new(*memory_kib, *iterations, *parallelism, Some(32))
+                    .map_err(|e| format!("Paramètres Argon2 invalides: {}", e))?;
+                let mut output = [0u8; 32];
+                argon2::Argon2::new(*algorithm, *version, params)
+                    .hash_password_into(key, salt, &mut output)
+                    .map_err(|e| format!("Échec de la dérivation Argon2: {}", e))?;
+                Ok(output)
+            }
+        }
+    }
+}
+
+// Clés de chiffrement et d'authentification dérivées de la clé composite
+pub struct DerivedKeys {
+    pub cipher_key: [u8; 32],
+    pub hmac_key: [u8; 64],
+}
+
+pub fn derive_keys(header: &OuterHeader, composite: &[u8; 32]) -> Result<DerivedKeys, String> {
+    let transformed = Kdf::from_parameters(&header.kdf_parameters)?.transform(composite)?;
+
+    let mut cipher_key = Sha256::new();
+    cipher_key.update(&header.master_seed);
+    cipher_key.update(transformed);
+
+    let mut hmac_key = Sha512::new();
+    hmac_key.update(&header.master_seed);
+    hmac_key.update(transformed);
+    hmac_key.update([1u8]);
+
+    Ok(DerivedKeys {
+        cipher_key: cipher_key.finalize().into(),
+        hmac_key: hmac_key.finalize().into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters(values: &[(u8, &str, Vec<u8>)]) -> VariantDictionary {
+        let mut bytes = 0x0100u16.to_le_bytes().to_vec();
+        for (kind, key, value) in values {
+            bytes.push(*kind);
+            bytes.extend_from_slice(&(key.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(key.as_bytes());
+            bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(value);
+        }
+        bytes.push(0);
+        VariantDictionary::parse(&bytes).unwrap()
+    }
+
+    fn aes_parameters(rounds: u64) -> VariantDictionary {
+        parameters(&[
+            (0x42, "$UUID", KDF_AES.to_vec()),
+            (0x42, "S", vec![4u8; 32]),
+            (0x05, "R", rounds.to_le_bytes().to_vec()),
+        ])
+    }
+
+    #[test]
+    fn composite_key_hashes_each_factor() {
+        let password_only: [u8; 32] = Sha256::digest(Sha256::digest(b"motdepasse")).into();
+        assert_eq!(composite_key("motdepasse", None).unwrap(), password_only);
+
+        // Fichier clé seul : le mot de passe vide n'entre pas dans la clé
+        let raw = [6u8; 32];
+        let key_only: [u8; 32] = Sha256::digest(raw).into();
+        assert_eq!(composite_key("", Some(&raw)).unwrap(), key_only);
+
+        let mut both = Sha256::new();
+        both.update(Sha256::digest(b"motdepasse"));
+        both.update(raw);
+        let both: [u8; 32] = both.finalize().into();
+        assert_eq!(composite_key("motdepasse", Some(&raw)).unwrap(), both);
+    }
+
+    #[test]
+    fn recognises_key_file_formats() {
+        let key = [0xABu8; 32];
+        let hex = "ab".repeat(32);
+        assert_eq!(key_file_key(&key).unwrap(), key);
+        assert_eq!(key_file_key(format!("{}\n", hex).as_bytes()).unwrap(), key);
+
+        let xml_v1 = format!(
+            "<?xml version=\"1.0\"?><KeyFile><Meta><Version>1.00</Version></Meta><Key><Data>{}</Data></Key></KeyFile>",
+            BASE64.encode(key)
+        );
+        assert_eq!(key_file_key(xml_v1.as_bytes()).unwrap(), key);
+
+        let xml_v2 = format!(
+            "<?xml version=\"1.0\"?><KeyFile><Meta><Version>2.0</Version></Meta><Key><Data>{} {}</Data></Key></KeyFile>",
+            &hex[..32],
+            &hex[32..]
+        );
+        assert_eq!(key_file_key(xml_v2.as_bytes()).unwrap(), key);
+
+        // Tout autre fichier est haché
+        let arbitrary = b"photo de vacances";
+        let hashed: [u8; 32] = Sha256::digest(arbitrary).into();
+        assert_eq!(key_file_key(arbitrary).unwrap(), hashed);
+    }
+
+    #[test]
+    fn rejects_malformed_xml_key_files() {
+        let short = "<?xml version=\"1.0\"?><KeyFile><Key><Data>AAAA</Data></Key></KeyFile>";
+        assert!(key_file_key(short.as_bytes()).is_err());
+        let broken = "<?xml version=\"1.0\"?><KeyFile><Key>";
+        assert!(key_file_key(broken.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn aes_kdf_without_rounds_hashes_the_key() {
+        let key = [2u8; 32];
+        let expected: [u8; 32] = Sha256::digest(key).into();
+        let kdf = Kdf::from_parameters(&aes_parameters(0)).unwrap();
+        assert_eq!(kdf.transform(&key).unwrap(), expected);
+
+        let rounds = Kdf::from_parameters(&aes_parameters(3)).unwrap();
+        assert_ne!(rounds.transform(&key).unwrap(), expected);
+    }
+
+    #[test]
+    fn reads_argon2_parameters() {
+        let kdf = Kdf::from_parameters(&parameters(&[
+            (0x42, "$UUID", KDF_ARGON2ID.to_vec()),
+            (0x42, "S", vec![1u8; 32]),
+            (0x04, "V", 0x13u32.to_le_bytes().to_vec()),
+            (0x05, "M", (64 * 1024u64).to_le_bytes().to_vec()),
+            (0x05, "I", 2u64.to_le_bytes().to_vec()),
+            (0x04, "P", 1u32.to_le_bytes().to_vec()),
+        ]))
+        .unwrap();
+        match &kdf {
+            Kdf::Argon2 { algorithm, memory_kib, iterations, parallelism, .. } => {
+                assert_eq!(*algorithm, argon2::Algorithm::Argon2id);
+                assert_eq!((*memory_kib, *iterations, *parallelism), (64, 2, 1));
+            }
+            Kdf::Aes { .. } => panic!("Argon2 attendu"),
+        }
+        let key = [3u8; 32];
+        assert_eq!(kdf.transform(&key).unwrap(), kdf.transform(&key).unwrap());
+    }
+
+    #[test]
+    fn rejects_unknown_kdf_and_argon2_version() {
+        let unknown = parameters(&[(0x42, "$UUID", vec![0u8; 16])]);
+        assert!(Kdf::from_parameters(&unknown).is_err());
+
+        let bad_version = parameters(&[
+            (0x42, "$UUID", KDF_ARGON2D.to_vec()),
+            (0x04, "V", 0x12u32.to_le_bytes().to_vec()),
+        ]);
+        assert!(Kdf::from_parameters(&bad_version).is_err());
+    }
+}
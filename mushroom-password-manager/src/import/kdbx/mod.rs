@@ -0,0 +1,235 @@
+pub mod format;
+pub mod keys;
+pub mod xml;
+
+use aes::Aes256;
+use cbc::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+use chacha20::cipher::StreamCipher;
+use chacha20::ChaCha20;
+use std::io::Read;
+
+use crate::import::kdbx::format::Cipher;
+use crate::import::{imported_entry, ParsedImport};
+use crate::vault::entry::{CustomField, FieldKind};
+
+// Champs standard d'une entrée KeePass, les autres deviennent des champs personnalisés
+const STANDARD_FIELDS: [&str; 5] = ["Title", "UserName", "Password", "URL", "Notes"];
+
+// Signature présente au début de tout fichier KDBX
+pub fn is_kdbx(data: &[u8]) -> bool {
+    data.starts_with(&[0x03, 0xD9, 0xA2, 0x9A, 0x67, 0xFB, 0x4B, 0xB5])
+}
+
+// Ouvre une base KDBX 4 avec le mot de passe et le fichier clé éventuel
+pub fn parse(data: &[u8], password: &str, key_file: Option<&[u8]>) -> Result<ParsedImport, String> {
+    let (header, raw_header, position) = format::read_outer_header(data)?;
+    let mut cursor = format::Cursor::new(&data[position..]);
+    let header_hmac = cursor.bytes(32)?;
+
+    let composite = keys::composite_key(password, key_file)?;
+    let keys = keys::derive_keys(&header, &composite)?;
+    format::verify_header_hmac(raw_header, header_hmac, &keys.hmac_key)?;
+
+    let encrypted = format::read_hmac_blocks(&data[position + cursor.position()..], &keys.hmac_key)?;
+    let decrypted = match header.cipher {
+        Cipher::Aes256 => cbc::Decryptor::<Aes256>::new_from_slices(&keys.cipher_key, &header.encryption_iv)
+            .map_err(|_| "Vecteur d'initialisation AES invalide")?
+            .decrypt_padded_vec_mut::<Pkcs7>(&encrypted)
+            .map_err(|_| "Contenu KDBX indéchiffrable")?,
+        Cipher::ChaCha20 => {
+            let mut payload = encrypted;
+            ChaCha20::new_from_slices(&keys.cipher_key, &header.encryption_iv)
+                .map_err(|_| "Vecteur d'initialisation ChaCha20 invalide")?
+                .apply_keystream(&mut payload);
+            payload
+        }
+    };
+
+    let content = if header.compressed {
+        let mut inflated = Vec::new();
+        flate2::read::GzDecoder::new(decrypted.as_slice())
+            .read_to_end(&mut inflated)
+            .map_err(|e| format!("Décompression KDBX impossible: {}", e))?;
+        inflated
+    } else {
+        decrypted
+    };
+
+    let (inner, xml_start) = format::read_inner_header(&content)?;
+    let document = std::str::from_utf8(&content[xml_start..]).map_err(|_| "XML KDBX invalide (UTF-8)")?;
+    let kdbx_entries = xml::parse_entries(document, inner.stream_id, &inner.stream_key)?;
+
+    let mut parsed = ParsedImport::default();
+    for (index, item) in kdbx_entries.iter().enumerate() {
+        let title = item.string("Title");
+        let url = item.string("URL");
+        if title.trim().is_empty() && url.trim().is_empty() {
+            parsed.warnings.push(format!("Entrée {} ignorée : ni titre ni URL", index + 1));
+            continue;
+        }
+
+        let mut entry = imported_entry(title, url, item.string("UserName"), item.string("Password"));
+        entry.notes = item.string("Notes").into();
+        entry.folder = item.folder.clone();
+        entry.tags = item.tags.clone();
+        entry.created_at = item.created_at.or(entry.created_at);
+        entry.modified_at = item.modified_at.or(entry.modified_at);
+        entry.last_used_at = item.last_used_at;
+
+        for (name, value, protected) in &item.strings {
+            if STANDARD_FIELDS.contains(&name.as_str()) {
+                continue;
+            }
+            // KeePassXC stocke la configuration TOTP (URI otpauth) dans le champ « otp »
+            let kind = if name == "otp" || name.eq_ignore_ascii_case("totp") {
+                FieldKind::Totp
+            } else if *protected {
+                FieldKind::Hidden
+            } else if value.starts_with("http://") || value.starts_with("https://") {
+                FieldKind::Url
+            } else {
+                FieldKind::Text
+            };
+            entry.custom_fields.push(CustomField {
+                name: name.clone(),
+                value: value.as_str().into(),
+                kind,
+            });
+        }
+
+        parsed.entries.push(entry);
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+    use hmac::{Hmac, Mac};
+    use sha2::{Digest, Sha256, Sha512};
+
+    const AES_KDF: [u8; 16] = [
+        0xc9, 0xd9, 0xf3, 0x9a, 0x62, 0x8a, 0x44, 0x60, 0xbf, 0x74, 0x0d, 0x08, 0xc1, 0x8a, 0x4f, 0xea,
+    ];
+    const CHACHA20_CIPHER: [u8; 16] = [
+        0xd6, 0x03, 0x8a, 0x2b, 0x8b, 0x6f, 0x4c, 0xb5, 0xa5, 0x24, 0x33, 0x9a, 0x31, 0xdb, 0xb5, 0x9a,
+    ];
+    const STREAM_KEY: [u8; 64] = [5u8; 64];
+
+    fn field(id: u8, value: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![id];
+        bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(value);
+        bytes
+    }
+
+    fn hmac(key: &[u8], index: u64, parts: &[&[u8]]) -> Vec<u8> {
+        let mut block_key = Sha512::new();
+        block_key.update(index.to_le_bytes());
+        block_key.update(key);
+        let mut mac = Hmac::<Sha256>::new_from_slice(&block_key.finalize()).unwrap();
+        for part in parts {
+            mac.update(part);
+        }
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    // Mot de passe protégé par le flux ChaCha20 de l'en-tête interne
+    fn protected(value: &str) -> String {
+        let hash = Sha512::digest(STREAM_KEY);
+        let mut bytes = value.as_bytes().to_vec();
+        ChaCha20::new_from_slices(&hash[..32], &hash[32..44]).unwrap().apply_keystream(&mut bytes);
+        BASE64.encode(bytes)
+    }
+
+    // Base KDBX 4 minimale : ChaCha20, AES-KDF à une ronde, sans compression
+    fn database(password: &str) -> Vec<u8> {
+        let mut kdf = 0x0100u16.to_le_bytes().to_vec();
+        for (kind, key, value) in [
+            (0x42u8, "$UUID", AES_KDF.to_vec()),
+            (0x42, "S", vec![8u8; 32]),
+            (0x05, "R", 1u64.to_le_bytes().to_vec()),
+        ] {
+            kdf.push(kind);
+            kdf.extend_from_slice(&(key.len() as u32).to_le_bytes());
+            kdf.extend_from_slice(key.as_bytes());
+            kdf.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            kdf.extend_from_slice(&value);
+        }
+        kdf.push(0);
+
+        let mut header = [0x03, 0xD9, 0xA2, 0x9A, 0x67, 0xFB, 0x4B, 0xB5, 0x01, 0x00, 0x04, 0x00].to_vec();
+        header.extend(field(2, &CHACHA20_CIPHER));
+        header.extend(field(3, &0u32.to_le_bytes()));
+        header.extend(field(4, &[1u8; 32]));
+        header.extend(field(7, &[2u8; 12]));
+        header.extend(field(11, &kdf));
+        header.extend(field(0, b"\r\n\r\n"));
+
+        let mut hashed = header.clone();
+        hashed.extend_from_slice(&Sha256::digest(&header));
+        let (outer, _, _) = format::read_outer_header(&hashed).unwrap();
+        let keys = keys::derive_keys(&outer, &keys::composite_key(password, None).unwrap()).unwrap();
+
+        let xml = format!(
+            r#"<KeePassFile><Root><Group><Name>Racine</Name>
+  <Entry>
+    <String><Key>Title</Key><Value>Forge</Value></String>
+    <String><Key>URL</Key><Value>https://forge.example.com</Value></String>
+    <String><Key>UserName</Key><Value>alice</Value></String>
+    <String><Key>Password</Key><Value Protected="True">{}</Value></String>
+    <String><Key>otp</Key><Value>otpauth://totp/Forge?secret=JBSWY3DPEHPK3PXP</Value></String>
+  </Entry>
+  <Entry><String><Key>Notes</Key><Value>orpheline</Value></String></Entry>
+</Group></Root></KeePassFile>"#,
+            protected("hunter2")
+        );
+        let mut payload = field(1, &3u32.to_le_bytes());
+        payload.extend(field(2, &STREAM_KEY));
+        payload.extend(field(0, b""));
+        payload.extend_from_slice(xml.as_bytes());
+        ChaCha20::new_from_slices(&keys.cipher_key, &[2u8; 12]).unwrap().apply_keystream(&mut payload);
+
+        let mut data = hashed;
+        data.extend(hmac(&keys.hmac_key, u64::MAX, &[&header]));
+        for (index, block) in [payload.as_slice(), &[]].into_iter().enumerate() {
+            let size = (block.len() as i32).to_le_bytes();
+            data.extend(hmac(&keys.hmac_key, index as u64, &[&(index as u64).to_le_bytes(), &size, block]));
+            data.extend_from_slice(&size);
+            data.extend_from_slice(block);
+        }
+        data
+    }
+
+    #[test]
+    fn opens_a_kdbx4_database() {
+        let data = database("correct horse");
+        assert!(is_kdbx(&data));
+
+        let parsed = parse(&data, "correct horse", None).unwrap();
+        assert_eq!(parsed.entries.len(), 1);
+        assert_eq!(parsed.warnings.len(), 1);
+
+        let entry = &parsed.entries[0];
+        assert_eq!(entry.service_name, "forge.example");
+        assert_eq!(entry.username, "alice");
+        assert_eq!(&*entry.service_password, "hunter2");
+        assert_eq!(entry.custom_fields.len(), 1);
+        assert_eq!(entry.custom_fields[0].kind, FieldKind::Totp);
+    }
+
+    #[test]
+    fn rejects_a_wrong_password() {
+        let data = database("correct horse");
+        let error = parse(&data, "battery staple", None).err().unwrap();
+        assert_eq!(error, "Mot de passe ou fichier clé incorrect");
+    }
+
+    #[test]
+    fn recognises_the_kdbx_signature() {
+        assert!(!is_kdbx(b"title,url,username,password"));
+        assert!(!is_kdbx(&[0x03, 0xD9, 0xA2]));
+    }
+}
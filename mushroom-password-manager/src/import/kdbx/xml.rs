@@ -0,0 +1,269 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20::cipher::{KeyIvInit, StreamCipher};
+use chacha20::ChaCha20;
+use chrono::{DateTime, Utc};
+use salsa20::Salsa20;
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+
+const STREAM_SALSA20: u32 = 2;
+const STREAM_CHACHA20: u32 = 3;
+const SALSA20_IV: [u8; 8] = [0xE8, 0x30, 0x09, 0x4B, 0x97, 0x20, 0x5D, 0x2A];
+
+// Secondes entre 0001-01-01 (origine des dates KDBX 4) et l'époque Unix
+const KDBX_EPOCH_OFFSET: i64 = 62_135_596_800;
+
+// Flux qui masque les valeurs protégées (mots de passe) dans le XML
+enum ProtectedStream {
+    Salsa20(Box<Salsa20>),
+    ChaCha20(Box<ChaCha20>),
+}
+
+impl ProtectedStream {
+    fn new(stream_id: u32, key: &[u8]) -> Result<Self, String> {
+        match stream_id {
+            STREAM_CHACHA20 => {
+                let hash = Sha512::digest(key);
+                Ok(ProtectedStream::ChaCha20(Box::new(ChaCha20::new(
+                    hash[..32].into(),
+                    hash[32..44].into(),
+                ))))
+            }
+            STREAM_SALSA20 => {
+                let hash = Sha256::digest(key);
+                Ok(ProtectedStream::Salsa20(Box::new(Salsa20::new(
+                    hash.as_slice().into(),
+                    (&SALSA20_IV).into(),
+                ))))
+            }
+            other => Err(format!("Flux de protection KDBX non supporté: {}", other)),
+        }
+    }
+
+    fn unprotect(&mut self, data: &mut [u8]) {
+        match self {
+            ProtectedStream::Salsa20(stream) => stream.apply_keystream(data),
+            ProtectedStream::ChaCha20(stream) => stream.apply_keystream(data),
+        }
+    }
+}
+
+// Entrée KDBX avec le chemin de son groupe (groupe racine exclu)
+pub struct KdbxEntry {
+    pub folder: String,
+    pub strings: Vec<(String, String, bool)>,
+    pub tags: Vec<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub modified_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl KdbxEntry {
+    pub fn string(&self, key: &str) -> &str {
+        self.strings
+            .iter()
+            .find(|(name, _, _)| name == key)
+            .map(|(_, value, _)| value.as_str())
+            .unwrap_or("")
+    }
+}
+
+pub fn parse_entries(xml: &str, stream_id: u32, stream_key: &[u8]) -> Result<Vec<KdbxEntry>, String> {
+    let document = roxmltree::Document::parse(xml).map_err(|e| format!("XML KDBX invalide: {}", e))?;
+
+    // Les valeurs protégées se déchiffrent dans l'ordre du document,
+    // y compris celles de l'historique qui ne sont pas importées
+    let mut stream = ProtectedStream::new(stream_id, stream_key)?;
+    let mut unprotected: HashMap<roxmltree::NodeId, String> = HashMap::new();
+    for node in document.descendants() {
+        if node.has_tag_name("Value") && node.attribute("Protected").is_some_and(|v| v.eq_ignore_ascii_case("true")) {
+            let mut bytes = BASE64
+                .decode(node.text().unwrap_or("").trim())
+                .map_err(|_| String::from("Valeur protégée KDBX invalide"))?;
+            stream.unprotect(&mut bytes);
+            unprotected.insert(node.id(), String::from_utf8_lossy(&bytes).into_owned());
+        }
+    }
+
+    let root = document.root_element();
+    let recycle_bin = child(root, "Meta")
+        .and_then(|meta| {
+            let enabled = child(meta, "RecycleBinEnabled").and_then(|node| node.text()) != Some("False");
+            enabled.then(|| child(meta, "RecycleBinUUID").and_then(|node| node.text()))?
+        })
+        .map(str::to_string);
+
+    let top_group = child(root, "Root")
+        .and_then(|node| child(node, "Group"))
+        .ok_or("Groupe racine absent du XML KDBX")?;
+
+    let mut entries = Vec::new();
+    collect_group(top_group, &[], recycle_bin.as_deref(), &unprotected, &mut entries);
+    Ok(entries)
+}
+
+fn child<'a, 'input>(node: roxmltree::Node<'a, 'input>, name: &str) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn collect_group(
+    group: roxmltree::Node,
+    path: &[String],
+    recycle_bin: Option<&str>,
+    unprotected: &HashMap<roxmltree::NodeId, String>,
+    entries: &mut Vec<KdbxEntry>,
+) {
+    for node in group.children().filter(|node| node.is_element()) {
+        if node.has_tag_name("Entry") {
+            entries.push(read_entry(node, path, unprotected));
+        } else if node.has_tag_name("Group") {
+            let uuid = child(node, "UUID").and_then(|uuid| uuid.text());
+            if recycle_bin.is_some() && uuid == recycle_bin {
+                continue;
+            }
+            let name = child(node, "Name").and_then(|name| name.text()).unwrap_or("").to_string();
+            let mut sub_path = path.to_vec();
+            sub_path.push(name);
+            collect_group(node, &sub_path, recycle_bin, unprotected, entries);
+        }
+    }
+}
+
+fn read_entry(node: roxmltree::Node, path: &[String], unprotected: &HashMap<roxmltree::NodeId, String>) -> KdbxEntry {
+    let mut strings = Vec::new();
+    for string in node.children().filter(|child| child.has_tag_name("String")) {
+        let key = child(string, "Key").and_then(|key| key.text()).unwrap_or("").to_string();
+        let (value, protected) = match child(string, "Value") {
+            Some(value) => match unprotected.get(&value.id()) {
+                Some(text) => (text.clone(), true),
+                None => (value.text().unwrap_or("").to_string(), false),
+            },
+            None => (String::new(), false),
+        };
+        strings.push((key, value, protected));
+    }
+
+    let tags = child(node, "Tags")
+        .and_then(|tags| tags.text())
+        .map(|text| {
+            text.split([';', ','])
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let times = child(node, "Times");
+    let time = |name: &str| times.and_then(|times| child(times, name)).and_then(|node| node.text()).and_then(parse_time);
+
+    KdbxEntry {
+        folder: path.iter().filter(|segment| !segment.is_empty()).cloned().collect::<Vec<_>>().join("/"),
+        strings,
+        tags,
+        created_at: time("CreationTime"),
+        modified_at: time("LastModificationTime"),
+        last_used_at: time("LastAccessTime"),
+    }
+}
+
+// KDBX 4 : secondes depuis 0001-01-01 en base64 ; KDBX 3 : ISO 8601
+fn parse_time(text: &str) -> Option<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(text) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+    let bytes: [u8; 8] = BASE64.decode(text.trim()).ok()?.try_into().ok()?;
+    let seconds = i64::from_le_bytes(bytes).checked_sub(KDBX_EPOCH_OFFSET)?;
+    DateTime::<Utc>::from_timestamp(seconds, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STREAM_KEY: [u8; 64] = [7u8; 64];
+
+    // Masque les valeurs dans l'ordre du document, comme le ferait KeePass
+    fn protect(values: &[&str]) -> Vec<String> {
+        let mut stream = ProtectedStream::new(STREAM_CHACHA20, &STREAM_KEY).unwrap();
+        values
+            .iter()
+            .map(|value| {
+                let mut bytes = value.as_bytes().to_vec();
+                stream.unprotect(&mut bytes);
+                BASE64.encode(bytes)
+            })
+            .collect()
+    }
+
+    fn document() -> String {
+        let protected = protect(&["historique", "s3cr3t", "1234"]);
+        format!(
+            r#"<KeePassFile>
+  <Meta><RecycleBinEnabled>True</RecycleBinEnabled><RecycleBinUUID>corbeille</RecycleBinUUID></Meta>
+  <Root><Group><UUID>racine</UUID><Name>Racine</Name>
+    <Group><UUID>banques</UUID><Name>Banques</Name>
+      <Group><UUID>perso</UUID><Name>Perso</Name>
+        <Entry>
+          <String><Key>Title</Key><Value>Banque</Value></String>
+          <History><Entry><String><Key>Password</Key><Value Protected="True">{}</Value></String></Entry></History>
+          <String><Key>Password</Key><Value Protected="True">{}</Value></String>
+          <String><Key>PIN</Key><Value Protected="True">{}</Value></String>
+          <Tags>finance; important,</Tags>
+          <Times><CreationTime>2020-01-02T03:04:05Z</CreationTime></Times>
+        </Entry>
+      </Group>
+    </Group>
+    <Group><UUID>corbeille</UUID><Name>Corbeille</Name>
+      <Entry><String><Key>Title</Key><Value>Supprimée</Value></String></Entry>
+    </Group>
+    <Entry><String><Key>Title</Key><Value>Racine</Value></String></Entry>
+  </Group></Root>
+</KeePassFile>"#,
+            protected[0], protected[1], protected[2]
+        )
+    }
+
+    #[test]
+    fn parses_entries_with_folders_and_protected_values() {
+        let entries = parse_entries(&document(), STREAM_CHACHA20, &STREAM_KEY).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let bank = &entries[0];
+        assert_eq!(bank.folder, "Banques/Perso");
+        assert_eq!(bank.string("Title"), "Banque");
+        // L'historique consomme le flux sans être importé
+        assert_eq!(bank.string("Password"), "s3cr3t");
+        assert_eq!(bank.string("PIN"), "1234");
+        assert!(bank.strings.iter().any(|(name, _, protected)| name == "PIN" && *protected));
+        assert_eq!(bank.tags, vec!["finance", "important"]);
+        assert_eq!(bank.created_at.unwrap().to_rfc3339(), "2020-01-02T03:04:05+00:00");
+        assert_eq!(bank.string("URL"), "");
+
+        assert_eq!(entries[1].folder, "");
+        assert_eq!(entries[1].string("Title"), "Racine");
+    }
+
+    #[test]
+    fn keeps_recycle_bin_when_disabled() {
+        let xml = document().replace("<RecycleBinEnabled>True", "<RecycleBinEnabled>False");
+        let entries = parse_entries(&xml, STREAM_CHACHA20, &STREAM_KEY).unwrap();
+        assert!(entries.iter().any(|entry| entry.folder == "Corbeille"));
+    }
+
+    #[test]
+    fn rejects_unknown_stream_and_invalid_xml() {
+        assert!(parse_entries(&document(), 1, &STREAM_KEY).is_err());
+        assert!(parse_entries("<KeePassFile>", STREAM_CHACHA20, &STREAM_KEY).is_err());
+        assert!(parse_entries("<KeePassFile><Root/></KeePassFile>", STREAM_CHACHA20, &STREAM_KEY).is_err());
+    }
+
+    #[test]
+    fn parses_kdbx4_and_iso_timestamps() {
+        let unix = 1_600_000_000i64;
+        let encoded = BASE64.encode((unix + KDBX_EPOCH_OFFSET).to_le_bytes());
+        assert_eq!(parse_time(&encoded).unwrap().timestamp(), unix);
+        assert_eq!(parse_time("2021-06-01T12:00:00Z").unwrap().timestamp(), 1_622_548_800);
+        assert!(parse_time("hier").is_none());
+    }
+}
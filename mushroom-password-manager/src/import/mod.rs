@@ -0,0 +1,347 @@
+pub mod bitwarden;
+pub mod csv_formats;
+pub mod kdbx;
+
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+use crate::i18n;
+use crate::vault::entry::{service_name_from_url, Entry};
+
+// Formats d'export des autres gestionnaires pris en charge
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Bitwarden,
+    Chrome,
+    Firefox,
+    KeePassXc,
+    Kdbx,
+}
+
+impl ImportFormat {
+    pub const ALL: [ImportFormat; 5] = [
+        ImportFormat::Bitwarden,
+        ImportFormat::Chrome,
+        ImportFormat::Firefox,
+        ImportFormat::KeePassXc,
+        ImportFormat::Kdbx,
+    ];
+
+    // Nom utilisé en ligne de commande
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "bitwarden" => Some(ImportFormat::Bitwarden),
+            "chrome" | "edge" => Some(ImportFormat::Chrome),
+            "firefox" => Some(ImportFormat::Firefox),
+            "keepassxc" | "keepassxc-csv" => Some(ImportFormat::KeePassXc),
+            "kdbx" | "keepass" => Some(ImportFormat::Kdbx),
+            _ => None,
+        }
+    }
+
+    // Les bases KeePass sont chiffrées : il faut le mot de passe pour les lire
+    pub fn needs_password(&self) -> bool {
+        matches!(self, ImportFormat::Kdbx)
+    }
+
+    // Devine le format d'après l'extension et l'en-tête du fichier
+    pub fn detect(path: &Path, data: &[u8]) -> Option<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+
+        if extension.as_deref() == Some("kdbx") || kdbx::is_kdbx(data) {
+            return Some(ImportFormat::Kdbx);
+        }
+        if extension.as_deref() == Some("json") {
+            return Some(ImportFormat::Bitwarden);
+        }
+
+        let text = String::from_utf8_lossy(data);
+        let header = text.trim_start_matches('\u{feff}').lines().next()?.to_ascii_lowercase();
+        let columns: Vec<String> = header.split(',').map(|column| column.trim().trim_matches('"').to_string()).collect();
+        let has = |name: &str| columns.iter().any(|column| column == name);
+
+        if has("group") && has("title") {
+            Some(ImportFormat::KeePassXc)
+        } else if has("httprealm") || has("formactionorigin") {
+            Some(ImportFormat::Firefox)
+        } else if has("name") && has("url") && has("password") {
+            Some(ImportFormat::Chrome)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let id = match self {
+            ImportFormat::Bitwarden => "import-format-bitwarden",
+            ImportFormat::Chrome => "import-format-chrome",
+            ImportFormat::Firefox => "import-format-firefox",
+            ImportFormat::KeePassXc => "import-format-keepassxc",
+            ImportFormat::Kdbx => "import-format-kdbx",
+        };
+        write!(f, "{}", i18n::tr(id))
+    }
+}
+
+// Entrées lues dans un export, avec les éléments ignorés signalés à l'utilisateur
+#[derive(Debug, Clone, Default)]
+pub struct ParsedImport {
+    pub entries: Vec<Entry>,
+    pub warnings: Vec<String>,
+}
+
+// Mot de passe et fichier clé d'une base chiffrée ; ignorés pour les autres formats
+#[derive(Debug, Clone, Default)]
+pub struct Credentials {
+    pub password: String,
+    pub key_file: Option<Vec<u8>>,
+}
+
+pub fn parse(format: ImportFormat, data: &[u8], credentials: &Credentials) -> Result<ParsedImport, String> {
+    match format {
+        ImportFormat::Bitwarden => bitwarden::parse(export_text(data)?),
+        ImportFormat::Chrome => csv_formats::parse_chrome(export_text(data)?),
+        ImportFormat::Firefox => csv_formats::parse_firefox(export_text(data)?),
+        ImportFormat::KeePassXc => csv_formats::parse_keepassxc(export_text(data)?),
+        ImportFormat::Kdbx => kdbx::parse(data, &credentials.password, credentials.key_file.as_deref()),
+    }
+}
+
+fn export_text(data: &[u8]) -> Result<&str, String> {
+    Ok(std::str::from_utf8(data)
+        .map_err(|_| String::from("Le fichier n'est pas un texte UTF-8"))?
+        .trim_start_matches('\u{feff}'))
+}
+
+// Doublon d'une entrée existante : même service et même identifiant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duplicate {
+    // Même mot de passe : rien à importer
+    Identical,
+    // Mot de passe différent : à vérifier avant d'importer
+    Conflict,
+}
+
+impl fmt::Display for Duplicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let id = match self {
+            Duplicate::Identical => "import-duplicate-identical",
+            Duplicate::Conflict => "import-duplicate-conflict",
+        };
+        write!(f, "{}", i18n::tr(id))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportCandidate {
+    pub entry: Entry,
+    // Doublon détecté et clé de l'entrée existante correspondante
+    pub duplicate: Option<(Duplicate, String)>,
+    pub selected: bool,
+}
+
+// Aperçu d'un import avant validation
+#[derive(Debug, Clone)]
+pub struct ImportPreview {
+    pub format: ImportFormat,
+    pub candidates: Vec<ImportCandidate>,
+    pub warnings: Vec<String>,
+}
+
+impl ImportPreview {
+    pub fn selected_count(&self) -> usize {
+        self.candidates.iter().filter(|candidate| candidate.selected).count()
+    }
+
+    pub fn duplicate_count(&self) -> usize {
+        self.candidates.iter().filter(|candidate| candidate.duplicate.is_some()).count()
+    }
+
+    pub fn selected_entries(&self) -> Vec<Entry> {
+        self.candidates
+            .iter()
+            .filter(|candidate| candidate.selected)
+            .map(|candidate| candidate.entry.clone())
+            .collect()
+    }
+}
+
+// Compare les entrées lues aux entrées existantes. Les doublons sont désélectionnés
+// par défaut et chaque entrée reçoit une clé libre pour ne rien écraser côté serveur.
+pub fn preview(format: ImportFormat, parsed: ParsedImport, existing: &[Entry]) -> ImportPreview {
+    let mut taken: HashSet<String> = existing.iter().map(|entry| entry.id.clone()).collect();
+
+    let candidates = parsed
+        .entries
+        .into_iter()
+        .map(|mut entry| {
+            let duplicate = find_duplicate(&entry, existing);
+            entry.id = unique_id(&entry, &mut taken);
+            ImportCandidate {
+                selected: duplicate.is_none(),
+                entry,
+                duplicate,
+            }
+        })
+        .collect();
+
+    ImportPreview {
+        format,
+        candidates,
+        warnings: parsed.warnings,
+    }
+}
+
+fn find_duplicate(entry: &Entry, existing: &[Entry]) -> Option<(Duplicate, String)> {
+    existing
+        .iter()
+        .filter(|other| {
+            other.service_name.eq_ignore_ascii_case(&entry.service_name)
+                && other.username.eq_ignore_ascii_case(&entry.username)
+        })
+        .map(|other| {
+            let kind = if other.service_password == entry.service_password {
+                Duplicate::Identical
+            } else {
+                Duplicate::Conflict
+            };
+            (kind, other.id.clone())
+        })
+        // Un doublon identique l'emporte sur un conflit
+        .min_by_key(|(kind, _)| *kind != Duplicate::Identical)
+}
+
+// Le serveur indexe les entrées par URL : plusieurs comptes d'un même site
+// reçoivent un suffixe après le chemin, qui n'affecte pas le nom du service
+fn unique_id(entry: &Entry, taken: &mut HashSet<String>) -> String {
+    let base = if entry.service_url.is_empty() {
+        entry.service_name.clone()
+    } else {
+        entry.service_url.clone()
+    };
+
+    let mut id = base.clone();
+    let mut counter = 2;
+    while taken.contains(&id) {
+        id = format!("{}/#{}", base.trim_end_matches('/'), counter);
+        counter += 1;
+    }
+    taken.insert(id.clone());
+    id
+}
+
+// Entrée importée : le nom du service est déduit de l'URL quand il y en a une
+pub(crate) fn imported_entry(name: &str, url: &str, username: &str, password: &str) -> Entry {
+    let url = url.trim();
+    let service_name = if url.is_empty() {
+        name.trim().to_string()
+    } else {
+        service_name_from_url(url)
+    };
+
+    let mut entry = Entry::new(url, &service_name);
+    entry.username = username.to_string();
+    entry.service_password = password.into();
+    entry
+}
+
+// Horodatages des exports : RFC 3339 ou millisecondes Unix
+pub(crate) fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+    value
+        .parse::<i64>()
+        .ok()
+        .and_then(DateTime::<Utc>::from_timestamp_millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn existing(url: &str, username: &str, password: &str) -> Entry {
+        let mut entry = imported_entry("", url, username, password);
+        entry.id = url.to_string();
+        entry
+    }
+
+    #[test]
+    fn detects_formats_from_extension_and_header() {
+        let detect = |name: &str, data: &str| ImportFormat::detect(Path::new(name), data.as_bytes());
+        assert_eq!(detect("export.json", "{}"), Some(ImportFormat::Bitwarden));
+        assert_eq!(detect("base.kdbx", ""), Some(ImportFormat::Kdbx));
+        assert_eq!(detect("a.csv", "\u{feff}name,url,username,password\n"), Some(ImportFormat::Chrome));
+        assert_eq!(
+            detect("a.csv", "\"url\",\"username\",\"password\",\"httpRealm\",\"formActionOrigin\"\n"),
+            Some(ImportFormat::Firefox)
+        );
+        assert_eq!(detect("a.csv", "\"Group\",\"Title\",\"Username\",\"Password\",\"URL\"\n"), Some(ImportFormat::KeePassXc));
+        assert_eq!(detect("a.csv", "foo,bar\n"), None);
+    }
+
+    #[test]
+    fn format_names_accept_aliases() {
+        assert_eq!(ImportFormat::from_name("Edge"), Some(ImportFormat::Chrome));
+        assert_eq!(ImportFormat::from_name("keepass"), Some(ImportFormat::Kdbx));
+        assert_eq!(ImportFormat::from_name("lastpass"), None);
+    }
+
+    #[test]
+    fn preview_deselects_duplicates() {
+        let current = vec![existing("https://exemple.fr", "alice", "secret")];
+        let parsed = ParsedImport {
+            entries: vec![
+                imported_entry("", "https://exemple.fr", "ALICE", "secret"),
+                imported_entry("", "https://exemple.fr", "alice", "autre"),
+                imported_entry("", "https://exemple.fr", "bob", "secret"),
+            ],
+            warnings: Vec::new(),
+        };
+        let preview = preview(ImportFormat::Chrome, parsed, &current);
+        let duplicates: Vec<Option<Duplicate>> =
+            preview.candidates.iter().map(|candidate| candidate.duplicate.as_ref().map(|(kind, _)| *kind)).collect();
+        assert_eq!(duplicates, vec![Some(Duplicate::Identical), Some(Duplicate::Conflict), None]);
+        assert_eq!(preview.selected_count(), 1);
+        assert_eq!(preview.duplicate_count(), 2);
+    }
+
+    #[test]
+    fn preview_never_reuses_a_taken_id() {
+        let current = vec![existing("https://exemple.fr/", "alice", "a")];
+        let parsed = ParsedImport {
+            entries: vec![
+                imported_entry("", "https://exemple.fr/", "bob", "b"),
+                imported_entry("", "https://exemple.fr/", "carol", "c"),
+                imported_entry("Wi-Fi", "", "", "d"),
+            ],
+            warnings: Vec::new(),
+        };
+        let ids: Vec<String> = preview(ImportFormat::Chrome, parsed, &current)
+            .candidates
+            .into_iter()
+            .map(|candidate| candidate.entry.id)
+            .collect();
+        assert_eq!(ids, vec!["https://exemple.fr/#2", "https://exemple.fr/#3", "Wi-Fi"]);
+    }
+
+    #[test]
+    fn timestamps_accept_rfc3339_and_unix_milliseconds() {
+        let expected = DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z").unwrap().with_timezone(&Utc);
+        assert_eq!(parse_timestamp("2024-01-02T03:04:05Z"), Some(expected));
+        assert_eq!(parse_timestamp("1704164645000"), Some(expected));
+        assert_eq!(parse_timestamp(" "), None);
+        assert_eq!(parse_timestamp("hier"), None);
+    }
+}
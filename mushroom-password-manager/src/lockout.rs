@@ -0,0 +1,278 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::export;
+use crate::i18n;
+use crate::vault::totp;
+
+const LOCKOUT_FILE: &str = "lockout.json";
+// Essais libres avant la première attente
+const FREE_ATTEMPTS: u32 = 3;
+// Attente (secondes) après le premier essai de trop, doublée à chaque nouvel échec
+const BASE_DELAY: u64 = 2;
+const MAX_DELAY: u64 = 15 * 60;
+
+// Source de l'heure (secondes Unix), remplaçable pour simuler l'écoulement du temps
+pub trait Clock {
+    fn now(&self) -> u64;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        totp::unix_now()
+    }
+}
+
+// Effacement du cache local après trop d'échecs, réglé par profil
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WipePolicy {
+    #[default]
+    Never,
+    After(u32),
+}
+
+impl WipePolicy {
+    pub const ALL: [WipePolicy; 4] = [WipePolicy::Never, WipePolicy::After(5), WipePolicy::After(10), WipePolicy::After(20)];
+
+    pub fn is_never(&self) -> bool {
+        *self == WipePolicy::Never
+    }
+}
+
+impl fmt::Display for WipePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            WipePolicy::Never => i18n::tr("wipe-policy-never"),
+            WipePolicy::After(failures) => i18n::tr_args("wipe-policy-after", &[("count", (*failures).into())]),
+        };
+        f.write_str(&text)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+struct LockoutState {
+    failures: u32,
+    // Heure du dernier échec (secondes Unix)
+    last_failure: u64,
+    // Dernier pas TOTP accepté : un code déjà utilisé n'ouvre plus le profil
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    second_factor_step: Option<u64>,
+}
+
+// Délai imposé après `failures` échecs consécutifs
+pub fn delay_after(failures: u32) -> u64 {
+    if failures < FREE_ATTEMPTS {
+        return 0;
+    }
+    let doublings = (failures - FREE_ATTEMPTS).min(32);
+    BASE_DELAY.saturating_mul(1 << doublings).min(MAX_DELAY)
+}
+
+// Échecs de connexion d'un profil, enregistrés sur le disque : relancer l'application
+// ne remet pas le compteur à zéro
+pub struct LoginLimiter<C: Clock = SystemClock> {
+    path: PathBuf,
+    state: LockoutState,
+    clock: C,
+}
+
+impl LoginLimiter {
+    pub fn open(dir: &Path) -> Self {
+        LoginLimiter::with_clock(dir, SystemClock)
+    }
+}
+
+impl<C: Clock> LoginLimiter<C> {
+    // Un fichier illisible repart de zéro : le supprimer aurait le même effet
+    pub fn with_clock(dir: &Path, clock: C) -> Self {
+        let path = dir.join(LOCKOUT_FILE);
+        let state = std::fs::read(&path)
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default();
+        LoginLimiter { path, state, clock }
+    }
+
+    pub fn failures(&self) -> u32 {
+        self.state.failures
+    }
+
+    // Secondes à attendre avant le prochain essai ; 0 s'il est permis.
+    // Une horloge qui recule ne prolonge pas l'attente au-delà du délai
+    pub fn remaining(&self) -> u64 {
+        let delay = delay_after(self.state.failures);
+        let elapsed = self.clock.now().saturating_sub(self.state.last_failure);
+        delay.saturating_sub(elapsed)
+    }
+
+    pub fn record_failure(&mut self) -> Result<(), String> {
+        self.state.failures = self.state.failures.saturating_add(1);
+        self.state.last_failure = self.clock.now();
+        self.save()
+    }
+
+    pub fn now(&self) -> u64 {
+        self.clock.now()
+    }
+
+    pub fn second_factor_step(&self) -> Option<u64> {
+        self.state.second_factor_step
+    }
+
+    pub fn record_second_factor(&mut self, step: u64) -> Result<(), String> {
+        self.state.second_factor_step = Some(step);
+        self.save()
+    }
+
+    // Le compteur repart de zéro ; le dernier pas TOTP accepté est conservé
+    pub fn record_success(&mut self) -> Result<(), String> {
+        self.state = LockoutState { second_factor_step: self.state.second_factor_step, ..LockoutState::default() };
+        if self.state.second_factor_step.is_some() {
+            return self.save();
+        }
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Suppression de '{}' impossible: {}", self.path.display(), e)),
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let data = serde_json::to_vec(&self.state).map_err(|e| format!("Erreur d'encodage JSON: {}", e))?;
+        export::write_private(&self.path, &data)
+    }
+
+    // Vrai dès que le nombre d'échecs atteint le seuil de la politique d'effacement
+    pub fn should_wipe(&self, policy: WipePolicy) -> bool {
+        match policy {
+            WipePolicy::Never => false,
+            WipePolicy::After(limit) => self.state.failures >= limit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use crate::test_support::TestDir;
+
+    // Horloge manuelle partagée entre le test et le limiteur
+    #[derive(Clone, Default)]
+    struct FakeClock(Rc<Cell<u64>>);
+
+    impl FakeClock {
+        fn advance(&self, seconds: u64) {
+            self.0.set(self.0.get() + seconds);
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> u64 {
+            self.0.get()
+        }
+    }
+
+    #[test]
+    fn delay_doubles_after_the_free_attempts() {
+        assert_eq!(delay_after(0), 0);
+        assert_eq!(delay_after(FREE_ATTEMPTS - 1), 0);
+        assert_eq!(delay_after(FREE_ATTEMPTS), BASE_DELAY);
+        assert_eq!(delay_after(FREE_ATTEMPTS + 1), 2 * BASE_DELAY);
+        assert_eq!(delay_after(FREE_ATTEMPTS + 2), 4 * BASE_DELAY);
+        assert_eq!(delay_after(FREE_ATTEMPTS + 20), MAX_DELAY);
+        assert_eq!(delay_after(u32::MAX), MAX_DELAY);
+    }
+
+    #[test]
+    fn remaining_wait_follows_the_clock() {
+        let dir = TestDir::new("wait");
+        let clock = FakeClock::default();
+        clock.advance(1_000);
+        let mut limiter = LoginLimiter::with_clock(&dir.0, clock.clone());
+
+        for _ in 0..FREE_ATTEMPTS - 1 {
+            limiter.record_failure().unwrap();
+            assert_eq!(limiter.remaining(), 0);
+        }
+        limiter.record_failure().unwrap();
+        assert_eq!(limiter.remaining(), BASE_DELAY);
+        clock.advance(1);
+        assert_eq!(limiter.remaining(), BASE_DELAY - 1);
+        clock.advance(BASE_DELAY);
+        assert_eq!(limiter.remaining(), 0);
+
+        limiter.record_failure().unwrap();
+        assert_eq!(limiter.remaining(), 2 * BASE_DELAY);
+        // Le compteur survit à un redémarrage de l'application
+        let reopened = LoginLimiter::with_clock(&dir.0, clock.clone());
+        assert_eq!(reopened.failures(), FREE_ATTEMPTS + 1);
+        assert_eq!(reopened.remaining(), 2 * BASE_DELAY);
+    }
+
+    #[test]
+    fn success_resets_the_counter() {
+        let dir = TestDir::new("reset");
+        let clock = FakeClock::default();
+        let mut limiter = LoginLimiter::with_clock(&dir.0, clock.clone());
+        for _ in 0..FREE_ATTEMPTS + 2 {
+            limiter.record_failure().unwrap();
+        }
+        assert!(limiter.remaining() > 0);
+
+        limiter.record_success().unwrap();
+        assert_eq!(limiter.failures(), 0);
+        assert_eq!(limiter.remaining(), 0);
+        assert!(!dir.0.join(LOCKOUT_FILE).exists());
+        assert_eq!(LoginLimiter::with_clock(&dir.0, clock).failures(), 0);
+    }
+
+    #[test]
+    fn wipe_policy_thresholds() {
+        let dir = TestDir::new("wipe");
+        let mut limiter = LoginLimiter::with_clock(&dir.0, FakeClock::default());
+        for _ in 0..4 {
+            limiter.record_failure().unwrap();
+        }
+        assert!(!limiter.should_wipe(WipePolicy::After(5)));
+        limiter.record_failure().unwrap();
+        assert!(limiter.should_wipe(WipePolicy::After(5)));
+        assert!(!limiter.should_wipe(WipePolicy::After(10)));
+        assert!(!limiter.should_wipe(WipePolicy::Never));
+    }
+
+    #[test]
+    fn unreadable_state_starts_from_zero() {
+        let dir = TestDir::new("corrupt");
+        std::fs::write(dir.0.join(LOCKOUT_FILE), b"{pas du json").unwrap();
+        let limiter = LoginLimiter::with_clock(&dir.0, FakeClock::default());
+        assert_eq!(limiter.failures(), 0);
+        assert_eq!(limiter.remaining(), 0);
+    }
+
+    #[test]
+    fn second_factor_step_survives_success_and_restart() {
+        let dir = TestDir::new("totp");
+        let clock = FakeClock::default();
+        let mut limiter = LoginLimiter::with_clock(&dir.0, clock.clone());
+        assert_eq!(limiter.second_factor_step(), None);
+
+        limiter.record_failure().unwrap();
+        limiter.record_second_factor(42).unwrap();
+        limiter.record_success().unwrap();
+        assert_eq!(limiter.failures(), 0);
+        assert_eq!(limiter.second_factor_step(), Some(42));
+
+        clock.advance(60);
+        let reopened = LoginLimiter::with_clock(&dir.0, clock);
+        assert_eq!(reopened.failures(), 0);
+        assert_eq!(reopened.remaining(), 0);
+        assert_eq!(reopened.second_factor_step(), Some(42));
+    }
+}
@@ -0,0 +1,194 @@
+use env_logger::filter::{Builder as FilterBuilder, Filter};
+use log::kv::{self, Key, Value, VisitSource};
+use log::{Log, Metadata, Record};
+use regex::Regex;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::sync::{Mutex, OnceLock};
+
+// Filtre par défaut, remplacé par celui de la configuration, lui-même remplacé par la
+// variable d'environnement (syntaxe RUST_LOG : "debug", "mushroom_password_manager::api=trace"...)
+const DEFAULT_FILTER: &str = "warn,mushroom_password_manager=info";
+const FILTER_ENV: &str = "MUSHROOM_LOG";
+const REDACTED: &str = "***";
+
+// Noms (ou fragments de noms) des champs dont la valeur est un secret
+const SECRET_FIELDS: [&str; 9] = ["password", "passwd", "secret", "token", "notes", "verifier", "wrapped_key", "totp", "private"];
+
+pub fn is_secret_field(name: &str) -> bool {
+    let name = name.to_lowercase();
+    SECRET_FIELDS.iter().any(|field| name.contains(field))
+}
+
+// Masque les valeurs des champs secrets dans un texte libre : objets JSON
+// ("service_password": "...", ainsi que "value" des champs personnalisés) et paires clé=valeur
+pub fn redact(text: &str) -> String {
+    static JSON: OnceLock<Regex> = OnceLock::new();
+    static PAIRS: OnceLock<Regex> = OnceLock::new();
+    let json = JSON.get_or_init(|| {
+        Regex::new(&format!(
+            r#"(?i)"([a-z_]*(?:{})[a-z_]*|value)"\s*:\s*(?:"(?:[^"\\]|\\.)*"|[^,}}\]\s]+)"#,
+            SECRET_FIELDS.join("|")
+        ))
+        .expect("expression de masquage valide")
+    });
+    let pairs = PAIRS.get_or_init(|| {
+        Regex::new(&format!(r#"(?i)\b([a-z_]*(?:{})[a-z_]*)\s*[=:]\s*[^\s,;}}]+"#, SECRET_FIELDS.join("|")))
+            .expect("expression de masquage valide")
+    });
+    let text = json.replace_all(text, format!(r#""$1": "{}""#, REDACTED));
+    pairs.replace_all(&text, format!("$1={}", REDACTED)).into_owned()
+}
+
+// Journal de l'application : chaque ligne passe par le masquage avant d'atteindre
+// la sortie, message comme champs structurés
+pub struct RedactingLogger {
+    filter: Filter,
+    sink: Mutex<Box<dyn Write + Send>>,
+}
+
+impl RedactingLogger {
+    pub fn new(filter: &str, sink: Box<dyn Write + Send>) -> Self {
+        RedactingLogger { filter: FilterBuilder::new().parse(filter).build(), sink: Mutex::new(sink) }
+    }
+
+    fn format(&self, record: &Record) -> String {
+        let mut line = format!(
+            "{} {:<5} {}: {}",
+            chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+            record.level(),
+            record.target(),
+            redact(&record.args().to_string())
+        );
+        let _ = record.key_values().visit(&mut Fields(&mut line));
+        line
+    }
+}
+
+// Champs structurés ajoutés en fin de ligne sous la forme clé=valeur
+struct Fields<'a>(&'a mut String);
+
+impl<'kvs> VisitSource<'kvs> for Fields<'_> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        let value = if is_secret_field(key.as_str()) {
+            REDACTED.to_string()
+        } else {
+            redact(&value.to_string())
+        };
+        let _ = write!(self.0, " {}={}", key, value);
+        Ok(())
+    }
+}
+
+impl Log for RedactingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.filter.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.filter.matches(record) {
+            return;
+        }
+        let line = self.format(record);
+        if let Ok(mut sink) = self.sink.lock() {
+            let _ = writeln!(sink, "{}", line);
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut sink) = self.sink.lock() {
+            let _ = sink.flush();
+        }
+    }
+}
+
+// Installe le journal sur la sortie d'erreur, au niveau choisi dans la configuration
+pub fn init(configured: Option<&str>) -> Result<(), String> {
+    let filter = std::env::var(FILTER_ENV)
+        .ok()
+        .or_else(|| configured.map(str::to_string))
+        .unwrap_or_else(|| DEFAULT_FILTER.to_string());
+    let logger = RedactingLogger::new(&filter, Box::new(std::io::stderr()));
+    log::set_max_level(logger.filter.filter());
+    log::set_boxed_logger(Box::new(logger)).map_err(|e| format!("Journalisation impossible: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::vault::entry::Entry;
+
+    const SECRET: &str = "Tr0ub4dor&3-jamais-journalise";
+
+    // Sortie partagée avec le test, pour relire ce que le journal a écrit
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(data);
+            Ok(data.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn emit(logger: &RedactingLogger, level: log::Level, args: std::fmt::Arguments, fields: &[(&str, &str)]) {
+        logger.log(
+            &Record::builder()
+                .level(level)
+                .target("mushroom_password_manager::api::client")
+                .args(args)
+                .key_values(&fields)
+                .build(),
+        );
+    }
+
+    #[test]
+    fn secrets_never_reach_the_sink() {
+        let captured = Captured::default();
+        let logger = RedactingLogger::new("trace", Box::new(captured.clone()));
+
+        let mut entry = Entry::new("https://exemple.fr", "exemple");
+        entry.service_password = SECRET.into();
+        entry.notes = SECRET.into();
+        let body = serde_json::json!({
+            "service_URL": "https://exemple.fr",
+            "service_password": SECRET,
+            "notes": SECRET,
+            "custom_fields": [{ "name": "PIN", "value": SECRET, "kind": "hidden" }],
+        });
+
+        emit(&logger, log::Level::Debug, format_args!("Réponse brute: {}", body), &[]);
+        emit(&logger, log::Level::Debug, format_args!("Entrée: {:?}", entry), &[]);
+        emit(&logger, log::Level::Info, format_args!("connexion password={} token: {}", SECRET, SECRET), &[]);
+        emit(
+            &logger,
+            log::Level::Info,
+            format_args!("Champs structurés"),
+            &[("master_password", SECRET), ("access_token", SECRET), ("service_url", "https://exemple.fr")],
+        );
+        logger.flush();
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(output.lines().count(), 4, "{}", output);
+        assert!(!output.contains(SECRET), "secret présent dans le journal :\n{}", output);
+        assert!(output.contains("service_url=https://exemple.fr"), "{}", output);
+        assert!(output.contains("exemple.fr"), "{}", output);
+    }
+
+    #[test]
+    fn filter_drops_lower_levels() {
+        let captured = Captured::default();
+        let logger = RedactingLogger::new("warn", Box::new(captured.clone()));
+        emit(&logger, log::Level::Debug, format_args!("détail"), &[]);
+        emit(&logger, log::Level::Warn, format_args!("alerte"), &[]);
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(!output.contains("détail") && output.contains("alerte"), "{}", output);
+    }
+}
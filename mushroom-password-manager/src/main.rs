@@ -1,11 +1,36 @@
 mod api;
+mod backup;
+mod cli;
+mod config;
+mod export;
+mod i18n;
+mod import;
+mod lockout;
+mod logging;
+mod paths;
+mod profile;
+#[cfg(test)]
+mod test_support;
 mod ui;
+mod vault;
 
 use iced::{Settings, window};
 use ui::app::PasswordManagerApp;
 use iced::Application;
 
 fn main() -> iced::Result {
+    let config = config::AppConfig::load().unwrap_or_default();
+    i18n::set_language(config.appearance.language);
+    if let Err(e) = logging::init(config.logging.level.as_deref()) {
+        eprintln!("{}", e);
+    }
+
+    // Les sous-commandes (import, ...) s'exécutent sans interface graphique
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
     PasswordManagerApp::run(Settings {
         window: window::Settings {
             size: (900, 600),
@@ -0,0 +1,17 @@
+use directories::ProjectDirs;
+use std::path::PathBuf;
+
+// Dossiers de l'application selon les conventions du système
+// (~/.local/share/mushroom-password-manager sous Linux)
+fn project_dirs() -> Result<ProjectDirs, String> {
+    ProjectDirs::from("fr", "Mushroom", "mushroom-password-manager")
+        .ok_or_else(|| String::from("Dossier personnel introuvable"))
+}
+
+pub fn data_dir() -> Result<PathBuf, String> {
+    Ok(project_dirs()?.data_dir().to_path_buf())
+}
+
+pub fn config_dir() -> Result<PathBuf, String> {
+    Ok(project_dirs()?.config_dir().to_path_buf())
+}
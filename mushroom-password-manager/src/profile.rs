@@ -0,0 +1,470 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::api::client::{ApiClient, DEFAULT_API_URL};
+use crate::api::error::ApiError;
+use crate::api::sync::SyncHandle;
+use crate::export;
+use crate::i18n;
+use crate::lockout::WipePolicy;
+use crate::paths;
+use crate::vault::cache;
+use crate::vault::crypto::{DecryptError, KEY_LENGTH};
+use crate::vault::e2e::{self, EntryCipher, VaultParams};
+use crate::vault::factors::KeyFile;
+use crate::vault::secure::{SecretKey, SecretString};
+use crate::vault::totp::Totp;
+
+const PROFILES_FILE: &str = "profiles.json";
+// Profil créé au premier lancement ; il garde les dossiers d'avant les profils
+const DEFAULT_PROFILE_ID: &str = "default";
+
+// Où vit le coffre d'un profil
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Backend {
+    Api { url: String },
+    // Coffre chiffré dans un fichier, sans serveur
+    LocalFile { path: PathBuf },
+}
+
+impl Backend {
+    pub fn describe(&self) -> String {
+        match self {
+            Backend::Api { url } => i18n::tr_args("backend-describe-api", &[("url", url.as_str().into())]),
+            Backend::LocalFile { path } => {
+                i18n::tr_args("backend-describe-local-file", &[("path", path.display().to_string().into())])
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    // Nom du dossier de données du profil
+    pub id: String,
+    pub name: String,
+    pub backend: Backend,
+    // Email proposé tant que le coffre n'en a pas
+    #[serde(default)]
+    pub default_email: String,
+    // Fichiers des collections partagées avec l'équipe
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shared: Vec<PathBuf>,
+    // Dernier fichier clé utilisé, proposé à la connexion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_file: Option<PathBuf>,
+    // Effacement du cache local après une série d'échecs de connexion
+    #[serde(default, skip_serializing_if = "WipePolicy::is_never")]
+    pub wipe_cache: WipePolicy,
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl Profile {
+    // Cache local et sauvegardes du profil
+    pub fn data_dir(&self) -> Result<PathBuf, String> {
+        let dir = if self.id == DEFAULT_PROFILE_ID {
+            paths::data_dir()?
+        } else {
+            paths::data_dir()?.join("profiles").join(&self.id)
+        };
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Création du dossier '{}' impossible: {}", dir.display(), e))?;
+        Ok(dir)
+    }
+}
+
+// Profils enregistrés et dernier profil ouvert
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProfileStore {
+    pub profiles: Vec<Profile>,
+    #[serde(default)]
+    pub current: Option<String>,
+}
+
+impl Default for ProfileStore {
+    fn default() -> Self {
+        ProfileStore {
+            profiles: vec![Profile {
+                id: DEFAULT_PROFILE_ID.to_string(),
+                name: String::from("Personnel"),
+                backend: Backend::Api { url: DEFAULT_API_URL.to_string() },
+                default_email: String::new(),
+                shared: Vec::new(),
+                key_file: None,
+                wipe_cache: WipePolicy::Never,
+            }],
+            current: Some(DEFAULT_PROFILE_ID.to_string()),
+        }
+    }
+}
+
+fn profiles_path() -> Result<PathBuf, String> {
+    Ok(paths::config_dir()?.join(PROFILES_FILE))
+}
+
+impl ProfileStore {
+    pub fn load() -> Result<Self, String> {
+        Self::load_from(&profiles_path()?)
+    }
+
+    fn load_from(path: &Path) -> Result<Self, String> {
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(ProfileStore::default()),
+            Err(e) => return Err(format!("Lecture de '{}' impossible: {}", path.display(), e)),
+        };
+        let store: ProfileStore = serde_json::from_slice(&data)
+            .map_err(|e| format!("Fichier des profils '{}' illisible: {}", path.display(), e))?;
+        if store.profiles.is_empty() {
+            return Ok(ProfileStore::default());
+        }
+        Ok(store)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = profiles_path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Création du dossier '{}' impossible: {}", dir.display(), e))?;
+        }
+        let data = serde_json::to_vec_pretty(self).map_err(|e| format!("Erreur d'encodage JSON: {}", e))?;
+        export::write_private(&path, &data)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.id == id)
+    }
+
+    // Dernier profil ouvert, sinon le premier
+    pub fn current(&self) -> &Profile {
+        self.current
+            .as_deref()
+            .and_then(|id| self.get(id))
+            .unwrap_or(&self.profiles[0])
+    }
+
+    pub fn current_mut(&mut self) -> &mut Profile {
+        let index = self
+            .current
+            .as_deref()
+            .and_then(|id| self.profiles.iter().position(|profile| profile.id == id))
+            .unwrap_or(0);
+        &mut self.profiles[index]
+    }
+
+    pub fn set_current(&mut self, id: &str) {
+        self.current = Some(id.to_string());
+    }
+
+    pub fn add(
+        &mut self,
+        name: &str,
+        backend: Backend,
+        default_email: &str,
+        key_file: Option<PathBuf>,
+    ) -> Result<Profile, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(String::from("Le nom du profil est requis"));
+        }
+        if self.profiles.iter().any(|profile| profile.name.eq_ignore_ascii_case(name)) {
+            return Err(format!("Un profil '{}' existe déjà", name));
+        }
+        let profile = Profile {
+            id: self.unique_id(name),
+            name: name.to_string(),
+            backend,
+            default_email: default_email.trim().to_string(),
+            shared: Vec::new(),
+            key_file,
+            wipe_cache: WipePolicy::Never,
+        };
+        self.profiles.push(profile.clone());
+        Ok(profile)
+    }
+
+    // Le coffre et le cache du profil restent sur le disque
+    pub fn remove(&mut self, id: &str) -> Result<(), String> {
+        if self.profiles.len() == 1 {
+            return Err(String::from("Le dernier profil ne peut pas être supprimé"));
+        }
+        self.profiles.retain(|profile| profile.id != id);
+        if self.current.as_deref() == Some(id) {
+            self.current = None;
+        }
+        Ok(())
+    }
+
+    fn unique_id(&self, name: &str) -> String {
+        let slug: String = name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect::<String>()
+            .split('-')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        let base = if slug.is_empty() { String::from("profil") } else { slug };
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while id == DEFAULT_PROFILE_ID || self.get(&id).is_some() {
+            id = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+        id
+    }
+}
+
+// Coffre déverrouillé : client authentifié muni de la clé du coffre, cache local éventuel
+#[derive(Debug, Clone)]
+pub struct OpenedVault {
+    pub client: ApiClient,
+    pub sync: Option<SyncHandle>,
+    // Ouvert, mais sans le cache local
+    pub warning: Option<String>,
+    // Code TOTP à vérifier avant d'afficher le coffre, si l'en-tête l'exige
+    pub second_factor: Option<Totp>,
+}
+
+// Échec d'ouverture d'un coffre. Seul un secret refusé (échange SRP, déchiffrement
+// du cache ou du fichier, valeur de contrôle) compte comme un essai manqué ; un serveur
+// injoignable ou un fichier illisible n'entame pas le compteur d'échecs
+#[derive(Debug, Clone, PartialEq)]
+pub enum OpenError {
+    Refused(String),
+    Failed(String),
+}
+
+impl fmt::Display for OpenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpenError::Refused(error) | OpenError::Failed(error) => f.write_str(error),
+        }
+    }
+}
+
+// Les erreurs rédigées des couches inférieures (fichier clé, TOTP) ne refusent aucun secret
+impl From<String> for OpenError {
+    fn from(error: String) -> Self {
+        OpenError::Failed(error)
+    }
+}
+
+impl From<DecryptError> for OpenError {
+    fn from(error: DecryptError) -> Self {
+        match error {
+            DecryptError::WrongSecret => OpenError::Refused(error.to_string()),
+            error => OpenError::Failed(error.to_string()),
+        }
+    }
+}
+
+impl From<ApiError> for OpenError {
+    fn from(error: ApiError) -> Self {
+        match error {
+            ApiError::AuthenticationRefused | ApiError::Decrypt(DecryptError::WrongSecret) => OpenError::Refused(error.to_string()),
+            error => OpenError::Failed(error.to_string()),
+        }
+    }
+}
+
+// Vérifie le mot de passe maître du profil et ouvre son coffre : le serveur le vérifie
+// par l'échange SRP, le cache local ou le fichier du coffre en le déchiffrant. Le fichier
+// clé éventuel entre dans la dérivation de la clé du coffre
+pub async fn open_vault(profile: Profile, password: SecretString, key_file: Option<PathBuf>) -> Result<OpenedVault, OpenError> {
+    let key_file = key_file.as_deref().map(KeyFile::read).transpose()?;
+    let mut client = ApiClient::open(&profile.backend, &password).await?;
+
+    if let Backend::LocalFile { .. } = profile.backend {
+        let (cipher, params) = e2e::unlock(&client, &password, key_file).await?;
+        let second_factor = cipher.second_factor(&params.factors)?;
+        client.set_cipher(Arc::new(cipher));
+        return Ok(OpenedVault { client, sync: None, warning: None, second_factor });
+    }
+
+    let dir = profile.data_dir()?;
+    // Serveur injoignable : seul le cache local, s'il existe, peut vérifier le mot de passe.
+    // Un refus du serveur n'ouvre jamais le cache, même avec le bon mot de passe local
+    let authenticated = client.authenticate().await;
+    if let Err(e) = &authenticated {
+        if !allows_offline(e) || !cache::cache_path(&dir)?.exists() {
+            return Err(e.clone().into());
+        }
+    }
+
+    match SyncHandle::open(dir, password.clone(), key_file.clone(), client.clone()).await {
+        Ok(sync) => {
+            let cipher = sync.cipher();
+            let params: Option<VaultParams> = sync.vault_params().await;
+            let second_factor = match &params {
+                Some(params) => cipher.second_factor(&params.factors)?,
+                None => None,
+            };
+            client.set_cipher(cipher);
+            Ok(OpenedVault { client, sync: Some(sync), warning: None, second_factor })
+        }
+        Err(e) if authenticated.is_ok() => {
+            // Sans cache, la clé du coffre reste indispensable : rien ne part en clair
+            let (cipher, params) = e2e::unlock(&client, &password, key_file).await?;
+            let second_factor = cipher.second_factor(&params.factors)?;
+            client.set_cipher(Arc::new(cipher));
+            Ok(OpenedVault {
+                client,
+                sync: None,
+                warning: Some(format!("Synchronisation désactivée: {}", e)),
+                second_factor,
+            })
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+// Remplace le mot de passe maître (et le fichier clé) d'un coffre rouvert par un kit de
+// récupération. Sans serveur, tout se passe dans le fichier du coffre ; sinon le serveur
+// accepte le nouvel en-tête sur le jeton du kit. Le cache local est réécrit pour le nouveau
+// mot de passe s'il est scellé pour ce kit, effacé sinon : il ne s'ouvrirait plus
+pub async fn reset_vault(
+    profile: Profile,
+    recovery_key: SecretKey<KEY_LENGTH>,
+    password: SecretString,
+    key_file: Option<PathBuf>,
+) -> Result<(), OpenError> {
+    let key_file = key_file.as_deref().map(KeyFile::read).transpose()?;
+    let mut client = ApiClient::recover(&profile.backend, &recovery_key).await.map_err(kit_error)?;
+    let params = client
+        .get_vault_params()
+        .await?
+        .ok_or_else(|| OpenError::Failed(i18n::tr("recovery-no-header")))?;
+    let cipher = Arc::new(EntryCipher::recover(&params, &recovery_key).map_err(kit_error)?);
+    client.set_cipher(cipher.clone());
+    let kdf = params.kdf;
+    let reset = e2e::reset_password(cipher.clone(), params.clone(), password.to_string(), key_file, kdf).await?;
+    let stored = client.rekey(&params, &reset, &password).await?;
+
+    if let Backend::Api { .. } = profile.backend {
+        let dir = profile.data_dir()?;
+        let rekeyed = match SyncHandle::recover(&dir, &recovery_key, cipher) {
+            Ok(sync) => sync.rekey(&password, stored).await,
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = rekeyed {
+            log::warn!(error:% = e; "Cache local non rouvert par le kit, effacé");
+            cache::wipe(&dir)?;
+        }
+    }
+    Ok(())
+}
+
+// Un secret refusé est ici un kit remplacé ou d'un autre profil
+fn kit_error(error: impl Into<OpenError>) -> OpenError {
+    match error.into() {
+        OpenError::Refused(_) => OpenError::Refused(i18n::tr("recovery-kit-refused")),
+        error => error,
+    }
+}
+
+// Seules les erreurs de transport justifient l'ouverture hors ligne
+fn allows_offline(error: &ApiError) -> bool {
+    matches!(error, ApiError::Network(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestDir;
+
+    fn store() -> ProfileStore {
+        let mut store = ProfileStore::default();
+        store.add("Travail", Backend::Api { url: String::from("https://vault.example.com") }, " bob@example.com ", None).unwrap();
+        store
+    }
+
+    #[test]
+    fn only_network_errors_allow_offline_opening() {
+        assert!(allows_offline(&ApiError::Network(String::from("connexion refusée"))));
+        assert!(!allows_offline(&ApiError::AuthenticationRefused));
+        assert!(!allows_offline(&ApiError::MissingServerProof));
+        assert!(!allows_offline(&ApiError::http(reqwest::StatusCode::UNAUTHORIZED)));
+        assert!(!allows_offline(&ApiError::http(reqwest::StatusCode::INTERNAL_SERVER_ERROR)));
+    }
+
+    #[test]
+    fn only_refused_secrets_count_as_failed_attempts() {
+        assert!(matches!(OpenError::from(ApiError::AuthenticationRefused), OpenError::Refused(_)));
+        let cache = DecryptError::WrongSecret.context("Ouverture du coffre '/tmp/coffre.mushroom' impossible");
+        assert_eq!(OpenError::from(ApiError::from(cache)), OpenError::Refused(DecryptError::WrongSecret.to_string()));
+        assert!(matches!(OpenError::from(DecryptError::WrongSecret), OpenError::Refused(_)));
+        assert!(matches!(OpenError::from(DecryptError::KeyFileRequired), OpenError::Failed(_)));
+        // Un message qui ressemble à un refus n'en est pas un : seule la variante compte
+        let refusal = DecryptError::WrongSecret.to_string();
+        assert!(matches!(OpenError::from(refusal.clone()), OpenError::Failed(_)));
+        assert!(matches!(OpenError::from(ApiError::Other(refusal)), OpenError::Failed(_)));
+
+        assert!(matches!(OpenError::from(ApiError::Network(String::from("délai dépassé"))), OpenError::Failed(_)));
+        assert!(matches!(OpenError::from(ApiError::http(reqwest::StatusCode::BAD_GATEWAY)), OpenError::Failed(_)));
+        assert!(matches!(
+            OpenError::from(String::from("Lecture du fichier clé '/media/usb/a.key' impossible: absent")),
+            OpenError::Failed(_)
+        ));
+        assert!(matches!(OpenError::from(String::from("Ce coffre exige son fichier clé")), OpenError::Failed(_)));
+        assert_eq!(OpenError::Failed(String::from("hors ligne")).to_string(), "hors ligne");
+    }
+
+    #[test]
+    fn adds_profiles_with_unique_ids() {
+        let mut store = store();
+        let work = store.get("travail").unwrap();
+        assert_eq!(work.default_email, "bob@example.com");
+
+        assert!(store.add("travail", Backend::LocalFile { path: PathBuf::from("/tmp/x") }, "", None).is_err());
+        assert!(store.add("  ", Backend::LocalFile { path: PathBuf::from("/tmp/x") }, "", None).is_err());
+
+        // Le slug évite l'identifiant réservé du profil par défaut et les doublons
+        let default = store.add("Default", Backend::LocalFile { path: PathBuf::from("/tmp/a") }, "", None).unwrap();
+        assert_eq!(default.id, "default-2");
+        let symbols = store.add("€€€", Backend::LocalFile { path: PathBuf::from("/tmp/b") }, "", None).unwrap();
+        assert_eq!(symbols.id, "profil");
+        let spaced = store.add("Clé  USB / perso", Backend::LocalFile { path: PathBuf::from("/tmp/c") }, "", None).unwrap();
+        assert_eq!(spaced.id, "clé-usb-perso");
+    }
+
+    #[test]
+    fn current_profile_falls_back_to_the_first() {
+        let mut store = store();
+        assert_eq!(store.current().id, DEFAULT_PROFILE_ID);
+        store.set_current("travail");
+        assert_eq!(store.current().name, "Travail");
+
+        store.remove("travail").unwrap();
+        assert_eq!(store.current, None);
+        assert_eq!(store.current().id, DEFAULT_PROFILE_ID);
+        assert!(store.remove(DEFAULT_PROFILE_ID).is_err());
+    }
+
+    #[test]
+    fn loads_missing_or_empty_profile_files_as_default() {
+        let dir = TestDir::new("profiles");
+        let path = dir.0.join(PROFILES_FILE);
+
+        assert_eq!(ProfileStore::load_from(&path).unwrap().profiles.len(), 1);
+        std::fs::write(&path, br#"{"profiles": []}"#).unwrap();
+        assert_eq!(ProfileStore::load_from(&path).unwrap().profiles[0].id, DEFAULT_PROFILE_ID);
+
+        let saved = serde_json::to_vec(&store()).unwrap();
+        std::fs::write(&path, saved).unwrap();
+        let loaded = ProfileStore::load_from(&path).unwrap();
+        assert_eq!(loaded.profiles, store().profiles);
+
+        std::fs::write(&path, b"{").unwrap();
+        assert!(ProfileStore::load_from(&path).is_err());
+    }
+}
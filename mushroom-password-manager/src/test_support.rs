@@ -0,0 +1,33 @@
+// Outils communs aux tests unitaires
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::vault::crypto::KdfParams;
+
+// Argon2id au plus bas : les tests vérifient le format, pas le coût de la dérivation
+pub const FAST: KdfParams = KdfParams { memory_kib: 8, iterations: 1, parallelism: 1 };
+
+// Dossier temporaire propre à un test, supprimé à la fin de celui-ci
+pub struct TestDir(pub PathBuf);
+
+impl TestDir {
+    pub fn new(name: &str) -> Self {
+        // Deux tests de modules différents peuvent porter le même nom
+        static NEXT: AtomicU32 = AtomicU32::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "mushroom-{}-{}-{}",
+            name,
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        TestDir(dir)
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
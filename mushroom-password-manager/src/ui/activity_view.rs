@@ -0,0 +1,145 @@
+use iced::{
+    Command, Element, Length,
+    widget::{Button, Column, Row, Scrollable, Text},
+};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::i18n;
+use crate::ui::app::Message;
+use crate::ui::widgets::{action_button, text_field};
+use crate::vault::audit::{self, AuditTrail};
+use crate::vault::e2e::EntryCipher;
+use crate::vault::entry;
+
+#[derive(Debug, Clone)]
+pub enum ActivityMessage {
+    // Relit et vérifie le journal
+    Load,
+    Loaded(Result<AuditTrail, String>),
+    FilterChanged(String),
+}
+
+// Journal d'activité du profil ouvert, du plus récent au plus ancien
+#[derive(Default)]
+pub struct ActivityView {
+    trail: Option<AuditTrail>,
+    filter: String,
+    loading: bool,
+    error: Option<String>,
+}
+
+impl ActivityView {
+    pub fn update(
+        &mut self,
+        message: ActivityMessage,
+        dir: Result<PathBuf, String>,
+        cipher: Option<Arc<EntryCipher>>,
+    ) -> Command<Message> {
+        match message {
+            ActivityMessage::Load => {
+                let (dir, Some(cipher)) = (dir, cipher) else {
+                    return Command::none();
+                };
+                self.loading = true;
+                return Command::perform(
+                    async move {
+                        let dir = dir?;
+                        tokio::task::spawn_blocking(move || audit::verify(&dir, &cipher))
+                            .await
+                            .map_err(|e| e.to_string())?
+                    },
+                    |result| Message::Activity(ActivityMessage::Loaded(result)),
+                );
+            }
+            ActivityMessage::Loaded(result) => {
+                self.loading = false;
+                match result {
+                    Ok(trail) => {
+                        self.trail = Some(trail);
+                        self.error = None;
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+            }
+            ActivityMessage::FilterChanged(filter) => self.filter = filter,
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let header = Row::new()
+            .spacing(20)
+            .push(Button::new(Text::new(i18n::tr("detail-back"))).on_press(Message::BackToMain))
+            .push(Text::new(i18n::tr("activity-title")).size(24));
+
+        let mut content = Column::new().spacing(20).padding(20).push(header);
+
+        if let Some(error) = &self.error {
+            content = content.push(
+                Text::new(error)
+                    .size(16)
+                    .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.2, 0.2))),
+            );
+        }
+        let Some(trail) = &self.trail else {
+            content = content.push(Text::new(i18n::tr("activity-loading")).size(16));
+            return Scrollable::new(content).into();
+        };
+
+        let verification = match &trail.problem {
+            None => Text::new(i18n::tr_args("activity-intact", &[("count", trail.records.len().into())]))
+                .size(16)
+                .style(iced::theme::Text::Color(iced::Color::from_rgb(0.2, 0.6, 0.3))),
+            Some(problem) => Text::new(i18n::tr_args(
+                "activity-tampered",
+                &[("problem", problem.to_string().into()), ("count", trail.records.len().into())],
+            ))
+            .size(16)
+            .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.2, 0.2))),
+        };
+        content = content.push(
+            Row::new()
+                .spacing(10)
+                .push(verification.width(Length::Fill))
+                .push(action_button(
+                    &i18n::tr(if self.loading { "activity-verifying" } else { "activity-verify" }),
+                    (!self.loading).then_some(Message::Activity(ActivityMessage::Load)),
+                )),
+        );
+
+        content = content.push(
+            text_field(&i18n::tr("activity-filter"), &self.filter)
+                .on_input(|filter| Message::Activity(ActivityMessage::FilterChanged(filter)))
+                .padding(10),
+        );
+
+        let filter = self.filter.to_lowercase();
+        let mut list = Column::new().spacing(6);
+        for record in trail.records.iter().rev() {
+            let action = record.action.to_string();
+            let name = record.entry.as_deref().unwrap_or("");
+            let matches = filter.is_empty()
+                || action.to_lowercase().contains(&filter)
+                || name.to_lowercase().contains(&filter)
+                || record.detail.to_lowercase().contains(&filter);
+            if !matches {
+                continue;
+            }
+            list = list.push(
+                Row::new()
+                    .spacing(15)
+                    .push(Text::new(entry::format_timestamp(&Some(record.at))).size(14))
+                    .push(Text::new(action).size(14).width(Length::Fixed(110.0)))
+                    .push(Text::new(name.to_string()).size(14).width(Length::Fill))
+                    .push(Text::new(record.detail.clone()).size(14)),
+            );
+        }
+        if trail.records.is_empty() {
+            list = list.push(Text::new(i18n::tr("activity-empty")).size(14));
+        }
+        content = content.push(list);
+
+        Scrollable::new(content).into()
+    }
+}
@@ -1,10 +1,42 @@
 use iced::{
-    Application, Command, Element, Length, Settings, Subscription, Theme,
-    widget::{Button, Column, Container, Row, Scrollable, Text, TextInput},
+    Application, Command, Element, Event, Length, Settings, Subscription, Theme,
+    widget::{text_input, Button, Canvas, Column, Container, PickList, Row, Scrollable, Text},
 };
-use iced::clipboard;
+use iced::{alignment, clipboard, event, keyboard, mouse, subscription, time};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use crate::api::client::ApiClient;
+use crate::api::error::ApiError;
+use crate::api::local;
+use crate::api::sync::{Connectivity, SyncHandle, SyncReport};
+use crate::config::AppConfig;
+use crate::export::{RestoreError, RestoreReport};
+use crate::i18n::{self, tr, tr_args};
+use crate::lockout::LoginLimiter;
+use crate::profile::{self, OpenError, OpenedVault, Profile, ProfileStore};
+use crate::ui::activity_view::{ActivityMessage, ActivityView};
+use crate::ui::backup_view::{BackupMessage, BackupView};
+use crate::ui::countdown::CountdownRing;
+use crate::ui::entry_editor::{self, EntryEdit};
+use crate::ui::export_view::{ExportMessage, ExportView};
+use crate::ui::import_wizard::{ImportMessage, ImportWizard};
+use crate::ui::merge_view::{MergeMessage, MergeView};
+use crate::ui::profile_view::{ProfileMessage, ProfileView};
+use crate::ui::recovery_view::{RecoveryMessage, RecoveryView};
+use crate::ui::search::{self, MatchedField, SearchResult};
+use crate::ui::security_view::{SecurityMessage, SecurityView};
+use crate::ui::settings_view::{SettingsMessage, SettingsView};
+use crate::ui::style::{self, ButtonType, ContainerStyle};
+use crate::ui::team_view::{self, CollectionChoice, TeamMessage, TeamView};
+use crate::ui::widgets::{action_button, secret_field, text_field};
+use crate::vault::audit::{AuditAction, AuditLog};
+use crate::vault::cache;
+use crate::vault::e2e;
+use crate::vault::entry::Entry;
+use crate::vault::secure::{self, CoreDumpGuard, SecretString};
+use crate::vault::shared::{self, TeamHandle, TeamSnapshot};
+use crate::vault::totp::{self, Totp};
 
 // Définition des différentes vues de l'application
 #[derive(Debug, Clone, PartialEq)]
@@ -12,6 +44,28 @@ pub enum View {
     Login,     // Nouvelle vue pour la connexion
     Main,
     ServiceDetail,
+    Import,
+    Export,
+    Backups,
+    Conflicts,
+    Profiles,
+    Team,
+    // Création du kit de récupération, coffre ouvert
+    RecoveryKit,
+    // Reconstitution du mot de passe maître depuis la connexion
+    Recover,
+    Security,
+    Settings,
+    Activity,
+}
+
+// Déplacements au clavier dans les résultats de recherche
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchNavigation {
+    Up,
+    Down,
+    Open,
+    Clear,
 }
 
 // Messages d'application
@@ -22,46 +76,168 @@ pub enum Message {
     ServiceUrlChanged(String),
     GeneratePassword,
     CopyToClipboard,
+    CopyTotpCode,
+    TotpTick,
     SavePassword,
     RefreshPasswords,
     EmailInputChanged(String),
-    SaveEmail,
+    // Email par défaut du coffre, saisi dans les paramètres
+    SaveEmail(String),
     EmailReceived(String),
-    PasswordSelected(String),
     PasswordGenerated(String),
-    PasswordsUpdated(Vec<String>),
+    PasswordsUpdated(Vec<Entry>),
+    // Identifiant de l'entrée enregistrée, pour le journal d'activité
+    PasswordSaved(String),
+    PasswordUpdated(String),
+    EmailSaved(String),
+    // Écritures terminées dans le coffre, comptées pour les sauvegardes automatiques
+    VaultWritten(usize),
     StatusUpdate(String),
     GetEmail,
     ServiceSelected(String),
-    PasswordDetailsReceived(String, String, String),
-    ToggleEditMode,
+    PasswordDetailsReceived(Box<Entry>),
+    EditEntry(EntryEdit),
     UpdatePassword,
-    ClearForm,
-    ClearFormFields,
     NavigateTo(View),
     BackToMain,
+
+    // Assistant d'import
+    Import(ImportMessage),
+
+    // Export et restauration
+    Export(ExportMessage),
+
+    // Sauvegardes automatiques
+    Backup(BackupMessage),
+
+    // Synchronisation avec le serveur et résolution des conflits
+    Synced(Result<SyncReport, String>),
+    Merge(MergeMessage),
+
+    // Recherche dans la liste des mots de passe
+    SearchQueryChanged(String),
+    SearchNavigate(SearchNavigation),
     
     // Nouveaux messages pour la page de connexion
     MasterPasswordInputChanged(String),
     LoginAttempt,
+    VaultOpened(Result<OpenedVault, OpenError>),
+    // Facteurs supplémentaires : fichier clé choisi à la connexion, code TOTP demandé ensuite
+    KeyFileChanged(String),
+    SecondFactorChanged(String),
+    SecondFactorSubmit,
+    Security(SecurityMessage),
+    // Changement du mot de passe maître, depuis les paramètres
+    Settings(SettingsMessage),
+    MasterPasswordChanged(String),
+    // Journal d'activité du profil
+    Activity(ActivityMessage),
+    // Rafraîchit l'attente affichée après des échecs de connexion
+    LockoutTick,
+    // Inactivité : action au clavier ou à la souris, vérification périodique du délai
+    UserActivity,
+    IdleTick,
+    // Délai du presse-papiers écoulé pour la copie de ce numéro
+    ClipboardExpired(u64),
+    // Bascule du mot de passe en clair ; masquage automatique de la révélation de ce numéro
+    ToggleReveal,
+    RevealExpired(u64),
+
+    // Profils : choix à la connexion, bascule depuis l'en-tête, gestion
+    ProfileSelected(Profile),
+    SwitchProfile(Profile),
+    Profiles(ProfileMessage),
+    // Verrouillage : état effacé, jeton de session révoqué côté serveur
+    Lock,
+    Locked(Result<(), ApiError>),
+    // Kit de récupération : création, puis nouveau mot de passe maître et fichier clé
+    // d'un coffre rouvert par ses parts
+    Recovery(RecoveryMessage),
+    PasswordRecovered(SecretString, String),
+
+    // Collections partagées avec l'équipe
+    TeamOpened(Result<TeamHandle, String>),
+    RefreshTeam,
+    TeamRefreshed(TeamSnapshot),
+    Team(TeamMessage),
+    ShareEntry(CollectionChoice),
+    UnshareEntry,
+    // Entrée retirée de sa collection partagée, pour le journal d'activité
+    EntryUnshared(String),
 }
 
 pub struct PasswordManagerApp {
     // État existant
     current_view: View,
-    password_value: String,
+    password_value: SecretString,
     service_url_value: String,
     email_value: String,
     api_client: ApiClient,
-    passwords: Vec<String>,
+    passwords: Vec<Entry>,
     selected_password: Option<String>,
     editing_mode: bool,
     current_service_url: Option<String>,
+    // Entrée ouverte dans la vue détaillée et saisie brute de ses tags
+    current_entry: Option<Entry>,
+    tags_value: String,
+    // Heure courante (secondes Unix), rafraîchie chaque seconde pour le code TOTP
+    now: u64,
     status_message: Option<String>,
+
+    // État de la recherche : requête, résultats classés et ligne surlignée
+    search_query: String,
+    search_results: Vec<SearchResult>,
+    search_cursor: usize,
+
+    import_wizard: ImportWizard,
+    export_view: ExportView,
+    backups: BackupView,
+    // Absent tant que le cache local n'est pas ouvert : accès direct au serveur
+    sync: Option<SyncHandle>,
+    merge_view: MergeView,
+    // Résultat de la dernière synchronisation, pour l'indicateur de connexion
+    connectivity: Connectivity,
+    pending_writes: usize,
+    syncing: bool,
     
-    // Nouvel état pour le mot de passe maître
-    master_password: String,
+    // Profils enregistrés ; le profil courant est celui ouvert ou choisi à la connexion
+    profiles: ProfileStore,
+    profile_view: ProfileView,
+
+    // Identité d'équipe du profil ouvert et entrées partagées déchiffrées
+    team: Option<TeamHandle>,
+    team_snapshot: TeamSnapshot,
+    team_view: TeamView,
+    recovery_view: RecoveryView,
+    security_view: SecurityView,
+    settings_view: SettingsView,
+    // Journal des opérations sur les entrées ; absent s'il n'a pas pu être ouvert
+    audit: Option<AuditLog>,
+    activity_view: ActivityView,
+    
+    // Mot de passe maître saisi à la connexion, vidé dès que l'essai part
+    login_password: SecretString,
+    // Chemin du fichier clé saisi à la connexion
+    key_file_value: String,
+    // Coffre déverrouillé en attente du code TOTP
+    pending_vault: Option<OpenedVault>,
+    second_factor_code: String,
+    // Échecs de connexion du profil courant ; absent si son dossier est inaccessible
+    login_limiter: Option<LoginLimiter>,
+    // Core dumps désactivés tant que le coffre est ouvert
+    core_dumps: Option<CoreDumpGuard>,
+    // Des secrets n'ont pas pu être verrouillés en mémoire : affiché sous l'indicateur de connexion
+    memory_warning: Option<String>,
+    // Préférences de l'application, appliquées dès leur modification
+    config: AppConfig,
+    // Numéro de la dernière copie : seule celle-ci vide le presse-papiers à expiration
+    clipboard_generation: u64,
+    // Mot de passe du formulaire affiché en clair, jusqu'au délai de la révélation de ce numéro
+    password_revealed: bool,
+    reveal_generation: u64,
+    last_activity: Instant,
     is_authenticated: bool,
+    unlocking: bool,
 }
 
 impl Application for PasswordManagerApp {
@@ -71,10 +247,21 @@ impl Application for PasswordManagerApp {
     type Flags = ();
 
     fn new(_flags: ()) -> (Self, Command<Message>) {
+        let (profiles, mut status_message) = match ProfileStore::load() {
+            Ok(profiles) => (profiles, None),
+            Err(e) => (ProfileStore::default(), Some(e)),
+        };
+        let config = AppConfig::load().unwrap_or_else(|e| {
+            status_message = Some(e);
+            AppConfig::default()
+        });
+        i18n::set_language(config.appearance.language);
+        let key_file_value = key_file_text(profiles.current());
+        let login_limiter = open_limiter(profiles.current());
         (
             Self {
                 current_view: View::Login,  // Commencer par la vue de connexion
-                password_value: String::new(),
+                password_value: SecretString::default(),
                 service_url_value: String::new(),
                 email_value: String::new(),
                 api_client: ApiClient::new(),
@@ -82,56 +269,237 @@ impl Application for PasswordManagerApp {
                 selected_password: None,
                 editing_mode: false,
                 current_service_url: None,
-                status_message: None,
-                master_password: String::new(),
+                current_entry: None,
+                tags_value: String::new(),
+                now: totp::unix_now(),
+                status_message,
+                search_query: String::new(),
+                search_results: Vec::new(),
+                search_cursor: 0,
+                import_wizard: ImportWizard::default(),
+                export_view: ExportView::default(),
+                backups: BackupView::default(),
+                sync: None,
+                merge_view: MergeView::default(),
+                connectivity: Connectivity::Unknown,
+                pending_writes: 0,
+                syncing: false,
+                profiles,
+                profile_view: ProfileView::default(),
+                team: None,
+                team_snapshot: TeamSnapshot::default(),
+                team_view: TeamView::default(),
+                recovery_view: RecoveryView::default(),
+                security_view: SecurityView::default(),
+                settings_view: SettingsView::default(),
+                audit: None,
+                activity_view: ActivityView::default(),
+                login_password: SecretString::default(),
+                key_file_value,
+                pending_vault: None,
+                second_factor_code: String::new(),
+                login_limiter,
+                core_dumps: None,
+                memory_warning: None,
+                config,
+                clipboard_generation: 0,
+                password_revealed: false,
+                reveal_generation: 0,
+                last_activity: Instant::now(),
                 is_authenticated: false,
+                unlocking: false,
             },
             Command::none(),
         )
     }
 
     fn title(&self) -> String {
-        String::from("Gestionnaire de mots de passe Mushroom")
+        tr("app-title")
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
+        if matches!(message, Message::UserActivity) {
+            self.last_activity = Instant::now();
+            return Command::none();
+        }
         match message {
             // Gestion des messages de connexion
             Message::MasterPasswordInputChanged(value) => {
-                self.master_password = value;
+                self.login_password = value.into();
                 Command::none()
             }
             Message::LoginAttempt => {
-                // Pour simplifier, utilisez un mot de passe fixe
-                // En production, utilisez plutôt un hash stocké de façon sécurisée
-                if self.master_password == "mushroom123" {  // Exemple de mot de passe
-                    self.is_authenticated = true;
-                    self.current_view = View::Main;
-                    self.status_message = Some(String::from("Connexion réussie"));
-                    
-                    // Charger les données après connexion
-                    return Command::batch(vec![
-                        Command::perform(async { Message::GetEmail }, |_| Message::GetEmail),
-                        Command::perform(async { Message::RefreshPasswords }, |msg| msg),
-                    ]);
+                if self.unlocking || self.login_password.is_empty() || self.lockout_remaining() > 0 {
+                    return Command::none();
+                }
+                self.unlocking = true;
+                self.status_message = Some(tr_args("status-opening-profile", &[("profile", self.profiles.current().name.as_str().into())]));
+
+                // Le mot de passe est vérifié par le serveur (échange SRP), ou hors ligne
+                // par le déchiffrement du cache local ou du fichier du coffre
+                let profile = self.profiles.current().clone();
+                let key_file = Some(self.key_file_value.trim()).filter(|path| !path.is_empty()).map(PathBuf::from);
+                let password = std::mem::take(&mut self.login_password);
+                Command::perform(
+                    profile::open_vault(profile, password, key_file),
+                    Message::VaultOpened,
+                )
+            }
+            Message::VaultOpened(result) => {
+                self.unlocking = false;
+                let vault = match result {
+                    Ok(vault) => vault,
+                    Err(OpenError::Refused(e)) => {
+                        self.record_login_failure(tr_args("status-open-failed", &[("error", e.into())]));
+                        return Command::none();
+                    }
+                    // Serveur injoignable, fichier clé ou disque illisible : pas un essai manqué
+                    Err(OpenError::Failed(e)) => {
+                        self.status_message = Some(tr_args("status-open-failed", &[("error", e.into())]));
+                        return Command::none();
+                    }
+                };
+                // Le fichier clé qui a ouvert le coffre est proposé la fois suivante
+                self.profiles.current_mut().key_file =
+                    Some(self.key_file_value.trim()).filter(|path| !path.is_empty()).map(PathBuf::from);
+                if vault.second_factor.is_some() {
+                    self.pending_vault = Some(vault);
+                    self.second_factor_code.clear();
+                    self.status_message = Some(tr("status-totp-required"));
+                    return Command::none();
+                }
+                self.finish_unlock(vault)
+            }
+            Message::LockoutTick => {
+                self.now = totp::unix_now();
+                Command::none()
+            }
+            Message::KeyFileChanged(value) => {
+                self.key_file_value = value;
+                Command::none()
+            }
+            Message::SecondFactorChanged(value) => {
+                self.second_factor_code = value;
+                Command::none()
+            }
+            Message::SecondFactorSubmit => {
+                if self.lockout_remaining() > 0 {
+                    return Command::none();
+                }
+                let (now, last_step) = match &self.login_limiter {
+                    Some(limiter) => (limiter.now(), limiter.second_factor_step()),
+                    None => (totp::unix_now(), None),
+                };
+                let step = match self.pending_vault.as_ref().and_then(|vault| vault.second_factor.as_ref()) {
+                    Some(totp) => e2e::verify_second_factor(totp, &self.second_factor_code, now, last_step),
+                    None => return Command::none(),
+                };
+                self.second_factor_code.clear();
+                let Some(step) = step else {
+                    self.record_login_failure(tr("status-totp-incorrect"));
+                    return Command::none();
+                };
+                let recorded = self.login_limiter.as_mut().map(|limiter| limiter.record_second_factor(step));
+                match self.pending_vault.take() {
+                    Some(mut vault) => {
+                        if let Some(Err(e)) = recorded {
+                            vault.warning = Some(e);
+                        }
+                        self.finish_unlock(vault)
+                    }
+                    None => Command::none(),
+                }
+            }
+            // Une ouverture en cours appartient au profil qui l'a lancée : ni choix ni gestion
+            // des profils d'ici là, son résultat (coffre, fichier clé retenu) irait à un autre
+            Message::ProfileSelected(_) | Message::Profiles(_) if self.unlocking || self.recovery_view.is_busy() => Command::none(),
+            Message::ProfileSelected(profile) => {
+                self.key_file_value = key_file_text(&profile);
+                self.pending_vault = None;
+                self.profiles.set_current(&profile.id);
+                self.login_limiter = open_limiter(&profile);
+                self.status_message = None;
+                Command::none()
+            }
+            Message::SwitchProfile(profile) => {
+                if profile.id == self.profiles.current().id {
+                    return Command::none();
+                }
+                // Le coffre ouvert est verrouillé avant de proposer celui du profil choisi
+                let command = self.update(Message::Lock);
+                self.profiles.set_current(&profile.id);
+                self.key_file_value = key_file_text(&profile);
+                self.login_limiter = open_limiter(&profile);
+                self.status_message = Some(tr_args("status-switched-profile", &[("profile", profile.name.as_str().into())]));
+                command
+            }
+            Message::Profiles(message) if !self.is_authenticated => {
+                self.profile_view.update(message, &mut self.profiles, &self.config.backend)
+            }
+            Message::NavigateTo(view @ (View::Login | View::Profiles | View::Recover)) if !self.is_authenticated => {
+                self.current_view = view;
+                Command::none()
+            }
+            Message::Recovery(message) => {
+                let profile = self.profiles.current().clone();
+                self.recovery_view.update(message, &self.api_client, &self.sync, &profile)
+            }
+            Message::PasswordRecovered(password, key_file) if !self.is_authenticated => {
+                // Le nouveau mot de passe ouvre directement le coffre, comme une connexion
+                self.login_password = password;
+                self.key_file_value = key_file;
+                self.current_view = View::Login;
+                self.update(Message::LoginAttempt)
+            }
+            Message::Lock => {
+                // Le client garde la session le temps de la révoquer
+                let client = self.api_client.clone();
+                // Un secret copié ne survit pas au verrouillage
+                let clipboard_pending = self.clipboard_generation > 0;
+                let (locked, _) = <Self as Application>::new(());
+                *self = locked;
+                self.status_message = Some(tr("status-locked"));
+                let logout = Command::perform(async move { client.logout().await }, Message::Locked);
+                if clipboard_pending {
+                    Command::batch(vec![clipboard::write(String::new()), logout])
                 } else {
-                    self.status_message = Some(String::from("Mot de passe incorrect"));
+                    logout
+                }
+            }
+            Message::IdleTick => match self.config.lock.idle.duration() {
+                Some(idle) if self.is_authenticated && self.last_activity.elapsed() >= idle => {
+                    let command = self.update(Message::Lock);
+                    self.status_message = Some(tr_args("status-locked-idle", &[("delay", self.config.lock.idle.to_string().into())]));
+                    command
+                }
+                _ => Command::none(),
+            },
+            Message::ClipboardExpired(generation) => {
+                if generation != self.clipboard_generation {
+                    return Command::none();
+                }
+                self.clipboard_generation = 0;
+                clipboard::write(String::new())
+            }
+            Message::Locked(result) => {
+                if let Err(e) = result {
+                    self.status_message = Some(tr_args("status-logout-failed", &[("error", e.to_string().into())]));
                 }
                 Command::none()
             }
             
             // Messages existants - ils ne doivent s'exécuter que si l'utilisateur est authentifié
             _ => {
-                if !self.is_authenticated && self.current_view == View::Login {
-                    // Si on n'est pas connecté et qu'on est sur la page de login,
-                    // bloquer tous les autres messages
+                if !self.is_authenticated {
+                    // Si on n'est pas connecté, seuls la connexion et les profils
+                    // (traités plus haut) sont accessibles : bloquer tous les autres messages
                     return Command::none();
                 }
                 
                 // Sinon, traiter normalement les messages comme avant
                 match message {
                     Message::InputChanged(value) => {
-                        self.password_value = value;
+                        self.password_value = value.into();
                         Command::none()
                     }
                     Message::ServiceUrlChanged(value) => {
@@ -139,108 +507,229 @@ impl Application for PasswordManagerApp {
                         Command::none()
                     }
                     Message::GeneratePassword => {
-                        let client = self.api_client.clone();
-                        return Command::perform(
-                            async move { client.generate_password().await },
-                            |result| match result {
-                                Ok(password) => Message::PasswordGenerated(password),
-                                Err(e) => Message::StatusUpdate(format!("Erreur génération: {}", e)),
-                            },
-                        );
+                        let password = local::generate_password(&self.config.generator);
+                        self.update(Message::PasswordGenerated(password))
+                    }
+                    Message::ToggleReveal => {
+                        self.password_revealed = !self.password_revealed;
+                        // Une révélation précédente ne masque pas celle-ci avant son propre délai
+                        self.reveal_generation += 1;
+                        match self.config.display.hide_password_after.duration() {
+                            Some(delay) if self.password_revealed => {
+                                let generation = self.reveal_generation;
+                                Command::perform(tokio::time::sleep(delay), move |_| Message::RevealExpired(generation))
+                            }
+                            _ => Command::none(),
+                        }
+                    }
+                    Message::RevealExpired(generation) => {
+                        if generation == self.reveal_generation {
+                            self.password_revealed = false;
+                        }
+                        Command::none()
                     }
                     Message::CopyToClipboard => {
-                        let command = clipboard::write::<Message>(self.password_value.clone());
-                        self.status_message = Some(String::from("Mot de passe copié dans le presse-papiers"));
-                        return command;
+                        self.copy_to_clipboard(self.password_value.to_string(), &tr("audit-detail-password"), &tr("status-password-copied"))
+                    }
+                    Message::CopyTotpCode => {
+                        self.now = totp::unix_now();
+                        match self.current_totp() {
+                            Some(Ok(totp)) => {
+                                self.copy_to_clipboard(totp.generate(self.now), &tr("audit-detail-totp"), &tr("status-totp-copied"))
+                            }
+                            Some(Err(e)) => {
+                                self.status_message = Some(tr_args("status-totp-error", &[("error", e.into())]));
+                                Command::none()
+                            }
+                            None => Command::none(),
+                        }
+                    }
+                    Message::TotpTick => {
+                        self.now = totp::unix_now();
+                        Command::none()
                     }
                     Message::SavePassword => {
-                        let client = self.api_client.clone();
-                        let service_url = self.service_url_value.clone();
-                        let password = self.password_value.clone();
-                        let email = self.email_value.clone();
+                        let name = self.api_client.extract_service_name(&self.service_url_value);
+                        let mut entry = Entry::new(&self.service_url_value, &name);
+                        entry.service_password = self.password_value.clone();
+                        entry.email = self.email_value.clone();
 
-                        return Command::perform(
-                            async move { client.save_password(&service_url, &password, &email).await },
-                            |result| match result {
-                                Ok(_) => {
-                                    Command::perform(
-                                        async { Message::RefreshPasswords }, 
-                                        |msg| msg
-                                    );
-                                    Message::StatusUpdate("Sauvegarde réussie".into())
-                                },
-                                Err(e) => Message::StatusUpdate(format!("Erreur: {}", e)),
+                        let id = entry.id.clone();
+                        Command::perform(
+                            self.store_entry(entry),
+                            move |result| match result {
+                                Ok(_) => Message::PasswordSaved(id),
+                                Err(e) => Message::StatusUpdate(tr_args("status-error", &[("error", e.into())])),
                             },
-                        );
+                        )
+                    }
+                    Message::PasswordSaved(id) => {
+                        self.status_message = Some(tr("status-saved"));
+                        self.audit(AuditAction::Created, Some(&id), "");
+                        Command::batch(vec![
+                            self.update(Message::RefreshPasswords),
+                            self.update(Message::VaultWritten(1)),
+                        ])
+                    }
+                    Message::PasswordUpdated(id) => {
+                        self.status_message = Some(tr("status-password-updated"));
+                        self.audit(AuditAction::Updated, Some(&id), "");
+                        Command::batch(vec![
+                            self.update(Message::RefreshPasswords),
+                            self.update(Message::VaultWritten(1)),
+                        ])
+                    }
+                    Message::VaultWritten(count) => {
+                        self.backups.record_writes(count, &self.api_client)
                     }
                     Message::RefreshPasswords => {
                         let client = self.api_client.clone();
-                        return Command::perform(
+                        if let Some(sync) = self.sync.clone() {
+                            // Une synchronisation à la fois ; la suivante reprendra les écritures
+                            if self.syncing {
+                                return Command::none();
+                            }
+                            self.syncing = true;
+                            return Command::perform(async move { sync.sync(&client).await }, Message::Synced);
+                        }
+                        Command::perform(
                             async move { client.get_saved_passwords().await },
                             |result| match result {
                                 Ok(passwords) => Message::PasswordsUpdated(passwords),
-                                Err(e) => Message::StatusUpdate(format!("Erreur: {}", e)),
+                                Err(e) => Message::StatusUpdate(tr_args("status-error", &[("error", e.to_string().into())])),
                             },
-                        );
+                        )
                     }
                     Message::EmailInputChanged(value) => {
                         self.email_value = value;
                         Command::none()
                     }
-                    Message::SaveEmail => {
+                    Message::SaveEmail(email) => {
                         let client = self.api_client.clone();
-                        let email = self.email_value.clone();
-                        return Command::perform(
-                            async move { client.save_email(&email).await },
+                        let sync = self.sync.clone();
+                        Command::perform(
+                            async move {
+                                match sync {
+                                    Some(sync) => sync.record_email(email.clone()).await?,
+                                    None => client.save_email(&email).await?,
+                                }
+                                Ok::<_, String>(email)
+                            },
                             |result| match result {
-                                Ok(_) => Message::StatusUpdate("Email sauvegardé!".into()),
-                                Err(e) => Message::StatusUpdate(format!("Erreur sauvegarde email: {}", e)),
+                                Ok(email) => Message::EmailSaved(email),
+                                Err(e) => Message::StatusUpdate(tr_args("status-email-error", &[("error", e.into())])),
                             },
-                        );
+                        )
                     }
-                    Message::PasswordSelected(password) => {
-                        self.selected_password = Some(password);
-                        Command::none()
+                    Message::EmailSaved(email) => {
+                        self.status_message = Some(tr("status-email-saved"));
+                        self.update(Message::EmailReceived(email))
                     }
                     Message::PasswordGenerated(password) => {
-                        self.password_value = password;
+                        self.password_value = password.into();
                         Command::none()
                     }
                     Message::PasswordsUpdated(passwords) => {
                         self.passwords = passwords;
+                        self.check_memory_lock();
+                        self.merge_shared();
+                        self.refresh_team()
+                    }
+                    Message::SearchQueryChanged(query) => {
+                        self.search_query = query;
+                        self.search_cursor = 0;
+                        self.refresh_search();
                         Command::none()
                     }
+                    Message::SearchNavigate(navigation) => {
+                        if self.current_view != View::Main {
+                            return Command::none();
+                        }
+
+                        match navigation {
+                            SearchNavigation::Up => {
+                                self.search_cursor = self.search_cursor.saturating_sub(1);
+                                Command::none()
+                            }
+                            SearchNavigation::Down => {
+                                if self.search_cursor + 1 < self.search_results.len() {
+                                    self.search_cursor += 1;
+                                }
+                                Command::none()
+                            }
+                            SearchNavigation::Open => {
+                                match self.search_results.get(self.search_cursor) {
+                                    Some(result) => {
+                                        let id = self.passwords[result.index].id.clone();
+                                        self.update(Message::ServiceSelected(id))
+                                    }
+                                    None => Command::none(),
+                                }
+                            }
+                            SearchNavigation::Clear => {
+                                self.search_query.clear();
+                                self.search_cursor = 0;
+                                self.refresh_search();
+                                text_input::focus(search_input_id())
+                            }
+                        }
+                    }
                     Message::StatusUpdate(status) => {
                         self.status_message = Some(status);
                         Command::none()
                     }
                     Message::GetEmail => {
                         let client = self.api_client.clone();
-                        return Command::perform(
-                            async move { client.get_email().await },
+                        let sync = self.sync.clone();
+                        Command::perform(
+                            async move {
+                                match sync {
+                                    Some(sync) => sync.email(&client).await,
+                                    None => Ok(client.get_email().await?),
+                                }
+                            },
                             |result| match result {
                                 Ok(email) => Message::EmailReceived(email),
-                                Err(e) => Message::StatusUpdate(format!("Erreur: {}", e)),
+                                Err(e) => Message::StatusUpdate(tr_args("status-error", &[("error", e.into())])),
                             },
-                        );
+                        )
                     }
                     Message::EmailReceived(email) => {
-                        self.email_value = email;
+                        // Tant que le coffre n'a pas d'email, celui du profil est proposé
+                        self.email_value = if email.is_empty() {
+                            self.profiles.current().default_email.clone()
+                        } else {
+                            email
+                        };
                         Command::none()
                     }
                     Message::ServiceSelected(service_name) => {
                         self.selected_password = Some(service_name.clone());
+                        self.password_revealed = false;
+                        self.audit(AuditAction::Viewed, Some(&service_name), "");
+
+                        // Avec le cache local, la liste contient déjà les entrées complètes,
+                        // comme pour les entrées partagées, lues dans leur collection
+                        if self.sync.is_some() || shared::parse_shared_id(&service_name).is_some() {
+                            if let Some(entry) = self.passwords.iter().find(|entry| entry.id == service_name) {
+                                let entry = Box::new(entry.clone());
+                                self.current_view = View::ServiceDetail;
+                                return self.update(Message::PasswordDetailsReceived(entry));
+                            }
+                        }
+
                         let service_name_clone = service_name.clone();
                         let client = self.api_client.clone();
                         
                         let cmd = Command::perform(
                             async move { client.get_password_details(&service_name_clone).await },
                             move |result| match result {
-                                Ok((service_url, password, email)) => {
-                                    Message::PasswordDetailsReceived(service_url, password, email)
-                                },
+                                Ok(entry) => Message::PasswordDetailsReceived(Box::new(entry)),
                                 Err(e) => {
-                                    Message::StatusUpdate(format!("Erreur: Service '{}' non trouvé. {}", service_name, e))
+                                    Message::StatusUpdate(tr_args(
+                                        "status-service-error",
+                                        &[("service", service_name.as_str().into()), ("error", e.to_string().into())],
+                                    ))
                                 },
                             },
                         );
@@ -248,69 +737,213 @@ impl Application for PasswordManagerApp {
                         self.current_view = View::ServiceDetail;
                         cmd
                     }
-                    Message::PasswordDetailsReceived(service_url, password, email) => {
-                        self.service_url_value = service_url.clone();
-                        self.password_value = password;
-                        self.email_value = email;
-                        self.current_service_url = Some(service_url);
+                    Message::PasswordDetailsReceived(entry) => {
+                        self.service_url_value = entry.id.clone();
+                        self.password_value = entry.service_password.clone();
+                        // Une entrée s'ouvre toujours mot de passe masqué
+                        self.password_revealed = false;
+                        self.email_value = entry.email.clone();
+                        self.current_service_url = Some(entry.id.clone());
+                        self.tags_value = entry.tags_text();
+                        self.current_entry = Some(*entry);
+                        self.now = totp::unix_now();
                         self.editing_mode = true;
-                        self.status_message = Some(format!("Informations du service chargées"));
+                        self.status_message = Some(tr("status-service-loaded"));
                         Command::none()
                     }
-                    Message::ToggleEditMode => {
-                        self.editing_mode = !self.editing_mode;
+                    Message::Import(message) => {
+                        let imported = match &message {
+                            ImportMessage::Imported(Ok(count)) => *count,
+                            ImportMessage::Imported(Err(ApiError::Import { imported, .. })) => *imported,
+                            _ => 0,
+                        };
+                        if imported > 0 {
+                            self.audit(AuditAction::Imported, None, &tr_args("audit-detail-imported", &[("count", imported.into())]));
+                        }
+                        let personal = self.personal_entries();
+                        self.import_wizard.update(message, &personal, &self.api_client)
+                    }
+                    Message::Export(message) => {
+                        if let ExportMessage::Exported(Ok(count)) = &message {
+                            let detail = self.export_view.describe(*count);
+                            self.audit(AuditAction::Exported, None, &detail);
+                        }
+                        if let ExportMessage::Restored(result) = &message {
+                            self.audit_restore(result, "audit-detail-restored-archive");
+                        }
+                        let personal = self.personal_entries();
+                        self.export_view.update(message, &personal, &self.api_client)
+                    }
+                    Message::Backup(message) => {
+                        if let BackupMessage::Restored(result) = &message {
+                            self.audit_restore(result, "audit-detail-restored-backup");
+                        }
+                        let personal = self.personal_entries();
+                        self.backups.update(message, &personal, &self.api_client)
+                    }
+                    Message::Synced(result) => {
+                        self.syncing = false;
+                        match result {
+                            Ok(report) => {
+                                self.passwords = report.entries;
+                                self.merge_shared();
+                                self.pending_writes = report.pending;
+                                if matches!(self.connectivity, Connectivity::Offline(_))
+                                    && report.connectivity == Connectivity::Online
+                                {
+                                    self.status_message = Some(tr("status-back-online"));
+                                }
+                                self.connectivity = report.connectivity;
+                                if report.unreadable > 0 {
+                                    self.status_message =
+                                        Some(tr_args("status-sync-unreadable", &[("count", report.unreadable.into())]));
+                                }
+                                if !report.conflicts.is_empty() {
+                                    self.status_message =
+                                        Some(tr_args("status-conflicts", &[("count", report.conflicts.len().into())]));
+                                } else if self.current_view == View::Conflicts {
+                                    self.status_message = Some(tr("status-conflicts-resolved"));
+                                    self.current_view = View::Main;
+                                }
+                                self.merge_view.set_conflicts(report.conflicts);
+                                self.refresh_team()
+                            }
+                            Err(e) => {
+                                self.merge_view.set_conflicts(Vec::new());
+                                self.status_message = Some(tr_args("status-sync-error", &[("error", e.into())]));
+                                Command::none()
+                            }
+                        }
+                    }
+                    Message::TeamOpened(result) => match result {
+                        Ok(team) => {
+                            self.team = Some(team);
+                            self.refresh_team()
+                        }
+                        Err(e) => {
+                            self.status_message = Some(tr_args("status-team-unavailable", &[("error", e.into())]));
+                            Command::none()
+                        }
+                    },
+                    Message::RefreshTeam => self.refresh_team(),
+                    Message::TeamRefreshed(snapshot) => {
+                        if let Some(error) = snapshot.errors.first() {
+                            self.status_message = Some(tr_args("status-team-unreadable", &[("error", error.as_str().into())]));
+                        }
+                        self.team_snapshot = snapshot;
+                        self.merge_shared();
+                        Command::none()
+                    }
+                    Message::Security(message) => {
+                        let profile = self.profiles.current().name.clone();
+                        self.security_view.update(message, &self.api_client, &self.sync, &profile)
+                    }
+                    Message::Settings(message) => {
+                        let key_file = self.profiles.current().key_file.clone();
+                        self.settings_view.update(message, &self.api_client, &self.sync, key_file, &mut self.config)
+                    }
+                    Message::MasterPasswordChanged(password) => {
+                        // Sessions révoquées par le serveur : la suivante s'ouvre avec le nouveau mot de passe
+                        self.api_client.change_session_password(&password);
+                        self.settings_view.update(SettingsMessage::Load, &self.api_client, &self.sync, None, &mut self.config)
+                    }
+                    Message::Activity(message) => {
+                        let dir = self.profiles.current().data_dir();
+                        self.activity_view.update(message, dir, self.api_client.cipher())
+                    }
+                    Message::Team(message) => {
+                        self.team_view.update(message, &self.team, &self.team_snapshot, &mut self.profiles)
+                    }
+                    Message::UnshareEntry => {
+                        let (Some(team), Some(entry)) = (self.team.clone(), self.current_entry.clone()) else {
+                            return Command::none();
+                        };
+                        self.current_view = View::Main;
+                        self.current_entry = None;
+                        Command::perform(
+                            async move { team.remove_entry(&entry.id).await.map(|_| entry.id) },
+                            |result| match result {
+                                Ok(id) => Message::EntryUnshared(id),
+                                Err(e) => Message::StatusUpdate(tr_args("status-error", &[("error", e.into())])),
+                            },
+                        )
+                    }
+                    Message::EntryUnshared(id) => {
+                        self.audit(AuditAction::Deleted, Some(&id), "");
+                        self.update(Message::Team(TeamMessage::Done(Ok(tr("status-unshared")))))
+                    }
+                    Message::ShareEntry(collection) => {
+                        let (Some(team), Some(entry)) = (self.team.clone(), self.current_entry.clone()) else {
+                            return Command::none();
+                        };
+                        Command::perform(
+                            async move { team.share(&collection.id, entry).await.map(|_| collection.name) },
+                            |result| match result {
+                                Ok(name) => Message::Team(TeamMessage::Done(Ok(tr_args("status-shared", &[("collection", name.into())])))),
+                                Err(e) => Message::StatusUpdate(tr_args("status-share-failed", &[("error", e.into())])),
+                            },
+                        )
+                    }
+                    Message::Merge(message) => {
+                        self.merge_view.update(message, &self.sync, &self.api_client)
+                    }
+                    Message::EditEntry(edit) => {
+                        if let Some(entry) = &mut self.current_entry {
+                            entry_editor::apply(entry, &mut self.tags_value, edit);
+                        }
                         Command::none()
                     }
                     Message::UpdatePassword => {
-                        if let Some(service_url) = &self.current_service_url {
-                            let client = self.api_client.clone();
-                            let service_url = service_url.clone();
-                            let password = self.password_value.clone();
-                            let email = self.email_value.clone();
+                        if let Some(entry) = &mut self.current_entry {
+                            entry.service_password = self.password_value.clone();
+                            entry.email = self.email_value.clone();
+                            entry.touch_modified();
+
+                            let entry = entry.clone();
                             
-                            self.status_message = Some(format!("Mise à jour en cours..."));
+                            self.status_message = Some(tr("status-updating"));
+                            log::debug!(service_url = entry.id.as_str(); "Mise à jour de l'entrée");
                             
+                            let id = entry.id.clone();
                             return Command::perform(
-                                async move { 
-                                    println!("Mise à jour du service: {}, email: {}", service_url, email);
-                                    client.update_password(&service_url, &password, &email).await 
-                                },
-                                |result| match result {
-                                    Ok(_) => Message::StatusUpdate("Mot de passe mis à jour avec succès".into()),
-                                    Err(e) => Message::StatusUpdate(format!("Erreur de mise à jour: {}", e)),
+                                self.store_entry(entry),
+                                move |result| match result {
+                                    Ok(_) => Message::PasswordUpdated(id),
+                                    Err(e) => Message::StatusUpdate(tr_args("status-update-error", &[("error", e.into())])),
                                 },
                             );
                         } else {
-                            self.status_message = Some(String::from("Erreur: URL du service non disponible"));
+                            self.status_message = Some(tr("status-no-service-url"));
                         }
                         Command::none()
                     }
-                    Message::ClearForm | Message::ClearFormFields => {
-                        self.password_value = String::new();
-                        self.service_url_value = String::new();
-                        self.current_service_url = None;
-                        self.editing_mode = false;
-                        self.selected_password = None;
-                        Command::none()
-                    }
                     Message::NavigateTo(view) => {
                         self.current_view = view;
-                        Command::none()
+                        match self.current_view {
+                            View::Backups => self.update(Message::Backup(BackupMessage::Refresh)),
+                            View::Security => self.update(Message::Security(SecurityMessage::Load)),
+                            View::Settings => self.update(Message::Settings(SettingsMessage::Load)),
+                            View::Activity => self.update(Message::Activity(ActivityMessage::Load)),
+                            _ => Command::none(),
+                        }
                     }
                     Message::BackToMain => {
                         self.current_view = View::Main;
                         self.selected_password = None;
                         self.editing_mode = false;
                         self.current_service_url = None;
+                        self.current_entry = None;
+                        self.tags_value = String::new();
                         
-                        self.password_value = String::new();
+                        self.password_value = SecretString::default();
+                        self.password_revealed = false;
                         self.service_url_value = String::new();
                         self.email_value = String::new();
                         
-                        return Command::perform(
+                        Command::perform(
                             async { Message::RefreshPasswords },
                             |msg| msg
-                        );
+                        )
                     }
                     // Éviter les cas impossibles comme LoginAttempt qui est déjà traité
                     _ => Command::none(),
@@ -319,42 +952,125 @@ impl Application for PasswordManagerApp {
         }
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         match self.current_view {
             View::Login => self.view_login(),
             View::Main => self.view_main(),
             View::ServiceDetail => self.view_service_detail(),
+            View::Import => self.import_wizard.view(),
+            View::Export => self.export_view.view(),
+            View::Backups => self.backups.view(),
+            View::Conflicts => self.merge_view.view(),
+            View::Profiles => self.profile_view.view(&self.profiles, &self.config.backend),
+            View::Team => self.team_view.view(&self.team_snapshot),
+            View::RecoveryKit => self.recovery_view.view_kit(),
+            View::Recover => self.recovery_view.view_recover(),
+            View::Security => self.security_view.view(),
+            View::Settings => self.settings_view.view(&self.config),
+            View::Activity => self.activity_view.view(),
         }
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        Subscription::none()
+        if !self.is_authenticated {
+            // Le temps d'attente restant est décompté à l'écran
+            if self.lockout_remaining() > 0 {
+                return time::every(Duration::from_secs(1)).map(|_| Message::LockoutTick);
+            }
+            return Subscription::none();
+        }
+
+        let view_subscription = match self.current_view {
+            View::Main => subscription::events_with(search_navigation),
+            // Le code TOTP et son anneau sont rafraîchis chaque seconde
+            View::ServiceDetail if self.current_totp().is_some() => {
+                time::every(Duration::from_secs(1)).map(|_| Message::TotpTick)
+            }
+            _ => Subscription::none(),
+        };
+
+        // Hors ligne ou avec des écritures en attente, le serveur est relancé régulièrement
+        let replaying = self.sync.is_some()
+            && (matches!(self.connectivity, Connectivity::Offline(_)) || self.pending_writes > 0);
+        let replay_subscription = if replaying {
+            time::every(Duration::from_secs(15)).map(|_| Message::RefreshPasswords)
+        } else {
+            Subscription::none()
+        };
+
+        // Verrouillage après le délai d'inactivité choisi dans les paramètres
+        let idle_subscription = if self.config.lock.idle.duration().is_some() {
+            Subscription::batch(vec![
+                subscription::events_with(user_activity),
+                time::every(Duration::from_secs(5)).map(|_| Message::IdleTick),
+            ])
+        } else {
+            Subscription::none()
+        };
+
+        // La sauvegarde quotidienne est vérifiée toutes les heures
+        Subscription::batch(vec![
+            view_subscription,
+            replay_subscription,
+            idle_subscription,
+            time::every(Duration::from_secs(3600)).map(|_| Message::Backup(BackupMessage::Tick)),
+        ])
+    }
+
+    fn theme(&self) -> Theme {
+        style::theme(self.config.appearance.theme)
     }
 }
 
 impl PasswordManagerApp {
     // Nouvelle méthode pour afficher la page de connexion
-    fn view_login(&self) -> Element<Message> {
-        let title = Text::new("Gestionnaire de mots de passe Mushroom")
+    fn view_login(&self) -> Element<'_, Message> {
+        let title = Text::new(tr("app-title"))
             .size(30);
 
-        let subtitle = Text::new("Veuillez entrer votre mot de passe maître pour accéder à vos mots de passe")
+        let subtitle = Text::new(tr("login-subtitle"))
             .size(16);
 
-        let password_input = TextInput::new(
-            "Mot de passe maître",
-            &self.master_password,
+        let password_input = text_field(
+            &tr("login-master-password"),
+            &self.login_password,
         )
         .on_input(Message::MasterPasswordInputChanged)
+        .on_submit(Message::LoginAttempt)
         .padding(10)
         .width(Length::Fill)
         .password(); // Masque les caractères saisis
 
-        let login_button = Button::new(
-            Text::new("Se connecter")
+        // Profil dont le coffre sera ouvert
+        let profile_row = Row::new()
+            .spacing(10)
+            .push(
+                PickList::new(&self.profiles.profiles[..], Some(self.profiles.current().clone()), Message::ProfileSelected)
+                    .width(Length::Fill),
+            )
+            .push(
+                Button::new(Text::new(tr("login-profiles")))
+                    .on_press(Message::NavigateTo(View::Profiles))
+                    .padding(10),
+            )
+            .push(
+                Button::new(Text::new(tr("login-recovery-kit")))
+                    .on_press(Message::NavigateTo(View::Recover))
+                    .padding(10),
+            );
+
+        // Fichier clé sur un support amovible, s'il a servi à créer le coffre
+        let key_file_input = text_field(&tr("login-key-file"), &self.key_file_value)
+            .on_input(Message::KeyFileChanged)
+            .on_submit(Message::LoginAttempt)
+            .padding(10)
+            .width(Length::Fill);
+
+        let remaining = self.lockout_remaining();
+        let login_button = action_button(
+            &tr(if self.unlocking { "login-opening" } else { "login-submit" }),
+            (!self.unlocking && remaining == 0).then_some(Message::LoginAttempt),
         )
-        .on_press(Message::LoginAttempt)
-        .padding(10)
         .width(Length::Fill);
 
         // Message d'état (erreur de connexion, etc.)
@@ -363,6 +1079,11 @@ impl PasswordManagerApp {
         } else {
             Text::new("").size(16)
         };
+        let lockout_message = if remaining > 0 {
+            Text::new(tr_args("login-lockout", &[("wait", format_wait(remaining).into())])).size(16)
+        } else {
+            Text::new("").size(16)
+        };
 
         let content = Column::new()
             .spacing(20)
@@ -370,9 +1091,29 @@ impl PasswordManagerApp {
             .padding(20)
             .push(title)
             .push(subtitle)
-            .push(password_input)
-            .push(login_button)
-            .push(status_message);
+            .push(profile_row);
+
+        // Mot de passe et fichier clé vérifiés : reste le code de l'application d'authentification
+        let content = if self.pending_vault.is_some() {
+            content
+                .push(
+                    text_field(&tr("login-totp-code"), &self.second_factor_code)
+                        .on_input(Message::SecondFactorChanged)
+                        .on_submit(Message::SecondFactorSubmit)
+                        .padding(10)
+                        .width(Length::Fill),
+                )
+                .push(action_button(&tr("login-totp-submit"), (remaining == 0).then_some(Message::SecondFactorSubmit)).width(Length::Fill))
+                .push(status_message)
+                .push(lockout_message)
+        } else {
+            content
+                .push(password_input)
+                .push(key_file_input)
+                .push(login_button)
+                .push(status_message)
+                .push(lockout_message)
+        };
 
         Container::new(content)
             .width(Length::Fill)
@@ -383,27 +1124,55 @@ impl PasswordManagerApp {
     }
 
     // Méthodes existantes pour les autres vues
-    fn view_main(&self) -> Element<Message> {
-        // Liste des mots de passe sauvegardés
+    fn view_main(&self) -> Element<'_, Message> {
+        // Liste des mots de passe sauvegardés, filtrée et classée par la recherche
         let mut passwords_list = Column::new().spacing(10);
     
-        for password in &self.passwords {
-            let is_selected = self.selected_password.as_ref().map_or(false, |s| s == password);
+        for (position, result) in self.search_results.iter().enumerate() {
+            let entry = &self.passwords[result.index];
+            let is_selected = position == self.search_cursor;
+
+            let name_positions: &[usize] = if result.field == MatchedField::Name {
+                &result.positions
+            } else {
+                &[]
+            };
+
+            let mut label = Column::new()
+                .spacing(2)
+                .push(highlighted_text(&entry.service_name, name_positions, if is_selected { 18 } else { 16 }));
+
+            // Si la correspondance vient d'un autre champ, on l'affiche sous le nom,
+            // sinon l'identifiant aide à distinguer plusieurs comptes d'un même service
+            if result.field != MatchedField::Name && !result.positions.is_empty() {
+                let value = search::field_text(entry, result.field);
+                label = label.push(
+                    Row::new()
+                        .push(Text::new(format!("{}: ", result.field.label())).size(13))
+                        .push(highlighted_text(&value, &result.positions, 13)),
+                );
+            } else if !entry.username.is_empty() {
+                label = label.push(Text::new(entry.username.clone()).size(13));
+            }
+            if let Some(badge) = team_view::shared_badge(&self.team_snapshot, &entry.id) {
+                label = label.push(badge);
+            }
             
-            let password_button = Button::new(
-                Text::new(password)
-                    .size(if is_selected { 18 } else { 16 })  // Plus grand si sélectionné
-            )
-            .on_press(Message::ServiceSelected(password.clone()))
+            let password_button = Button::new(label)
+            .on_press(Message::ServiceSelected(entry.id.clone()))
             .width(Length::Fill)
             .style(if is_selected {
-                iced::theme::Button::Primary  // Style différent si sélectionné
+                ButtonType::Primary.into()  // Style différent si sélectionné
             } else {
-                iced::theme::Button::Secondary
+                ButtonType::Secondary.into()
             });
     
             passwords_list = passwords_list.push(password_button);
         }
+
+        if self.search_results.is_empty() && !self.search_query.is_empty() {
+            passwords_list = passwords_list.push(Text::new(tr("main-no-result")).size(16));
+        }
     
         // Section de statut
         let status_section = if let Some(message) = &self.status_message {
@@ -414,21 +1183,13 @@ impl PasswordManagerApp {
     
         // Créer la section du mot de passe séparément
         let password_section = Column::new()
-            .push(Text::new("Mot de passe").size(16))
-            .push(
-                TextInput::new(
-                    "Mot de passe",
-                    &self.password_value
-                )
-                .on_input(Message::InputChanged)
-                .padding(10)
-                .width(Length::Fill)
-            );
+            .push(Text::new(tr("field-password")).size(16))
+            .push(secret_field(&tr("field-password"), &self.password_value, self.password_revealed, Message::InputChanged));
         
         // Ajouter un texte d'avertissement si le mot de passe est trop court
         let password_section = if self.password_value.len() < 4 && !self.password_value.is_empty() {
             password_section.push(
-                Text::new("Mot de passe trop court ")
+                Text::new(tr("main-password-too-short"))
                     .size(14)
                     .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.2, 0.2)))  // Rouge
             )
@@ -439,13 +1200,13 @@ impl PasswordManagerApp {
         // Formulaire principal pour ajouter un nouveau mot de passe
         let main_form = Column::new()
             .spacing(20)
-            .push(Text::new("Ajouter un nouveau mot de passe").size(20))
+            .push(Text::new(tr("main-new-password")).size(20))
             .push(
                 Column::new()
-                    .push(Text::new("URL du service").size(16))
+                    .push(Text::new(tr("field-service-url")).size(16))
                     .push(
-                        TextInput::new(
-                            "URL du service (ex: www.exemple.com)",
+                        text_field(
+                            &tr("field-service-url-placeholder"),
                             &self.service_url_value
                         )
                         .on_input(Message::ServiceUrlChanged)
@@ -456,10 +1217,10 @@ impl PasswordManagerApp {
             .push(password_section)  // Utilisez la section du mot de passe modifiée ici
             .push(
                 Column::new()
-                    .push(Text::new("Email").size(16))
+                    .push(Text::new(tr("field-email")).size(16))
                     .push(
-                        TextInput::new(
-                            "Email",
+                        text_field(
+                            &tr("field-email"),
                             &self.email_value
                         )
                         .on_input(Message::EmailInputChanged)
@@ -472,17 +1233,17 @@ impl PasswordManagerApp {
         let action_buttons = Row::new()
             .spacing(10)
             .push(
-                Button::new(Text::new("Générer"))
+                Button::new(Text::new(tr("action-generate")))
                     .on_press(Message::GeneratePassword)
                     .padding(10)
             )
             .push(
-                Button::new(Text::new("Copier"))
+                Button::new(Text::new(tr("action-copy")))
                     .on_press(Message::CopyToClipboard)
                     .padding(10)
             )
             .push(
-                Button::new(Text::new("Sauvegarder"))
+                Button::new(Text::new(tr("action-save")))
                     .on_press(Message::SavePassword)
                     .padding(10)
             );
@@ -493,16 +1254,105 @@ impl PasswordManagerApp {
         // Section des mots de passe enregistrés
         let saved_passwords_section = Column::new()
             .spacing(20)
-            .push(Text::new("Mots de passe enregistrés").size(20))
+            .push(Text::new(tr("main-saved-passwords")).size(20))
             .push(
-                Button::new(Text::new("Actualiser"))
-                    .on_press(Message::RefreshPasswords)
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        text_field(
+                            &tr("main-search"),
+                            &self.search_query
+                        )
+                        .id(search_input_id())
+                        .on_input(Message::SearchQueryChanged)
+                        .on_submit(Message::SearchNavigate(SearchNavigation::Open))
+                        .padding(10)
+                        .width(Length::Fill)
+                    )
+                    .push(
+                        Button::new(Text::new(tr("main-refresh")))
+                            .on_press(Message::RefreshPasswords)
+                            .padding(10)
+                    )
+                    .push(
+                        Button::new(Text::new(tr("main-import")))
+                            .on_press(Message::NavigateTo(View::Import))
+                            .padding(10)
+                    )
+                    .push(
+                        Button::new(Text::new(tr("main-export")))
+                            .on_press(Message::NavigateTo(View::Export))
+                            .padding(10)
+                    )
+                    .push(
+                        Button::new(Text::new(tr("main-backups")))
+                            .on_press(Message::NavigateTo(View::Backups))
+                            .padding(10)
+                    )
+                    .push(
+                        Button::new(Text::new(tr("main-team")))
+                            .on_press(Message::NavigateTo(View::Team))
+                            .padding(10)
+                    )
+                    .push(
+                        Button::new(Text::new(tr("main-recovery")))
+                            .on_press(Message::NavigateTo(View::RecoveryKit))
+                            .padding(10)
+                    )
+                    .push(
+                        Button::new(Text::new(tr("main-security")))
+                            .on_press(Message::NavigateTo(View::Security))
+                            .padding(10)
+                    )
+                    .push(
+                        Button::new(Text::new(tr("main-activity")))
+                            .on_press(Message::NavigateTo(View::Activity))
+                            .padding(10)
+                    )
+                    .push(
+                        Button::new(Text::new(tr("main-settings")))
+                            .on_press(Message::NavigateTo(View::Settings))
+                            .padding(10)
+                    )
+                    .push(
+                        PickList::new(&self.profiles.profiles[..], Some(self.profiles.current().clone()), Message::SwitchProfile)
+                            .padding(10)
+                    )
+                    .push(
+                        Button::new(Text::new(tr("main-lock")))
+                            .on_press(Message::Lock)
+                            .padding(10)
+                    )
+            );
+
+        // Accès aux conflits de synchronisation en attente
+        let saved_passwords_section = if self.merge_view.conflict_count() > 0 {
+            saved_passwords_section.push(
+                Button::new(Text::new(tr_args("main-conflicts", &[("count", self.merge_view.conflict_count().into())])))
+                    .on_press(Message::NavigateTo(View::Conflicts))
+                    .style(ButtonType::Danger.into())
                     .padding(10)
             )
+        } else {
+            saved_passwords_section
+        };
+
+        let saved_passwords_section = saved_passwords_section
+            .push(
+                Row::new()
+                    .spacing(20)
+                    .push(Text::new(tr_args(
+                        "main-entry-count",
+                        &[("shown", self.search_results.len().into()), ("total", self.passwords.len().into())],
+                    )).size(14))
+                    .push(self.view_connectivity())
+            )
             .push(
                 Container::new(
                     Scrollable::new(passwords_list)
                 )
+                .padding(10)
+                .style(ContainerStyle)
                 .height(Length::Fill)
             );
     
@@ -514,7 +1364,7 @@ impl PasswordManagerApp {
                 Column::new()
                     .width(Length::FillPortion(3))
                     .spacing(20)
-                    .push(main_form)
+                    .push(Container::new(main_form).padding(15).style(ContainerStyle))
                     .push(status_section)
             )
             .push(
@@ -530,8 +1380,11 @@ impl PasswordManagerApp {
     }
     
     // Vue détaillée d'un service avec ses informations (identique à l'original)
-    fn view_service_detail(&self) -> Element<Message> {
-        let service_name = self.selected_password.clone().unwrap_or_default();
+    fn view_service_detail(&self) -> Element<'_, Message> {
+        let service_name = match &self.current_entry {
+            Some(entry) => entry.service_name.clone(),
+            None => self.selected_password.clone().unwrap_or_default(),
+        };
         
         // En-tête avec le nom du service
         let header = Column::new()
@@ -539,8 +1392,8 @@ impl PasswordManagerApp {
             .push(
                 Row::new()
                     .spacing(20)
-                    .push(Button::new(Text::new("< Retour")).on_press(Message::BackToMain))
-                    .push(Text::new(format!("Détails du service: {}", service_name)).size(24))
+                    .push(Button::new(Text::new(tr("detail-back"))).on_press(Message::BackToMain))
+                    .push(Text::new(tr_args("detail-title", &[("service", service_name.into())])).size(24))
             );
             
         // Message de statut
@@ -555,23 +1408,15 @@ impl PasswordManagerApp {
             .spacing(20)
             .push(
                 Column::new()
-                    .push(Text::new("Mot de passe").size(16))
-                    .push(
-                        TextInput::new(
-                            "Mot de passe",
-                            &self.password_value
-                        )
-                        .on_input(Message::InputChanged)
-                        .padding(10)
-                        .width(Length::Fill)
-                    )
+                    .push(Text::new(tr("field-password")).size(16))
+                    .push(secret_field(&tr("field-password"), &self.password_value, self.password_revealed, Message::InputChanged))
             )
             .push(
                 Column::new()
-                    .push(Text::new("Email").size(16))
+                    .push(Text::new(tr("field-email")).size(16))
                     .push(
-                        TextInput::new(
-                            "Email",
+                        text_field(
+                            &tr("field-email"),
                             &self.email_value
                         )
                         .on_input(Message::EmailInputChanged)
@@ -580,35 +1425,67 @@ impl PasswordManagerApp {
                     )
             );
         
+        // Code TOTP en direct, avec le temps restant avant le prochain code
+        let detail_form = match self.current_totp() {
+            Some(Ok(totp)) => detail_form.push(self.view_totp(&totp)),
+            Some(Err(e)) => detail_form.push(Text::new(tr_args("detail-totp-invalid", &[("error", e.into())])).size(14)),
+            None => detail_form,
+        };
+
+        // Éditeurs des autres champs de l'entrée
+        let detail_form = match &self.current_entry {
+            Some(entry) => detail_form.push(entry_editor::view(entry, &self.tags_value)),
+            None => detail_form,
+        };
+        
         // Boutons d'action
         let action_buttons = Row::new()
             .spacing(10)
             .push(
-                Button::new(Text::new("Générer nouveau mot de passe"))
+                Button::new(Text::new(tr("detail-generate")))
                     .on_press(Message::GeneratePassword)
                     .padding(10)
             )
             .push(
-                Button::new(Text::new("Copier"))
+                Button::new(Text::new(tr("action-copy")))
                     .on_press(Message::CopyToClipboard)
                     .padding(10)
             )
             .push(
-                Button::new(Text::new("Mettre à jour"))
+                Button::new(Text::new(tr("detail-update")))
                     .on_press(Message::UpdatePassword)
                     .padding(10)
             );
+
+        // Une entrée personnelle peut être copiée dans une collection de l'équipe
+        let is_shared = self.current_entry.as_ref().is_some_and(|entry| shared::parse_shared_id(&entry.id).is_some());
+        let action_buttons = if !is_shared && !self.team_snapshot.collections.is_empty() {
+            action_buttons.push(
+                PickList::new(team_view::collection_choices(&self.team_snapshot), None, Message::ShareEntry)
+                    .placeholder(tr("detail-share"))
+                    .padding(10)
+            )
+        } else if is_shared {
+            action_buttons.push(
+                Button::new(Text::new(tr("detail-unshare")))
+                    .on_press(Message::UnshareEntry)
+                    .style(ButtonType::Danger.into())
+                    .padding(10)
+            )
+        } else {
+            action_buttons
+        };
         
         // Mise en page complète
         let content = Column::new()
             .spacing(30)
             .padding(20)
             .push(header)
-            .push(detail_form)
+            .push(Container::new(detail_form).padding(15).style(ContainerStyle))
             .push(action_buttons)
             .push(status_section);
         
-        Container::new(content)
+        Container::new(Scrollable::new(content))
             .width(Length::Fill)
             .height(Length::Fill)
             .center_x()
@@ -616,6 +1493,318 @@ impl PasswordManagerApp {
     }
 }
 
+impl PasswordManagerApp {
+    fn view_totp(&self, totp: &Totp) -> Element<'_, Message> {
+        let code = totp.generate(self.now);
+        // Groupement par trois pour la lecture : "123 456"
+        let (head, tail) = code.split_at(code.len() / 2);
+        let remaining = totp.seconds_remaining(self.now);
+
+        Column::new()
+            .push(Text::new(tr("totp-title")).size(16))
+            .push(
+                Row::new()
+                    .spacing(15)
+                    .align_items(alignment::Alignment::Center)
+                    .push(Text::new(format!("{} {}", head, tail)).size(28))
+                    .push(
+                        Canvas::new(CountdownRing { remaining, period: totp.period })
+                            .width(Length::Fixed(36.0))
+                            .height(Length::Fixed(36.0)),
+                    )
+                    .push(
+                        Button::new(Text::new(tr("totp-copy")))
+                            .on_press(Message::CopyTotpCode)
+                            .padding(10),
+                    ),
+            )
+            .into()
+    }
+
+    // Indicateur de connexion au serveur et écritures en attente d'envoi
+    fn view_connectivity(&self) -> Element<'_, Message> {
+        let (label, color) = match &self.connectivity {
+            Connectivity::Unknown => (tr("connectivity-unknown"), iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            Connectivity::Online if self.pending_writes > 0 => (
+                tr_args("connectivity-online-pending", &[("count", self.pending_writes.into())]),
+                iced::Color::from_rgb(0.9, 0.5, 0.1),
+            ),
+            Connectivity::Online => (tr("connectivity-online"), iced::Color::from_rgb(0.2, 0.6, 0.3)),
+            Connectivity::Offline(_) => (
+                tr_args("connectivity-offline", &[("count", self.pending_writes.into())]),
+                iced::Color::from_rgb(0.9, 0.2, 0.2),
+            ),
+        };
+        let mut indicator = Column::new().push(Text::new(label).size(14).style(iced::theme::Text::Color(color)));
+        if let Connectivity::Offline(reason) = &self.connectivity {
+            indicator = indicator.push(Text::new(reason.clone()).size(12));
+        }
+        if let Some(warning) = &self.memory_warning {
+            indicator = indicator.push(
+                Text::new(warning.clone())
+                    .size(12)
+                    .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.5, 0.1))),
+            );
+        }
+        indicator.into()
+    }
+
+    fn current_totp(&self) -> Option<Result<Totp, String>> {
+        self.current_entry.as_ref().and_then(totp::entry_totp)
+    }
+
+    // Chemin commun de copie des secrets ; depuis la vue détaillée,
+    // la copie compte comme une utilisation de l'entrée
+    fn copy_to_clipboard(&mut self, value: String, field: &str, status: &str) -> Command<Message> {
+        let mut command = clipboard::write::<Message>(value);
+        // Le presse-papiers est vidé après le délai choisi, sauf si une autre copie a suivi
+        self.clipboard_generation += 1;
+        if let Some(delay) = self.config.clipboard.clear_after.duration() {
+            let generation = self.clipboard_generation;
+            command = Command::batch(vec![
+                command,
+                Command::perform(tokio::time::sleep(delay), move |_| Message::ClipboardExpired(generation)),
+            ]);
+        }
+        self.status_message = Some(status.to_string());
+        let id = self.current_entry.as_ref().filter(|_| self.current_view == View::ServiceDetail).map(|entry| entry.id.clone());
+        self.audit(AuditAction::Copied, id.as_deref(), field);
+
+        match (&self.current_view, &mut self.current_entry) {
+            (View::ServiceDetail, Some(entry)) => {
+                entry.touch_used();
+                let entry = entry.clone();
+                let status = status.to_string();
+                Command::batch(vec![
+                    command,
+                    Command::perform(
+                        self.store_entry(entry),
+                        move |result| match result {
+                            Ok(_) => Message::StatusUpdate(status),
+                            Err(e) => Message::StatusUpdate(tr_args("status-error", &[("error", e.into())])),
+                        },
+                    ),
+                ])
+            }
+            _ => command,
+        }
+    }
+
+    // Enregistre une entrée : dans le cache local, envoyé à la prochaine synchronisation,
+    // ou directement sur le serveur si la synchronisation n'est pas disponible
+    fn store_entry(&self, entry: Entry) -> impl std::future::Future<Output = Result<(), String>> + 'static {
+        let client = self.api_client.clone();
+        let sync = self.sync.clone();
+        // Une entrée partagée est réécrite dans le fichier de sa collection
+        let team = self.team.clone().filter(|_| shared::parse_shared_id(&entry.id).is_some());
+        async move {
+            match (team, sync) {
+                (Some(team), _) => team.save_entry(entry).await,
+                (None, Some(sync)) => sync.record_edit(entry).await,
+                (None, None) => Ok(client.update_password(&entry).await?),
+            }
+        }
+    }
+
+    // Entrées du coffre, sans celles des collections partagées
+    fn personal_entries(&self) -> Vec<Entry> {
+        self.passwords
+            .iter()
+            .filter(|entry| shared::parse_shared_id(&entry.id).is_none())
+            .cloned()
+            .collect()
+    }
+
+    // Remplace les entrées partagées de la liste par celles de la dernière lecture des collections
+    fn merge_shared(&mut self) {
+        self.passwords.retain(|entry| shared::parse_shared_id(&entry.id).is_none());
+        self.passwords
+            .extend(self.team_snapshot.entries.iter().map(|(entry, _)| entry.clone()));
+        self.refresh_search();
+    }
+
+    fn refresh_team(&self) -> Command<Message> {
+        match &self.team {
+            Some(team) => {
+                let team = team.clone();
+                let paths = self.profiles.current().shared.clone();
+                Command::perform(async move { team.snapshot(paths).await }, Message::TeamRefreshed)
+            }
+            None => Command::none(),
+        }
+    }
+
+    // Coffre ouvert, second facteur vérifié : chargement des entrées et des services du profil
+    fn finish_unlock(&mut self, vault: OpenedVault) -> Command<Message> {
+        self.api_client = vault.client;
+        self.sync = vault.sync;
+        self.is_authenticated = true;
+        self.current_view = View::Main;
+        self.status_message = Some(vault.warning.unwrap_or_else(|| tr("status-connected")));
+        if let Some(Err(e)) = self.login_limiter.as_mut().map(LoginLimiter::record_success) {
+            self.status_message = Some(e);
+        }
+        match CoreDumpGuard::acquire() {
+            Ok(guard) => self.core_dumps = Some(guard),
+            Err(e) => self.status_message = Some(e),
+        }
+        self.check_memory_lock();
+
+        let profile = self.profiles.current().clone();
+        self.profiles.set_current(&profile.id);
+        let mut commands = vec![self.update(Message::GetEmail), self.update(Message::RefreshPasswords)];
+        match profile.data_dir() {
+            Ok(dir) => {
+                commands.push(self.backups.start(&dir));
+                // L'identité d'équipe et le journal d'activité sont chiffrés avec la clé du coffre
+                if let Some(cipher) = self.api_client.cipher() {
+                    match AuditLog::open(&dir, cipher.clone()) {
+                        Ok(audit) => self.audit = Some(audit),
+                        Err(e) => self.status_message = Some(e),
+                    }
+                    commands.push(Command::perform(
+                        TeamHandle::open(dir, cipher, profile.shared.clone()),
+                        Message::TeamOpened,
+                    ));
+                }
+            }
+            Err(e) => self.status_message = Some(tr_args("status-backups-disabled", &[("error", e.into())])),
+        }
+        if let Err(e) = self.profiles.save() {
+            self.status_message = Some(e);
+        }
+        Command::batch(commands)
+    }
+
+    // Journalise une restauration, même interrompue : chaque suppression, puis le bilan
+    fn audit_restore(&mut self, result: &Result<RestoreReport, RestoreError>, detail: &str) {
+        let (applied, removed) = match result {
+            Ok(report) => (report.applied, &report.removed),
+            Err(e) => (e.applied, &e.removed),
+        };
+        for id in removed {
+            self.audit(AuditAction::Deleted, Some(id), "");
+        }
+        if applied > 0 {
+            self.audit(AuditAction::Restored, None, &tr_args(detail, &[("count", applied.into())]));
+        }
+    }
+
+    // Journalise une opération ; un échec d'écriture est signalé sans bloquer l'opération
+    fn audit(&mut self, action: AuditAction, entry: Option<&str>, detail: &str) {
+        if let Some(Err(e)) = self.audit.as_mut().map(|audit| audit.record(action, entry, detail)) {
+            self.status_message = Some(tr_args("status-audit-failed", &[("error", e.into())]));
+        }
+    }
+
+    // L'avertissement reste affiché jusqu'au verrouillage
+    fn check_memory_lock(&mut self) {
+        if let Some(warning) = secure::take_lock_warning() {
+            self.memory_warning = Some(warning);
+        }
+    }
+
+    fn lockout_remaining(&self) -> u64 {
+        self.login_limiter.as_ref().map(LoginLimiter::remaining).unwrap_or(0)
+    }
+
+    // Secret refusé : l'attente avant le prochain essai double, et le cache local
+    // est effacé si la politique du profil l'exige
+    fn record_login_failure(&mut self, error: String) {
+        let mut status = error;
+        if let Some(limiter) = &mut self.login_limiter {
+            if let Err(e) = limiter.record_failure() {
+                status = tr_args("status-failures-not-recorded", &[("status", status.into()), ("error", e.into())]);
+            }
+            let profile = self.profiles.current();
+            if limiter.should_wipe(profile.wipe_cache) {
+                // Le coffre en attente du code TOTP ne doit plus réécrire le cache
+                self.pending_vault = None;
+                status = match profile.data_dir().and_then(|dir| cache::wipe(&dir)) {
+                    Ok(()) => tr_args("status-cache-wiped", &[("status", status.into()), ("count", limiter.failures().into())]),
+                    Err(e) => tr_args("status-cache-wipe-failed", &[("status", status.into()), ("error", e.into())]),
+                };
+            }
+        }
+        self.status_message = Some(status);
+    }
+
+    // Recalcule les résultats après un changement de requête ou de liste
+    fn refresh_search(&mut self) {
+        self.search_results = search::search(&self.passwords, &self.search_query);
+        if self.search_cursor >= self.search_results.len() {
+            self.search_cursor = self.search_results.len().saturating_sub(1);
+        }
+    }
+}
+
+fn open_limiter(profile: &Profile) -> Option<LoginLimiter> {
+    profile.data_dir().ok().map(|dir| LoginLimiter::open(&dir))
+}
+
+fn format_wait(seconds: u64) -> String {
+    if seconds < 60 {
+        tr_args("wait-seconds", &[("seconds", seconds.into())])
+    } else {
+        tr_args("wait-minutes", &[("minutes", (seconds / 60).into()), ("seconds", format!("{:02}", seconds % 60).into())])
+    }
+}
+
+fn key_file_text(profile: &Profile) -> String {
+    profile.key_file.as_ref().map(|path| path.display().to_string()).unwrap_or_default()
+}
+
+fn search_input_id() -> text_input::Id {
+    text_input::Id::new("search")
+}
+
+// Flèches pour parcourir les résultats, Entrée pour ouvrir, Échap pour effacer
+fn search_navigation(event: Event, status: event::Status) -> Option<Message> {
+    match event {
+        Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. }) => match key_code {
+            keyboard::KeyCode::Up => Some(Message::SearchNavigate(SearchNavigation::Up)),
+            keyboard::KeyCode::Down => Some(Message::SearchNavigate(SearchNavigation::Down)),
+            keyboard::KeyCode::Escape => Some(Message::SearchNavigate(SearchNavigation::Clear)),
+            // Entrée dans un champ de saisie est déjà gérée par le champ lui-même
+            keyboard::KeyCode::Enter | keyboard::KeyCode::NumpadEnter
+                if status == event::Status::Ignored =>
+            {
+                Some(Message::SearchNavigate(SearchNavigation::Open))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Toute action au clavier ou à la souris repousse le verrouillage automatique
+fn user_activity(event: Event, _status: event::Status) -> Option<Message> {
+    match event {
+        Event::Keyboard(keyboard::Event::KeyPressed { .. })
+        | Event::Mouse(mouse::Event::ButtonPressed(_) | mouse::Event::WheelScrolled { .. }) => Some(Message::UserActivity),
+        _ => None,
+    }
+}
+
+// Texte dont certains caractères sont surlignés (correspondances de la recherche)
+fn highlighted_text<'a>(text: &str, positions: &[usize], size: u16) -> Element<'a, Message> {
+    if positions.is_empty() {
+        return Text::new(text.to_string()).size(size).into();
+    }
+
+    search::highlight_segments(text, positions)
+        .into_iter()
+        .fold(Row::new(), |row, (segment, highlighted)| {
+            let segment = Text::new(segment).size(size);
+            row.push(if highlighted {
+                segment.style(iced::theme::Text::Color(iced::Color::from_rgb(1.0, 0.8, 0.2)))
+            } else {
+                segment
+            })
+        })
+        .into()
+}
+
 #[allow(dead_code)]
 pub fn main() -> iced::Result {
     PasswordManagerApp::run(Settings::default())
@@ -0,0 +1,378 @@
+use chrono::Utc;
+use iced::{
+    Command, Element, Length,
+    widget::{Button, Column, Row, Scrollable, Text},
+};
+use std::path::Path;
+
+use crate::api::client::ApiClient;
+use crate::backup::{self, BackupPolicy, Scheduler, Snapshot, SnapshotDiff};
+use crate::export::{self, RestoreError, RestoreMode, RestoreReport};
+use crate::i18n;
+use crate::ui::app::Message;
+use crate::ui::style::ButtonType;
+use crate::ui::widgets::action_button;
+use crate::vault::entry::{format_timestamp, Entry, EntryField};
+
+#[derive(Debug, Clone)]
+pub enum BackupMessage {
+    // Vérification périodique de la sauvegarde quotidienne
+    Tick,
+    SnapshotNow,
+    SnapshotCreated(Result<Snapshot, String>),
+    Refresh,
+    Listed(Result<Vec<Snapshot>, String>),
+    Compare(usize),
+    Compared(Result<(Vec<Entry>, SnapshotDiff), String>),
+    CloseDiff,
+    Restore,
+    Restored(Result<RestoreReport, RestoreError>),
+}
+
+// Sauvegardes automatiques et vue « Restaurer une sauvegarde »
+#[derive(Default)]
+pub struct BackupView {
+    scheduler: Option<Scheduler>,
+    snapshots: Vec<Snapshot>,
+    // Instantané comparé au coffre, son contenu et les différences
+    selected: Option<usize>,
+    selected_entries: Vec<Entry>,
+    diff: Option<SnapshotDiff>,
+    working: bool,
+    message: Option<String>,
+    error: Option<String>,
+}
+
+impl BackupView {
+    // Prépare le dossier de sauvegarde du profil après la connexion
+    pub fn start(&mut self, data_dir: &Path) -> Command<Message> {
+        match backup::backup_dir(data_dir) {
+            Ok(dir) => {
+                self.scheduler = Some(Scheduler::load(dir, BackupPolicy::default()));
+                Command::perform(async {}, |_| Message::Backup(BackupMessage::Tick))
+            }
+            Err(e) => Command::perform(async {}, move |_| {
+                Message::StatusUpdate(i18n::tr_args("status-backups-disabled", &[("error", e.into())]))
+            }),
+        }
+    }
+
+    // Appelé après chaque écriture dans le coffre
+    pub fn record_writes(&mut self, count: usize, client: &ApiClient) -> Command<Message> {
+        let Some(scheduler) = &mut self.scheduler else {
+            return Command::none();
+        };
+        match scheduler.record_writes(count) {
+            Ok(true) => self.snapshot(client),
+            Ok(false) => Command::none(),
+            Err(e) => Command::perform(async {}, move |_| {
+                Message::StatusUpdate(i18n::tr_args("backup-state-failed", &[("error", e.into())]))
+            }),
+        }
+    }
+
+    fn snapshot(&mut self, client: &ApiClient) -> Command<Message> {
+        let (Some(scheduler), Some(cipher)) = (&self.scheduler, client.cipher()) else {
+            return Command::none();
+        };
+        if self.working {
+            return Command::none();
+        }
+        self.working = true;
+
+        let dir = scheduler.dir().to_path_buf();
+        let policy = *scheduler.policy();
+        let client = client.clone();
+
+        Command::perform(
+            async move {
+                let entries = client.get_saved_passwords().await?;
+                // Chiffrement et écriture disque hors de la boucle de l'interface
+                tokio::task::spawn_blocking(move || backup::create_snapshot(&dir, &entries, &cipher, &policy))
+                    .await
+                    .map_err(|e| e.to_string())?
+            },
+            |result| Message::Backup(BackupMessage::SnapshotCreated(result)),
+        )
+    }
+
+    pub fn update(&mut self, message: BackupMessage, current: &[Entry], client: &ApiClient) -> Command<Message> {
+        match message {
+            BackupMessage::Tick => match &self.scheduler {
+                Some(scheduler) if scheduler.daily_due(Utc::now()) => self.snapshot(client),
+                _ => Command::none(),
+            },
+            BackupMessage::SnapshotNow => self.snapshot(client),
+            BackupMessage::SnapshotCreated(result) => {
+                self.working = false;
+                let status = match result {
+                    Ok(snapshot) => match self.scheduler.as_mut().map(|scheduler| scheduler.snapshot_taken(snapshot.created_at)) {
+                        Some(Err(e)) => i18n::tr_args("backup-state-failed", &[("error", e.into())]),
+                        _ => i18n::tr_args("backup-created", &[("count", snapshot.entry_count.into())]),
+                    },
+                    Err(e) => i18n::tr_args("backup-failed", &[("error", e.into())]),
+                };
+                Command::batch(vec![
+                    Command::perform(async {}, move |_| Message::StatusUpdate(status)),
+                    self.update(BackupMessage::Refresh, current, client),
+                ])
+            }
+            BackupMessage::Refresh => match &self.scheduler {
+                Some(scheduler) => {
+                    let dir = scheduler.dir().to_path_buf();
+                    Command::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || backup::list_snapshots(&dir))
+                                .await
+                                .map_err(|e| e.to_string())?
+                        },
+                        |result| Message::Backup(BackupMessage::Listed(result)),
+                    )
+                }
+                None => Command::none(),
+            },
+            BackupMessage::Listed(result) => {
+                match result {
+                    Ok(snapshots) => {
+                        // La sélection ne survit pas à un changement de la liste
+                        if snapshots != self.snapshots {
+                            self.selected = None;
+                            self.diff = None;
+                            self.selected_entries.clear();
+                        }
+                        self.snapshots = snapshots;
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                Command::none()
+            }
+            BackupMessage::Compare(index) => {
+                let (Some(snapshot), Some(cipher)) = (self.snapshots.get(index).cloned(), client.cipher()) else {
+                    return Command::none();
+                };
+                self.selected = Some(index);
+                self.diff = None;
+                self.error = None;
+                self.message = None;
+                let current = current.to_vec();
+
+                Command::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            let entries = backup::load_snapshot(&snapshot, &cipher)?;
+                            let diff = backup::diff(&entries, &current);
+                            Ok((entries, diff))
+                        })
+                        .await
+                        .map_err(|e| e.to_string())?
+                    },
+                    |result| Message::Backup(BackupMessage::Compared(result)),
+                )
+            }
+            BackupMessage::Compared(result) => {
+                match result {
+                    Ok((entries, diff)) => {
+                        self.selected_entries = entries;
+                        self.diff = Some(diff);
+                    }
+                    Err(e) => {
+                        self.selected = None;
+                        self.error = Some(e);
+                    }
+                }
+                Command::none()
+            }
+            BackupMessage::CloseDiff => {
+                self.selected = None;
+                self.diff = None;
+                self.selected_entries.clear();
+                Command::none()
+            }
+            BackupMessage::Restore => {
+                let (Some(scheduler), Some(cipher)) = (&self.scheduler, client.cipher()) else {
+                    return Command::none();
+                };
+                if self.diff.is_none() || self.working {
+                    return Command::none();
+                }
+                self.working = true;
+
+                let dir = scheduler.dir().to_path_buf();
+                let policy = *scheduler.policy();
+                let restored = self.selected_entries.clone();
+                let client = client.clone();
+
+                Command::perform(
+                    async move {
+                        // L'état actuel est sauvegardé d'abord : la restauration reste réversible
+                        let current = async {
+                            let current = client.get_saved_passwords().await?;
+                            let snapshot_entries = current.clone();
+                            tokio::task::spawn_blocking(move || {
+                                backup::create_snapshot(&dir, &snapshot_entries, &cipher, &policy)
+                            })
+                            .await
+                            .map_err(|e| e.to_string())??;
+                            Ok::<_, String>(current)
+                        }
+                        .await
+                        .map_err(|error| RestoreError { applied: 0, removed: Vec::new(), error })?;
+
+                        let plan = export::plan_restore(restored, &current, RestoreMode::Replace);
+                        export::apply_restore(&client, &plan).await
+                    },
+                    |result| Message::Backup(BackupMessage::Restored(result)),
+                )
+            }
+            BackupMessage::Restored(result) => {
+                self.working = false;
+                match result {
+                    Ok(report) => {
+                        let count = report.applied;
+                        self.selected = None;
+                        self.diff = None;
+                        self.selected_entries.clear();
+                        self.message = Some(i18n::tr_args("backup-restored", &[("count", count.into())]));
+                        Command::batch(vec![
+                            Command::perform(async {}, |_| Message::RefreshPasswords),
+                            self.update(BackupMessage::Refresh, current, client),
+                        ])
+                    }
+                    // Restauration partielle : la liste est rechargée pour refléter le coffre
+                    Err(e) => {
+                        let applied = e.applied;
+                        self.error = Some(e.to_string());
+                        if applied == 0 {
+                            return Command::none();
+                        }
+                        Command::batch(vec![
+                            Command::perform(async {}, |_| Message::RefreshPasswords),
+                            self.update(BackupMessage::Refresh, current, client),
+                        ])
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let header = Row::new()
+            .spacing(20)
+            .push(Button::new(Text::new(i18n::tr("detail-back"))).on_press(Message::BackToMain))
+            .push(Text::new(i18n::tr("backup-title")).size(24));
+
+        let mut content = Column::new().spacing(20).padding(20).push(header);
+
+        let policy = self.scheduler.as_ref().map(|scheduler| *scheduler.policy()).unwrap_or_default();
+        let mut description = i18n::tr_args("backup-policy", &[("count", policy.every_n_writes.into())]);
+        if policy.daily {
+            description.push_str(&i18n::tr("backup-policy-daily"));
+        }
+        if let Some(scheduler) = &self.scheduler {
+            description.push_str(&i18n::tr_args("backup-policy-dir", &[("dir", scheduler.dir().display().to_string().into())]));
+        }
+        content = content.push(Text::new(description).size(14)).push(action_button(
+            &i18n::tr(if self.working { "backup-working" } else { "backup-now" }),
+            (!self.working && self.scheduler.is_some()).then_some(Message::Backup(BackupMessage::SnapshotNow)),
+        ));
+
+        let mut list = Column::new().spacing(8);
+        for (index, snapshot) in self.snapshots.iter().enumerate() {
+            let label = Column::new()
+                .push(Text::new(format_timestamp(&Some(snapshot.created_at))).size(16))
+                .push(Text::new(i18n::tr_args(
+                    "backup-snapshot-size",
+                    &[("count", snapshot.entry_count.into()), ("size", snapshot.size.div_ceil(1024).into())],
+                )).size(13));
+            list = list.push(
+                Button::new(label)
+                    .on_press(Message::Backup(BackupMessage::Compare(index)))
+                    .width(Length::Fill)
+                    .style(if self.selected == Some(index) {
+                        ButtonType::Primary.into()
+                    } else {
+                        ButtonType::Secondary.into()
+                    }),
+            );
+        }
+        if self.snapshots.is_empty() {
+            list = list.push(Text::new(i18n::tr("backup-empty")).size(16));
+        }
+
+        let mut body = Row::new()
+            .spacing(20)
+            .push(Scrollable::new(list).width(Length::FillPortion(2)).height(Length::Fill));
+        if let (Some(diff), Some(index)) = (&self.diff, self.selected) {
+            body = body.push(self.view_diff(diff, &self.snapshots[index]));
+        }
+
+        if let Some(message) = &self.message {
+            content = content.push(Text::new(message).size(16));
+        }
+        if let Some(error) = &self.error {
+            content = content.push(
+                Text::new(error)
+                    .size(16)
+                    .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.2, 0.2))),
+            );
+        }
+
+        content.push(body).into()
+    }
+
+    fn view_diff<'a>(&'a self, diff: &'a SnapshotDiff, snapshot: &Snapshot) -> Element<'a, Message> {
+        let mut details = Column::new()
+            .spacing(6)
+            .push(Text::new(i18n::tr_args(
+                "backup-diff-title",
+                &[("date", format_timestamp(&Some(snapshot.created_at)).into())],
+            )).size(18))
+            .push(
+                Text::new(i18n::tr_args(
+                    "backup-diff-summary",
+                    &[
+                        ("added", diff.only_in_snapshot.len().into()),
+                        ("removed", diff.only_in_vault.len().into()),
+                        ("changed", diff.changed.len().into()),
+                        ("unchanged", diff.unchanged.into()),
+                    ],
+                ))
+                .size(14),
+            );
+
+        let line = |prefix: &str, entry: &Entry, detail: String, color: iced::Color| {
+            let mut text = format!("{} {}", prefix, entry.service_name);
+            if !entry.username.is_empty() {
+                text.push_str(&format!(" ({})", entry.username));
+            }
+            text.push_str(&detail);
+            Text::new(text).size(13).style(iced::theme::Text::Color(color))
+        };
+        for entry in &diff.only_in_snapshot {
+            details = details.push(line("+", entry, String::new(), iced::Color::from_rgb(0.2, 0.6, 0.3)));
+        }
+        for entry in &diff.only_in_vault {
+            details = details.push(line("−", entry, String::new(), iced::Color::from_rgb(0.9, 0.2, 0.2)));
+        }
+        for (entry, fields) in &diff.changed {
+            let fields: Vec<String> = fields.iter().map(EntryField::label).collect();
+            details = details.push(line("~", entry, format!(" : {}", fields.join(", ")), iced::Color::from_rgb(0.9, 0.5, 0.1)));
+        }
+
+        let actions = Row::new()
+            .spacing(10)
+            .push(action_button(
+                &i18n::tr(if self.working { "restore-restoring" } else { "backup-restore" }),
+                (!self.working && !diff.is_empty()).then_some(Message::Backup(BackupMessage::Restore)),
+            ))
+            .push(action_button(&i18n::tr("backup-close"), Some(Message::Backup(BackupMessage::CloseDiff))));
+
+        Column::new()
+            .spacing(10)
+            .width(Length::FillPortion(3))
+            .push(Scrollable::new(details).height(Length::Fill))
+            .push(Text::new(i18n::tr("backup-restore-hint")).size(13))
+            .push(actions)
+            .into()
+    }
+}
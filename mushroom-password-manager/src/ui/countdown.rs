@@ -0,0 +1,57 @@
+use iced::widget::canvas::{self, path::Arc, Cursor, Frame, Geometry, Path, Program, Stroke};
+use iced::{Color, Rectangle, Theme};
+use std::f32::consts::PI;
+
+use crate::ui::app::Message;
+
+// Anneau de compte à rebours : la partie pleine représente le temps restant
+pub struct CountdownRing {
+    pub remaining: u64,
+    pub period: u64,
+}
+
+impl Program<Message> for CountdownRing {
+    type State = ();
+
+    fn draw(&self, _state: &(), theme: &Theme, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        let palette = theme.extended_palette();
+        let center = frame.center();
+        let width = 4.0;
+        let radius = frame.width().min(frame.height()) / 2.0 - width;
+
+        let track = Path::circle(center, radius);
+        frame.stroke(&track, Stroke::default().with_width(width).with_color(palette.background.strong.color));
+
+        let fraction = self.remaining as f32 / self.period.max(1) as f32;
+        // Rouge dans les dernières secondes pour signaler que le code va changer
+        let color = if self.remaining <= 5 {
+            palette.danger.base.color
+        } else {
+            palette.primary.base.color
+        };
+
+        let start_angle = -PI / 2.0;
+        let arc = Path::new(|builder| {
+            builder.arc(Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle: start_angle + 2.0 * PI * fraction,
+            })
+        });
+        frame.stroke(&arc, Stroke::default().with_width(width).with_color(color));
+
+        frame.fill_text(canvas::Text {
+            content: self.remaining.to_string(),
+            position: center,
+            color: Color { a: 0.8, ..palette.background.base.text },
+            size: radius,
+            horizontal_alignment: iced::alignment::Horizontal::Center,
+            vertical_alignment: iced::alignment::Vertical::Center,
+            ..canvas::Text::default()
+        });
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,246 @@
+use iced::{
+    Element, Length,
+    widget::{Button, Column, PickList, Row, Text, TextInput},
+};
+
+use crate::i18n::{tr, tr_args};
+use crate::ui::app::Message;
+use crate::ui::style::ButtonType;
+use crate::ui::widgets::text_field;
+use crate::vault::entry::{format_timestamp, CustomField, Entry, FieldKind};
+
+// Modifications possibles d'une entrée dans la vue détaillée
+#[derive(Debug, Clone)]
+pub enum EntryEdit {
+    Username(String),
+    Folder(String),
+    Tags(String),
+    NoteLine(usize, String),
+    InsertNoteLine(usize),
+    RemoveNoteLine(usize),
+    AddCustomField,
+    RemoveCustomField(usize),
+    CustomFieldName(usize, String),
+    CustomFieldValue(usize, String),
+    CustomFieldKind(usize, FieldKind),
+}
+
+// Applique une modification ; le texte brut des tags est conservé pour la saisie
+pub fn apply(entry: &mut Entry, tags_value: &mut String, edit: EntryEdit) {
+    match edit {
+        EntryEdit::Username(value) => entry.username = value,
+        EntryEdit::Folder(value) => entry.folder = value,
+        EntryEdit::Tags(value) => {
+            entry.set_tags_from_text(&value);
+            *tags_value = value;
+        }
+        EntryEdit::NoteLine(index, value) => {
+            let mut lines = note_lines(&entry.notes);
+            if let Some(line) = lines.get_mut(index) {
+                *line = value;
+            }
+            entry.notes = lines.join("\n").into();
+        }
+        EntryEdit::InsertNoteLine(index) => {
+            let mut lines = note_lines(&entry.notes);
+            lines.insert((index + 1).min(lines.len()), String::new());
+            entry.notes = lines.join("\n").into();
+        }
+        EntryEdit::RemoveNoteLine(index) => {
+            let mut lines = note_lines(&entry.notes);
+            if index < lines.len() {
+                lines.remove(index);
+            }
+            entry.notes = lines.join("\n").into();
+        }
+        EntryEdit::AddCustomField => entry.custom_fields.push(CustomField::default()),
+        EntryEdit::RemoveCustomField(index) => {
+            if index < entry.custom_fields.len() {
+                entry.custom_fields.remove(index);
+            }
+        }
+        EntryEdit::CustomFieldName(index, value) => {
+            if let Some(field) = entry.custom_fields.get_mut(index) {
+                field.name = value;
+            }
+        }
+        EntryEdit::CustomFieldValue(index, value) => {
+            if let Some(field) = entry.custom_fields.get_mut(index) {
+                field.value = value.into();
+            }
+        }
+        EntryEdit::CustomFieldKind(index, kind) => {
+            if let Some(field) = entry.custom_fields.get_mut(index) {
+                field.kind = kind;
+            }
+        }
+    }
+}
+
+// Une note vide s'édite comme une seule ligne vide
+fn note_lines(notes: &str) -> Vec<String> {
+    notes.split('\n').map(String::from).collect()
+}
+
+fn labeled<'a>(label: &str, input: impl Into<Element<'a, Message>>) -> Column<'a, Message> {
+    Column::new()
+        .push(Text::new(label.to_string()).size(16))
+        .push(input)
+}
+
+fn edit_input<'a>(placeholder: &str, value: &str, on_change: fn(String) -> EntryEdit) -> TextInput<'a, Message> {
+    text_field(placeholder, value)
+        .on_input(move |value| Message::EditEntry(on_change(value)))
+        .padding(10)
+        .width(Length::Fill)
+}
+
+// Éditeurs des champs de l'entrée qui ne sont pas déjà dans la vue détaillée
+pub fn view<'a>(entry: &Entry, tags_value: &str) -> Element<'a, Message> {
+    let identity = Row::new()
+        .spacing(20)
+        .push(labeled(&tr("entry-username"), edit_input(&tr("entry-username"), &entry.username, EntryEdit::Username)).width(Length::Fill))
+        .push(labeled(&tr("entry-folder"), edit_input(&tr("entry-folder-placeholder"), &entry.folder, EntryEdit::Folder)).width(Length::Fill));
+
+    let tags = labeled(&tr("entry-tags"), edit_input(&tr("entry-tags-placeholder"), tags_value, EntryEdit::Tags));
+
+    // Notes multi-lignes : une ligne par champ, Entrée insère une nouvelle ligne
+    let lines = note_lines(&entry.notes);
+    let can_remove_line = lines.len() > 1;
+    let mut notes = Column::new().spacing(5).push(Text::new(tr("entry-notes")).size(16));
+    for (index, line) in lines.iter().enumerate() {
+        let mut row = Row::new().spacing(5).push(
+            text_field("", line)
+                .on_input(move |value| Message::EditEntry(EntryEdit::NoteLine(index, value)))
+                .on_submit(Message::EditEntry(EntryEdit::InsertNoteLine(index)))
+                .padding(5)
+                .width(Length::Fill),
+        );
+        if can_remove_line {
+            row = row.push(
+                Button::new(Text::new("×"))
+                    .on_press(Message::EditEntry(EntryEdit::RemoveNoteLine(index)))
+                    .style(iced::theme::Button::Text),
+            );
+        }
+        notes = notes.push(row);
+    }
+
+    // Champs personnalisés typés
+    let mut custom_fields = Column::new().spacing(5).push(Text::new(tr("entry-custom-fields")).size(16));
+    for (index, field) in entry.custom_fields.iter().enumerate() {
+        let mut value_input = text_field(&tr("entry-field-value"), &field.value)
+            .on_input(move |value| Message::EditEntry(EntryEdit::CustomFieldValue(index, value)))
+            .padding(5)
+            .width(Length::FillPortion(3));
+        if field.kind.is_secret() {
+            value_input = value_input.password();
+        }
+
+        custom_fields = custom_fields.push(
+            Row::new()
+                .spacing(5)
+                .push(
+                    text_field(&tr("entry-field-name"), &field.name)
+                        .on_input(move |value| Message::EditEntry(EntryEdit::CustomFieldName(index, value)))
+                        .padding(5)
+                        .width(Length::FillPortion(2)),
+                )
+                .push(value_input)
+                .push(
+                    PickList::new(&FieldKind::ALL[..], Some(field.kind), move |kind| {
+                        Message::EditEntry(EntryEdit::CustomFieldKind(index, kind))
+                    })
+                    .width(Length::Shrink),
+                )
+                .push(
+                    Button::new(Text::new("×"))
+                        .on_press(Message::EditEntry(EntryEdit::RemoveCustomField(index)))
+                        .style(ButtonType::Danger.into()),
+                ),
+        );
+    }
+    custom_fields = custom_fields.push(
+        Button::new(Text::new(tr("entry-add-field")))
+            .on_press(Message::EditEntry(EntryEdit::AddCustomField))
+            .style(ButtonType::Secondary.into()),
+    );
+
+    let timestamps = Text::new(tr_args(
+        "entry-timestamps",
+        &[
+            ("created", format_timestamp(&entry.created_at).into()),
+            ("modified", format_timestamp(&entry.modified_at).into()),
+            ("used", format_timestamp(&entry.last_used_at).into()),
+        ],
+    ))
+    .size(14);
+
+    Column::new()
+        .spacing(20)
+        .push(identity)
+        .push(tags)
+        .push(notes)
+        .push(custom_fields)
+        .push(timestamps)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edited(notes: &str, edits: Vec<EntryEdit>) -> Entry {
+        let mut entry = Entry::new("forge.example", "Forge");
+        entry.notes = notes.into();
+        let mut tags_value = String::new();
+        for edit in edits {
+            apply(&mut entry, &mut tags_value, edit);
+        }
+        entry
+    }
+
+    #[test]
+    fn note_lines_are_edited_inserted_and_removed() {
+        let entry = edited("un\ndeux", vec![EntryEdit::NoteLine(1, String::from("DEUX")), EntryEdit::InsertNoteLine(0)]);
+        assert_eq!(&*entry.notes, "un\n\nDEUX");
+
+        let entry = edited("un\ndeux\ntrois", vec![EntryEdit::RemoveNoteLine(1), EntryEdit::RemoveNoteLine(9)]);
+        assert_eq!(&*entry.notes, "un\ntrois");
+
+        // Une note vide s'édite comme une ligne vide ; un indice hors limites est ignoré
+        let entry = edited("", vec![EntryEdit::NoteLine(3, String::from("perdu")), EntryEdit::NoteLine(0, String::from("note"))]);
+        assert_eq!(&*entry.notes, "note");
+    }
+
+    #[test]
+    fn custom_fields_are_added_edited_and_removed() {
+        let entry = edited(
+            "",
+            vec![
+                EntryEdit::AddCustomField,
+                EntryEdit::AddCustomField,
+                EntryEdit::CustomFieldName(1, String::from("PIN")),
+                EntryEdit::CustomFieldValue(1, String::from("1234")),
+                EntryEdit::CustomFieldKind(1, FieldKind::Hidden),
+                EntryEdit::RemoveCustomField(0),
+                EntryEdit::RemoveCustomField(5),
+                EntryEdit::CustomFieldName(7, String::from("ignoré")),
+            ],
+        );
+        assert_eq!(entry.custom_fields, vec![CustomField { name: String::from("PIN"), value: "1234".into(), kind: FieldKind::Hidden }]);
+    }
+
+    #[test]
+    fn tags_keep_the_raw_text() {
+        let mut entry = Entry::new("forge.example", "Forge");
+        let mut tags_value = String::new();
+        apply(&mut entry, &mut tags_value, EntryEdit::Tags(String::from("travail, perso,")));
+        assert_eq!(tags_value, "travail, perso,");
+        assert_eq!(entry.tags, vec![String::from("travail"), String::from("perso")]);
+
+        apply(&mut entry, &mut tags_value, EntryEdit::Username(String::from("alice")));
+        apply(&mut entry, &mut tags_value, EntryEdit::Folder(String::from("Pro")));
+        assert_eq!((entry.username.as_str(), entry.folder.as_str()), ("alice", "Pro"));
+    }
+}
@@ -0,0 +1,405 @@
+use iced::{
+    Command, Element, Length,
+    widget::{Button, Checkbox, Column, PickList, Row, Text},
+};
+use std::path::PathBuf;
+
+use crate::api::client::ApiClient;
+use crate::export::{self, ExportFormat, RestoreError, RestoreMode, RestorePlan, RestoreReport};
+use crate::i18n;
+use crate::ui::app::Message;
+use crate::ui::widgets::{action_button, text_field};
+use crate::vault::entry::Entry;
+
+#[derive(Debug, Clone)]
+pub enum ExportMessage {
+    PathChanged(String),
+    FormatSelected(ExportFormat),
+    PasswordChanged(String),
+    PasswordConfirmationChanged(String),
+    PlaintextAcknowledged(bool),
+    Export,
+    Exported(Result<usize, String>),
+
+    RestorePathChanged(String),
+    RestorePasswordChanged(String),
+    RestoreModeSelected(RestoreMode),
+    AnalyzeRestore,
+    RestoreAnalyzed(Result<RestorePlan, String>),
+    ApplyRestore,
+    CancelRestore,
+    Restored(Result<RestoreReport, RestoreError>),
+}
+
+// Export du coffre (archive chiffrée ou fichier en clair) et restauration
+pub struct ExportView {
+    path: String,
+    format: ExportFormat,
+    password: String,
+    password_confirmation: String,
+    // L'export en clair doit être explicitement accepté
+    plaintext_acknowledged: bool,
+    exporting: bool,
+
+    restore_path: String,
+    restore_password: String,
+    restore_mode: RestoreMode,
+    restore_plan: Option<RestorePlan>,
+    restoring: bool,
+
+    message: Option<String>,
+    error: Option<String>,
+}
+
+impl Default for ExportView {
+    fn default() -> Self {
+        ExportView {
+            path: String::new(),
+            format: ExportFormat::Archive,
+            password: String::new(),
+            password_confirmation: String::new(),
+            plaintext_acknowledged: false,
+            exporting: false,
+            restore_path: String::new(),
+            restore_password: String::new(),
+            restore_mode: RestoreMode::Merge,
+            restore_plan: None,
+            restoring: false,
+            message: None,
+            error: None,
+        }
+    }
+}
+
+impl ExportView {
+    pub fn update(&mut self, message: ExportMessage, existing: &[Entry], client: &ApiClient) -> Command<Message> {
+        match message {
+            ExportMessage::PathChanged(path) => {
+                self.path = path;
+                Command::none()
+            }
+            ExportMessage::FormatSelected(format) => {
+                self.format = format;
+                self.plaintext_acknowledged = false;
+                Command::none()
+            }
+            ExportMessage::PasswordChanged(password) => {
+                self.password = password;
+                Command::none()
+            }
+            ExportMessage::PasswordConfirmationChanged(password) => {
+                self.password_confirmation = password;
+                Command::none()
+            }
+            ExportMessage::PlaintextAcknowledged(acknowledged) => {
+                self.plaintext_acknowledged = acknowledged;
+                Command::none()
+            }
+            ExportMessage::Export => {
+                if !self.can_export() {
+                    return Command::none();
+                }
+                let path = PathBuf::from(self.path.trim());
+                let format = self.format;
+                let password = (!format.is_plaintext()).then(|| self.password.clone());
+                let client = client.clone();
+                self.exporting = true;
+                self.message = None;
+                self.error = None;
+
+                Command::perform(
+                    async move {
+                        let entries = client.get_saved_passwords().await?;
+                        // La dérivation Argon2 bloquerait l'interface
+                        tokio::task::spawn_blocking(move || {
+                            let data = export::export(format, &entries, password.as_deref())?;
+                            export::write_private(&path, &data)?;
+                            Ok(entries.len())
+                        })
+                        .await
+                        .map_err(|e| e.to_string())?
+                    },
+                    |result| Message::Export(ExportMessage::Exported(result)),
+                )
+            }
+            ExportMessage::Exported(result) => {
+                self.exporting = false;
+                match result {
+                    Ok(count) => {
+                        self.message = Some(i18n::tr_args(
+                            "export-done",
+                            &[("count", count.into()), ("path", self.path.trim().into())],
+                        ));
+                        self.password.clear();
+                        self.password_confirmation.clear();
+                        self.plaintext_acknowledged = false;
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                Command::none()
+            }
+            ExportMessage::RestorePathChanged(path) => {
+                self.restore_path = path;
+                self.restore_plan = None;
+                Command::none()
+            }
+            ExportMessage::RestorePasswordChanged(password) => {
+                self.restore_password = password;
+                Command::none()
+            }
+            ExportMessage::RestoreModeSelected(mode) => {
+                self.restore_mode = mode;
+                self.restore_plan = None;
+                Command::none()
+            }
+            ExportMessage::AnalyzeRestore => {
+                let path = PathBuf::from(self.restore_path.trim());
+                let password = self.restore_password.clone();
+                let mode = self.restore_mode;
+                let existing = existing.to_vec();
+                self.message = None;
+                self.error = None;
+
+                Command::perform(
+                    async move {
+                        let data = tokio::fs::read(&path)
+                            .await
+                            .map_err(|e| {
+                                i18n::tr_args(
+                                    "file-read-failed",
+                                    &[("path", path.display().to_string().into()), ("error", e.to_string().into())],
+                                )
+                            })?;
+                        let restored = tokio::task::spawn_blocking(move || export::read_export(&data, &password))
+                            .await
+                            .map_err(|e| e.to_string())??;
+                        Ok(export::plan_restore(restored, &existing, mode))
+                    },
+                    |result| Message::Export(ExportMessage::RestoreAnalyzed(result)),
+                )
+            }
+            ExportMessage::RestoreAnalyzed(result) => {
+                match result {
+                    Ok(plan) => self.restore_plan = Some(plan),
+                    Err(e) => self.error = Some(e),
+                }
+                Command::none()
+            }
+            ExportMessage::ApplyRestore => {
+                let plan = match &self.restore_plan {
+                    Some(plan) if !plan.is_empty() => plan.clone(),
+                    _ => return Command::none(),
+                };
+                let client = client.clone();
+                self.restoring = true;
+
+                Command::perform(
+                    async move { export::apply_restore(&client, &plan).await },
+                    |result| Message::Export(ExportMessage::Restored(result)),
+                )
+            }
+            ExportMessage::CancelRestore => {
+                self.restore_plan = None;
+                Command::none()
+            }
+            ExportMessage::Restored(result) => {
+                self.restoring = false;
+                match result {
+                    Ok(report) => {
+                        let count = report.applied;
+                        self.restore_plan = None;
+                        self.restore_password.clear();
+                        self.message = Some(i18n::tr_args("export-restored", &[("count", count.into())]));
+                        Command::batch(vec![
+                            Command::perform(async {}, |_| Message::RefreshPasswords),
+                            Command::perform(async {}, move |_| Message::VaultWritten(count)),
+                        ])
+                    }
+                    // Restauration partielle : le plan n'est plus à jour, il faut relancer l'analyse
+                    Err(e) => {
+                        let applied = e.applied;
+                        self.error = Some(e.to_string());
+                        if applied == 0 {
+                            return Command::none();
+                        }
+                        self.restore_plan = None;
+                        Command::batch(vec![
+                            Command::perform(async {}, |_| Message::RefreshPasswords),
+                            Command::perform(async {}, move |_| Message::VaultWritten(applied)),
+                        ])
+                    }
+                }
+            }
+        }
+    }
+
+    // Description de l'export terminé pour le journal d'activité, sans le mot de passe de l'archive
+    pub fn describe(&self, count: usize) -> String {
+        i18n::tr_args(
+            "audit-detail-exported",
+            &[("count", count.into()), ("format", self.format.to_string().into()), ("path", self.path.trim().into())],
+        )
+    }
+
+    fn can_export(&self) -> bool {
+        let has_path = !self.path.trim().is_empty();
+        if self.format.is_plaintext() {
+            has_path && self.plaintext_acknowledged
+        } else {
+            has_path && !self.password.is_empty() && self.password == self.password_confirmation
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let header = Row::new()
+            .spacing(20)
+            .push(Button::new(Text::new(i18n::tr("detail-back"))).on_press(Message::BackToMain))
+            .push(Text::new(i18n::tr("export-title")).size(24));
+
+        let mut content = Column::new()
+            .spacing(20)
+            .padding(20)
+            .push(header)
+            .push(self.view_export())
+            .push(self.view_restore());
+
+        if let Some(message) = &self.message {
+            content = content.push(Text::new(message).size(16));
+        }
+        if let Some(error) = &self.error {
+            content = content.push(
+                Text::new(error)
+                    .size(16)
+                    .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.2, 0.2))),
+            );
+        }
+
+        iced::widget::Scrollable::new(content).into()
+    }
+
+    fn view_export(&self) -> Element<'_, Message> {
+        let placeholder = i18n::tr_args("export-path", &[("extension", self.format.extension().into())]);
+        let mut section = Column::new()
+            .spacing(10)
+            .push(Text::new(i18n::tr("export-section")).size(20))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        text_field(&placeholder, &self.path)
+                            .on_input(|path| Message::Export(ExportMessage::PathChanged(path)))
+                            .padding(10)
+                            .width(Length::Fill),
+                    )
+                    .push(PickList::new(&ExportFormat::ALL[..], Some(self.format), |format| {
+                        Message::Export(ExportMessage::FormatSelected(format))
+                    })),
+            );
+
+        if self.format.is_plaintext() {
+            section = section
+                .push(
+                    Text::new(i18n::tr("export-plaintext-warning"))
+                        .size(14)
+                        .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.5, 0.1))),
+                )
+                .push(Checkbox::new(
+                    i18n::tr("export-plaintext-acknowledge"),
+                    self.plaintext_acknowledged,
+                    |acknowledged| Message::Export(ExportMessage::PlaintextAcknowledged(acknowledged)),
+                ));
+        } else {
+            section = section
+                .push(Text::new(i18n::tr("export-password-hint")).size(14))
+                .push(
+                    text_field(&i18n::tr("export-password"), &self.password)
+                        .on_input(|password| Message::Export(ExportMessage::PasswordChanged(password)))
+                        .password()
+                        .padding(10)
+                        .width(Length::Fill),
+                )
+                .push(
+                    text_field(&i18n::tr("export-password-confirmation"), &self.password_confirmation)
+                        .on_input(|password| Message::Export(ExportMessage::PasswordConfirmationChanged(password)))
+                        .on_submit(Message::Export(ExportMessage::Export))
+                        .password()
+                        .padding(10)
+                        .width(Length::Fill),
+                );
+            if !self.password_confirmation.is_empty() && self.password != self.password_confirmation {
+                section = section.push(
+                    Text::new(i18n::tr("export-password-mismatch"))
+                        .size(14)
+                        .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.2, 0.2))),
+                );
+            }
+        }
+
+        section
+            .push(action_button(
+                &i18n::tr(if self.exporting { "export-exporting" } else { "export-submit" }),
+                (!self.exporting && self.can_export()).then_some(Message::Export(ExportMessage::Export)),
+            ))
+            .into()
+    }
+
+    fn view_restore(&self) -> Element<'_, Message> {
+        let mut section = Column::new()
+            .spacing(10)
+            .push(Text::new(i18n::tr("restore-section")).size(20))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        text_field(&i18n::tr("restore-path"), &self.restore_path)
+                            .on_input(|path| Message::Export(ExportMessage::RestorePathChanged(path)))
+                            .padding(10)
+                            .width(Length::Fill),
+                    )
+                    .push(PickList::new(&RestoreMode::ALL[..], Some(self.restore_mode), |mode| {
+                        Message::Export(ExportMessage::RestoreModeSelected(mode))
+                    })),
+            )
+            .push(
+                text_field(&i18n::tr("restore-password"), &self.restore_password)
+                    .on_input(|password| Message::Export(ExportMessage::RestorePasswordChanged(password)))
+                    .on_submit(Message::Export(ExportMessage::AnalyzeRestore))
+                    .password()
+                    .padding(10)
+                    .width(Length::Fill),
+            );
+
+        match &self.restore_plan {
+            None => {
+                section = section.push(action_button(
+                    &i18n::tr("restore-analyze"),
+                    (!self.restore_path.trim().is_empty()).then_some(Message::Export(ExportMessage::AnalyzeRestore)),
+                ));
+            }
+            Some(plan) => {
+                section = section.push(Text::new(format!("{} : {}", self.restore_mode, plan.summary())).size(16));
+                for entry in &plan.removed {
+                    section = section.push(
+                        Text::new(i18n::tr_args(
+                            "restore-removed-entry",
+                            &[("service", entry.service_name.as_str().into()), ("id", entry.id.as_str().into())],
+                        ))
+                            .size(13)
+                            .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.2, 0.2))),
+                    );
+                }
+                section = section.push(
+                    Row::new()
+                        .spacing(10)
+                        .push(action_button(
+                            &i18n::tr(if self.restoring { "restore-restoring" } else { "restore-submit" }),
+                            (!self.restoring && !plan.is_empty()).then_some(Message::Export(ExportMessage::ApplyRestore)),
+                        ))
+                        .push(action_button(&i18n::tr("restore-cancel"), Some(Message::Export(ExportMessage::CancelRestore)))),
+                );
+            }
+        }
+
+        section.into()
+    }
+}
@@ -0,0 +1,329 @@
+use iced::{
+    Command, Element, Length,
+    widget::{Button, Checkbox, Column, PickList, Row, Scrollable, Text},
+};
+use std::path::PathBuf;
+
+use crate::api::client::ApiClient;
+use crate::api::error::ApiError;
+use crate::import::{self, Credentials, Duplicate, ImportFormat, ImportPreview};
+use crate::i18n;
+use crate::ui::app::Message;
+use crate::ui::widgets::{action_button, text_field};
+use crate::vault::entry::Entry;
+
+#[derive(Debug, Clone)]
+pub enum ImportMessage {
+    PathChanged(String),
+    FormatSelected(ImportFormat),
+    PasswordChanged(String),
+    KeyFileChanged(String),
+    Analyze,
+    Analyzed(Result<ImportPreview, String>),
+    ToggleCandidate(usize, bool),
+    SelectAll(bool),
+    Confirm,
+    Imported(Result<usize, ApiError>),
+    Reset,
+}
+
+// Assistant d'import : fichier et format, aperçu avec doublons, puis validation
+#[derive(Default)]
+pub struct ImportWizard {
+    path: String,
+    format: Option<ImportFormat>,
+    // Déverrouillage d'une base KeePass
+    password: String,
+    key_file: String,
+    preview: Option<ImportPreview>,
+    importing: bool,
+    error: Option<String>,
+}
+
+impl ImportWizard {
+    pub fn update(&mut self, message: ImportMessage, existing: &[Entry], client: &ApiClient) -> Command<Message> {
+        match message {
+            ImportMessage::PathChanged(path) => {
+                self.path = path;
+                Command::none()
+            }
+            ImportMessage::FormatSelected(format) => {
+                self.format = Some(format);
+                Command::none()
+            }
+            ImportMessage::PasswordChanged(password) => {
+                self.password = password;
+                Command::none()
+            }
+            ImportMessage::KeyFileChanged(key_file) => {
+                self.key_file = key_file;
+                Command::none()
+            }
+            ImportMessage::Analyze => {
+                let path = PathBuf::from(self.path.trim());
+                let format = self.format;
+                let password = self.password.clone();
+                let key_file = (!self.key_file.trim().is_empty()).then(|| PathBuf::from(self.key_file.trim()));
+                let existing = existing.to_vec();
+                self.error = None;
+
+                Command::perform(
+                    async move {
+                        let data = tokio::fs::read(&path)
+                            .await
+                            .map_err(|e| {
+                                i18n::tr_args(
+                                    "file-read-failed",
+                                    &[("path", path.display().to_string().into()), ("error", e.to_string().into())],
+                                )
+                            })?;
+                        let format = format
+                            .or_else(|| ImportFormat::detect(&path, &data))
+                            .ok_or_else(|| i18n::tr("import-unknown-format"))?;
+                        let mut credentials = Credentials {
+                            password,
+                            key_file: None,
+                        };
+                        if let Some(key_file) = key_file {
+                            credentials.key_file = Some(tokio::fs::read(&key_file).await.map_err(|e| {
+                                i18n::tr_args(
+                                    "file-read-failed",
+                                    &[("path", key_file.display().to_string().into()), ("error", e.to_string().into())],
+                                )
+                            })?);
+                        }
+                        let parsed = import::parse(format, &data, &credentials)?;
+                        Ok(import::preview(format, parsed, &existing))
+                    },
+                    |result| Message::Import(ImportMessage::Analyzed(result)),
+                )
+            }
+            ImportMessage::Analyzed(result) => {
+                match result {
+                    Ok(preview) => {
+                        self.format = Some(preview.format);
+                        self.preview = Some(preview);
+                        self.password.clear();
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                Command::none()
+            }
+            ImportMessage::ToggleCandidate(index, selected) => {
+                if let Some(candidate) = self.preview.as_mut().and_then(|preview| preview.candidates.get_mut(index)) {
+                    candidate.selected = selected;
+                }
+                Command::none()
+            }
+            ImportMessage::SelectAll(selected) => {
+                if let Some(preview) = &mut self.preview {
+                    for candidate in &mut preview.candidates {
+                        // Les doublons identiques n'apportent rien
+                        candidate.selected = selected
+                            && !matches!(candidate.duplicate, Some((Duplicate::Identical, _)));
+                    }
+                }
+                Command::none()
+            }
+            ImportMessage::Confirm => {
+                let entries = match &self.preview {
+                    Some(preview) if preview.selected_count() > 0 => preview.selected_entries(),
+                    _ => return Command::none(),
+                };
+                self.importing = true;
+                let client = client.clone();
+
+                Command::perform(
+                    async move { client.import_entries(&entries).await },
+                    |result| Message::Import(ImportMessage::Imported(result)),
+                )
+            }
+            ImportMessage::Imported(result) => {
+                self.importing = false;
+                match result {
+                    Ok(count) => {
+                        *self = ImportWizard::default();
+                        Command::batch(vec![
+                            Command::perform(async {}, move |_| {
+                                Message::StatusUpdate(i18n::tr_args("import-done", &[("count", count.into())]))
+                            }),
+                            Command::perform(async {}, |_| Message::RefreshPasswords),
+                            Command::perform(async {}, move |_| Message::VaultWritten(count)),
+                        ])
+                    }
+                    Err(e) => {
+                        self.error = Some(e.to_string());
+                        Command::none()
+                    }
+                }
+            }
+            ImportMessage::Reset => {
+                *self = ImportWizard::default();
+                Command::none()
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let header = Row::new()
+            .spacing(20)
+            .push(Button::new(Text::new(i18n::tr("detail-back"))).on_press(Message::BackToMain))
+            .push(Text::new(i18n::tr("import-title")).size(24));
+
+        let mut content = Column::new().spacing(20).padding(20).push(header);
+
+        match &self.preview {
+            None => content = content.push(self.view_source()),
+            Some(preview) => content = content.push(self.view_preview(preview)),
+        }
+
+        if let Some(error) = &self.error {
+            content = content.push(
+                Text::new(error)
+                    .size(16)
+                    .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.2, 0.2))),
+            );
+        }
+
+        content.into()
+    }
+
+    // Étape 1 : fichier et format
+    fn view_source(&self) -> Element<'_, Message> {
+        let analyze = action_button(
+            &i18n::tr("import-analyze"),
+            (!self.path.trim().is_empty()).then_some(Message::Import(ImportMessage::Analyze)),
+        );
+
+        let mut source = Column::new()
+            .spacing(20)
+            .push(
+                Column::new()
+                    .push(Text::new(i18n::tr("import-file")).size(16))
+                    .push(
+                        text_field(&i18n::tr("import-file-placeholder"), &self.path)
+                            .on_input(|path| Message::Import(ImportMessage::PathChanged(path)))
+                            .on_submit(Message::Import(ImportMessage::Analyze))
+                            .padding(10)
+                            .width(Length::Fill),
+                    ),
+            )
+            .push(
+                Column::new()
+                    .push(Text::new(i18n::tr("import-format")).size(16))
+                    .push(PickList::new(&ImportFormat::ALL[..], self.format, |format| {
+                        Message::Import(ImportMessage::FormatSelected(format))
+                    })),
+            );
+
+        // Une base KeePass se reconnaît à son extension avant même d'être lue
+        let is_kdbx = match self.format {
+            Some(format) => format.needs_password(),
+            None => self.path.trim().to_ascii_lowercase().ends_with(".kdbx"),
+        };
+        if is_kdbx {
+            source = source
+                .push(
+                    Column::new()
+                        .push(Text::new(i18n::tr("import-database-password")).size(16))
+                        .push(
+                            text_field(&i18n::tr("field-password"), &self.password)
+                                .on_input(|password| Message::Import(ImportMessage::PasswordChanged(password)))
+                                .on_submit(Message::Import(ImportMessage::Analyze))
+                                .password()
+                                .padding(10)
+                                .width(Length::Fill),
+                        ),
+                )
+                .push(
+                    Column::new()
+                        .push(Text::new(i18n::tr("import-key-file")).size(16))
+                        .push(
+                            text_field(&i18n::tr("import-key-file-placeholder"), &self.key_file)
+                                .on_input(|key_file| Message::Import(ImportMessage::KeyFileChanged(key_file)))
+                                .padding(10)
+                                .width(Length::Fill),
+                        ),
+                );
+        }
+
+        source.push(analyze).into()
+    }
+
+    // Étape 2 : correspondance des champs, doublons et sélection
+    fn view_preview<'a>(&'a self, preview: &'a ImportPreview) -> Element<'a, Message> {
+        let summary = Text::new(i18n::tr_args(
+            "import-summary",
+            &[
+                ("format", preview.format.to_string().into()),
+                ("read", preview.candidates.len().into()),
+                ("duplicates", preview.duplicate_count().into()),
+                ("selected", preview.selected_count().into()),
+            ],
+        ))
+        .size(16);
+
+        let mut rows = Column::new().spacing(8);
+        for (index, candidate) in preview.candidates.iter().enumerate() {
+            let entry = &candidate.entry;
+            let mut details = vec![format!("URL: {}", entry.service_url)];
+            if !entry.username.is_empty() {
+                details.push(i18n::tr_args("import-detail-username", &[("username", entry.username.as_str().into())]));
+            }
+            if !entry.folder.is_empty() {
+                details.push(i18n::tr_args("import-detail-folder", &[("folder", entry.folder.as_str().into())]));
+            }
+            if !entry.tags.is_empty() {
+                details.push(i18n::tr_args("import-detail-tags", &[("tags", entry.tags_text().into())]));
+            }
+            if !entry.custom_fields.is_empty() {
+                details.push(i18n::tr_args("import-detail-custom-fields", &[("count", entry.custom_fields.len().into())]));
+            }
+
+            let mut label = Column::new()
+                .push(Text::new(entry.service_name.clone()).size(16))
+                .push(Text::new(details.join(" · ")).size(13));
+            if let Some((duplicate, existing)) = &candidate.duplicate {
+                label = label.push(
+                    Text::new(format!("⚠ {} ({})", duplicate, existing))
+                        .size(13)
+                        .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.5, 0.1))),
+                );
+            }
+
+            rows = rows.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Checkbox::new("", candidate.selected, move |selected| {
+                        Message::Import(ImportMessage::ToggleCandidate(index, selected))
+                    }))
+                    .push(label),
+            );
+        }
+
+        let mut warnings = Column::new().spacing(4);
+        for warning in &preview.warnings {
+            warnings = warnings.push(Text::new(format!("• {}", warning)).size(13));
+        }
+
+        let confirm = action_button(
+            &i18n::tr(if self.importing { "import-importing" } else { "import-submit" }),
+            (!self.importing && preview.selected_count() > 0).then_some(Message::Import(ImportMessage::Confirm)),
+        );
+
+        let actions = Row::new()
+            .spacing(10)
+            .push(Button::new(Text::new(i18n::tr("import-select-all"))).on_press(Message::Import(ImportMessage::SelectAll(true))))
+            .push(Button::new(Text::new(i18n::tr("import-select-none"))).on_press(Message::Import(ImportMessage::SelectAll(false))))
+            .push(Button::new(Text::new(i18n::tr("import-change-file"))).on_press(Message::Import(ImportMessage::Reset)))
+            .push(confirm);
+
+        Column::new()
+            .spacing(15)
+            .push(summary)
+            .push(actions)
+            .push(warnings)
+            .push(Scrollable::new(rows).height(Length::Fill))
+            .into()
+    }
+}
@@ -0,0 +1,225 @@
+use iced::{
+    Command, Element, Length,
+    widget::{Button, Column, Row, Scrollable, Text},
+};
+use std::collections::HashMap;
+
+use crate::api::client::ApiClient;
+use crate::api::sync::{Conflict, Resolution, SyncHandle};
+use crate::i18n;
+use crate::ui::app::Message;
+use crate::ui::style::ButtonType;
+use crate::ui::widgets::action_button;
+use crate::vault::entry::{self, Entry, EntryField};
+
+// Version retenue pour un champ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Local,
+    Remote,
+}
+
+#[derive(Debug, Clone)]
+pub enum MergeMessage {
+    Select(usize),
+    Choose(EntryField, Side),
+    KeepLocal,
+    KeepRemote,
+    ApplyMerge,
+}
+
+// Résolution des conflits de synchronisation, champ par champ
+#[derive(Default)]
+pub struct MergeView {
+    conflicts: Vec<Conflict>,
+    selected: usize,
+    // Champs pour lesquels la version du serveur est retenue (la version locale sinon)
+    choices: HashMap<EntryField, Side>,
+    working: bool,
+}
+
+impl MergeView {
+    pub fn conflict_count(&self) -> usize {
+        self.conflicts.len()
+    }
+
+    // Conflits restants après une synchronisation
+    pub fn set_conflicts(&mut self, conflicts: Vec<Conflict>) {
+        let current = self.conflicts.get(self.selected).map(|conflict| conflict.id.clone());
+        self.working = false;
+        self.conflicts = conflicts;
+        match current.and_then(|id| self.conflicts.iter().position(|conflict| conflict.id == id)) {
+            Some(index) => self.selected = index,
+            None => {
+                self.selected = 0;
+                self.choices.clear();
+            }
+        }
+    }
+
+    pub fn update(&mut self, message: MergeMessage, sync: &Option<SyncHandle>, client: &ApiClient) -> Command<Message> {
+        match message {
+            MergeMessage::Select(index) => {
+                if index != self.selected {
+                    self.selected = index;
+                    self.choices.clear();
+                }
+                Command::none()
+            }
+            MergeMessage::Choose(field, side) => {
+                self.choices.insert(field, side);
+                Command::none()
+            }
+            MergeMessage::KeepLocal => self.resolve(Resolution::KeepLocal, sync, client),
+            MergeMessage::KeepRemote => self.resolve(Resolution::KeepRemote, sync, client),
+            MergeMessage::ApplyMerge => match self.merged() {
+                Some(merged) => self.resolve(Resolution::Merged(Box::new(merged)), sync, client),
+                None => Command::none(),
+            },
+        }
+    }
+
+    // Enregistre le choix puis resynchronise pour envoyer la version retenue
+    fn resolve(&mut self, resolution: Resolution, sync: &Option<SyncHandle>, client: &ApiClient) -> Command<Message> {
+        let (Some(sync), Some(conflict)) = (sync.clone(), self.conflicts.get(self.selected)) else {
+            return Command::none();
+        };
+        if self.working {
+            return Command::none();
+        }
+        self.working = true;
+        self.choices.clear();
+
+        let id = conflict.id.clone();
+        let client = client.clone();
+        Command::perform(
+            async move {
+                sync.resolve(&id, resolution).await?;
+                sync.sync(&client).await
+            },
+            Message::Synced,
+        )
+    }
+
+    // Version locale complétée des champs du serveur choisis
+    fn merged(&self) -> Option<Entry> {
+        let conflict = self.conflicts.get(self.selected)?;
+        let (local, remote) = (conflict.local.as_ref()?, conflict.remote.as_ref()?);
+        let mut merged = local.clone();
+        for (field, side) in &self.choices {
+            if *side == Side::Remote {
+                field.copy(remote, &mut merged);
+            }
+        }
+        merged.touch_modified();
+        Some(merged)
+    }
+
+    fn choice(&self, field: EntryField) -> Side {
+        self.choices.get(&field).copied().unwrap_or(Side::Local)
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let header = Row::new()
+            .spacing(20)
+            .push(Button::new(Text::new(i18n::tr("detail-back"))).on_press(Message::BackToMain))
+            .push(Text::new(i18n::tr("merge-title")).size(24));
+
+        let mut content = Column::new().spacing(20).padding(20).push(header);
+
+        if self.conflicts.is_empty() {
+            return content.push(Text::new(i18n::tr("merge-empty")).size(16)).into();
+        }
+
+        let mut list = Column::new().spacing(8);
+        for (index, conflict) in self.conflicts.iter().enumerate() {
+            list = list.push(
+                Button::new(Text::new(conflict.name().to_string()))
+                    .on_press(Message::Merge(MergeMessage::Select(index)))
+                    .width(Length::Fill)
+                    .style(if index == self.selected {
+                        ButtonType::Primary.into()
+                    } else {
+                        ButtonType::Secondary.into()
+                    }),
+            );
+        }
+
+        let mut body = Row::new()
+            .spacing(20)
+            .push(Scrollable::new(list).width(Length::FillPortion(1)).height(Length::Fill));
+        if let Some(conflict) = self.conflicts.get(self.selected) {
+            body = body.push(self.view_conflict(conflict));
+        }
+
+        content = content.push(
+            Text::new(i18n::tr("merge-hint")).size(14),
+        );
+        content.push(body).into()
+    }
+
+    fn view_conflict<'a>(&'a self, conflict: &'a Conflict) -> Element<'a, Message> {
+        let mut details = Column::new()
+            .spacing(10)
+            .width(Length::FillPortion(3))
+            .push(Text::new(format!("{} ({})", conflict.name(), conflict.id)).size(18));
+
+        let keep_buttons = |details: Column<'a, Message>| {
+            details.push(
+                Row::new()
+                    .spacing(10)
+                    .push(action_button(&i18n::tr("merge-keep-local"), (!self.working).then_some(Message::Merge(MergeMessage::KeepLocal))))
+                    .push(action_button(
+                        &i18n::tr("merge-keep-remote"),
+                        (!self.working).then_some(Message::Merge(MergeMessage::KeepRemote)),
+                    )),
+            )
+        };
+
+        let (local, remote) = match (&conflict.local, &conflict.remote) {
+            (Some(local), Some(remote)) => (local, remote),
+            (Some(_), None) => {
+                details = details.push(Text::new(i18n::tr("merge-deleted-remotely")).size(14));
+                return keep_buttons(details).into();
+            }
+            _ => {
+                details = details.push(Text::new(i18n::tr("merge-deleted-locally")).size(14));
+                return keep_buttons(details).into();
+            }
+        };
+
+        details = details.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new("").width(Length::FillPortion(1)))
+                .push(Text::new(i18n::tr("merge-local")).size(14).width(Length::FillPortion(2)))
+                .push(Text::new(i18n::tr("merge-remote")).size(14).width(Length::FillPortion(2))),
+        );
+        for field in entry::changed_fields(local, remote) {
+            let chosen = self.choice(field);
+            let option = |entry: &'a Entry, side: Side| {
+                Button::new(Text::new(field.display(entry)).size(14))
+                    .on_press(Message::Merge(MergeMessage::Choose(field, side)))
+                    .width(Length::FillPortion(2))
+                    .style(if chosen == side {
+                        ButtonType::Primary.into()
+                    } else {
+                        ButtonType::Secondary.into()
+                    })
+            };
+            details = details.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(field.label()).size(14).width(Length::FillPortion(1)))
+                    .push(option(local, Side::Local))
+                    .push(option(remote, Side::Remote)),
+            );
+        }
+
+        details = details.push(action_button(
+            &i18n::tr(if self.working { "merge-syncing" } else { "merge-apply" }),
+            (!self.working).then_some(Message::Merge(MergeMessage::ApplyMerge)),
+        ));
+        keep_buttons(details).into()
+    }
+}
@@ -1,2 +1,16 @@
+pub mod activity_view;
 pub mod app;
-pub mod style;
\ No newline at end of file
+pub mod backup_view;
+pub mod countdown;
+pub mod entry_editor;
+pub mod export_view;
+pub mod import_wizard;
+pub mod merge_view;
+pub mod profile_view;
+pub mod recovery_view;
+pub mod search;
+pub mod security_view;
+pub mod settings_view;
+pub mod style;
+pub mod team_view;
+pub mod widgets;
\ No newline at end of file
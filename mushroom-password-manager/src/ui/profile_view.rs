@@ -0,0 +1,205 @@
+use iced::{
+    Command, Element, Length,
+    widget::{Button, Column, PickList, Row, Scrollable, Text},
+};
+use std::path::PathBuf;
+
+use crate::config::{BackendKind, BackendSettings};
+use crate::i18n;
+use crate::lockout::WipePolicy;
+use crate::profile::{Backend, ProfileStore};
+use crate::ui::app::{Message, View};
+use crate::ui::widgets::{action_button, danger_button, text_field};
+use crate::vault::factors::KeyFile;
+
+#[derive(Debug, Clone)]
+pub enum ProfileMessage {
+    NameChanged(String),
+    KindSelected(BackendKind),
+    LocationChanged(String),
+    EmailChanged(String),
+    KeyFileChanged(String),
+    Add,
+    Remove(String),
+    WipePolicySelected(String, WipePolicy),
+}
+
+// Création et suppression des profils, depuis l'écran de connexion
+#[derive(Default)]
+pub struct ProfileView {
+    name: String,
+    // Type choisi dans le formulaire ; celui des paramètres sinon
+    kind: Option<BackendKind>,
+    // URL du serveur ou chemin du fichier du coffre
+    location: String,
+    email: String,
+    // Fichier clé du futur coffre, créé s'il n'existe pas encore
+    key_file: String,
+    message: Option<String>,
+    error: Option<String>,
+}
+
+impl ProfileView {
+    pub fn update(&mut self, message: ProfileMessage, profiles: &mut ProfileStore, defaults: &BackendSettings) -> Command<Message> {
+        match message {
+            ProfileMessage::NameChanged(name) => self.name = name,
+            ProfileMessage::KindSelected(kind) => self.kind = Some(kind),
+            ProfileMessage::LocationChanged(location) => self.location = location,
+            ProfileMessage::EmailChanged(email) => self.email = email,
+            ProfileMessage::KeyFileChanged(key_file) => self.key_file = key_file,
+            ProfileMessage::Add => {
+                let kind = self.kind.unwrap_or(defaults.kind);
+                // Sans URL saisie, le serveur des paramètres
+                let location = match self.location.trim() {
+                    "" if kind == BackendKind::Api => defaults.server_url.trim(),
+                    location => location,
+                };
+                let backend = match kind {
+                    BackendKind::Api => Backend::Api { url: location.to_string() },
+                    BackendKind::LocalFile => Backend::LocalFile { path: PathBuf::from(location) },
+                };
+                let key_file = Some(self.key_file.trim()).filter(|path| !path.is_empty()).map(PathBuf::from);
+                let result = if location.is_empty() {
+                    Err(i18n::tr("profiles-location-required"))
+                } else {
+                    profiles.add(&self.name, backend, &self.email, key_file.clone()).and_then(|profile| {
+                        if let Some(path) = key_file.filter(|path| !path.exists()) {
+                            KeyFile::generate(&path)?;
+                        }
+                        profiles.save()?;
+                        Ok(profile)
+                    })
+                };
+                match result {
+                    Ok(profile) => {
+                        *self = ProfileView::default();
+                        self.message = Some(i18n::tr_args("profiles-created", &[("profile", profile.name.as_str().into())]));
+                    }
+                    Err(e) => {
+                        self.message = None;
+                        self.error = Some(e);
+                    }
+                }
+            }
+            ProfileMessage::WipePolicySelected(id, policy) => {
+                let Some(profile) = profiles.profiles.iter_mut().find(|profile| profile.id == id) else {
+                    return Command::none();
+                };
+                profile.wipe_cache = policy;
+                let name = profile.name.clone();
+                match profiles.save() {
+                    Ok(()) => {
+                        self.error = None;
+                        self.message = Some(i18n::tr_args(
+                            "profiles-wipe-policy",
+                            &[("profile", name.into()), ("policy", policy.to_string().to_lowercase().into())],
+                        ));
+                    }
+                    Err(e) => {
+                        self.message = None;
+                        self.error = Some(e);
+                    }
+                }
+            }
+            ProfileMessage::Remove(id) => match profiles.remove(&id).and_then(|_| profiles.save()) {
+                Ok(()) => {
+                    self.error = None;
+                    self.message = Some(i18n::tr("profiles-removed"));
+                }
+                Err(e) => {
+                    self.message = None;
+                    self.error = Some(e);
+                }
+            },
+        }
+        Command::none()
+    }
+
+    pub fn view<'a>(&'a self, profiles: &'a ProfileStore, defaults: &BackendSettings) -> Element<'a, Message> {
+        let header = Row::new()
+            .spacing(20)
+            .push(Button::new(Text::new(i18n::tr("detail-back"))).on_press(Message::NavigateTo(View::Login)))
+            .push(Text::new(i18n::tr("profiles-title")).size(24));
+
+        let mut list = Column::new().spacing(8);
+        for profile in &profiles.profiles {
+            list = list.push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        Column::new()
+                            .width(Length::Fill)
+                            .push(Text::new(profile.name.clone()).size(16))
+                            .push(Text::new(profile.backend.describe()).size(13)),
+                    )
+                    .push(PickList::new(&WipePolicy::ALL[..], Some(profile.wipe_cache), |policy| {
+                        Message::Profiles(ProfileMessage::WipePolicySelected(profile.id.clone(), policy))
+                    }))
+                    .push(danger_button(
+                        &i18n::tr("profiles-remove"),
+                        (profiles.profiles.len() > 1).then(|| Message::Profiles(ProfileMessage::Remove(profile.id.clone()))),
+                    )),
+            );
+        }
+
+        let kind = self.kind.unwrap_or(defaults.kind);
+        let placeholder = match kind {
+            BackendKind::Api => i18n::tr_args("profiles-server-url", &[("url", defaults.server_url.as_str().into())]),
+            BackendKind::LocalFile => i18n::tr("profiles-vault-file"),
+        };
+        let form = Column::new()
+            .spacing(10)
+            .push(Text::new(i18n::tr("profiles-new")).size(20))
+            .push(
+                text_field(&i18n::tr("profiles-name"), &self.name)
+                    .on_input(|name| Message::Profiles(ProfileMessage::NameChanged(name)))
+                    .padding(10),
+            )
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(PickList::new(&BackendKind::ALL[..], Some(kind), |kind| {
+                        Message::Profiles(ProfileMessage::KindSelected(kind))
+                    }))
+                    .push(
+                        text_field(&placeholder, &self.location)
+                            .on_input(|location| Message::Profiles(ProfileMessage::LocationChanged(location)))
+                            .padding(10)
+                            .width(Length::Fill),
+                    ),
+            )
+            .push(
+                text_field(&i18n::tr("profiles-email"), &self.email)
+                    .on_input(|email| Message::Profiles(ProfileMessage::EmailChanged(email)))
+                    .on_submit(Message::Profiles(ProfileMessage::Add))
+                    .padding(10),
+            )
+            .push(
+                text_field(&i18n::tr("profiles-key-file"), &self.key_file)
+                    .on_input(|key_file| Message::Profiles(ProfileMessage::KeyFileChanged(key_file)))
+                    .padding(10),
+            )
+            .push(Text::new(i18n::tr("profiles-hint")).size(14))
+            .push(action_button(&i18n::tr("profiles-create"), Some(Message::Profiles(ProfileMessage::Add))));
+
+        let mut content = Column::new()
+            .spacing(20)
+            .padding(20)
+            .push(header)
+            .push(list)
+            .push(form);
+
+        if let Some(message) = &self.message {
+            content = content.push(Text::new(message).size(16));
+        }
+        if let Some(error) = &self.error {
+            content = content.push(
+                Text::new(error)
+                    .size(16)
+                    .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.2, 0.2))),
+            );
+        }
+
+        Scrollable::new(content).into()
+    }
+}
@@ -0,0 +1,372 @@
+use iced::{
+    Command, Element, Length,
+    widget::{Button, Canvas, Column, PickList, Row, Scrollable, Text},
+};
+use std::path::PathBuf;
+
+use crate::api::client::ApiClient;
+use crate::api::sync::SyncHandle;
+use crate::export;
+use crate::i18n;
+use crate::profile::{self, OpenError, Profile};
+use crate::ui::app::{Message, View};
+use crate::ui::widgets::{action_button, text_field, QrCanvas};
+use crate::vault::factors::UnlockFactors;
+use crate::vault::recovery::{self, RecoveryKit, RecoveryShare};
+use crate::vault::secure::SecretString;
+
+// Nombre de parts proposé à la création d'un kit
+const SHARE_COUNTS: [u8; 9] = [2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+#[derive(Debug, Clone)]
+pub enum RecoveryMessage {
+    ThresholdSelected(u8),
+    CountSelected(u8),
+    Generate,
+    Generated(Result<RecoveryKit, String>),
+    PathChanged(String),
+    Save,
+    ShareChanged(usize, String),
+    AddShare,
+    RemoveShare(usize),
+    PasswordChanged(String),
+    ConfirmChanged(String),
+    KeyFileChanged(String),
+    Recover,
+    Reset(Result<(), OpenError>),
+}
+
+// Création d'un kit de récupération (coffre ouvert) et remplacement du mot de passe maître
+// d'un coffre rouvert par ses parts (connexion)
+pub struct RecoveryView {
+    threshold: u8,
+    count: u8,
+    kit: Option<RecoveryKit>,
+    codes: Vec<QrCanvas>,
+    path: String,
+    // Parts saisies à la connexion, puis nouveau mot de passe maître et fichier clé éventuel
+    shares: Vec<String>,
+    password: SecretString,
+    confirm: SecretString,
+    key_file: String,
+    busy: bool,
+    message: Option<String>,
+    error: Option<String>,
+}
+
+impl Default for RecoveryView {
+    fn default() -> Self {
+        RecoveryView {
+            threshold: 3,
+            count: 5,
+            kit: None,
+            codes: Vec::new(),
+            path: String::new(),
+            shares: vec![String::new(); 2],
+            password: SecretString::default(),
+            confirm: SecretString::default(),
+            key_file: String::new(),
+            busy: false,
+            message: None,
+            error: None,
+        }
+    }
+}
+
+impl RecoveryView {
+    pub fn update(
+        &mut self,
+        message: RecoveryMessage,
+        client: &ApiClient,
+        sync: &Option<SyncHandle>,
+        profile: &Profile,
+    ) -> Command<Message> {
+        match message {
+            RecoveryMessage::ThresholdSelected(threshold) => self.threshold = threshold,
+            RecoveryMessage::CountSelected(count) => {
+                self.count = count;
+                self.threshold = self.threshold.min(count);
+            }
+            RecoveryMessage::Generate => {
+                let Some(cipher) = client.cipher() else {
+                    return Command::none();
+                };
+                if self.busy {
+                    return Command::none();
+                }
+                self.busy = true;
+                self.error = None;
+                self.message = Some(i18n::tr("recovery-creating"));
+                let (client, sync) = (client.clone(), sync.clone());
+                let (name, threshold, count) = (profile.name.clone(), self.threshold, self.count);
+                return Command::perform(
+                    async move {
+                        let previous = match &sync {
+                            Some(sync) => sync.vault_params().await,
+                            None => client.get_vault_params().await.map_err(|e| e.to_string())?,
+                        };
+                        let previous = previous.ok_or_else(|| i18n::tr("recovery-no-header"))?;
+                        // La clé n'existe qu'en parts une fois le kit créé ; l'en-tête garde
+                        // la clé des entrées chiffrée par elle
+                        let (key, factor) = cipher.recovery_factor()?;
+                        let kit = recovery::split(&key, &name, threshold, count)?;
+                        let factors = UnlockFactors { recovery: Some(factor), ..previous.factors.clone() };
+                        let params = client.update_vault_factors(&previous, &factors).await.map_err(|e| e.to_string())?;
+                        if let Some(sync) = sync {
+                            sync.set_vault_params(params).await?;
+                        }
+                        Ok(kit)
+                    },
+                    |result| Message::Recovery(RecoveryMessage::Generated(result)),
+                );
+            }
+            RecoveryMessage::Generated(result) => {
+                self.busy = false;
+                let result = result.and_then(|kit| {
+                    let codes = kit
+                        .shares
+                        .iter()
+                        .map(|share| Ok(QrCanvas::new(&share.qr_code()?)))
+                        .collect::<Result<Vec<_>, String>>()?;
+                    Ok((kit, codes))
+                });
+                match result {
+                    Ok((kit, codes)) => {
+                        self.kit = Some(kit);
+                        self.codes = codes;
+                        self.error = None;
+                        self.message = Some(i18n::tr("recovery-created"));
+                    }
+                    Err(e) => self.fail(e),
+                }
+            }
+            RecoveryMessage::PathChanged(path) => self.path = path,
+            RecoveryMessage::Save => {
+                let Some(kit) = &self.kit else {
+                    return Command::none();
+                };
+                let path = PathBuf::from(self.path.trim());
+                match kit.printable().and_then(|text| export::write_private(&path, text.as_bytes())) {
+                    Ok(()) => {
+                        self.error = None;
+                        self.message = Some(i18n::tr_args("recovery-saved", &[("path", path.display().to_string().into())]));
+                    }
+                    Err(e) => self.fail(e),
+                }
+            }
+            RecoveryMessage::ShareChanged(index, text) => {
+                if let Some(share) = self.shares.get_mut(index) {
+                    *share = text;
+                }
+            }
+            RecoveryMessage::AddShare => self.shares.push(String::new()),
+            RecoveryMessage::RemoveShare(index) => {
+                if self.shares.len() > 1 {
+                    self.shares.remove(index);
+                }
+            }
+            RecoveryMessage::PasswordChanged(password) => self.password = password.into(),
+            RecoveryMessage::ConfirmChanged(confirm) => self.confirm = confirm.into(),
+            RecoveryMessage::KeyFileChanged(path) => self.key_file = path,
+            RecoveryMessage::Recover => {
+                if self.busy {
+                    return Command::none();
+                }
+                if self.password.is_empty() {
+                    self.fail(i18n::tr("settings-new-password-required"));
+                    return Command::none();
+                }
+                if self.password != self.confirm {
+                    self.fail(i18n::tr("settings-confirmation-mismatch"));
+                    return Command::none();
+                }
+                let result = self
+                    .shares
+                    .iter()
+                    .filter(|text| !text.trim().is_empty())
+                    .map(|text| text.parse::<RecoveryShare>())
+                    .collect::<Result<Vec<_>, String>>()
+                    .and_then(|shares| recovery::recover(&shares));
+                match result {
+                    Ok(key) => {
+                        self.busy = true;
+                        self.error = None;
+                        self.message = Some(i18n::tr("recovery-recovering"));
+                        let key_file = Some(self.key_file.trim()).filter(|path| !path.is_empty()).map(PathBuf::from);
+                        return Command::perform(
+                            profile::reset_vault(profile.clone(), key, self.password.clone(), key_file),
+                            |result| Message::Recovery(RecoveryMessage::Reset(result)),
+                        );
+                    }
+                    Err(e) => self.fail(e),
+                }
+            }
+            RecoveryMessage::Reset(result) => {
+                self.busy = false;
+                match result {
+                    // Le nouveau mot de passe ouvre aussitôt le coffre, second facteur compris
+                    Ok(()) => {
+                        let password = std::mem::take(&mut self.password);
+                        let key_file = std::mem::take(&mut self.key_file);
+                        *self = RecoveryView::default();
+                        return Command::perform(async move { (password, key_file) }, |(password, key_file)| {
+                            Message::PasswordRecovered(password, key_file)
+                        });
+                    }
+                    Err(e) => self.fail(e.to_string()),
+                }
+            }
+        }
+        Command::none()
+    }
+
+    // Remplacement du mot de passe maître en cours pour le profil courant
+    pub fn is_busy(&self) -> bool {
+        self.busy
+    }
+
+    fn fail(&mut self, error: String) {
+        self.message = None;
+        self.error = Some(error);
+    }
+
+    // Création du kit, coffre ouvert
+    pub fn view_kit(&self) -> Element<'_, Message> {
+        let header = Row::new()
+            .spacing(20)
+            .push(Button::new(Text::new(i18n::tr("detail-back"))).on_press(Message::BackToMain))
+            .push(Text::new(i18n::tr("recovery-kit-title")).size(24));
+
+        let thresholds: Vec<u8> = (2..=self.count).collect();
+        let settings = Column::new()
+            .spacing(10)
+            .push(Text::new(i18n::tr("recovery-kit-hint")).size(14))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(i18n::tr("recovery-shares")).size(16))
+                    .push(PickList::new(&SHARE_COUNTS[..], Some(self.count), |count| {
+                        Message::Recovery(RecoveryMessage::CountSelected(count))
+                    }))
+                    .push(Text::new(i18n::tr("recovery-threshold")).size(16))
+                    .push(PickList::new(thresholds, Some(self.threshold), |threshold| {
+                        Message::Recovery(RecoveryMessage::ThresholdSelected(threshold))
+                    }))
+                    .push(action_button(
+                        &i18n::tr("recovery-create"),
+                        (!self.busy).then_some(Message::Recovery(RecoveryMessage::Generate)),
+                    )),
+            );
+
+        let mut content = Column::new().spacing(20).padding(20).push(header).push(settings);
+
+        if let Some(kit) = &self.kit {
+            content = content.push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        text_field(&i18n::tr("recovery-path"), &self.path)
+                            .on_input(|path| Message::Recovery(RecoveryMessage::PathChanged(path)))
+                            .on_submit(Message::Recovery(RecoveryMessage::Save))
+                            .padding(10)
+                            .width(Length::Fill),
+                    )
+                    .push(action_button(
+                        &i18n::tr("recovery-save"),
+                        (!self.path.trim().is_empty()).then_some(Message::Recovery(RecoveryMessage::Save)),
+                    )),
+            );
+            for (share, code) in kit.shares.iter().zip(&self.codes) {
+                content = content.push(
+                    Row::new()
+                        .spacing(20)
+                        .push(Canvas::new(code).width(Length::Fixed(180.0)).height(Length::Fixed(180.0)))
+                        .push(
+                            Column::new()
+                                .spacing(5)
+                                .width(Length::Fill)
+                                .push(Text::new(i18n::tr_args(
+                                    "recovery-share-of",
+                                    &[("index", share.index().into()), ("count", share.count.into())],
+                                )).size(16))
+                                .push(Text::new(share.to_string()).size(13)),
+                        ),
+                );
+            }
+        }
+
+        self.push_status(content)
+    }
+
+    // Reconstitution de la clé de récupération depuis l'écran de connexion, puis nouveau
+    // mot de passe maître ; sans réseau pour un coffre local
+    pub fn view_recover(&self) -> Element<'_, Message> {
+        let header = Row::new()
+            .spacing(20)
+            .push(Button::new(Text::new(i18n::tr("detail-back"))).on_press(Message::NavigateTo(View::Login)))
+            .push(Text::new(i18n::tr("recovery-title")).size(24));
+
+        let mut shares = Column::new()
+            .spacing(10)
+            .push(Text::new(i18n::tr("recovery-enter-shares")).size(14));
+        for (index, share) in self.shares.iter().enumerate() {
+            shares = shares.push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        text_field(&i18n::tr_args("recovery-share", &[("index", (index + 1).into())]), share)
+                            .on_input(move |text| Message::Recovery(RecoveryMessage::ShareChanged(index, text)))
+                            .on_submit(Message::Recovery(RecoveryMessage::Recover))
+                            .padding(10)
+                            .width(Length::Fill),
+                    )
+                    .push(action_button(
+                        &i18n::tr("recovery-remove-share"),
+                        (self.shares.len() > 1).then_some(Message::Recovery(RecoveryMessage::RemoveShare(index))),
+                    )),
+            );
+        }
+        shares = shares
+            .push(action_button(&i18n::tr("recovery-add-share"), Some(Message::Recovery(RecoveryMessage::AddShare))))
+            .push(Text::new(i18n::tr("recovery-new-password")).size(14))
+            .push(
+                text_field(&i18n::tr("settings-new-password"), &self.password)
+                    .on_input(|password| Message::Recovery(RecoveryMessage::PasswordChanged(password)))
+                    .padding(10)
+                    .password(),
+            )
+            .push(
+                text_field(&i18n::tr("settings-confirm-password"), &self.confirm)
+                    .on_input(|confirm| Message::Recovery(RecoveryMessage::ConfirmChanged(confirm)))
+                    .on_submit(Message::Recovery(RecoveryMessage::Recover))
+                    .padding(10)
+                    .password(),
+            )
+            .push(
+                text_field(&i18n::tr("login-key-file"), &self.key_file)
+                    .on_input(|path| Message::Recovery(RecoveryMessage::KeyFileChanged(path)))
+                    .padding(10),
+            )
+            .push(action_button(
+                &i18n::tr(if self.busy { "recovery-recovering" } else { "recovery-recover" }),
+                (!self.busy).then_some(Message::Recovery(RecoveryMessage::Recover)),
+            ));
+
+        let content = Column::new().spacing(20).padding(20).push(header).push(shares);
+        self.push_status(content)
+    }
+
+    fn push_status<'a>(&'a self, mut content: Column<'a, Message>) -> Element<'a, Message> {
+        if let Some(message) = &self.message {
+            content = content.push(Text::new(message).size(16));
+        }
+        if let Some(error) = &self.error {
+            content = content.push(
+                Text::new(error)
+                    .size(16)
+                    .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.2, 0.2))),
+            );
+        }
+        Scrollable::new(content).into()
+    }
+}
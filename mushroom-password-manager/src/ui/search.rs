@@ -0,0 +1,338 @@
+use crate::i18n;
+use crate::vault::entry::Entry;
+
+// Champ d'une entrée sur lequel la recherche a trouvé une correspondance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchedField {
+    Name,
+    Url,
+    Username,
+    Email,
+    Tags,
+    Notes,
+}
+
+impl MatchedField {
+    pub fn label(&self) -> String {
+        i18n::tr(match self {
+            MatchedField::Name => "search-field-name",
+            MatchedField::Url => "search-field-url",
+            MatchedField::Username => "search-field-username",
+            MatchedField::Email => "search-field-email",
+            MatchedField::Tags => "search-field-tags",
+            MatchedField::Notes => "search-field-notes",
+        })
+    }
+}
+
+// Résultat de recherche : index dans la liste complète, score et caractères surlignés
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    pub index: usize,
+    pub score: i64,
+    pub field: MatchedField,
+    pub positions: Vec<usize>,
+}
+
+// Bonus et pénalités du score flou
+const SCORE_MATCH: i64 = 16;
+const BONUS_CONSECUTIVE: i64 = 24;
+const BONUS_WORD_START: i64 = 20;
+const BONUS_FIRST_CHAR: i64 = 12;
+const PENALTY_GAP: i64 = 2;
+const PENALTY_LEADING_GAP: i64 = 1;
+
+// Pondération des champs : le nom du service compte plus que le reste
+fn field_weight(field: MatchedField) -> i64 {
+    match field {
+        MatchedField::Name => 3,
+        MatchedField::Url | MatchedField::Username | MatchedField::Tags => 2,
+        MatchedField::Email | MatchedField::Notes => 1,
+    }
+}
+
+fn is_word_start(chars: &[char], index: usize) -> bool {
+    if index == 0 {
+        return true;
+    }
+    let previous = chars[index - 1];
+    !previous.is_alphanumeric() || (previous.is_lowercase() && chars[index].is_uppercase())
+}
+
+// Correspondance floue d'un motif dans un texte (sous-séquence, insensible à la casse).
+// Renvoie le score et la position (en caractères du texte) de chaque caractère trouvé.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    if pattern.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    // Même repliement des deux côtés : une minuscule peut compter plusieurs caractères
+    // (« İ » donne « i̇ »), chacun renvoie au caractère du texte dont il vient
+    let original: Vec<char> = text.chars().collect();
+    let (lowered, origin): (Vec<char>, Vec<usize>) = original
+        .iter()
+        .enumerate()
+        .flat_map(|(index, c)| c.to_lowercase().map(move |lower| (lower, index)))
+        .unzip();
+
+    // Première passe gloutonne pour vérifier que le motif est une sous-séquence
+    let mut end = None;
+    let mut p = 0;
+    for (i, c) in lowered.iter().enumerate() {
+        if *c == pattern[p] {
+            p += 1;
+            if p == pattern.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    // Seconde passe à rebours depuis la fin trouvée pour resserrer la correspondance
+    let mut start = end;
+    let mut p = pattern.len();
+    for i in (0..=end).rev() {
+        if lowered[i] == pattern[p - 1] {
+            p -= 1;
+            start = i;
+            if p == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut p = 0;
+    for (i, c) in lowered.iter().enumerate().take(end + 1).skip(start) {
+        if p < pattern.len() && *c == pattern[p] {
+            positions.push(i);
+            p += 1;
+        }
+    }
+
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    for &position in &positions {
+        score += SCORE_MATCH;
+        let starts_char = position == 0 || origin[position - 1] != origin[position];
+        if starts_char && is_word_start(&original, origin[position]) {
+            score += BONUS_WORD_START;
+        }
+        match previous {
+            Some(prev) if position == prev + 1 => score += BONUS_CONSECUTIVE,
+            Some(prev) => score -= PENALTY_GAP * (position - prev - 1) as i64,
+            None => {
+                if position == 0 {
+                    score += BONUS_FIRST_CHAR;
+                }
+                score -= PENALTY_LEADING_GAP * position.min(10) as i64;
+            }
+        }
+        previous = Some(position);
+    }
+
+    let mut positions: Vec<usize> = positions.into_iter().map(|position| origin[position]).collect();
+    positions.dedup();
+    Some((score, positions))
+}
+
+// Texte d'un champ tel qu'il est cherché et affiché
+pub fn field_text(entry: &Entry, field: MatchedField) -> String {
+    match field {
+        MatchedField::Name => entry.service_name.clone(),
+        MatchedField::Url => entry.service_url.clone(),
+        MatchedField::Username => entry.username.clone(),
+        MatchedField::Email => entry.email.clone(),
+        MatchedField::Tags => entry.tags_text(),
+        // Les notes sont cherchées sur une seule ligne
+        MatchedField::Notes => entry.notes.replace('\n', " "),
+    }
+}
+
+// Meilleure correspondance d'un terme sur l'ensemble des champs d'une entrée
+fn best_field_match(term: &str, entry: &Entry) -> Option<(i64, MatchedField, Vec<usize>)> {
+    let fields = [
+        MatchedField::Name,
+        MatchedField::Url,
+        MatchedField::Username,
+        MatchedField::Email,
+        MatchedField::Tags,
+        MatchedField::Notes,
+    ];
+
+    fields
+        .iter()
+        .filter_map(|field| {
+            fuzzy_match(term, &field_text(entry, *field))
+                .map(|(score, positions)| (score * field_weight(*field), *field, positions))
+        })
+        .max_by_key(|(score, _, _)| *score)
+}
+
+// Filtre et classe les entrées : chaque mot de la requête doit correspondre à un champ.
+// Une requête vide renvoie toutes les entrées dans leur ordre d'origine.
+pub fn search(entries: &[Entry], query: &str) -> Vec<SearchResult> {
+    let terms: Vec<&str> = query.split_whitespace().collect();
+
+    if terms.is_empty() {
+        return entries
+            .iter()
+            .enumerate()
+            .map(|(index, _)| SearchResult {
+                index,
+                score: 0,
+                field: MatchedField::Name,
+                positions: Vec::new(),
+            })
+            .collect();
+    }
+
+    let mut results: Vec<SearchResult> = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let mut total = 0;
+            let mut highlight: Option<(i64, MatchedField, Vec<usize>)> = None;
+
+            for term in &terms {
+                let (score, field, positions) = best_field_match(term, entry)?;
+                total += score;
+
+                // On surligne le champ du terme le plus discriminant, en fusionnant
+                // les positions des termes qui tombent sur le même champ
+                match &mut highlight {
+                    Some((best, current, merged)) if *current == field => {
+                        *best += score;
+                        merged.extend(positions);
+                    }
+                    Some((best, _, _)) if *best >= score => {}
+                    _ => highlight = Some((score, field, positions)),
+                }
+            }
+
+            let (_, field, mut positions) = highlight?;
+            positions.sort_unstable();
+            positions.dedup();
+
+            Some(SearchResult {
+                index,
+                score: total,
+                field,
+                positions,
+            })
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| entries[a.index].service_name.len().cmp(&entries[b.index].service_name.len()))
+            .then_with(|| a.index.cmp(&b.index))
+    });
+    results
+}
+
+// Découpe un texte en segments (texte, surligné ?) selon les positions trouvées
+pub fn highlight_segments(text: &str, positions: &[usize]) -> Vec<(String, bool)> {
+    let mut segments: Vec<(String, bool)> = Vec::new();
+
+    for (i, c) in text.chars().enumerate() {
+        let highlighted = positions.binary_search(&i).is_ok();
+        match segments.last_mut() {
+            Some((segment, flag)) if *flag == highlighted => segment.push(c),
+            _ => segments.push((c.to_string(), highlighted)),
+        }
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, username: &str, notes: &str) -> Entry {
+        let mut entry = Entry::new(&format!("https://{}.fr", name.to_lowercase()), name);
+        entry.username = username.to_string();
+        entry.notes = notes.into();
+        entry
+    }
+
+    #[test]
+    fn fuzzy_match_is_a_case_insensitive_subsequence() {
+        let (_, positions) = fuzzy_match("gth", "GitHub").unwrap();
+        assert_eq!(positions, vec![0, 2, 3]);
+        assert!(fuzzy_match("hg", "GitHub").is_none());
+        assert_eq!(fuzzy_match("", "GitHub"), Some((0, Vec::new())));
+    }
+
+    #[test]
+    fn consecutive_and_word_start_matches_score_higher() {
+        let (tight, _) = fuzzy_match("hub", "GitHub").unwrap();
+        let (loose, _) = fuzzy_match("hub", "Hautbois").unwrap();
+        assert!(tight > loose);
+
+        let (start, _) = fuzzy_match("b", "ma-banque").unwrap();
+        let (middle, _) = fuzzy_match("n", "ma-banque").unwrap();
+        assert!(start > middle);
+    }
+
+    #[test]
+    fn multi_char_lowercase_folds_the_same_way_on_both_sides() {
+        // « İ » se replie en « i » suivi d'un point combinant, des deux côtés
+        let (_, positions) = fuzzy_match("İstanbul", "Banque İstanbul").unwrap();
+        assert_eq!(positions, (7..15).collect::<Vec<_>>());
+        let (_, positions) = fuzzy_match("i̇s", "İSTANBUL").unwrap();
+        assert_eq!(positions, vec![0, 1]);
+    }
+
+    #[test]
+    fn every_term_must_match_a_field() {
+        let entries = vec![entry("GitHub", "alice", ""), entry("GitLab", "bob", "")];
+        let results = search(&entries, "git bob");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].index, 1);
+        assert!(search(&entries, "git carol").is_empty());
+    }
+
+    #[test]
+    fn empty_query_keeps_the_original_order() {
+        let entries = vec![entry("Zeta", "", ""), entry("Alpha", "", "")];
+        let indexes: Vec<usize> = search(&entries, "  ").iter().map(|result| result.index).collect();
+        assert_eq!(indexes, vec![0, 1]);
+    }
+
+    #[test]
+    fn name_matches_outrank_notes_matches() {
+        let entries = vec![entry("Banque", "", "code du coffre"), entry("Coffre", "", "")];
+        let results = search(&entries, "coffre");
+        assert_eq!(results[0].index, 1);
+        assert_eq!(results[0].field, MatchedField::Name);
+        assert_eq!(results[1].field, MatchedField::Notes);
+    }
+
+    #[test]
+    fn merged_highlight_keeps_the_combined_score() {
+        // « g » et « hub » tombent sur le nom ; « alice », mieux noté que « g » seul
+        // mais moins que les deux termes réunis, ne doit pas prendre le surlignage
+        let entries = vec![entry("GitHub", "alice", "")];
+        let results = search(&entries, "g hub alice");
+        assert_eq!(results[0].field, MatchedField::Name);
+        assert_eq!(results[0].positions, vec![0, 3, 4, 5]);
+    }
+
+    #[test]
+    fn highlight_segments_split_on_matched_characters() {
+        assert_eq!(
+            highlight_segments("GitHub", &[0, 3, 4]),
+            vec![
+                ("G".to_string(), true),
+                ("it".to_string(), false),
+                ("Hu".to_string(), true),
+                ("b".to_string(), false),
+            ]
+        );
+    }
+}
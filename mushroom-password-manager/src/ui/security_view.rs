@@ -0,0 +1,263 @@
+use iced::{
+    Command, Element, Length,
+    widget::{Button, Canvas, Column, Row, Scrollable, Text},
+};
+use url::Url;
+
+use crate::api::client::ApiClient;
+use crate::api::error::ApiError;
+use crate::api::sync::SyncHandle;
+use crate::i18n;
+use crate::ui::app::Message;
+use crate::ui::widgets::{action_button, text_field, QrCanvas};
+use crate::vault::crypto;
+use crate::vault::e2e::{self, VaultParams};
+use crate::vault::factors::UnlockFactors;
+use crate::vault::totp::{self, Totp};
+
+#[derive(Debug, Clone)]
+pub enum SecurityMessage {
+    Load,
+    Loaded(Result<Option<VaultParams>, ApiError>),
+    StartTotp,
+    CodeChanged(String),
+    ConfirmTotp,
+    DisableTotp,
+    Saved(Result<VaultParams, String>),
+}
+
+// Secret TOTP proposé, en attente d'un premier code correct
+struct PendingTotp {
+    secret: String,
+    uri: String,
+    qr: QrCanvas,
+}
+
+// Facteurs de déverrouillage du coffre ouvert
+#[derive(Default)]
+pub struct SecurityView {
+    params: Option<VaultParams>,
+    pending: Option<PendingTotp>,
+    code: String,
+    busy: bool,
+    message: Option<String>,
+    error: Option<String>,
+}
+
+impl SecurityView {
+    pub fn update(
+        &mut self,
+        message: SecurityMessage,
+        client: &ApiClient,
+        sync: &Option<SyncHandle>,
+        profile: &str,
+    ) -> Command<Message> {
+        match message {
+            SecurityMessage::Load => {
+                let client = client.clone();
+                let sync = sync.clone();
+                return Command::perform(
+                    async move {
+                        match sync {
+                            Some(sync) => Ok(sync.vault_params().await),
+                            None => client.get_vault_params().await,
+                        }
+                    },
+                    |result| Message::Security(SecurityMessage::Loaded(result)),
+                );
+            }
+            SecurityMessage::Loaded(result) => match result {
+                Ok(params) => self.params = params,
+                Err(e) => self.fail(e.to_string()),
+            },
+            SecurityMessage::StartTotp => {
+                let secret = totp::encode_base32(&crypto::random_bytes::<20>());
+                match second_factor_uri(&secret, profile).and_then(|uri| {
+                    let code = qrcode::QrCode::new(&uri).map_err(|e| i18n::tr_args("security-qr-failed", &[("error", e.to_string().into())]))?;
+                    Ok((uri, QrCanvas::new(&code)))
+                }) {
+                    Ok((uri, qr)) => {
+                        self.pending = Some(PendingTotp { secret, uri, qr });
+                        self.code.clear();
+                        self.error = None;
+                        self.message = Some(i18n::tr("security-totp-added"));
+                    }
+                    Err(e) => self.fail(e),
+                }
+            }
+            SecurityMessage::CodeChanged(code) => self.code = code,
+            SecurityMessage::ConfirmTotp => {
+                let (Some(params), Some(pending), Some(cipher)) = (&self.params, &self.pending, client.cipher()) else {
+                    return Command::none();
+                };
+                let result = Totp::parse(&pending.secret).and_then(|totp| {
+                    if e2e::verify_second_factor(&totp, &self.code, totp::unix_now(), None).is_none() {
+                        return Err(i18n::tr("status-totp-incorrect"));
+                    }
+                    let mut factors = params.factors.clone();
+                    factors.totp = Some(cipher.seal_second_factor(&pending.secret)?);
+                    Ok(factors)
+                });
+                match result {
+                    Ok(factors) => return self.save(factors, client, sync),
+                    Err(e) => self.fail(e),
+                }
+            }
+            SecurityMessage::DisableTotp => {
+                let (Some(params), Some(cipher)) = (&self.params, client.cipher()) else {
+                    return Command::none();
+                };
+                // Le code courant est exigé : une session laissée ouverte ne suffit pas à retirer le facteur
+                let result = cipher.second_factor(&params.factors).and_then(|totp| match totp {
+                    Some(totp) if e2e::verify_second_factor(&totp, &self.code, totp::unix_now(), None).is_some() => {
+                        Ok(UnlockFactors { totp: None, ..params.factors.clone() })
+                    }
+                    Some(_) => Err(i18n::tr("status-totp-incorrect")),
+                    None => Err(i18n::tr("security-totp-disabled")),
+                });
+                match result {
+                    Ok(factors) => return self.save(factors, client, sync),
+                    Err(e) => self.fail(e),
+                }
+            }
+            SecurityMessage::Saved(result) => {
+                self.busy = false;
+                match result {
+                    Ok(params) => {
+                        self.message = Some(i18n::tr_args("security-factors", &[("factors", params.factors.describe().into())]));
+                        self.error = None;
+                        self.params = Some(params);
+                        self.pending = None;
+                        self.code.clear();
+                    }
+                    Err(e) => self.fail(e),
+                }
+            }
+        }
+        Command::none()
+    }
+
+    // Enregistre les facteurs dans l'en-tête du coffre et dans le cache local
+    fn save(&mut self, factors: UnlockFactors, client: &ApiClient, sync: &Option<SyncHandle>) -> Command<Message> {
+        let Some(previous) = self.params.clone() else {
+            return Command::none();
+        };
+        self.busy = true;
+        let client = client.clone();
+        let sync = sync.clone();
+        Command::perform(
+            async move {
+                let params = client.update_vault_factors(&previous, &factors).await?;
+                if let Some(sync) = sync {
+                    sync.set_vault_params(params.clone()).await?;
+                }
+                Ok(params)
+            },
+            |result| Message::Security(SecurityMessage::Saved(result)),
+        )
+    }
+
+    fn fail(&mut self, error: String) {
+        self.message = None;
+        self.error = Some(error);
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let header = Row::new()
+            .spacing(20)
+            .push(Button::new(Text::new(i18n::tr("detail-back"))).on_press(Message::BackToMain))
+            .push(Text::new(i18n::tr("security-title")).size(24));
+
+        let mut content = Column::new().spacing(20).padding(20).push(header);
+
+        let Some(params) = &self.params else {
+            content = content.push(Text::new(i18n::tr("settings-loading")).size(16));
+            return self.push_status(content);
+        };
+
+        let key_file = if params.factors.key_file {
+            i18n::tr("security-key-file-required")
+        } else {
+            i18n::tr("security-key-file-none")
+        };
+        content = content.push(
+            Column::new()
+                .spacing(5)
+                .push(Text::new(i18n::tr_args("security-factors", &[("factors", params.factors.describe().into())])).size(18))
+                .push(Text::new(key_file).size(14))
+                .push(Text::new(i18n::tr("security-key-file-hint")).size(14))
+                .push(Text::new(i18n::tr("security-totp-hint")).size(14)),
+        );
+
+        let code_input = text_field(&i18n::tr("security-code"), &self.code)
+            .on_input(|code| Message::Security(SecurityMessage::CodeChanged(code)))
+            .padding(10)
+            .width(Length::Fixed(200.0));
+
+        let mut totp_section = Column::new().spacing(10).push(Text::new(i18n::tr("security-totp")).size(20));
+        if params.factors.totp.is_some() {
+            totp_section = totp_section
+                .push(Text::new(i18n::tr("security-totp-enabled")).size(14))
+                .push(
+                    Row::new()
+                        .spacing(10)
+                        .push(code_input.on_submit(Message::Security(SecurityMessage::DisableTotp)))
+                        .push(action_button(
+                            &i18n::tr("security-totp-disable"),
+                            (!self.busy).then_some(Message::Security(SecurityMessage::DisableTotp)),
+                        )),
+                );
+        } else if let Some(pending) = &self.pending {
+            totp_section = totp_section
+                .push(
+                    Row::new()
+                        .spacing(20)
+                        .push(Canvas::new(&pending.qr).width(Length::Fixed(180.0)).height(Length::Fixed(180.0)))
+                        .push(
+                            Column::new()
+                                .spacing(5)
+                                .width(Length::Fill)
+                                .push(Text::new(i18n::tr_args("security-totp-secret", &[("secret", pending.secret.as_str().into())])).size(14))
+                                .push(Text::new(pending.uri.clone()).size(12)),
+                        ),
+                )
+                .push(
+                    Row::new()
+                        .spacing(10)
+                        .push(code_input.on_submit(Message::Security(SecurityMessage::ConfirmTotp)))
+                        .push(action_button(
+                            &i18n::tr("security-totp-enable"),
+                            (!self.busy).then_some(Message::Security(SecurityMessage::ConfirmTotp)),
+                        )),
+                );
+        } else {
+            totp_section = totp_section
+                .push(Text::new(i18n::tr("security-totp-offer")).size(14))
+                .push(action_button(&i18n::tr("security-totp-configure"), Some(Message::Security(SecurityMessage::StartTotp))));
+        }
+
+        content = content.push(totp_section);
+        self.push_status(content)
+    }
+
+    fn push_status<'a>(&'a self, mut content: Column<'a, Message>) -> Element<'a, Message> {
+        if let Some(message) = &self.message {
+            content = content.push(Text::new(message).size(16));
+        }
+        if let Some(error) = &self.error {
+            content = content.push(
+                Text::new(error)
+                    .size(16)
+                    .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.2, 0.2))),
+            );
+        }
+        Scrollable::new(content).into()
+    }
+}
+
+fn second_factor_uri(secret: &str, profile: &str) -> Result<String, String> {
+    let mut uri = Url::parse("otpauth://totp/").map_err(|e| e.to_string())?;
+    uri.set_path(&format!("Mushroom:{}", profile));
+    uri.query_pairs_mut().append_pair("secret", secret).append_pair("issuer", "Mushroom");
+    Ok(uri.to_string())
+}
@@ -0,0 +1,616 @@
+use iced::{
+    clipboard, Command, Element, Length,
+    widget::{Button, Checkbox, Column, PickList, Row, Scrollable, Text},
+};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::api::client::ApiClient;
+use crate::api::error::ApiError;
+use crate::api::sync::SyncHandle;
+use crate::config::{AppConfig, BackendKind, Delay, GeneratorSettings, Language, ThemeChoice};
+use crate::i18n;
+use crate::ui::app::Message;
+use crate::ui::widgets::{action_button, danger_button, text_field};
+use crate::vault::crypto::{self, KdfParams};
+use crate::vault::e2e::{self, VaultParams};
+use crate::vault::factors::KeyFile;
+
+#[derive(Debug, Clone)]
+pub enum SettingsMessage {
+    // Préférences de l'application, enregistrées dans le fichier de configuration
+    GeneratorLengthChanged(String),
+    GeneratorDigitsToggled(bool),
+    GeneratorSymbolsToggled(bool),
+    ClipboardDelaySelected(Delay),
+    IdleDelaySelected(Delay),
+    RevealDelaySelected(Delay),
+    BackendKindSelected(BackendKind),
+    ServerUrlChanged(String),
+    ThemeSelected(ThemeChoice),
+    LanguageSelected(Language),
+
+    Load,
+    Loaded(Result<Option<VaultParams>, ApiError>),
+    EmailLoaded(String),
+    EmailChanged(String),
+    CurrentChanged(String),
+    NewChanged(String),
+    ConfirmChanged(String),
+    KdfSelected(KdfParams),
+    ChangePassword,
+    // Mesure de cette machine et renforcement de la dérivation, sans changer de mot de passe
+    Calibrate,
+    Calibrated(Result<Calibration, String>),
+    Strengthen,
+    PasswordChanged(Result<String, String>),
+    // Appairage de l'extension Chrome : jeton délivré par le serveur, affiché une seule fois
+    PairExtension,
+    ExtensionPaired(Result<String, ApiError>),
+    CopyPairingToken,
+    UnpairExtension,
+    ExtensionUnpaired(Result<(), ApiError>),
+}
+
+// Durée de la dérivation actuelle et réglage proposé pour atteindre la durée visée
+#[derive(Debug, Clone)]
+pub struct Calibration {
+    current: Duration,
+    proposed: KdfParams,
+    proposed_time: Duration,
+}
+
+// Préférences de l'application, puis paramètres du coffre ouvert : changement du mot de passe maître
+#[derive(Default)]
+pub struct SettingsView {
+    // Longueur saisie, gardée telle quelle tant qu'elle n'est pas valide
+    length: Option<String>,
+    preferences_error: Option<String>,
+    // Email par défaut du coffre, proposé pour les nouvelles entrées et rempli par l'extension
+    email: String,
+    params: Option<VaultParams>,
+    current: String,
+    new: String,
+    confirm: String,
+    // Réglage Argon2id du nouvel en-tête ; l'actuel par défaut
+    kdf: Option<KdfParams>,
+    calibration: Option<Calibration>,
+    // Jeton d'appairage que l'extension doit recevoir ; oublié en quittant la vue
+    pairing_token: Option<String>,
+    busy: bool,
+    // Message affiché une fois l'en-tête réécrit
+    done_message: String,
+    message: Option<String>,
+    error: Option<String>,
+}
+
+impl SettingsView {
+    pub fn update(
+        &mut self,
+        message: SettingsMessage,
+        client: &ApiClient,
+        sync: &Option<SyncHandle>,
+        key_file: Option<PathBuf>,
+        config: &mut AppConfig,
+    ) -> Command<Message> {
+        match message {
+            SettingsMessage::GeneratorLengthChanged(value) => {
+                match value.trim().parse::<usize>() {
+                    Ok(length) if (GeneratorSettings::MIN_LENGTH..=GeneratorSettings::MAX_LENGTH).contains(&length) => {
+                        config.generator.length = length;
+                        self.save_preferences(config);
+                    }
+                    _ => {
+                        self.preferences_error = Some(i18n::tr_args(
+                            "settings-length-range",
+                            &[("min", GeneratorSettings::MIN_LENGTH.into()), ("max", GeneratorSettings::MAX_LENGTH.into())],
+                        ))
+                    }
+                }
+                self.length = Some(value);
+            }
+            SettingsMessage::GeneratorDigitsToggled(digits) => {
+                config.generator.digits = digits;
+                self.save_preferences(config);
+            }
+            SettingsMessage::GeneratorSymbolsToggled(symbols) => {
+                config.generator.symbols = symbols;
+                self.save_preferences(config);
+            }
+            SettingsMessage::ClipboardDelaySelected(delay) => {
+                config.clipboard.clear_after = delay;
+                self.save_preferences(config);
+            }
+            SettingsMessage::IdleDelaySelected(delay) => {
+                config.lock.idle = delay;
+                self.save_preferences(config);
+            }
+            SettingsMessage::RevealDelaySelected(delay) => {
+                config.display.hide_password_after = delay;
+                self.save_preferences(config);
+            }
+            SettingsMessage::BackendKindSelected(kind) => {
+                config.backend.kind = kind;
+                self.save_preferences(config);
+            }
+            SettingsMessage::ServerUrlChanged(url) => {
+                config.backend.server_url = url;
+                self.save_preferences(config);
+            }
+            SettingsMessage::ThemeSelected(theme) => {
+                config.appearance.theme = theme;
+                self.save_preferences(config);
+            }
+            SettingsMessage::LanguageSelected(language) => {
+                config.appearance.language = language;
+                i18n::set_language(language);
+                self.save_preferences(config);
+            }
+            SettingsMessage::Load => {
+                let (params_client, client, sync) = (client.clone(), client.clone(), sync.clone());
+                return Command::batch(vec![
+                    Command::perform(async move { params_client.get_vault_params().await }, |result| {
+                        Message::Settings(SettingsMessage::Loaded(result))
+                    }),
+                    Command::perform(
+                        async move {
+                            match sync {
+                                Some(sync) => sync.email(&client).await,
+                                None => Ok(client.get_email().await?),
+                            }
+                        },
+                        |result| match result {
+                            Ok(email) => Message::Settings(SettingsMessage::EmailLoaded(email)),
+                            Err(e) => Message::StatusUpdate(i18n::tr_args("status-error", &[("error", e.into())])),
+                        },
+                    ),
+                ]);
+            }
+            SettingsMessage::EmailLoaded(email) | SettingsMessage::EmailChanged(email) => self.email = email,
+            SettingsMessage::Loaded(result) => match result {
+                Ok(params) => {
+                    self.kdf = params.as_ref().map(|params| params.kdf);
+                    self.params = params;
+                }
+                Err(e) => self.fail(e.to_string()),
+            },
+            SettingsMessage::CurrentChanged(value) => self.current = value,
+            SettingsMessage::NewChanged(value) => self.new = value,
+            SettingsMessage::ConfirmChanged(value) => self.confirm = value,
+            SettingsMessage::KdfSelected(kdf) => self.kdf = Some(kdf),
+            SettingsMessage::ChangePassword => {
+                if self.busy {
+                    return Command::none();
+                }
+                let (Some(params), Some(kdf)) = (self.params.clone(), self.kdf) else {
+                    return Command::none();
+                };
+                if self.new.is_empty() {
+                    self.fail(i18n::tr("settings-new-password-required"));
+                    return Command::none();
+                }
+                if self.new != self.confirm {
+                    self.fail(i18n::tr("settings-confirmation-mismatch"));
+                    return Command::none();
+                }
+                if self.new == self.current && kdf == params.kdf {
+                    self.fail(i18n::tr("settings-password-unchanged"));
+                    return Command::none();
+                }
+
+                self.done_message = i18n::tr("settings-password-changed");
+                return self.rekey(params, self.new.clone(), kdf, client, sync, key_file);
+            }
+            SettingsMessage::Calibrate => {
+                let Some(params) = &self.params else {
+                    return Command::none();
+                };
+                self.busy = true;
+                self.error = None;
+                self.message = Some(i18n::tr("settings-calibrating"));
+                let current = params.kdf;
+                return Command::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            let proposed = crypto::calibrate(crypto::TARGET_UNLOCK)?;
+                            Ok(Calibration {
+                                current: crypto::measure(&current)?,
+                                proposed,
+                                proposed_time: crypto::measure(&proposed)?,
+                            })
+                        })
+                        .await
+                        .map_err(|e| e.to_string())?
+                    },
+                    |result| Message::Settings(SettingsMessage::Calibrated(result)),
+                );
+            }
+            SettingsMessage::Calibrated(result) => {
+                self.busy = false;
+                match result {
+                    Ok(calibration) => {
+                        self.message = None;
+                        self.calibration = Some(calibration);
+                    }
+                    Err(e) => self.fail(e),
+                }
+            }
+            SettingsMessage::Strengthen => {
+                let (Some(params), Some(calibration)) = (self.params.clone(), &self.calibration) else {
+                    return Command::none();
+                };
+                if self.busy {
+                    return Command::none();
+                }
+                let kdf = calibration.proposed;
+                self.done_message = i18n::tr_args("settings-strengthened", &[("kdf", kdf.to_string().into())]);
+                return self.rekey(params, self.current.clone(), kdf, client, sync, key_file);
+            }
+            SettingsMessage::PasswordChanged(result) => {
+                self.busy = false;
+                match result {
+                    Ok(password) => {
+                        let done = std::mem::take(&mut self.done_message);
+                        *self = SettingsView::default();
+                        self.message = Some(done);
+                        return Command::perform(async move { password }, Message::MasterPasswordChanged);
+                    }
+                    Err(e) => self.fail(e),
+                }
+            }
+            SettingsMessage::PairExtension => {
+                let client = client.clone();
+                self.error = None;
+                return Command::perform(async move { client.pair_extension().await }, |result| {
+                    Message::Settings(SettingsMessage::ExtensionPaired(result))
+                });
+            }
+            SettingsMessage::ExtensionPaired(result) => match result {
+                Ok(token) => {
+                    self.pairing_token = Some(token);
+                    self.message = Some(i18n::tr("settings-extension-paired"));
+                }
+                Err(e) => self.fail(e.to_string()),
+            },
+            SettingsMessage::CopyPairingToken => {
+                if let Some(token) = &self.pairing_token {
+                    return clipboard::write(token.clone());
+                }
+            }
+            SettingsMessage::UnpairExtension => {
+                let client = client.clone();
+                self.error = None;
+                return Command::perform(async move { client.unpair_extension().await }, |result| {
+                    Message::Settings(SettingsMessage::ExtensionUnpaired(result))
+                });
+            }
+            SettingsMessage::ExtensionUnpaired(result) => match result {
+                Ok(()) => {
+                    self.pairing_token = None;
+                    self.message = Some(i18n::tr("settings-extension-unpaired"));
+                }
+                Err(e) => self.fail(e.to_string()),
+            },
+        }
+        Command::none()
+    }
+
+    // Vérifie le mot de passe actuel puis réécrit l'en-tête du coffre pour `password` et `kdf`
+    fn rekey(
+        &mut self,
+        params: VaultParams,
+        password: String,
+        kdf: KdfParams,
+        client: &ApiClient,
+        sync: &Option<SyncHandle>,
+        key_file: Option<PathBuf>,
+    ) -> Command<Message> {
+        let Some(cipher) = client.cipher() else {
+            return Command::none();
+        };
+        self.busy = true;
+        self.error = None;
+        self.message = Some(i18n::tr("settings-rekeying"));
+        let (client, sync) = (client.clone(), sync.clone());
+        let current = self.current.clone();
+        Command::perform(
+            async move {
+                let key_file = key_file.as_deref().map(KeyFile::read).transpose()?;
+                let rekeyed = e2e::change_password(cipher, params.clone(), current, password.clone(), key_file, kdf).await?;
+                // Le coffre d'abord : c'est lui qui fait foi pour les autres appareils
+                let rekeyed = client.rekey(&params, &rekeyed, &password).await?;
+                if let Some(sync) = sync {
+                    sync.rekey(&password, rekeyed)
+                        .await
+                        .map_err(|e| i18n::tr_args("settings-cache-not-rekeyed", &[("error", e.into())]))?;
+                }
+                Ok(password)
+            },
+            |result| Message::Settings(SettingsMessage::PasswordChanged(result)),
+        )
+    }
+
+    // Les préférences s'appliquent dès leur changement ; le fichier suit
+    fn save_preferences(&mut self, config: &AppConfig) {
+        self.preferences_error = config.save().err();
+    }
+
+    fn fail(&mut self, error: String) {
+        self.message = None;
+        self.error = Some(error);
+    }
+
+    pub fn view<'a>(&'a self, config: &AppConfig) -> Element<'a, Message> {
+        let header = Row::new()
+            .spacing(20)
+            .push(Button::new(Text::new(i18n::tr("detail-back"))).on_press(Message::BackToMain))
+            .push(Text::new(i18n::tr("settings-title")).size(24));
+
+        let mut content = Column::new()
+            .spacing(20)
+            .padding(20)
+            .push(header)
+            .push(self.view_preferences(config))
+            .push(self.view_email())
+            .push(self.view_extension());
+
+        let Some(params) = &self.params else {
+            content = content.push(Text::new(i18n::tr("settings-loading")).size(16));
+            return self.push_status(content);
+        };
+
+        // Réglages proposés : l'actuel, puis ceux qui le renforcent, dont celui de la calibration
+        let stronger = |kdf: &KdfParams| {
+            *kdf != params.kdf && kdf.memory_kib >= params.kdf.memory_kib && kdf.iterations >= params.kdf.iterations
+        };
+        let mut choices = vec![params.kdf];
+        for kdf in self.calibration.iter().map(|calibration| calibration.proposed).chain(KdfParams::PRESETS) {
+            if stronger(&kdf) && !choices.contains(&kdf) {
+                choices.push(kdf);
+            }
+        }
+
+        let password_input = |placeholder: &str, value, on_input: fn(String) -> SettingsMessage, on_submit| {
+            text_field(placeholder, value)
+                .on_input(move |value| Message::Settings(on_input(value)))
+                .on_submit(Message::Settings(on_submit))
+                .padding(10)
+                .password()
+        };
+
+        content = content.push(
+            Column::new()
+                .spacing(10)
+                .push(Text::new(i18n::tr("settings-current-required")).size(14))
+                .push(password_input(
+                    &i18n::tr("settings-current-password"),
+                    &self.current,
+                    SettingsMessage::CurrentChanged,
+                    SettingsMessage::ChangePassword,
+                )),
+        );
+
+        let mut kdf_section = Column::new()
+            .spacing(10)
+            .push(Text::new(i18n::tr("settings-kdf")).size(20))
+            .push(Text::new(i18n::tr_args("settings-kdf-current", &[("kdf", params.kdf.to_string().into())])).size(16));
+        match &self.calibration {
+            None => {
+                kdf_section = kdf_section
+                    .push(Text::new(i18n::tr_args(
+                        "settings-calibration-hint",
+                        &[("milliseconds", (crypto::TARGET_UNLOCK.as_millis() as u64).into())],
+                    )).size(14))
+                    .push(action_button(
+                        &i18n::tr("settings-calibrate"),
+                        (!self.busy).then_some(Message::Settings(SettingsMessage::Calibrate)),
+                    ));
+            }
+            Some(calibration) => {
+                kdf_section = kdf_section
+                    .push(Text::new(i18n::tr_args(
+                        "settings-calibration-current",
+                        &[("milliseconds", (calibration.current.as_millis() as u64).into())],
+                    )).size(14))
+                    .push(Text::new(i18n::tr_args(
+                        "settings-calibration-proposed",
+                        &[
+                            ("kdf", calibration.proposed.to_string().into()),
+                            ("milliseconds", (calibration.proposed_time.as_millis() as u64).into()),
+                        ],
+                    )).size(14));
+                kdf_section = if stronger(&calibration.proposed) {
+                    kdf_section.push(action_button(
+                        &i18n::tr("settings-strengthen"),
+                        (!self.busy && !self.current.is_empty()).then_some(Message::Settings(SettingsMessage::Strengthen)),
+                    ))
+                } else {
+                    kdf_section.push(Text::new(i18n::tr("settings-kdf-strong-enough")).size(14))
+                };
+            }
+        }
+        content = content.push(kdf_section);
+
+        let form = Column::new()
+            .spacing(10)
+            .push(Text::new(i18n::tr("settings-change-password-title")).size(20))
+            .push(Text::new(i18n::tr("settings-change-password-hint")).size(14))
+            .push(password_input(
+                &i18n::tr("settings-new-password"),
+                &self.new,
+                SettingsMessage::NewChanged,
+                SettingsMessage::ChangePassword,
+            ))
+            .push(password_input(
+                &i18n::tr("settings-confirm-password"),
+                &self.confirm,
+                SettingsMessage::ConfirmChanged,
+                SettingsMessage::ChangePassword,
+            ))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(i18n::tr("settings-kdf")).size(16))
+                    .push(PickList::new(choices, self.kdf, |kdf| Message::Settings(SettingsMessage::KdfSelected(kdf)))),
+            )
+            .push(action_button(
+                &i18n::tr(if self.busy { "settings-changing" } else { "settings-change-password" }),
+                (!self.busy).then_some(Message::Settings(SettingsMessage::ChangePassword)),
+            ))
+            .width(Length::Fill);
+
+        content = content.push(form);
+        self.push_status(content)
+    }
+
+    fn view_preferences<'a>(&'a self, config: &AppConfig) -> Element<'a, Message> {
+        let setting = |label: &str, control: Element<'a, Message>| {
+            Row::new()
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .push(Text::new(label.to_string()).size(16).width(Length::Fixed(260.0)))
+                .push(control)
+        };
+        let length = self.length.clone().unwrap_or_else(|| config.generator.length.to_string());
+
+        let mut preferences = Column::new()
+            .spacing(10)
+            .push(Text::new(i18n::tr("settings-preferences")).size(20))
+            .push(setting(
+                &i18n::tr("settings-generator-length"),
+                text_field("25", &length)
+                    .on_input(|value| Message::Settings(SettingsMessage::GeneratorLengthChanged(value)))
+                    .padding(8)
+                    .width(Length::Fixed(80.0))
+                    .into(),
+            ))
+            .push(setting(
+                &i18n::tr("settings-generator-characters"),
+                Row::new()
+                    .spacing(20)
+                    .push(Checkbox::new(i18n::tr("settings-generator-digits"), config.generator.digits, |digits| {
+                        Message::Settings(SettingsMessage::GeneratorDigitsToggled(digits))
+                    }))
+                    .push(Checkbox::new(i18n::tr("settings-generator-symbols"), config.generator.symbols, |symbols| {
+                        Message::Settings(SettingsMessage::GeneratorSymbolsToggled(symbols))
+                    }))
+                    .into(),
+            ))
+            .push(setting(
+                &i18n::tr("settings-clipboard-delay"),
+                PickList::new(&Delay::CLIPBOARD[..], Some(config.clipboard.clear_after), |delay| {
+                    Message::Settings(SettingsMessage::ClipboardDelaySelected(delay))
+                })
+                .into(),
+            ))
+            .push(setting(
+                &i18n::tr("settings-idle-delay"),
+                PickList::new(&Delay::IDLE[..], Some(config.lock.idle), |delay| {
+                    Message::Settings(SettingsMessage::IdleDelaySelected(delay))
+                })
+                .into(),
+            ))
+            .push(setting(
+                &i18n::tr("settings-reveal-delay"),
+                PickList::new(&Delay::REVEAL[..], Some(config.display.hide_password_after), |delay| {
+                    Message::Settings(SettingsMessage::RevealDelaySelected(delay))
+                })
+                .into(),
+            ))
+            .push(setting(
+                &i18n::tr("settings-backend"),
+                Row::new()
+                    .spacing(10)
+                    .push(PickList::new(&BackendKind::ALL[..], Some(config.backend.kind), |kind| {
+                        Message::Settings(SettingsMessage::BackendKindSelected(kind))
+                    }))
+                    .push(
+                        text_field(&i18n::tr("settings-server-url"), &config.backend.server_url)
+                            .on_input(|url| Message::Settings(SettingsMessage::ServerUrlChanged(url)))
+                            .padding(8)
+                            .width(Length::Fill),
+                    )
+                    .into(),
+            ))
+            .push(setting(
+                &i18n::tr("settings-theme"),
+                PickList::new(&ThemeChoice::ALL[..], Some(config.appearance.theme), |theme| {
+                    Message::Settings(SettingsMessage::ThemeSelected(theme))
+                })
+                .into(),
+            ))
+            .push(setting(
+                &i18n::tr("settings-language"),
+                PickList::new(&Language::ALL[..], Some(config.appearance.language), |language| {
+                    Message::Settings(SettingsMessage::LanguageSelected(language))
+                })
+                .into(),
+            ));
+        if let Some(error) = &self.preferences_error {
+            preferences = preferences.push(
+                Text::new(error)
+                    .size(14)
+                    .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.2, 0.2))),
+            );
+        }
+        preferences.into()
+    }
+
+    fn view_email(&self) -> Element<'_, Message> {
+        let save = Message::SaveEmail(self.email.trim().to_string());
+        Column::new()
+            .spacing(10)
+            .push(Text::new(i18n::tr("settings-email")).size(20))
+            .push(Text::new(i18n::tr("settings-email-hint")).size(14))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        text_field(&i18n::tr("settings-email-placeholder"), &self.email)
+                            .on_input(|email| Message::Settings(SettingsMessage::EmailChanged(email)))
+                            .on_submit(save.clone())
+                            .padding(8)
+                            .width(Length::Fill),
+                    )
+                    .push(action_button(&i18n::tr("settings-save"), Some(save))),
+            )
+            .into()
+    }
+
+    fn view_extension(&self) -> Element<'_, Message> {
+        let mut section = Column::new()
+            .spacing(10)
+            .push(Text::new(i18n::tr("settings-extension")).size(20))
+            .push(Text::new(i18n::tr("settings-extension-hint")).size(14));
+        if let Some(token) = &self.pairing_token {
+            section = section.push(
+                Row::new()
+                    .spacing(10)
+                    .push(text_field("", token).padding(8).width(Length::Fill))
+                    .push(action_button(&i18n::tr("action-copy"), Some(Message::Settings(SettingsMessage::CopyPairingToken)))),
+            );
+        }
+        section
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(action_button(&i18n::tr("settings-extension-pair"), Some(Message::Settings(SettingsMessage::PairExtension))))
+                    .push(danger_button(&i18n::tr("settings-extension-unpair"), Some(Message::Settings(SettingsMessage::UnpairExtension)))),
+            )
+            .into()
+    }
+
+    fn push_status<'a>(&'a self, mut content: Column<'a, Message>) -> Element<'a, Message> {
+        if let Some(message) = &self.message {
+            content = content.push(Text::new(message).size(16));
+        }
+        if let Some(error) = &self.error {
+            content = content.push(
+                Text::new(error)
+                    .size(16)
+                    .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.2, 0.2))),
+            );
+        }
+        Scrollable::new(content).into()
+    }
+}
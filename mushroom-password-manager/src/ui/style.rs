@@ -1,64 +1,248 @@
-use iced::{widget::{button, container}, Background, Color};
+use iced::theme::{self, Palette};
+use iced::widget::{button, container, text_input};
+use iced::{Background, Color, Theme};
+use std::sync::OnceLock;
+
+use crate::config::ThemeChoice;
+
+// Thème « Système » : indication lue dans l'environnement (light, dark ou high_contrast),
+// à défaut la variante sombre d'un thème GTK
+const THEME_ENV: &str = "MUSHROOM_THEME";
+
+const LIGHT: Palette = Palette {
+    background: Color::WHITE,
+    text: Color::from_rgb(0.1, 0.1, 0.1),
+    primary: Color::from_rgb(0.2, 0.5, 0.8),
+    success: Color::from_rgb(0.2, 0.6, 0.3),
+    danger: Color::from_rgb(0.8, 0.2, 0.2),
+};
+
+const DARK: Palette = Palette {
+    background: Color::from_rgb(0.12, 0.13, 0.15),
+    text: Color::from_rgb(0.9, 0.9, 0.9),
+    primary: Color::from_rgb(0.3, 0.55, 0.85),
+    success: Color::from_rgb(0.3, 0.7, 0.4),
+    danger: Color::from_rgb(0.85, 0.3, 0.3),
+};
+
+// Noir et blanc, couleurs saturées : boutons et champs se détachent sans nuance de gris
+const HIGH_CONTRAST: Palette = Palette {
+    background: Color::BLACK,
+    text: Color::WHITE,
+    primary: Color::from_rgb(1.0, 0.85, 0.0),
+    success: Color::from_rgb(0.0, 1.0, 0.5),
+    danger: Color::from_rgb(1.0, 0.3, 0.3),
+};
+
+// Thème de l'application : les styles ci-dessous et ceux d'iced en tirent leurs couleurs
+pub fn theme(choice: ThemeChoice) -> Theme {
+    Theme::custom(match resolve(choice) {
+        ThemeChoice::Dark => DARK,
+        ThemeChoice::HighContrast => HIGH_CONTRAST,
+        _ => LIGHT,
+    })
+}
+
+fn resolve(choice: ThemeChoice) -> ThemeChoice {
+    static SYSTEM: OnceLock<ThemeChoice> = OnceLock::new();
+    match choice {
+        ThemeChoice::System => *SYSTEM.get_or_init(system_hint),
+        choice => choice,
+    }
+}
+
+fn system_hint() -> ThemeChoice {
+    if let Ok(hint) = std::env::var(THEME_ENV) {
+        match hint.trim().to_lowercase().replace('-', "_").as_str() {
+            "dark" => return ThemeChoice::Dark,
+            "high_contrast" => return ThemeChoice::HighContrast,
+            "light" => return ThemeChoice::Light,
+            _ => {}
+        }
+    }
+    match std::env::var("GTK_THEME") {
+        Ok(gtk) if gtk.to_lowercase().contains("dark") => ThemeChoice::Dark,
+        _ => ThemeChoice::Light,
+    }
+}
 
-#[allow(dead_code)]
 pub enum ButtonType {
     Primary,
     Secondary,
     Danger,
 }
 
+impl ButtonType {
+    fn pair(&self, theme: &Theme, hovered: bool) -> theme::palette::Pair {
+        let palette = theme.extended_palette();
+        let (base, strong) = match self {
+            ButtonType::Primary => (palette.primary.base, palette.primary.strong),
+            ButtonType::Secondary => (palette.secondary.base, palette.secondary.strong),
+            ButtonType::Danger => (palette.danger.base, palette.danger.strong),
+        };
+        if hovered { strong } else { base }
+    }
+}
+
+impl From<ButtonType> for theme::Button {
+    fn from(kind: ButtonType) -> Self {
+        theme::Button::Custom(Box::new(kind))
+    }
+}
+
 impl button::StyleSheet for ButtonType {
-    type Style = ();
-    fn active(&self, _style: &Self::Style) -> button::Appearance {
-        match self {
-            ButtonType::Primary => button::Appearance {
-                background: Some(Background::Color(Color::from_rgb(0.2, 0.5, 0.8))),
-                border_radius: 4.0,
-                text_color: Color::WHITE,
-                ..button::Appearance::default()
-            },
-            ButtonType::Secondary => button::Appearance {
-                background: Some(Background::Color(Color::from_rgb(0.3, 0.3, 0.3))),
-                border_radius: 4.0,
-                text_color: Color::WHITE,
-                ..button::Appearance::default()
-            },
-            ButtonType::Danger => button::Appearance {
-                background: Some(Background::Color(Color::from_rgb(0.8, 0.2, 0.2))),
-                border_radius: 4.0,
-                text_color: Color::WHITE,
-                ..button::Appearance::default()
-            },
+    type Style = Theme;
+
+    fn active(&self, theme: &Self::Style) -> button::Appearance {
+        let pair = self.pair(theme, false);
+        button::Appearance {
+            background: Some(Background::Color(pair.color)),
+            border_radius: 4.0,
+            border_width: 1.0,
+            border_color: theme.extended_palette().background.strong.color,
+            text_color: pair.text,
+            ..button::Appearance::default()
         }
     }
 
-    fn hovered(&self, style: &Self::Style) -> button::Appearance {
-        let active = self.active(style);
+    fn hovered(&self, theme: &Self::Style) -> button::Appearance {
+        let pair = self.pair(theme, true);
+        button::Appearance {
+            background: Some(Background::Color(pair.color)),
+            text_color: pair.text,
+            ..self.active(theme)
+        }
+    }
 
+    fn disabled(&self, theme: &Self::Style) -> button::Appearance {
+        let active = self.active(theme);
+        let faded = |color: Color| Color { a: color.a * 0.5, ..color };
         button::Appearance {
-            background: match self {
-                ButtonType::Primary => Some(Background::Color(Color::from_rgb(0.3, 0.6, 0.9))),
-                ButtonType::Secondary => Some(Background::Color(Color::from_rgb(0.4, 0.4, 0.4))),
-                ButtonType::Danger => Some(Background::Color(Color::from_rgb(0.9, 0.3, 0.3))),
-            },
+            background: active.background.map(|background| match background {
+                Background::Color(color) => Background::Color(faded(color)),
+            }),
+            text_color: faded(active.text_color),
             ..active
         }
     }
 }
 
+// Encadré des sections (formulaire, liste des entrées)
 pub struct ContainerStyle;
 
+impl From<ContainerStyle> for theme::Container {
+    fn from(style: ContainerStyle) -> Self {
+        theme::Container::Custom(Box::new(style))
+    }
+}
+
 impl container::StyleSheet for ContainerStyle {
-    type Style = ();
+    type Style = Theme;
 
-    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+    fn appearance(&self, theme: &Self::Style) -> container::Appearance {
+        let palette = theme.extended_palette();
         container::Appearance {
-            background: Some(Background::Color(Color::from_rgb(0.95, 0.95, 0.95))),
-            text_color: Some(Color::from_rgb(0.1, 0.1, 0.1)),
+            background: Some(Background::Color(palette.background.weak.color)),
+            text_color: Some(palette.background.weak.text),
             border_radius: 5.0,
             border_width: 1.0,
-            border_color: Color::from_rgb(0.8, 0.8, 0.8),
-            ..container::Appearance::default()
+            border_color: palette.background.strong.color,
+        }
+    }
+}
+
+// Champs de saisie : bordure au contraste du texte, couleur principale une fois actif
+pub struct InputStyle;
+
+impl From<InputStyle> for theme::TextInput {
+    fn from(style: InputStyle) -> Self {
+        theme::TextInput::Custom(Box::new(style))
+    }
+}
+
+impl text_input::StyleSheet for InputStyle {
+    type Style = Theme;
+
+    fn active(&self, theme: &Self::Style) -> text_input::Appearance {
+        let palette = theme.extended_palette();
+        text_input::Appearance {
+            background: Background::Color(palette.background.base.color),
+            border_radius: 4.0,
+            border_width: 1.0,
+            border_color: palette.background.strong.text,
+            icon_color: palette.background.weak.text,
+        }
+    }
+
+    fn focused(&self, theme: &Self::Style) -> text_input::Appearance {
+        text_input::Appearance {
+            border_width: 2.0,
+            border_color: theme.extended_palette().primary.strong.color,
+            ..self.active(theme)
         }
     }
-}
\ No newline at end of file
+
+    fn hovered(&self, theme: &Self::Style) -> text_input::Appearance {
+        text_input::Appearance {
+            border_color: theme.extended_palette().primary.base.color,
+            ..self.active(theme)
+        }
+    }
+
+    fn disabled(&self, theme: &Self::Style) -> text_input::Appearance {
+        text_input::Appearance {
+            background: Background::Color(theme.extended_palette().background.weak.color),
+            ..self.active(theme)
+        }
+    }
+
+    fn placeholder_color(&self, theme: &Self::Style) -> Color {
+        theme.extended_palette().background.strong.color
+    }
+
+    fn value_color(&self, theme: &Self::Style) -> Color {
+        theme.extended_palette().background.base.text
+    }
+
+    fn disabled_color(&self, theme: &Self::Style) -> Color {
+        theme.extended_palette().background.strong.color
+    }
+
+    fn selection_color(&self, theme: &Self::Style) -> Color {
+        theme.extended_palette().primary.weak.color
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Luminance relative (WCAG 2)
+    fn luminance(color: Color) -> f32 {
+        let channel = |c: f32| if c <= 0.03928 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) };
+        0.2126 * channel(color.r) + 0.7152 * channel(color.g) + 0.0722 * channel(color.b)
+    }
+
+    fn contrast(a: Color, b: Color) -> f32 {
+        let (a, b) = (luminance(a), luminance(b));
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    #[test]
+    fn explicit_choices_use_their_palette() {
+        assert_eq!(theme(ThemeChoice::Light).palette(), LIGHT);
+        assert_eq!(theme(ThemeChoice::Dark).palette(), DARK);
+        assert_eq!(theme(ThemeChoice::HighContrast).palette(), HIGH_CONTRAST);
+        assert_eq!(resolve(ThemeChoice::Dark), ThemeChoice::Dark);
+        assert_ne!(resolve(ThemeChoice::System), ThemeChoice::System);
+    }
+
+    #[test]
+    fn text_is_readable_on_every_background() {
+        // Seuil WCAG AA pour le texte courant, AAA pour le thème à contraste élevé
+        assert!(contrast(LIGHT.text, LIGHT.background) >= 4.5);
+        assert!(contrast(DARK.text, DARK.background) >= 4.5);
+        assert!(contrast(HIGH_CONTRAST.text, HIGH_CONTRAST.background) >= 7.0);
+        assert!(contrast(HIGH_CONTRAST.primary, HIGH_CONTRAST.background) >= 7.0);
+    }
+}
@@ -0,0 +1,391 @@
+use iced::{
+    Command, Element, Length,
+    widget::{Button, Column, PickList, Row, Scrollable, Text},
+};
+use iced::clipboard;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::i18n;
+use crate::profile::ProfileStore;
+use crate::ui::app::Message;
+use crate::ui::widgets::{action_button, danger_button, text_field};
+use crate::vault::shared::{self, SharedCollection, TeamHandle, TeamSnapshot};
+
+// Collection proposée dans les listes de choix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectionChoice {
+    pub id: String,
+    pub name: String,
+}
+
+impl fmt::Display for CollectionChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+pub fn collection_choices(snapshot: &TeamSnapshot) -> Vec<CollectionChoice> {
+    snapshot
+        .collections
+        .iter()
+        .map(|attached| CollectionChoice { id: attached.collection.id.clone(), name: attached.collection.name.clone() })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub enum TeamMessage {
+    CopyPublicKey,
+    NameChanged(String),
+    PathChanged(String),
+    OwnerChanged(String),
+    Create,
+    Created(Result<PathBuf, String>),
+    AttachPathChanged(String),
+    Attach,
+    Detach(PathBuf),
+    CollectionSelected(CollectionChoice),
+    MemberNameChanged(String),
+    MemberKeyChanged(String),
+    AddMember,
+    RemoveMember(Vec<u8>),
+    Done(Result<String, String>),
+}
+
+// Collections partagées du profil : création, rattachement et membres
+#[derive(Default)]
+pub struct TeamView {
+    name: String,
+    path: String,
+    owner: String,
+    attach_path: String,
+    selected: Option<CollectionChoice>,
+    member_name: String,
+    member_key: String,
+    busy: bool,
+    message: Option<String>,
+    error: Option<String>,
+}
+
+impl TeamView {
+    pub fn update(
+        &mut self,
+        message: TeamMessage,
+        team: &Option<TeamHandle>,
+        snapshot: &TeamSnapshot,
+        profiles: &mut ProfileStore,
+    ) -> Command<Message> {
+        let Some(team) = team.clone() else {
+            self.error = Some(i18n::tr("team-no-identity"));
+            return Command::none();
+        };
+        match message {
+            TeamMessage::CopyPublicKey => {
+                self.message = Some(i18n::tr("team-key-copied"));
+                return clipboard::write(snapshot.public_key.clone());
+            }
+            TeamMessage::NameChanged(name) => self.name = name,
+            TeamMessage::PathChanged(path) => self.path = path,
+            TeamMessage::OwnerChanged(owner) => self.owner = owner,
+            TeamMessage::Create => {
+                let path = PathBuf::from(self.path.trim());
+                if self.name.trim().is_empty() || self.owner.trim().is_empty() || self.path.trim().is_empty() {
+                    self.error = Some(i18n::tr("team-create-required"));
+                    return Command::none();
+                }
+                self.busy = true;
+                let name = self.name.clone();
+                let owner = self.owner.clone();
+                return Command::perform(
+                    async move { team.create(path.clone(), &name, &owner).await.map(|_| path) },
+                    |result| Message::Team(TeamMessage::Created(result)),
+                );
+            }
+            TeamMessage::Created(result) => {
+                self.busy = false;
+                match result.and_then(|path| self.attach(path, profiles)) {
+                    Ok(()) => {
+                        self.message = Some(i18n::tr_args("team-created", &[("collection", self.name.trim().into())]));
+                        self.name.clear();
+                        self.path.clear();
+                        return refresh_team();
+                    }
+                    Err(e) => self.fail(e),
+                }
+            }
+            TeamMessage::AttachPathChanged(path) => self.attach_path = path,
+            TeamMessage::Attach => {
+                let path = PathBuf::from(self.attach_path.trim());
+                let result = SharedCollection::load(&path).and_then(|collection| {
+                    self.attach(path, profiles)?;
+                    Ok(collection.name)
+                });
+                match result {
+                    Ok(name) => {
+                        self.message = Some(i18n::tr_args("team-attached", &[("collection", name.into())]));
+                        self.attach_path.clear();
+                        return refresh_team();
+                    }
+                    Err(e) => self.fail(e),
+                }
+            }
+            TeamMessage::Detach(path) => {
+                profiles.current_mut().shared.retain(|shared| shared != &path);
+                match profiles.save() {
+                    Ok(()) => {
+                        self.selected = None;
+                        self.message = Some(i18n::tr("team-detached"));
+                        return refresh_team();
+                    }
+                    Err(e) => self.fail(e),
+                }
+            }
+            TeamMessage::CollectionSelected(choice) => self.selected = Some(choice),
+            TeamMessage::MemberNameChanged(name) => self.member_name = name,
+            TeamMessage::MemberKeyChanged(key) => self.member_key = key,
+            TeamMessage::AddMember => {
+                let Some(collection) = self.selected.clone() else {
+                    return Command::none();
+                };
+                let public_key = match shared::parse_public_key(&self.member_key) {
+                    Ok(key) => key,
+                    Err(e) => {
+                        self.fail(e);
+                        return Command::none();
+                    }
+                };
+                if self.member_name.trim().is_empty() {
+                    self.fail(i18n::tr("team-member-name-required"));
+                    return Command::none();
+                }
+                self.busy = true;
+                let name = self.member_name.clone();
+                self.member_name.clear();
+                self.member_key.clear();
+                return Command::perform(
+                    async move {
+                        team.add_member(&collection.id, name.clone(), public_key).await?;
+                        Ok(i18n::tr_args(
+                            "team-member-added",
+                            &[("member", name.trim().into()), ("collection", collection.name.as_str().into())],
+                        ))
+                    },
+                    |result| Message::Team(TeamMessage::Done(result)),
+                );
+            }
+            TeamMessage::RemoveMember(public_key) => {
+                let Some(collection) = self.selected.clone() else {
+                    return Command::none();
+                };
+                self.busy = true;
+                return Command::perform(
+                    async move {
+                        team.remove_member(&collection.id, public_key).await?;
+                        Ok(i18n::tr_args("team-member-removed", &[("collection", collection.name.as_str().into())]))
+                    },
+                    |result| Message::Team(TeamMessage::Done(result)),
+                );
+            }
+            TeamMessage::Done(result) => {
+                self.busy = false;
+                match result {
+                    Ok(status) => {
+                        self.error = None;
+                        self.message = Some(status);
+                        return refresh_team();
+                    }
+                    Err(e) => self.fail(e),
+                }
+            }
+        }
+        Command::none()
+    }
+
+    fn attach(&mut self, path: PathBuf, profiles: &mut ProfileStore) -> Result<(), String> {
+        let profile = profiles.current_mut();
+        if profile.shared.contains(&path) {
+            return Err(i18n::tr("team-already-attached"));
+        }
+        profile.shared.push(path);
+        self.error = None;
+        profiles.save()
+    }
+
+    fn fail(&mut self, error: String) {
+        self.message = None;
+        self.error = Some(error);
+    }
+
+    pub fn view<'a>(&'a self, snapshot: &'a TeamSnapshot) -> Element<'a, Message> {
+        let header = Row::new()
+            .spacing(20)
+            .push(Button::new(Text::new(i18n::tr("detail-back"))).on_press(Message::BackToMain))
+            .push(Text::new(i18n::tr("team-title")).size(24));
+
+        let identity = Column::new()
+            .spacing(10)
+            .push(Text::new(i18n::tr("team-public-key")).size(20))
+            .push(Text::new(i18n::tr("team-public-key-hint")).size(14))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(snapshot.public_key.clone()).size(14).width(Length::Fill))
+                    .push(action_button(&i18n::tr("action-copy"), Some(Message::Team(TeamMessage::CopyPublicKey)))),
+            );
+
+        let mut collections = Column::new().spacing(8).push(Text::new(i18n::tr("team-collections")).size(20));
+        for attached in &snapshot.collections {
+            collections = collections.push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        Column::new()
+                            .width(Length::Fill)
+                            .push(Text::new(attached.collection.name.clone()).size(16))
+                            .push(
+                                Text::new(i18n::tr_args(
+                                    "team-collection-summary",
+                                    &[
+                                        ("count", attached.collection.members.len().into()),
+                                        ("path", attached.path.display().to_string().into()),
+                                    ],
+                                ))
+                                .size(13),
+                            ),
+                    )
+                    .push(action_button(
+                        &i18n::tr("team-detach"),
+                        Some(Message::Team(TeamMessage::Detach(attached.path.clone()))),
+                    )),
+            );
+        }
+        for error in &snapshot.errors {
+            collections = collections.push(error_text(error));
+        }
+
+        let create = Column::new()
+            .spacing(10)
+            .push(Text::new(i18n::tr("team-new-collection")).size(20))
+            .push(
+                text_field(&i18n::tr("team-collection-name"), &self.name)
+                    .on_input(|name| Message::Team(TeamMessage::NameChanged(name)))
+                    .padding(10),
+            )
+            .push(
+                text_field(&i18n::tr("team-owner-name"), &self.owner)
+                    .on_input(|owner| Message::Team(TeamMessage::OwnerChanged(owner)))
+                    .padding(10),
+            )
+            .push(
+                text_field(&i18n::tr("team-collection-path"), &self.path)
+                    .on_input(|path| Message::Team(TeamMessage::PathChanged(path)))
+                    .on_submit(Message::Team(TeamMessage::Create))
+                    .padding(10),
+            )
+            .push(action_button(&i18n::tr("team-create"), (!self.busy).then_some(Message::Team(TeamMessage::Create))))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        text_field(&i18n::tr("team-attach-path"), &self.attach_path)
+                            .on_input(|path| Message::Team(TeamMessage::AttachPathChanged(path)))
+                            .on_submit(Message::Team(TeamMessage::Attach))
+                            .padding(10)
+                            .width(Length::Fill),
+                    )
+                    .push(action_button(&i18n::tr("team-attach"), Some(Message::Team(TeamMessage::Attach)))),
+            );
+
+        let choices = collection_choices(snapshot);
+        let mut members = Column::new()
+            .spacing(10)
+            .push(Text::new(i18n::tr("team-members")).size(20))
+            .push(PickList::new(choices, self.selected.clone(), |choice| {
+                Message::Team(TeamMessage::CollectionSelected(choice))
+            }).placeholder(i18n::tr("team-choose-collection")));
+
+        let selected = self.selected.as_ref().and_then(|choice| {
+            snapshot.collections.iter().find(|attached| attached.collection.id == choice.id)
+        });
+        if let Some(attached) = selected {
+            for member in &attached.collection.members {
+                let key = member.key_text();
+                let is_me = key == snapshot.public_key;
+                let label = if is_me { i18n::tr_args("team-member-me", &[("member", member.name.as_str().into())]) } else { member.name.clone() };
+                members = members.push(
+                    Row::new()
+                        .spacing(10)
+                        .push(
+                            Column::new()
+                                .width(Length::Fill)
+                                .push(Text::new(label).size(16))
+                                .push(Text::new(key).size(12)),
+                        )
+                        .push(danger_button(
+                            &i18n::tr("team-remove-member"),
+                            (!is_me && !self.busy)
+                                .then(|| Message::Team(TeamMessage::RemoveMember(member.public_key.clone()))),
+                        )),
+                );
+            }
+            members = members
+                .push(
+                    text_field(&i18n::tr("team-member-name"), &self.member_name)
+                        .on_input(|name| Message::Team(TeamMessage::MemberNameChanged(name)))
+                        .padding(10),
+                )
+                .push(
+                    text_field(&i18n::tr("team-member-key"), &self.member_key)
+                        .on_input(|key| Message::Team(TeamMessage::MemberKeyChanged(key)))
+                        .on_submit(Message::Team(TeamMessage::AddMember))
+                        .padding(10),
+                )
+                .push(action_button(&i18n::tr("team-add-member"), (!self.busy).then_some(Message::Team(TeamMessage::AddMember))))
+                .push(Text::new(i18n::tr("team-remove-hint")).size(14));
+        }
+
+        let mut content = Column::new()
+            .spacing(20)
+            .padding(20)
+            .push(header)
+            .push(identity)
+            .push(collections)
+            .push(create)
+            .push(members);
+
+        if let Some(message) = &self.message {
+            content = content.push(Text::new(message).size(16));
+        }
+        if let Some(error) = &self.error {
+            content = content.push(error_text(error));
+        }
+
+        Scrollable::new(content).into()
+    }
+}
+
+fn error_text(error: &str) -> Text<'_> {
+    Text::new(error)
+        .size(16)
+        .style(iced::theme::Text::Color(iced::Color::from_rgb(0.9, 0.2, 0.2)))
+}
+
+fn refresh_team() -> Command<Message> {
+    Command::perform(async {}, |_| Message::RefreshTeam)
+}
+
+// Badge des entrées d'une collection partagée dans la liste
+pub fn shared_badge<'a>(snapshot: &TeamSnapshot, id: &str) -> Option<Element<'a, Message>> {
+    let (collection_id, _) = shared::parse_shared_id(id)?;
+    let name = snapshot
+        .collections
+        .iter()
+        .find(|attached| attached.collection.id == collection_id)
+        .map(|attached| attached.collection.name.clone())?;
+    Some(
+        Text::new(i18n::tr_args("team-shared-badge", &[("collection", name.into())]))
+            .size(12)
+            .style(iced::theme::Text::Color(iced::Color::from_rgb(0.3, 0.5, 0.9)))
+            .into(),
+    )
+}
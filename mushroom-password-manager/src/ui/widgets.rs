@@ -0,0 +1,75 @@
+use iced::widget::canvas::{Cursor, Frame, Geometry, Program};
+use iced::widget::{Button, Row, Text, TextInput};
+use iced::{Alignment, Color, Length, Point, Rectangle, Size, Theme};
+use qrcode::QrCode;
+
+use crate::i18n::tr;
+use crate::ui::app::Message;
+use crate::ui::style::{ButtonType, InputStyle};
+
+// Bouton désactivé tant que l'action n'est pas possible
+pub fn action_button<'a>(label: &str, message: Option<Message>) -> Button<'a, Message> {
+    styled_button(label, message, ButtonType::Primary)
+}
+
+// Action irréversible (suppression, retrait), dans la couleur de danger du thème
+pub fn danger_button<'a>(label: &str, message: Option<Message>) -> Button<'a, Message> {
+    styled_button(label, message, ButtonType::Danger)
+}
+
+fn styled_button<'a>(label: &str, message: Option<Message>, kind: ButtonType) -> Button<'a, Message> {
+    let button = Button::new(Text::new(label.to_string())).padding(10).style(kind.into());
+    match message {
+        Some(message) => button.on_press(message),
+        None => button,
+    }
+}
+
+// Champ de saisie au style du thème
+pub fn text_field<'a>(placeholder: &str, value: &str) -> TextInput<'a, Message> {
+    TextInput::new(placeholder, value).style(InputStyle)
+}
+
+// Mot de passe masqué, sauf à la demande : le bouton voisin bascule l'affichage en clair
+pub fn secret_field<'a>(placeholder: &str, value: &str, revealed: bool, on_input: fn(String) -> Message) -> Row<'a, Message> {
+    let input = text_field(placeholder, value).on_input(on_input).padding(10).width(Length::Fill);
+    let input = if revealed { input } else { input.password() };
+    let toggle = Button::new(Text::new(tr(if revealed { "password-hide" } else { "password-reveal" })))
+        .padding(10)
+        .style(ButtonType::Secondary.into())
+        .on_press(Message::ToggleReveal);
+    Row::new().spacing(10).align_items(Alignment::Center).push(input).push(toggle)
+}
+
+// QR code dessiné sur un canevas : fond blanc et marge de quatre modules,
+// lisibles par les lecteurs de QR codes quel que soit le thème
+pub struct QrCanvas {
+    width: usize,
+    dark: Vec<bool>,
+}
+
+impl QrCanvas {
+    pub fn new(code: &QrCode) -> Self {
+        QrCanvas {
+            width: code.width(),
+            dark: code.to_colors().into_iter().map(|color| color == qrcode::Color::Dark).collect(),
+        }
+    }
+}
+
+impl Program<Message> for QrCanvas {
+    type State = ();
+
+    fn draw(&self, _state: &(), _theme: &Theme, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        frame.fill_rectangle(Point::ORIGIN, bounds.size(), Color::WHITE);
+        let module = frame.width().min(frame.height()) / (self.width + 8) as f32;
+        for (index, dark) in self.dark.iter().enumerate() {
+            if *dark {
+                let (x, y) = ((index % self.width + 4) as f32, (index / self.width + 4) as f32);
+                frame.fill_rectangle(Point::new(x * module, y * module), Size::new(module, module), Color::BLACK);
+            }
+        }
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,387 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::export;
+use crate::i18n;
+use crate::vault::crypto;
+use crate::vault::e2e::EntryCipher;
+
+const AUDIT_FILE: &str = "audit.log";
+// Nombre d'enregistrements et empreinte du dernier : révèle un journal tronqué
+const HEAD_FILE: &str = "audit.head";
+// Identifiant réservé dans les données associées du chiffrement, distinct de toute entrée
+const AUDIT_ID: &str = "\0audit";
+// Empreinte « précédente » du premier enregistrement
+const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+// Opérations journalisées sur les entrées du coffre
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Viewed,
+    Copied,
+    Created,
+    Updated,
+    Deleted,
+    Imported,
+    Exported,
+    Restored,
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let id = match self {
+            AuditAction::Viewed => "audit-action-viewed",
+            AuditAction::Copied => "audit-action-copied",
+            AuditAction::Created => "audit-action-created",
+            AuditAction::Updated => "audit-action-updated",
+            AuditAction::Deleted => "audit-action-deleted",
+            AuditAction::Imported => "audit-action-imported",
+            AuditAction::Exported => "audit-action-exported",
+            AuditAction::Restored => "audit-action-restored",
+        };
+        write!(f, "{}", i18n::tr(id))
+    }
+}
+
+// Un enregistrement du journal ; jamais de secret, seulement l'identifiant de l'entrée
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditRecord {
+    pub seq: u64,
+    pub at: DateTime<Utc>,
+    pub action: AuditAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub detail: String,
+    // Empreinte SHA-256 de l'enregistrement précédent, tel qu'il a été scellé
+    previous: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AuditHead {
+    count: u64,
+    last: String,
+}
+
+// Anomalie du journal ; les numéros d'enregistrement comptent à partir de 1
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuditProblem {
+    Unreadable(u64),
+    BrokenChain(u64),
+    Truncated { count: u64, expected: u64 },
+    HeadMismatch,
+    MissingHead,
+    UnreadableHead(String),
+}
+
+impl fmt::Display for AuditProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            AuditProblem::Unreadable(record) => i18n::tr_args("audit-problem-unreadable", &[("record", (*record).into())]),
+            AuditProblem::BrokenChain(record) => i18n::tr_args("audit-problem-broken-chain", &[("record", (*record).into())]),
+            AuditProblem::Truncated { count, expected } => i18n::tr_args(
+                "audit-problem-truncated",
+                &[("count", (*count).into()), ("expected", (*expected).into())],
+            ),
+            AuditProblem::HeadMismatch => i18n::tr("audit-problem-head-mismatch"),
+            AuditProblem::MissingHead => i18n::tr("audit-problem-missing-head"),
+            AuditProblem::UnreadableHead(error) => error.clone(),
+        };
+        f.write_str(&text)
+    }
+}
+
+// Contenu vérifié du journal : les enregistrements lisibles jusqu'à la première anomalie
+#[derive(Debug, Clone, Default)]
+pub struct AuditTrail {
+    pub records: Vec<AuditRecord>,
+    pub problem: Option<AuditProblem>,
+    // Empreinte du dernier enregistrement lisible
+    last: String,
+}
+
+// Journal des opérations du profil, en ajout seul. Chaque ligne est scellée avec la clé du
+// coffre et son numéro d'ordre ; chaque enregistrement contient l'empreinte du précédent,
+// et l'en-tête scellé celle du dernier : une ligne modifiée, déplacée, supprimée ou un
+// journal tronqué sont détectés à la vérification
+pub struct AuditLog {
+    dir: PathBuf,
+    cipher: Arc<EntryCipher>,
+    count: u64,
+    last: String,
+}
+
+impl fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AuditLog")
+    }
+}
+
+impl AuditLog {
+    // Un journal altéré n'est pas prolongé : la suite masquerait l'anomalie
+    pub fn open(dir: &Path, cipher: Arc<EntryCipher>) -> Result<Self, String> {
+        let trail = verify(dir, &cipher)?;
+        if let Some(problem) = trail.problem {
+            return Err(i18n::tr_args("audit-tampered", &[("problem", problem.to_string().into())]));
+        }
+        let count = trail.records.len() as u64;
+        // L'en-tête existe dès la création : son absence, même après un seul enregistrement,
+        // ne peut venir que d'une suppression
+        if count == 0 {
+            write_head(dir, &cipher, &AuditHead { count, last: trail.last.clone() })?;
+        }
+        Ok(AuditLog { dir: dir.to_path_buf(), cipher, count, last: trail.last })
+    }
+
+    pub fn record(&mut self, action: AuditAction, entry: Option<&str>, detail: &str) -> Result<(), String> {
+        let record = AuditRecord {
+            seq: self.count,
+            at: Utc::now(),
+            action,
+            entry: entry.map(str::to_string),
+            detail: detail.to_string(),
+            previous: self.last.clone(),
+        };
+        let plaintext = serde_json::to_string(&record).map_err(|e| format!("Erreur d'encodage JSON: {}", e))?;
+        let line = self.cipher.seal(AUDIT_ID, &record.seq.to_string(), &plaintext)?;
+
+        let path = self.dir.join(AUDIT_FILE);
+        let mut options = std::fs::OpenOptions::new();
+        options.append(true).create(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(&path)
+            .and_then(|mut file| file.write_all(format!("{}\n", line).as_bytes()))
+            .map_err(|e| format!("Écriture de '{}' impossible: {}", path.display(), e))?;
+
+        self.count += 1;
+        self.last = crypto::sha256_hex(plaintext.as_bytes());
+        write_head(&self.dir, &self.cipher, &AuditHead { count: self.count, last: self.last.clone() })
+    }
+}
+
+// Relit tout le journal et contrôle le chaînage ; seule une erreur de lecture du disque
+// est une erreur, une anomalie du contenu est rapportée dans `problem`
+pub fn verify(dir: &Path, cipher: &EntryCipher) -> Result<AuditTrail, String> {
+    let path = dir.join(AUDIT_FILE);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Lecture de '{}' impossible: {}", path.display(), e)),
+    };
+
+    let mut trail = AuditTrail::default();
+    // Empreintes après chaque enregistrement, la première étant celle d'un journal vide
+    let mut hashes = vec![GENESIS.to_string()];
+    for (seq, line) in content.lines().enumerate() {
+        let seq = seq as u64;
+        let record = cipher
+            .open(AUDIT_ID, &seq.to_string(), line)
+            .ok()
+            .and_then(|plaintext| Some((serde_json::from_str::<AuditRecord>(&plaintext).ok()?, plaintext)));
+        let Some((record, plaintext)) = record else {
+            trail.problem = Some(AuditProblem::Unreadable(seq + 1));
+            return Ok(trail);
+        };
+        if record.seq != seq || Some(&record.previous) != hashes.last() {
+            trail.problem = Some(AuditProblem::BrokenChain(seq + 1));
+            return Ok(trail);
+        }
+        hashes.push(crypto::sha256_hex(plaintext.as_bytes()));
+        trail.records.push(record);
+    }
+
+    let count = trail.records.len() as u64;
+    trail.problem = match read_head(dir, cipher) {
+        Ok(Some(head)) if head.count > count => Some(AuditProblem::Truncated { count, expected: head.count }),
+        // Un enregistrement de plus que l'en-tête : écriture interrompue entre les deux fichiers ;
+        // la ligne est scellée avec la clé du coffre, elle ne peut venir que de l'application
+        Ok(Some(head)) if count - head.count <= 1 && hashes[head.count as usize] == head.last => None,
+        Ok(Some(_)) => Some(AuditProblem::HeadMismatch),
+        Ok(None) if count == 0 => None,
+        Ok(None) => Some(AuditProblem::MissingHead),
+        Err(e) => Some(AuditProblem::UnreadableHead(e)),
+    };
+    trail.last = hashes.pop().unwrap_or_default();
+    Ok(trail)
+}
+
+fn read_head(dir: &Path, cipher: &EntryCipher) -> Result<Option<AuditHead>, String> {
+    let path = dir.join(HEAD_FILE);
+    let sealed = match std::fs::read_to_string(&path) {
+        Ok(sealed) => sealed,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Lecture de '{}' impossible: {}", path.display(), e)),
+    };
+    cipher
+        .open(AUDIT_ID, "head", sealed.trim())
+        .ok()
+        .and_then(|plaintext| serde_json::from_str(&plaintext).ok())
+        .map(Some)
+        .ok_or_else(|| String::from("en-tête du journal illisible ou modifié"))
+}
+
+fn write_head(dir: &Path, cipher: &EntryCipher, head: &AuditHead) -> Result<(), String> {
+    let plaintext = serde_json::to_string(head).map_err(|e| format!("Erreur d'encodage JSON: {}", e))?;
+    let sealed = cipher.seal(AUDIT_ID, "head", &plaintext)?;
+    let path = dir.join(HEAD_FILE);
+    let temporary = path.with_extension("tmp");
+    export::write_private(&temporary, sealed.as_bytes())?;
+    std::fs::rename(&temporary, &path).map_err(|e| format!("Écriture de '{}' impossible: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::e2e;
+    use crate::test_support::{FAST, TestDir};
+
+    fn cipher() -> Arc<EntryCipher> {
+        Arc::new(EntryCipher::derive("motdepasse", &e2e::initial_params(FAST, "motdepasse", None).unwrap(), None).unwrap())
+    }
+
+    // Journal de trois enregistrements
+    fn filled(dir: &Path, cipher: &Arc<EntryCipher>) {
+        let mut log = AuditLog::open(dir, cipher.clone()).unwrap();
+        log.record(AuditAction::Created, Some("forge.example"), "").unwrap();
+        log.record(AuditAction::Copied, Some("forge.example"), "mot de passe").unwrap();
+        log.record(AuditAction::Exported, None, "3 entrées").unwrap();
+    }
+
+    fn lines(dir: &Path) -> Vec<String> {
+        std::fs::read_to_string(dir.join(AUDIT_FILE)).unwrap().lines().map(String::from).collect()
+    }
+
+    fn write_lines(dir: &Path, lines: &[String]) {
+        std::fs::write(dir.join(AUDIT_FILE), lines.iter().map(|line| format!("{}\n", line)).collect::<String>()).unwrap();
+    }
+
+    #[test]
+    fn records_are_chained_and_verified() {
+        let dir = TestDir::new("chain");
+        let cipher = cipher();
+        filled(&dir.0, &cipher);
+
+        let trail = verify(&dir.0, &cipher).unwrap();
+        assert_eq!(trail.problem, None);
+        assert_eq!(trail.records.len(), 3);
+        assert_eq!(trail.records[0].previous, GENESIS);
+        assert_eq!(trail.records[1].action, AuditAction::Copied);
+        assert_eq!(trail.records[1].detail, "mot de passe");
+        assert_eq!(trail.records[2].entry, None);
+
+        // Un journal rouvert continue la numérotation et le chaînage
+        let mut log = AuditLog::open(&dir.0, cipher.clone()).unwrap();
+        log.record(AuditAction::Viewed, Some("forge.example"), "").unwrap();
+        let trail = verify(&dir.0, &cipher).unwrap();
+        assert_eq!(trail.problem, None);
+        assert_eq!(trail.records[3].seq, 3);
+    }
+
+    #[test]
+    fn missing_log_is_empty() {
+        let dir = TestDir::new("empty");
+        let trail = verify(&dir.0, &cipher()).unwrap();
+        assert!(trail.records.is_empty());
+        assert_eq!(trail.problem, None);
+    }
+
+    #[test]
+    fn modified_or_moved_lines_are_detected() {
+        let dir = TestDir::new("tamper");
+        let cipher = cipher();
+        filled(&dir.0, &cipher);
+        let original = lines(&dir.0);
+
+        let mut swapped = original.clone();
+        swapped.swap(0, 1);
+        write_lines(&dir.0, &swapped);
+        let trail = verify(&dir.0, &cipher).unwrap();
+        assert!(trail.records.is_empty());
+        assert_eq!(trail.problem, Some(AuditProblem::Unreadable(1)));
+
+        let mut altered = original.clone();
+        let replacement = if &altered[1][10..11] == "A" { "B" } else { "A" };
+        altered[1].replace_range(10..11, replacement);
+        write_lines(&dir.0, &altered);
+        let trail = verify(&dir.0, &cipher).unwrap();
+        assert_eq!(trail.records.len(), 1);
+        assert_eq!(trail.problem, Some(AuditProblem::Unreadable(2)));
+
+        let mut removed = original;
+        removed.remove(1);
+        write_lines(&dir.0, &removed);
+        assert!(verify(&dir.0, &cipher).unwrap().problem.is_some());
+        // Un journal altéré n'est plus prolongé
+        assert!(AuditLog::open(&dir.0, cipher).is_err());
+    }
+
+    #[test]
+    fn truncation_and_missing_head_are_detected() {
+        let dir = TestDir::new("truncate");
+        let cipher = cipher();
+        filled(&dir.0, &cipher);
+        let original = lines(&dir.0);
+
+        write_lines(&dir.0, &original[..2]);
+        let trail = verify(&dir.0, &cipher).unwrap();
+        assert_eq!(trail.records.len(), 2);
+        assert_eq!(trail.problem, Some(AuditProblem::Truncated { count: 2, expected: 3 }));
+
+        write_lines(&dir.0, &original);
+        std::fs::remove_file(dir.0.join(HEAD_FILE)).unwrap();
+        assert_eq!(verify(&dir.0, &cipher).unwrap().problem, Some(AuditProblem::MissingHead));
+        // Un seul enregistrement sans en-tête n'est pas une écriture interrompue
+        write_lines(&dir.0, &original[..1]);
+        assert_eq!(verify(&dir.0, &cipher).unwrap().problem, Some(AuditProblem::MissingHead));
+    }
+
+    #[test]
+    fn first_write_interrupted_before_head_update_is_accepted() {
+        let dir = TestDir::new("first");
+        let cipher = cipher();
+        let mut log = AuditLog::open(&dir.0, cipher.clone()).unwrap();
+        let head = std::fs::read(dir.0.join(HEAD_FILE)).unwrap();
+        log.record(AuditAction::Imported, None, "2 entrées").unwrap();
+        std::fs::write(dir.0.join(HEAD_FILE), head).unwrap();
+
+        let trail = verify(&dir.0, &cipher).unwrap();
+        assert_eq!(trail.problem, None);
+        assert_eq!(trail.records[0].action, AuditAction::Imported);
+    }
+
+    #[test]
+    fn interrupted_write_before_head_update_is_accepted() {
+        let dir = TestDir::new("interrupted");
+        let cipher = cipher();
+        filled(&dir.0, &cipher);
+        let head = std::fs::read(dir.0.join(HEAD_FILE)).unwrap();
+
+        let mut log = AuditLog::open(&dir.0, cipher.clone()).unwrap();
+        log.record(AuditAction::Updated, Some("forge.example"), "").unwrap();
+        // Ligne ajoutée, en-tête resté à l'état précédent
+        std::fs::write(dir.0.join(HEAD_FILE), head).unwrap();
+
+        let trail = verify(&dir.0, &cipher).unwrap();
+        assert_eq!(trail.problem, None);
+        assert_eq!(trail.records.len(), 4);
+    }
+
+    #[test]
+    fn another_key_cannot_read_the_log() {
+        let dir = TestDir::new("key");
+        filled(&dir.0, &cipher());
+        let trail = verify(&dir.0, &cipher()).unwrap();
+        assert!(trail.records.is_empty());
+        assert!(trail.problem.is_some());
+    }
+}
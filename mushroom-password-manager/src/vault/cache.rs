@@ -0,0 +1,263 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+use std::path::{Path, PathBuf};
+
+use crate::export;
+use crate::vault::crypto::{self, base64_bytes, DecryptError, KdfParams, KEY_LENGTH, NONCE_LENGTH};
+use crate::vault::secure::SecretKey;
+
+const CACHE_FORMAT: &str = "mushroom-cache";
+const CACHE_VERSION: u32 = 1;
+const CACHE_FILE: &str = "cache.mushroom";
+const RECOVERY_AAD: &[u8] = b"mushroom-cache/recovery";
+
+// En-tête en clair du cache local, authentifié avec le contenu chiffré
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CacheHeader {
+    format: String,
+    version: u32,
+    kdf: KdfParams,
+    #[serde(with = "base64_bytes")]
+    salt: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    nonce: Vec<u8>,
+    // Clé du fichier scellée par la clé de récupération du coffre : le kit rouvre
+    // le fichier sans le mot de passe maître
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    recovery: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    header: CacheHeader,
+    #[serde(with = "base64_bytes")]
+    payload: Vec<u8>,
+}
+
+// Clé du cache, dérivée une seule fois du mot de passe maître à la connexion
+pub struct CacheKey {
+    key: SecretKey<KEY_LENGTH>,
+    salt: Vec<u8>,
+    params: KdfParams,
+    recovery: Option<String>,
+}
+
+// Cache du profil dont `dir` est le dossier de données
+pub fn cache_path(dir: &Path) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Création du dossier '{}' impossible: {}", dir.display(), e))?;
+    Ok(dir.join(CACHE_FILE))
+}
+
+impl CacheKey {
+    // Reprend le sel et les paramètres d'un cache existant, sinon en choisit de nouveaux
+    pub fn derive(path: &Path, password: &str) -> Result<Self, String> {
+        let (salt, params, recovery) = match read_file(path)? {
+            Some(file) => (file.header.salt, file.header.kdf, file.header.recovery),
+            None => (crypto::random_bytes::<{ crypto::SALT_LENGTH }>().to_vec(), KdfParams::default(), None),
+        };
+        let key = SecretKey::new(crypto::derive_key(password, &salt, &params)?);
+        Ok(CacheKey { key, salt, params, recovery })
+    }
+
+    // Nouvelle clé, nouveau sel : pour réécrire le cache après un changement de mot de passe
+    pub fn generate(password: &str, params: KdfParams) -> Result<Self, String> {
+        let salt = crypto::random_bytes::<{ crypto::SALT_LENGTH }>().to_vec();
+        let key = SecretKey::new(crypto::derive_key(password, &salt, &params)?);
+        Ok(CacheKey { key, salt, params, recovery: None })
+    }
+
+    // Clé d'un cache existant rouverte par la clé de récupération du coffre, sans dérivation
+    pub fn recover(path: &Path, recovery_key: &SecretKey<KEY_LENGTH>) -> Result<Self, DecryptError> {
+        let file = read_file(path)?.ok_or(DecryptError::NoRecoveryKit)?;
+        let sealed = file.header.recovery.as_deref().ok_or(DecryptError::NoRecoveryKit)?;
+        let key = open_recovery(sealed, recovery_key)?;
+        Ok(CacheKey { key, salt: file.header.salt, params: file.header.kdf, recovery: file.header.recovery })
+    }
+
+    // Scelle la clé du fichier pour la clé de récupération, à la prochaine écriture ;
+    // faux si le scellement en place l'était déjà pour elle
+    pub fn seal_recovery(&mut self, recovery_key: &SecretKey<KEY_LENGTH>) -> Result<bool, String> {
+        let current = self.recovery.as_deref().and_then(|sealed| open_recovery(sealed, recovery_key).ok());
+        if current.is_some_and(|key| key == self.key) {
+            return Ok(false);
+        }
+        let nonce = crypto::random_bytes::<NONCE_LENGTH>();
+        let mut data = nonce.to_vec();
+        data.extend(crypto::seal(recovery_key.bytes(), &nonce, RECOVERY_AAD, self.key.bytes())?);
+        self.recovery = Some(BASE64.encode(data));
+        Ok(true)
+    }
+}
+
+fn open_recovery(sealed: &str, recovery_key: &SecretKey<KEY_LENGTH>) -> Result<SecretKey<KEY_LENGTH>, DecryptError> {
+    let data = BASE64
+        .decode(sealed)
+        .ok()
+        .filter(|data| data.len() > NONCE_LENGTH)
+        .ok_or_else(|| DecryptError::Unreadable(String::from("recovery")))?;
+    let (nonce, ciphertext) = data.split_at(NONCE_LENGTH);
+    let key = Zeroizing::new(crypto::open(recovery_key.bytes(), nonce, RECOVERY_AAD, ciphertext)?);
+    let key = <[u8; KEY_LENGTH]>::try_from(key.as_slice()).map_err(|_| DecryptError::Unreadable(String::from("recovery")))?;
+    Ok(SecretKey::new(key))
+}
+
+fn read_file(path: &Path) -> Result<Option<CacheFile>, String> {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Lecture de '{}' impossible: {}", path.display(), e)),
+    };
+    let file: CacheFile = serde_json::from_slice(&data).map_err(|e| format!("Cache local illisible: {}", e))?;
+    if file.header.format != CACHE_FORMAT {
+        return Err(String::from("Ce fichier n'est pas un cache Mushroom"));
+    }
+    if file.header.version > CACHE_VERSION {
+        return Err(format!("Version de cache {} non prise en charge", file.header.version));
+    }
+    Ok(Some(file))
+}
+
+// Contenu déchiffré du cache ; None s'il n'existe pas encore
+pub fn load<T: DeserializeOwned>(path: &Path, key: &CacheKey) -> Result<Option<T>, DecryptError> {
+    let Some(file) = read_file(path)? else {
+        return Ok(None);
+    };
+    let aad = serde_json::to_vec(&file.header).map_err(|e| format!("Erreur d'encodage JSON: {}", e))?;
+    // Le contenu en clair est effacé une fois désérialisé
+    let plaintext = Zeroizing::new(crypto::open(key.key.bytes(), &file.header.nonce, &aad, &file.payload)?);
+    serde_json::from_slice(&plaintext)
+        .map(Some)
+        .map_err(|e| DecryptError::Unreadable(e.to_string()))
+}
+
+// Réécrit le cache avec un nouveau nonce, via un fichier temporaire renommé
+pub fn save<T: Serialize>(path: &Path, key: &CacheKey, value: &T) -> Result<(), String> {
+    let nonce = crypto::random_bytes::<{ crypto::NONCE_LENGTH }>();
+    let header = CacheHeader {
+        format: CACHE_FORMAT.to_string(),
+        version: CACHE_VERSION,
+        kdf: key.params,
+        salt: key.salt.clone(),
+        nonce: nonce.to_vec(),
+        recovery: key.recovery.clone(),
+    };
+    let aad = serde_json::to_vec(&header).map_err(|e| format!("Erreur d'encodage JSON: {}", e))?;
+    let plaintext = Zeroizing::new(serde_json::to_vec(value).map_err(|e| format!("Erreur d'encodage JSON: {}", e))?);
+    let payload = crypto::seal(key.key.bytes(), &nonce, &aad, &plaintext)?;
+
+    let data = serde_json::to_vec(&CacheFile { header, payload }).map_err(|e| format!("Erreur d'encodage JSON: {}", e))?;
+    let temporary = path.with_extension("tmp");
+    export::write_private(&temporary, &data)?;
+    std::fs::rename(&temporary, path).map_err(|e| format!("Écriture de '{}' impossible: {}", path.display(), e))
+}
+
+// Supprime le cache du profil ; les écritures en attente de synchronisation sont perdues
+pub fn wipe(dir: &Path) -> Result<(), String> {
+    let path = dir.join(CACHE_FILE);
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Suppression de '{}' impossible: {}", path.display(), e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{FAST, TestDir};
+
+    #[test]
+    fn cache_round_trips_and_reuses_its_salt() {
+        let dir = TestDir::new("round-trip");
+        let path = cache_path(&dir.0).unwrap();
+        let key = CacheKey::generate("motdepasse", FAST).unwrap();
+        assert_eq!(load::<Vec<String>>(&path, &key).unwrap(), None);
+
+        let value = vec![String::from("forge.example"), String::from("mail.example")];
+        save(&path, &key, &value).unwrap();
+        assert_eq!(load::<Vec<String>>(&path, &key).unwrap(), Some(value.clone()));
+
+        // La clé se retrouve à partir du sel et des paramètres enregistrés
+        let derived = CacheKey::derive(&path, "motdepasse").unwrap();
+        assert_eq!(derived.params, FAST);
+        assert_eq!(load::<Vec<String>>(&path, &derived).unwrap(), Some(value));
+        assert!(!path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn wrong_password_or_modified_header_is_refused() {
+        let dir = TestDir::new("refused");
+        let path = cache_path(&dir.0).unwrap();
+        save(&path, &CacheKey::generate("motdepasse", FAST).unwrap(), &vec![1u32, 2, 3]).unwrap();
+
+        let wrong = CacheKey::derive(&path, "autre").unwrap();
+        assert_eq!(load::<Vec<u32>>(&path, &wrong).unwrap_err(), DecryptError::WrongSecret);
+
+        // L'en-tête est authentifié avec le contenu
+        let mut file: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        file["header"]["version"] = 0.into();
+        std::fs::write(&path, serde_json::to_vec(&file).unwrap()).unwrap();
+        let key = CacheKey::derive(&path, "motdepasse").unwrap();
+        assert_eq!(load::<Vec<u32>>(&path, &key).unwrap_err(), DecryptError::WrongSecret);
+    }
+
+    #[test]
+    fn foreign_or_newer_files_are_rejected() {
+        let dir = TestDir::new("foreign");
+        let path = cache_path(&dir.0).unwrap();
+        std::fs::write(&path, b"pas du json").unwrap();
+        assert!(CacheKey::derive(&path, "motdepasse").is_err());
+
+        let key = CacheKey::generate("motdepasse", FAST).unwrap();
+        save(&path, &key, &0u8).unwrap();
+        let mut file: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        file["header"]["version"] = (CACHE_VERSION + 1).into();
+        std::fs::write(&path, serde_json::to_vec(&file).unwrap()).unwrap();
+        assert!(load::<u8>(&path, &key).unwrap_err().to_string().contains("non prise en charge"));
+    }
+
+    #[test]
+    fn recovery_key_reopens_the_cache() {
+        let dir = TestDir::new("recovery");
+        let path = cache_path(&dir.0).unwrap();
+        let recovery_key = SecretKey::new(crypto::random_bytes::<KEY_LENGTH>());
+        let mut key = CacheKey::generate("motdepasse", FAST).unwrap();
+        save(&path, &key, &vec![1u32, 2]).unwrap();
+        assert_eq!(CacheKey::recover(&path, &recovery_key).err(), Some(DecryptError::NoRecoveryKit));
+
+        assert!(key.seal_recovery(&recovery_key).unwrap());
+        assert!(!key.seal_recovery(&recovery_key).unwrap());
+        save(&path, &key, &vec![1u32, 2]).unwrap();
+        let recovered = CacheKey::recover(&path, &recovery_key).unwrap();
+        assert_eq!(load::<Vec<u32>>(&path, &recovered).unwrap(), Some(vec![1, 2]));
+        let other = SecretKey::new(crypto::random_bytes::<KEY_LENGTH>());
+        assert_eq!(CacheKey::recover(&path, &other).err(), Some(DecryptError::WrongSecret));
+
+        // Le scellement survit aux écritures suivantes avec la clé dérivée du mot de passe
+        let derived = CacheKey::derive(&path, "motdepasse").unwrap();
+        save(&path, &derived, &vec![3u32]).unwrap();
+        let recovered = CacheKey::recover(&path, &recovery_key).unwrap();
+        assert_eq!(load::<Vec<u32>>(&path, &recovered).unwrap(), Some(vec![3]));
+
+        // Une nouvelle clé de fichier doit être scellée à nouveau
+        let mut rekeyed = CacheKey::generate("nouveau", FAST).unwrap();
+        save(&path, &rekeyed, &vec![4u32]).unwrap();
+        assert_eq!(CacheKey::recover(&path, &recovery_key).err(), Some(DecryptError::NoRecoveryKit));
+        assert!(rekeyed.seal_recovery(&recovery_key).unwrap());
+        save(&path, &rekeyed, &vec![4u32]).unwrap();
+        assert!(CacheKey::recover(&path, &recovery_key).is_ok());
+    }
+
+    #[test]
+    fn wipe_removes_the_cache_and_tolerates_its_absence() {
+        let dir = TestDir::new("wipe");
+        let path = cache_path(&dir.0).unwrap();
+        save(&path, &CacheKey::generate("motdepasse", FAST).unwrap(), &0u8).unwrap();
+        wipe(&dir.0).unwrap();
+        assert!(!path.exists());
+        wipe(&dir.0).unwrap();
+    }
+}
@@ -0,0 +1,295 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::i18n;
+
+pub const KEY_LENGTH: usize = 32;
+pub const SALT_LENGTH: usize = 16;
+pub const NONCE_LENGTH: usize = 24;
+// Échec de déchiffrement. Seul `WrongSecret` signale un secret refusé par l'authentification
+// du chiffrement, ce qui compte comme un essai manqué. Le texte est traduit à l'affichage
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecryptError {
+    // Mauvaise clé ou données modifiées : le chiffrement authentifié ne distingue pas les deux
+    WrongSecret,
+    KeyFileRequired,
+    KeyFileUnexpected,
+    // Aucun kit de récupération ne rouvre ce coffre ou ce fichier
+    NoRecoveryKit,
+    // En-tête sans valeur de contrôle : un mauvais secret ne serait pas détecté
+    MissingCheck,
+    UnsupportedFormat(String),
+    // Déchiffré, mais le contenu ne se relit pas
+    Unreadable(String),
+    Other(String),
+}
+
+impl DecryptError {
+    // Même nature d'échec, précédée de son contexte ; un secret refusé garde un message
+    // générique, le contexte ne va qu'au journal
+    pub fn context(self, context: impl fmt::Display) -> Self {
+        match self {
+            DecryptError::Other(message) => DecryptError::Other(format!("{}: {}", context, message)),
+            error => {
+                log::warn!("{}: {:?}", context, error);
+                error
+            }
+        }
+    }
+}
+
+impl fmt::Display for DecryptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            DecryptError::WrongSecret => i18n::tr("decrypt-error-wrong-secret"),
+            DecryptError::KeyFileRequired => i18n::tr("decrypt-error-key-file-required"),
+            DecryptError::KeyFileUnexpected => i18n::tr("decrypt-error-key-file-unexpected"),
+            DecryptError::NoRecoveryKit => i18n::tr("decrypt-error-no-recovery-kit"),
+            DecryptError::MissingCheck => i18n::tr("decrypt-error-missing-check"),
+            DecryptError::UnsupportedFormat(format) => {
+                i18n::tr_args("decrypt-error-unsupported-format", &[("format", format.as_str().into())])
+            }
+            DecryptError::Unreadable(detail) => i18n::tr_args("decrypt-error-unreadable", &[("detail", detail.as_str().into())]),
+            DecryptError::Other(message) => message.clone(),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl From<String> for DecryptError {
+    fn from(message: String) -> Self {
+        DecryptError::Other(message)
+    }
+}
+
+impl From<DecryptError> for String {
+    fn from(error: DecryptError) -> Self {
+        error.to_string()
+    }
+}
+
+// Durée d'une dérivation visée par la calibration, à la création du coffre
+pub const TARGET_UNLOCK: Duration = Duration::from_millis(500);
+// Plancher OWASP pour Argon2id (19 Mio, 2 passes), même sur une machine lente
+const MIN_MEMORY_MIB: u32 = 19;
+const MIN_ITERATIONS: u32 = 2;
+const MAX_MEMORY_MIB: u32 = 1024;
+// Plafonds des paramètres lus dans une archive, un cache ou l'en-tête envoyé par le serveur :
+// au-delà, une dérivation épuiserait la mémoire ou bloquerait l'application. Bien au-dessus
+// de ce que la calibration et les réglages proposés produisent
+const MAX_ITERATIONS: u32 = 64;
+const MAX_PARALLELISM: u32 = 16;
+// Dérivation d'essai : assez longue pour être mesurée, assez courte pour ne pas faire attendre
+const PROBE: KdfParams = KdfParams { memory_kib: 32 * 1024, iterations: 1, parallelism: 1 };
+
+// Paramètres Argon2id, enregistrés avec les données qu'ils protègent
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    // Recommandation OWASP pour Argon2id : 64 Mio, 3 passes
+    fn default() -> Self {
+        KdfParams {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    // Réglages proposés au changement du mot de passe maître, du plus léger au plus coûteux
+    pub const PRESETS: [KdfParams; 3] = [
+        KdfParams { memory_kib: 64 * 1024, iterations: 3, parallelism: 1 },
+        KdfParams { memory_kib: 128 * 1024, iterations: 4, parallelism: 1 },
+        KdfParams { memory_kib: 256 * 1024, iterations: 4, parallelism: 1 },
+    ];
+
+    // Refuse des coûts qu'aucune version de l'application ne choisit
+    pub fn check_limits(&self) -> Result<(), String> {
+        if self.memory_kib > MAX_MEMORY_MIB * 1024 || self.iterations > MAX_ITERATIONS || self.parallelism > MAX_PARALLELISM {
+            return Err(format!(
+                "Paramètres Argon2 hors limites ({} Kio, {} passes, {} voies)",
+                self.memory_kib, self.iterations, self.parallelism
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for KdfParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let args = [
+            ("memory", (self.memory_kib / 1024).into()),
+            ("iterations", self.iterations.into()),
+            ("parallelism", self.parallelism.into()),
+        ];
+        let id = if self.parallelism > 1 { "kdf-params-parallel" } else { "kdf-params" };
+        f.write_str(&i18n::tr_args(id, &args))
+    }
+}
+
+// Durée d'une dérivation avec ces paramètres sur cette machine
+pub fn measure(params: &KdfParams) -> Result<Duration, String> {
+    let start = Instant::now();
+    derive_key("mushroom-calibration", &[0u8; SALT_LENGTH], params)?;
+    Ok(start.elapsed())
+}
+
+// Choisit les coûts qui font durer une dérivation environ `target` sur cette machine.
+// Le temps d'Argon2id croît avec mémoire × passes : la mémoire, qui coûte le plus
+// à un attaquant, est augmentée d'abord, à 3 passes ; les passes ensuite
+pub fn calibrate(target: Duration) -> Result<KdfParams, String> {
+    let probe = measure(&PROBE)?;
+    let per_mib_pass = probe.as_secs_f64().max(1e-6) / (PROBE.memory_kib / 1024) as f64;
+    // Mio × passes que le budget permet
+    let budget = target.as_secs_f64() / per_mib_pass;
+    let (memory_mib, iterations) = if budget >= f64::from(3 * MAX_MEMORY_MIB) {
+        (MAX_MEMORY_MIB, ((budget / f64::from(MAX_MEMORY_MIB)) as u32).min(MAX_ITERATIONS))
+    } else if budget >= f64::from(3 * MIN_MEMORY_MIB) {
+        ((budget / 3.0) as u32, 3)
+    } else {
+        (((budget / 2.0) as u32).max(MIN_MEMORY_MIB), MIN_ITERATIONS)
+    };
+    Ok(KdfParams { memory_kib: memory_mib * 1024, iterations, parallelism: 1 })
+}
+
+// Dérive une clé de 256 bits d'un mot de passe avec Argon2id. Les paramètres viennent
+// souvent d'un fichier ou du serveur : ils sont bornés avant toute allocation
+pub fn derive_key(password: &str, salt: &[u8], params: &KdfParams) -> Result<[u8; KEY_LENGTH], String> {
+    params.check_limits()?;
+    let argon_params = argon2::Params::new(params.memory_kib, params.iterations, params.parallelism, Some(KEY_LENGTH))
+        .map_err(|e| format!("Paramètres Argon2 invalides: {}", e))?;
+    let mut key = [0u8; KEY_LENGTH];
+    argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, argon_params)
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Échec de la dérivation de clé: {}", e))?;
+    Ok(key)
+}
+
+pub fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+// Chiffrement authentifié XChaCha20-Poly1305 ; `aad` est authentifié sans être chiffré
+pub fn seal(key: &[u8; KEY_LENGTH], nonce: &[u8; NONCE_LENGTH], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    XChaCha20Poly1305::new(key.into())
+        .encrypt(XNonce::from_slice(nonce), Payload { msg: plaintext, aad })
+        .map_err(|_| String::from("Échec du chiffrement"))
+}
+
+// Un échec signifie une clé incorrecte ou des données modifiées, sans distinction possible
+pub fn open(key: &[u8; KEY_LENGTH], nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, DecryptError> {
+    if nonce.len() != NONCE_LENGTH {
+        return Err(DecryptError::Other(String::from("Nonce de chiffrement invalide")));
+    }
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+        .map_err(|_| DecryptError::WrongSecret)
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Comparaison dont la durée ne dépend pas de la position de la première différence
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+// Octets binaires représentés en base64 dans les fichiers JSON
+pub mod base64_bytes {
+    use super::{Engine, BASE64};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&BASE64.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        BASE64.decode(text).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FAST;
+
+    #[test]
+    fn calibration_never_goes_below_the_floor() {
+        // Un budget nul donne le plancher OWASP
+        let params = calibrate(Duration::ZERO).unwrap();
+        assert_eq!(params, KdfParams { memory_kib: MIN_MEMORY_MIB * 1024, iterations: MIN_ITERATIONS, parallelism: 1 });
+
+        let params = calibrate(TARGET_UNLOCK).unwrap();
+        assert!(params.memory_kib >= MIN_MEMORY_MIB * 1024 && params.memory_kib <= MAX_MEMORY_MIB * 1024);
+        assert!(params.iterations >= MIN_ITERATIONS);
+    }
+
+    #[test]
+    fn derivation_depends_on_password_salt_and_costs() {
+        let key = derive_key("motdepasse", b"sel-de-test", &FAST).unwrap();
+        assert_eq!(derive_key("motdepasse", b"sel-de-test", &FAST).unwrap(), key);
+        assert_ne!(derive_key("autre", b"sel-de-test", &FAST).unwrap(), key);
+        assert_ne!(derive_key("motdepasse", b"autre-sel", &FAST).unwrap(), key);
+        let slower = KdfParams { iterations: 2, ..FAST };
+        assert_ne!(derive_key("motdepasse", b"sel-de-test", &slower).unwrap(), key);
+        assert!(derive_key("motdepasse", b"court", &FAST).is_err());
+    }
+
+    #[test]
+    fn oversized_costs_are_refused_before_deriving() {
+        for params in [
+            KdfParams { memory_kib: 64 * 1024 * 1024, ..FAST },
+            KdfParams { iterations: u32::MAX, ..FAST },
+            KdfParams { parallelism: 1024, ..FAST },
+        ] {
+            assert!(params.check_limits().is_err());
+            assert!(derive_key("motdepasse", b"sel-de-test", &params).is_err());
+        }
+        for params in KdfParams::PRESETS {
+            assert!(params.check_limits().is_ok());
+        }
+        let largest = KdfParams { memory_kib: MAX_MEMORY_MIB * 1024, iterations: MAX_ITERATIONS, parallelism: MAX_PARALLELISM };
+        assert!(largest.check_limits().is_ok());
+    }
+
+    #[test]
+    fn sealed_data_is_authenticated() {
+        let key = random_bytes::<KEY_LENGTH>();
+        let nonce = random_bytes::<NONCE_LENGTH>();
+        let sealed = seal(&key, &nonce, b"en-tete", b"secret").unwrap();
+        assert_eq!(open(&key, &nonce, b"en-tete", &sealed).unwrap(), b"secret");
+
+        assert_eq!(open(&key, &nonce, b"autre", &sealed).unwrap_err(), DecryptError::WrongSecret);
+        assert_eq!(open(&random_bytes::<KEY_LENGTH>(), &nonce, b"en-tete", &sealed).unwrap_err(), DecryptError::WrongSecret);
+        let mut altered = sealed.clone();
+        altered[0] ^= 1;
+        assert_eq!(open(&key, &nonce, b"en-tete", &altered).unwrap_err(), DecryptError::WrongSecret);
+        assert!(open(&key, &nonce[..12], b"en-tete", &sealed).is_err());
+    }
+
+    #[test]
+    fn hashing_comparison_and_display() {
+        assert_eq!(sha256_hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"abcd"));
+        assert_eq!(KdfParams::default().to_string(), "Argon2id 64 Mio, 3 passes");
+        assert_eq!(KdfParams { parallelism: 4, ..KdfParams::default() }.to_string(), "Argon2id 64 Mio, 3 passes, 4 fils");
+    }
+}
@@ -0,0 +1,558 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+use std::sync::Arc;
+
+use crate::api::client::ApiClient;
+use crate::vault::crypto::{self, base64_bytes, DecryptError, KdfParams, KEY_LENGTH, NONCE_LENGTH};
+use crate::vault::entry::Entry;
+use crate::vault::factors::{self, KeyFile, RecoveryFactor, UnlockFactors};
+use crate::vault::secure::SecretKey;
+use crate::vault::totp::Totp;
+
+// Marqueur des entrées dont les secrets sont chiffrés par le client
+pub const E2E_FORMAT: &str = "mushroom-e2e/v1";
+// Préfixe de chaque valeur chiffrée : nonce et texte chiffré en base64
+const VALUE_PREFIX: &str = "e2e1:";
+// Identifiant des valeurs chiffrées de l'en-tête du coffre, distinct de toute URL d'entrée
+const VAULT_ID: &str = "\0vault";
+
+// Sel et paramètres Argon2id de la clé du coffre, conservés par le serveur
+// pour que tous les appareils dérivent la même clé du mot de passe maître
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VaultParams {
+    pub format: String,
+    pub kdf: KdfParams,
+    #[serde(with = "base64_bytes")]
+    pub salt: Vec<u8>,
+    #[serde(default, skip_serializing_if = "UnlockFactors::is_empty")]
+    pub factors: UnlockFactors,
+    // Clé des entrées chiffrée par la clé dérivée du mot de passe maître. Absente,
+    // la clé dérivée chiffre directement les entrées, comme avant le premier changement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrapped_key: Option<String>,
+    // Révision de l'en-tête sur le serveur : une modification des facteurs s'appuie sur elle
+    // et échoue si un autre appareil l'a changé entre-temps
+    #[serde(default)]
+    pub revision: u64,
+}
+
+impl VaultParams {
+    pub fn generate(kdf: KdfParams) -> Self {
+        VaultParams {
+            format: E2E_FORMAT.to_string(),
+            kdf,
+            salt: crypto::random_bytes::<{ crypto::SALT_LENGTH }>().to_vec(),
+            factors: UnlockFactors::default(),
+            wrapped_key: None,
+            revision: 0,
+        }
+    }
+}
+
+// Chiffre les secrets des entrées avant l'envoi et les déchiffre à la réception :
+// le mot de passe, les notes et la valeur des champs personnalisés secrets
+pub struct EntryCipher {
+    key: SecretKey<KEY_LENGTH>,
+}
+
+impl std::fmt::Debug for EntryCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EntryCipher")
+    }
+}
+
+impl EntryCipher {
+    // Argon2id : à appeler hors de la boucle de l'interface. La valeur de contrôle de
+    // l'en-tête est exigée : sans elle un mauvais secret ne serait pas détecté
+    pub fn derive(password: &str, params: &VaultParams, key_file: Option<&KeyFile>) -> Result<Self, DecryptError> {
+        let check = params.factors.check.as_deref().ok_or(DecryptError::MissingCheck)?;
+        let cipher = EntryCipher::derive_unchecked(password, params, key_file)?;
+        cipher
+            .open(VAULT_ID, "check", check)
+            .map_err(|_| DecryptError::WrongSecret)?;
+        Ok(cipher)
+    }
+
+    fn derive_unchecked(password: &str, params: &VaultParams, key_file: Option<&KeyFile>) -> Result<Self, DecryptError> {
+        if params.format != E2E_FORMAT {
+            return Err(DecryptError::UnsupportedFormat(params.format.clone()));
+        }
+        match (params.factors.key_file, key_file) {
+            (true, None) => return Err(DecryptError::KeyFileRequired),
+            (false, Some(_)) => return Err(DecryptError::KeyFileUnexpected),
+            _ => {}
+        }
+        let secret = factors::composite_secret(password, key_file);
+        let derived = EntryCipher { key: SecretKey::new(crypto::derive_key(&secret, &params.salt, &params.kdf)?) };
+        match &params.wrapped_key {
+            Some(wrapped) => derived.unwrap_key(wrapped, "key"),
+            None => Ok(derived),
+        }
+    }
+
+    // Clé des entrées rouverte par un kit de récupération, sans mot de passe ni fichier clé
+    pub fn recover(params: &VaultParams, recovery_key: &SecretKey<KEY_LENGTH>) -> Result<Self, DecryptError> {
+        if params.format != E2E_FORMAT {
+            return Err(DecryptError::UnsupportedFormat(params.format.clone()));
+        }
+        let factor = params.factors.recovery.as_ref().ok_or(DecryptError::NoRecoveryKit)?;
+        let check = params.factors.check.as_deref().ok_or(DecryptError::MissingCheck)?;
+        let recovery = EntryCipher { key: recovery_key.clone() };
+        let cipher = recovery.unwrap_key(&factor.wrapped_key, "recovery")?;
+        cipher
+            .open(VAULT_ID, "check", check)
+            .map_err(|_| DecryptError::WrongSecret)?;
+        Ok(cipher)
+    }
+
+    fn unwrap_key(&self, wrapped: &str, field: &str) -> Result<EntryCipher, DecryptError> {
+        let encoded = Zeroizing::new(
+            self.open(VAULT_ID, field, wrapped)
+                .map_err(|_| DecryptError::WrongSecret)?,
+        );
+        let decoded = Zeroizing::new(BASE64.decode(encoded.as_bytes()).map_err(|_| String::from("Clé du coffre invalide"))?);
+        let key = <[u8; KEY_LENGTH]>::try_from(decoded.as_slice()).map_err(|_| String::from("Clé du coffre invalide"))?;
+        Ok(EntryCipher { key: SecretKey::new(key) })
+    }
+
+    // En-tête du coffre pour un nouveau mot de passe maître : la clé des entrées ne change pas,
+    // elle est chiffrée par la clé dérivée du nouveau mot de passe, avec un nouveau sel.
+    // Les facteurs (TOTP, kit de récupération) restent, scellés par la même clé ; le fichier
+    // clé exigé est désormais `key_file`
+    pub fn rekey(&self, params: &VaultParams, password: &str, key_file: Option<&KeyFile>, kdf: KdfParams) -> Result<VaultParams, String> {
+        let salt = crypto::random_bytes::<{ crypto::SALT_LENGTH }>().to_vec();
+        let secret = factors::composite_secret(password, key_file);
+        let wrapping = EntryCipher { key: SecretKey::new(crypto::derive_key(&secret, &salt, &kdf)?) };
+        Ok(VaultParams {
+            kdf,
+            salt,
+            factors: UnlockFactors { key_file: key_file.is_some(), ..params.factors.clone() },
+            wrapped_key: Some(wrapping.seal(VAULT_ID, "key", &BASE64.encode(self.key.bytes()))?),
+            ..params.clone()
+        })
+    }
+
+    // Nouvelle clé de récupération et facteur qui l'inscrit dans l'en-tête. Un nouveau kit
+    // remplace le précédent : l'ancienne clé n'emballe plus rien dans l'en-tête
+    pub fn recovery_factor(&self) -> Result<(SecretKey<KEY_LENGTH>, RecoveryFactor), String> {
+        let recovery = EntryCipher { key: SecretKey::new(crypto::random_bytes::<KEY_LENGTH>()) };
+        let factor = RecoveryFactor {
+            wrapped_key: recovery.seal(VAULT_ID, "recovery", &BASE64.encode(self.key.bytes()))?,
+            sealed_key: self.seal(VAULT_ID, "recovery-key", &BASE64.encode(recovery.key.bytes()))?,
+            token_digest: crypto::sha256_hex(recovery_token(&recovery.key).as_bytes()),
+        };
+        Ok((recovery.key, factor))
+    }
+
+    // Clé de récupération du kit en cours, pour sceller un cache local ; None sans kit
+    pub fn recovery_key(&self, factors: &UnlockFactors) -> Result<Option<SecretKey<KEY_LENGTH>>, String> {
+        factors
+            .recovery
+            .as_ref()
+            .map(|factor| Ok(self.unwrap_key(&factor.sealed_key, "recovery-key")?.key))
+            .transpose()
+    }
+
+    // Clé des instantanés locaux, dérivée de la clé des entrées : un changement de mot
+    // de passe maître ne rechiffre que l'emballage de cette clé, les instantanés restent lisibles
+    pub fn snapshot_key(&self) -> SecretKey<KEY_LENGTH> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.key.bytes()).expect("clé HMAC");
+        mac.update(b"mushroom-snapshot/v1");
+        SecretKey::new(mac.finalize().into_bytes().into())
+    }
+
+    pub fn check_value(&self) -> Result<String, String> {
+        self.seal(VAULT_ID, "check", E2E_FORMAT)
+    }
+
+    // Second facteur TOTP exigé par l'en-tête du coffre, déchiffré
+    pub fn second_factor(&self, factors: &UnlockFactors) -> Result<Option<Totp>, String> {
+        factors
+            .totp
+            .as_ref()
+            .map(|sealed| Totp::parse(&self.open(VAULT_ID, "totp", sealed)?))
+            .transpose()
+    }
+
+    pub fn seal_second_factor(&self, secret: &str) -> Result<String, String> {
+        self.seal(VAULT_ID, "totp", secret)
+    }
+
+    pub fn encrypt_entry(&self, entry: &Entry) -> Result<Entry, String> {
+        let mut encrypted = entry.clone();
+        let id = entry.key().to_string();
+        encrypted.service_password = self.seal(&id, "password", &entry.service_password)?.into();
+        encrypted.notes = self.seal(&id, "notes", &entry.notes)?.into();
+        for field in encrypted.custom_fields.iter_mut().filter(|field| field.kind.is_secret()) {
+            field.value = self.seal(&id, &format!("field:{}", field.name), &field.value)?.into();
+        }
+        encrypted.encryption = Some(E2E_FORMAT.to_string());
+        Ok(encrypted)
+    }
+
+    // Une entrée sans marqueur est rendue telle quelle : ses secrets sont en clair
+    pub fn decrypt_entry(&self, mut entry: Entry) -> Result<Entry, String> {
+        match entry.encryption.as_deref() {
+            None => return Ok(entry),
+            Some(E2E_FORMAT) => {}
+            Some(other) => return Err(format!("Entrée '{}' : format de chiffrement '{}' inconnu", entry.id, other)),
+        }
+        let id = entry.key().to_string();
+        entry.service_password = self.open(&id, "password", &entry.service_password)?.into();
+        entry.notes = self.open(&id, "notes", &entry.notes)?.into();
+        for field in entry.custom_fields.iter_mut().filter(|field| field.kind.is_secret()) {
+            field.value = self.open(&id, &format!("field:{}", field.name), &field.value)?.into();
+        }
+        entry.encryption = None;
+        Ok(entry)
+    }
+
+    // L'entrée et le champ sont authentifiés : une valeur déplacée ailleurs est rejetée
+    pub fn seal(&self, id: &str, field: &str, value: &str) -> Result<String, String> {
+        if value.is_empty() {
+            return Ok(String::new());
+        }
+        let nonce = crypto::random_bytes::<NONCE_LENGTH>();
+        let mut data = nonce.to_vec();
+        data.extend(crypto::seal(self.key.bytes(), &nonce, associated_data(id, field).as_bytes(), value.as_bytes())?);
+        Ok(format!("{}{}", VALUE_PREFIX, BASE64.encode(data)))
+    }
+
+    pub fn open(&self, id: &str, field: &str, value: &str) -> Result<String, String> {
+        if value.is_empty() {
+            return Ok(String::new());
+        }
+        let data = value
+            .strip_prefix(VALUE_PREFIX)
+            .and_then(|encoded| BASE64.decode(encoded).ok())
+            .filter(|data| data.len() > NONCE_LENGTH)
+            .ok_or_else(|| format!("Entrée '{}' : valeur chiffrée invalide ({})", id, field))?;
+        let (nonce, ciphertext) = data.split_at(NONCE_LENGTH);
+        let plaintext = crypto::open(self.key.bytes(), nonce, associated_data(id, field).as_bytes(), ciphertext)
+            .map_err(|_| format!("Entrée '{}' : déchiffrement impossible ({})", id, field))?;
+        String::from_utf8(plaintext).map_err(|_| format!("Entrée '{}' : valeur déchiffrée invalide ({})", id, field))
+    }
+}
+
+// Jeton qui prouve au serveur la possession de la clé de récupération ; il n'en révèle rien
+pub fn recovery_token(recovery_key: &SecretKey<KEY_LENGTH>) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(recovery_key.bytes()).expect("clé HMAC");
+    mac.update(b"mushroom-recovery-token/v1");
+    BASE64.encode(mac.finalize().into_bytes())
+}
+
+fn associated_data(id: &str, field: &str) -> String {
+    format!("{}\0{}\0{}", E2E_FORMAT, id, field)
+}
+
+// Paramètres du coffre enregistrés sur le serveur ; le premier appareil les crée,
+// avec le fichier clé choisi à la connexion
+pub async fn vault_params(client: &ApiClient, password: &str, key_file: Option<&KeyFile>) -> Result<VaultParams, String> {
+    match client.get_vault_params().await? {
+        Some(params) => Ok(params),
+        None => Ok(client.create_vault_params(&new_params(password, key_file).await?).await?),
+    }
+}
+
+// Les coûts d'Argon2id sont calibrés sur la machine qui crée le coffre, puis enregistrés dans son en-tête
+async fn new_params(password: &str, key_file: Option<&KeyFile>) -> Result<VaultParams, String> {
+    let password = password.to_string();
+    let key_file = key_file.cloned();
+    tokio::task::spawn_blocking(move || {
+        let kdf = crypto::calibrate(crypto::TARGET_UNLOCK)?;
+        initial_params(kdf, &password, key_file.as_ref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// La valeur de contrôle distingue un mauvais mot de passe ou fichier clé d'une entrée corrompue
+pub fn initial_params(kdf: KdfParams, password: &str, key_file: Option<&KeyFile>) -> Result<VaultParams, String> {
+    let mut params = VaultParams::generate(kdf);
+    params.factors.key_file = key_file.is_some();
+    let cipher = EntryCipher::derive_unchecked(password, &params, key_file)?;
+    params.factors.check = Some(cipher.check_value()?);
+    Ok(params)
+}
+
+// Dérive la clé du coffre à partir des paramètres du serveur
+pub async fn unlock(client: &ApiClient, password: &str, key_file: Option<KeyFile>) -> Result<(EntryCipher, VaultParams), DecryptError> {
+    let params = vault_params(client, password, key_file.as_ref()).await?;
+    let cipher = derive(password, params.clone(), key_file).await?;
+    Ok((cipher, params))
+}
+
+pub async fn derive(password: &str, params: VaultParams, key_file: Option<KeyFile>) -> Result<EntryCipher, DecryptError> {
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || EntryCipher::derive(&password, &params, key_file.as_ref()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// Vérifie le mot de passe actuel en redérivant la clé du coffre, puis prépare
+// l'en-tête du nouveau mot de passe ; Argon2id deux fois, hors de la boucle de l'interface
+pub async fn change_password(
+    cipher: Arc<EntryCipher>,
+    params: VaultParams,
+    current: String,
+    password: String,
+    key_file: Option<KeyFile>,
+    kdf: KdfParams,
+) -> Result<VaultParams, String> {
+    tokio::task::spawn_blocking(move || {
+        let verified = EntryCipher::derive(&current, &params, key_file.as_ref())
+            .map_err(|_| String::from("Mot de passe maître actuel incorrect"))?;
+        if verified.key != cipher.key {
+            return Err(String::from("Mot de passe maître actuel incorrect"));
+        }
+        cipher.rekey(&params, &password, key_file.as_ref(), kdf)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Nouveau mot de passe maître d'un coffre rouvert par un kit de récupération : l'ancien
+// n'est pas demandé, le kit l'a remplacé. Argon2id, hors de la boucle de l'interface
+pub async fn reset_password(
+    cipher: Arc<EntryCipher>,
+    params: VaultParams,
+    password: String,
+    key_file: Option<KeyFile>,
+    kdf: KdfParams,
+) -> Result<VaultParams, String> {
+    tokio::task::spawn_blocking(move || cipher.rekey(&params, &password, key_file.as_ref(), kdf))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// Le code TOTP contrôle l'ouverture de l'application, il ne renforce pas le chiffrement :
+// son secret est scellé par la clé du coffre, déjà dérivée quand le code est demandé.
+// Seuls le pas courant et le précédent (code saisi à cheval sur deux périodes) sont
+// acceptés, jamais un pas déjà utilisé. Renvoie le pas reconnu, à enregistrer
+pub fn verify_second_factor(totp: &Totp, code: &str, now: u64, last_step: Option<u64>) -> Option<u64> {
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    let current = now / totp.period;
+    let mut accepted = None;
+    // Les deux pas sont toujours comparés, en temps constant
+    for step in [current.saturating_sub(1), current] {
+        let matches = crypto::constant_time_eq(totp.generate(step * totp.period).as_bytes(), code.as_bytes());
+        if matches && last_step.is_none_or(|last| step > last) {
+            accepted = Some(step);
+        }
+    }
+    accepted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::entry::{CustomField, FieldKind};
+    use crate::vault::totp;
+    use crate::test_support::{TestDir, FAST};
+
+    fn entry() -> Entry {
+        let mut entry = Entry::new("forge.example", "Forge");
+        entry.service_password = "motdepasse-du-service".into();
+        entry.notes = "note secrète".into();
+        entry.custom_fields = vec![
+            CustomField { name: String::from("PIN"), value: "1234".into(), kind: FieldKind::Hidden },
+            CustomField { name: String::from("Site"), value: "https://forge.example".into(), kind: FieldKind::Url },
+        ];
+        entry
+    }
+
+    #[test]
+    fn entries_round_trip_with_secrets_sealed() {
+        let params = initial_params(FAST, "motdepasse", None).unwrap();
+        let cipher = EntryCipher::derive("motdepasse", &params, None).unwrap();
+
+        let entry = entry();
+        let encrypted = cipher.encrypt_entry(&entry).unwrap();
+        assert_eq!(encrypted.encryption.as_deref(), Some(E2E_FORMAT));
+        assert!(encrypted.service_password.starts_with(VALUE_PREFIX));
+        assert!(encrypted.notes.starts_with(VALUE_PREFIX));
+        assert!(encrypted.custom_fields[0].value.starts_with(VALUE_PREFIX));
+        // Les champs non secrets restent lisibles par le serveur
+        assert_eq!(&*encrypted.custom_fields[1].value, "https://forge.example");
+
+        assert_eq!(cipher.decrypt_entry(encrypted).unwrap(), entry);
+    }
+
+    #[test]
+    fn sealed_values_are_bound_to_their_entry_and_field() {
+        let cipher = EntryCipher::derive("motdepasse", &initial_params(FAST, "motdepasse", None).unwrap(), None).unwrap();
+        let sealed = cipher.seal("forge.example", "password", "secret").unwrap();
+
+        assert_eq!(cipher.open("forge.example", "password", &sealed).unwrap(), "secret");
+        assert!(cipher.open("autre.example", "password", &sealed).is_err());
+        assert!(cipher.open("forge.example", "notes", &sealed).is_err());
+        assert!(cipher.open("forge.example", "password", "e2e1:AAAA").is_err());
+        assert!(cipher.open("forge.example", "password", "clair").is_err());
+    }
+
+    #[test]
+    fn new_params_always_carry_a_check_value() {
+        let params = initial_params(FAST, "motdepasse", None).unwrap();
+        assert!(!params.factors.key_file);
+        assert!(params.factors.check.is_some());
+
+        // La valeur de contrôle survit à l'aller-retour par le serveur
+        let stored: VaultParams = serde_json::from_value(serde_json::to_value(&params).unwrap()).unwrap();
+        assert_eq!(stored, params);
+
+        let error = EntryCipher::derive("mauvais", &stored, None).unwrap_err();
+        assert_eq!(error, DecryptError::WrongSecret);
+    }
+
+    #[test]
+    fn key_file_vaults_require_their_file() {
+        let dir = TestDir::new("e2e-keyfile");
+        let key_file = KeyFile::generate(&dir.0.join("a.key")).unwrap();
+        let other = KeyFile::generate(&dir.0.join("b.key")).unwrap();
+
+        let params = initial_params(FAST, "motdepasse", Some(&key_file)).unwrap();
+        assert!(params.factors.key_file);
+        assert!(EntryCipher::derive("motdepasse", &params, Some(&key_file)).is_ok());
+        assert!(EntryCipher::derive("motdepasse", &params, Some(&other)).is_err());
+        assert!(EntryCipher::derive("motdepasse", &params, None).is_err());
+    }
+
+    #[test]
+    fn headers_without_a_check_value_are_refused() {
+        let params = VaultParams::generate(FAST);
+        assert_eq!(EntryCipher::derive("motdepasse", &params, None).unwrap_err(), DecryptError::MissingCheck);
+    }
+
+    #[test]
+    fn server_headers_with_oversized_costs_are_refused() {
+        let params = initial_params(FAST, "motdepasse", None).unwrap();
+        let crafted = VaultParams { kdf: KdfParams { iterations: u32::MAX, ..FAST }, ..params };
+        assert!(matches!(EntryCipher::derive("motdepasse", &crafted, None), Err(DecryptError::Other(_))));
+    }
+
+    #[test]
+    fn rekey_keeps_the_entry_key() {
+        let params = initial_params(FAST, "ancien", None).unwrap();
+        let cipher = EntryCipher::derive("ancien", &params, None).unwrap();
+        let entry = entry();
+        let encrypted = cipher.encrypt_entry(&entry).unwrap();
+
+        let rekeyed = cipher.rekey(&params, "nouveau", None, FAST).unwrap();
+        assert_ne!(rekeyed.salt, params.salt);
+        assert!(EntryCipher::derive("ancien", &rekeyed, None).is_err());
+        let reopened = EntryCipher::derive("nouveau", &rekeyed, None).unwrap();
+        assert_eq!(reopened.decrypt_entry(encrypted).unwrap(), entry);
+        assert_eq!(reopened.snapshot_key().bytes(), cipher.snapshot_key().bytes());
+    }
+
+    #[test]
+    fn recovery_key_outlives_password_and_key_file_changes() {
+        let dir = TestDir::new("e2e-recovery");
+        let key_file = KeyFile::generate(&dir.0.join("a.key")).unwrap();
+        let params = initial_params(FAST, "ancien", Some(&key_file)).unwrap();
+        let cipher = EntryCipher::derive("ancien", &params, Some(&key_file)).unwrap();
+        let entry = entry();
+        let encrypted = cipher.encrypt_entry(&entry).unwrap();
+        assert_eq!(EntryCipher::recover(&params, &SecretKey::new([7; KEY_LENGTH])).unwrap_err(), DecryptError::NoRecoveryKit);
+
+        let (recovery_key, factor) = cipher.recovery_factor().unwrap();
+        assert_eq!(factor.token_digest, crypto::sha256_hex(recovery_token(&recovery_key).as_bytes()));
+        let params = VaultParams { factors: UnlockFactors { recovery: Some(factor), ..params.factors }, ..params };
+        let recovered = EntryCipher::recover(&params, &recovery_key).unwrap();
+        assert_eq!(recovered.decrypt_entry(encrypted.clone()).unwrap(), entry);
+        assert_eq!(
+            EntryCipher::recover(&params, &SecretKey::new([7; KEY_LENGTH])).unwrap_err(),
+            DecryptError::WrongSecret
+        );
+
+        // Nouveau mot de passe, sans fichier clé : le kit rouvre toujours le coffre
+        let rekeyed = recovered.rekey(&params, "nouveau", None, FAST).unwrap();
+        assert!(!rekeyed.factors.key_file);
+        assert!(EntryCipher::derive("nouveau", &rekeyed, None).is_ok());
+        let reopened = EntryCipher::recover(&rekeyed, &recovery_key).unwrap();
+        assert_eq!(reopened.decrypt_entry(encrypted).unwrap(), entry);
+        assert!(reopened.recovery_key(&rekeyed.factors).unwrap().unwrap() == recovery_key);
+        assert!(reopened.recovery_key(&UnlockFactors::default()).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn change_password_checks_the_current_password() {
+        let params = initial_params(FAST, "ancien", None).unwrap();
+        let cipher = Arc::new(EntryCipher::derive("ancien", &params, None).unwrap());
+        let stronger = KdfParams { memory_kib: 16, iterations: 2, parallelism: 1 };
+
+        let refused = change_password(cipher.clone(), params.clone(), "faux".into(), "nouveau".into(), None, FAST).await;
+        assert_eq!(refused.unwrap_err(), "Mot de passe maître actuel incorrect");
+
+        let changed = change_password(cipher.clone(), params.clone(), "ancien".into(), "nouveau".into(), None, stronger)
+            .await
+            .unwrap();
+        // Les nouveaux coûts Argon2id sont enregistrés avec l'en-tête
+        assert_eq!(changed.kdf, stronger);
+        assert_eq!(changed.factors, params.factors);
+        let reopened = EntryCipher::derive("nouveau", &changed, None).unwrap();
+        assert!(reopened.key == cipher.key);
+    }
+
+    #[test]
+    fn decrypt_entry_handles_plain_and_unknown_formats() {
+        let cipher = EntryCipher::derive("motdepasse", &initial_params(FAST, "motdepasse", None).unwrap(), None).unwrap();
+        let plain = entry();
+        assert_eq!(cipher.decrypt_entry(plain.clone()).unwrap(), plain);
+
+        let mut unknown = plain;
+        unknown.encryption = Some(String::from("autre/v9"));
+        let error = cipher.decrypt_entry(unknown).unwrap_err();
+        assert!(error.contains("autre/v9"));
+    }
+
+    #[test]
+    fn second_factor_is_sealed_in_the_header() {
+        let params = initial_params(FAST, "motdepasse", None).unwrap();
+        let cipher = EntryCipher::derive("motdepasse", &params, None).unwrap();
+        assert!(cipher.second_factor(&params.factors).unwrap().is_none());
+
+        let factors = UnlockFactors {
+            totp: Some(cipher.seal_second_factor("JBSWY3DPEHPK3PXP").unwrap()),
+            ..params.factors.clone()
+        };
+        let totp = cipher.second_factor(&factors).unwrap().unwrap();
+        assert_eq!(totp.secret, totp::decode_base32("JBSWY3DPEHPK3PXP").unwrap());
+    }
+
+    fn totp() -> Totp {
+        Totp::parse("JBSWY3DPEHPK3PXP").unwrap()
+    }
+
+    #[test]
+    fn second_factor_accepts_current_and_previous_steps_only() {
+        let totp = totp();
+        let now = 1_000_000_020;
+        let step = now / totp.period;
+
+        assert_eq!(verify_second_factor(&totp, &totp.generate(now), now, None), Some(step));
+        assert_eq!(verify_second_factor(&totp, &totp.generate(now - totp.period), now, None), Some(step - 1));
+        assert_eq!(verify_second_factor(&totp, &totp.generate(now + totp.period), now, None), None);
+        assert_eq!(verify_second_factor(&totp, &totp.generate(now - 2 * totp.period), now, None), None);
+        // Les espaces recopiés depuis l'application d'authentification sont ignorés
+        let code = totp.generate(now);
+        assert_eq!(verify_second_factor(&totp, &format!("{} {}", &code[..3], &code[3..]), now, None), Some(step));
+        assert_eq!(verify_second_factor(&totp, "", now, None), None);
+    }
+
+    #[test]
+    fn second_factor_refuses_replayed_codes() {
+        let totp = totp();
+        let now = 1_000_000_020;
+        let step = verify_second_factor(&totp, &totp.generate(now), now, None).unwrap();
+
+        assert_eq!(verify_second_factor(&totp, &totp.generate(now), now, Some(step)), None);
+        assert_eq!(verify_second_factor(&totp, &totp.generate(now - totp.period), now, Some(step)), None);
+        // Le pas suivant reste utilisable
+        let later = now + totp.period;
+        assert_eq!(verify_second_factor(&totp, &totp.generate(later), later, Some(step)), Some(step + 1));
+    }
+}
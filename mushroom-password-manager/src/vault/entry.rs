@@ -0,0 +1,373 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::i18n;
+use crate::vault::secure::SecretString;
+
+// Version courante du schéma des entrées.
+// v1 : format historique du serveur (service_URL, service_name, service_password, email)
+// v2 : identifiant séparé, notes, tags, dossier, champs personnalisés et horodatages
+pub const ENTRY_SCHEMA_VERSION: u32 = 2;
+
+fn legacy_schema_version() -> u32 {
+    1
+}
+
+// Type d'un champ personnalisé
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    #[default]
+    Text,
+    Hidden,
+    Url,
+    Totp,
+}
+
+impl FieldKind {
+    pub const ALL: [FieldKind; 4] = [FieldKind::Text, FieldKind::Hidden, FieldKind::Url, FieldKind::Totp];
+
+    // Les valeurs de ces champs sont des secrets (masqués, chiffrés côté serveur)
+    pub fn is_secret(&self) -> bool {
+        matches!(self, FieldKind::Hidden | FieldKind::Totp)
+    }
+}
+
+impl fmt::Display for FieldKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let id = match self {
+            FieldKind::Text => "field-kind-text",
+            FieldKind::Hidden => "field-kind-hidden",
+            FieldKind::Url => "field-kind-url",
+            FieldKind::Totp => "field-kind-totp",
+        };
+        write!(f, "{}", i18n::tr(id))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct CustomField {
+    pub name: String,
+    #[serde(default)]
+    pub value: SecretString,
+    #[serde(default)]
+    pub kind: FieldKind,
+}
+
+// Entrée du coffre telle qu'échangée avec le serveur
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Entry {
+    // Clé de l'entrée côté serveur (URL saisie à la création)
+    #[serde(skip)]
+    pub id: String,
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
+    // Format du chiffrement de bout en bout des secrets, présent seulement sur le serveur ;
+    // absent sur une entrée du serveur, les secrets y sont en clair (entrée à migrer)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<String>,
+    #[serde(rename = "service_URL", default)]
+    pub service_url: String,
+    #[serde(default)]
+    pub service_name: String,
+    #[serde(default)]
+    pub service_password: SecretString,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub notes: SecretString,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // Chemin du dossier, segments séparés par des '/'
+    #[serde(default)]
+    pub folder: String,
+    #[serde(default)]
+    pub custom_fields: Vec<CustomField>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub modified_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl Entry {
+    pub fn new(service_url: &str, service_name: &str) -> Self {
+        let now = Utc::now();
+        Entry {
+            id: service_url.to_string(),
+            schema_version: ENTRY_SCHEMA_VERSION,
+            encryption: None,
+            service_url: service_url.to_string(),
+            service_name: service_name.to_string(),
+            service_password: SecretString::default(),
+            email: String::new(),
+            username: String::new(),
+            notes: SecretString::default(),
+            tags: Vec::new(),
+            folder: String::new(),
+            custom_fields: Vec::new(),
+            created_at: Some(now),
+            modified_at: Some(now),
+            last_used_at: None,
+        }
+    }
+
+    // Désérialise une entrée du serveur et la migre vers le schéma courant
+    pub fn from_value(id: &str, value: serde_json::Value) -> Result<Self, String> {
+        let mut entry: Entry = serde_json::from_value(value)
+            .map_err(|e| format!("Entrée '{}' invalide: {}", id, e))?;
+        entry.id = id.to_string();
+        entry.migrate();
+        Ok(entry)
+    }
+
+    // Met à niveau une entrée d'un ancien schéma
+    pub fn migrate(&mut self) {
+        if self.schema_version < 2 {
+            // En v1, le champ email servait aussi d'identifiant
+            if self.username.is_empty() {
+                self.username = self.email.clone();
+            }
+        }
+        self.schema_version = ENTRY_SCHEMA_VERSION;
+    }
+
+    // Clé de l'entrée côté serveur ; une nouvelle entrée est indexée par son URL
+    pub fn key(&self) -> &str {
+        if self.id.is_empty() { &self.service_url } else { &self.id }
+    }
+
+    pub fn touch_modified(&mut self) {
+        let now = Utc::now();
+        self.created_at.get_or_insert(now);
+        self.modified_at = Some(now);
+    }
+
+    pub fn touch_used(&mut self) {
+        self.last_used_at = Some(Utc::now());
+    }
+
+    pub fn tags_text(&self) -> String {
+        self.tags.join(", ")
+    }
+
+    pub fn set_tags_from_text(&mut self, text: &str) {
+        self.tags = parse_tags(text);
+    }
+}
+
+// Champs comparés entre deux versions d'une entrée ; les dates ne comptent pas,
+// celle de dernière utilisation changeant à chaque copie
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntryField {
+    Service,
+    Username,
+    Email,
+    Password,
+    Notes,
+    Tags,
+    Folder,
+    CustomFields,
+}
+
+impl EntryField {
+    pub const ALL: [EntryField; 8] = [
+        EntryField::Service,
+        EntryField::Username,
+        EntryField::Email,
+        EntryField::Password,
+        EntryField::Notes,
+        EntryField::Tags,
+        EntryField::Folder,
+        EntryField::CustomFields,
+    ];
+
+    pub fn label(&self) -> String {
+        i18n::tr(match self {
+            EntryField::Service => "entry-diff-service",
+            EntryField::Username => "entry-diff-username",
+            EntryField::Email => "entry-diff-email",
+            EntryField::Password => "entry-diff-password",
+            EntryField::Notes => "entry-diff-notes",
+            EntryField::Tags => "entry-diff-tags",
+            EntryField::Folder => "entry-diff-folder",
+            EntryField::CustomFields => "entry-diff-custom-fields",
+        })
+    }
+
+    pub fn differs(&self, a: &Entry, b: &Entry) -> bool {
+        match self {
+            EntryField::Service => a.service_name != b.service_name || a.service_url != b.service_url,
+            EntryField::Username => a.username != b.username,
+            EntryField::Email => a.email != b.email,
+            EntryField::Password => a.service_password != b.service_password,
+            EntryField::Notes => a.notes != b.notes,
+            EntryField::Tags => a.tags != b.tags,
+            EntryField::Folder => a.folder != b.folder,
+            EntryField::CustomFields => a.custom_fields != b.custom_fields,
+        }
+    }
+
+    // Recopie la valeur du champ de `source` dans `target`
+    pub fn copy(&self, source: &Entry, target: &mut Entry) {
+        match self {
+            EntryField::Service => {
+                target.service_name = source.service_name.clone();
+                target.service_url = source.service_url.clone();
+            }
+            EntryField::Username => target.username = source.username.clone(),
+            EntryField::Email => target.email = source.email.clone(),
+            EntryField::Password => target.service_password = source.service_password.clone(),
+            EntryField::Notes => target.notes = source.notes.clone(),
+            EntryField::Tags => target.tags = source.tags.clone(),
+            EntryField::Folder => target.folder = source.folder.clone(),
+            EntryField::CustomFields => target.custom_fields = source.custom_fields.clone(),
+        }
+    }
+
+    // Valeur affichée pour comparer deux versions ; les secrets restent masqués
+    // et les notes sont lues sur place, sans copie hors de la mémoire verrouillée
+    pub fn display<'a>(&self, entry: &'a Entry) -> Cow<'a, str> {
+        match self {
+            EntryField::Service => Cow::Owned(format!("{} ({})", entry.service_name, entry.service_url)),
+            EntryField::Username => Cow::Borrowed(&entry.username),
+            EntryField::Email => Cow::Borrowed(&entry.email),
+            EntryField::Password => Cow::Owned("•".repeat(entry.service_password.chars().count().min(12))),
+            EntryField::Notes => Cow::Borrowed(entry.notes.as_str()),
+            EntryField::Tags => Cow::Owned(entry.tags_text()),
+            EntryField::Folder => Cow::Borrowed(&entry.folder),
+            EntryField::CustomFields => Cow::Owned(
+                entry
+                    .custom_fields
+                    .iter()
+                    .map(|field| field.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+        }
+    }
+}
+
+// Champs qui diffèrent entre deux versions d'une entrée
+pub fn changed_fields(a: &Entry, b: &Entry) -> Vec<EntryField> {
+    EntryField::ALL.into_iter().filter(|field| field.differs(a, b)).collect()
+}
+
+// Nom du service à partir d'une URL, comme le calcule le serveur
+// ("https://www.compte.auchan.fr/login" -> "compte.auchan")
+pub fn service_name_from_url(url: &str) -> String {
+    // Simplifier l'URL en supprimant http/https/www
+    let url = url.replace("https://", "")
+                .replace("http://", "")
+                .replace("www.", "");
+
+    // Prendre uniquement la partie avant le premier '/'
+    let domain_part = url.split('/').next().unwrap_or("");
+
+    let parts: Vec<&str> = domain_part.split('.').collect();
+    if parts.len() >= 2 {
+        format!("{}.{}", parts[0], parts[1])
+    } else {
+        domain_part.to_string()
+    }
+}
+
+// "perso, banque ,, travail" -> ["perso", "banque", "travail"]
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+        if !tags.iter().any(|existing| existing.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+// Format d'affichage des horodatages
+pub fn format_timestamp(timestamp: &Option<DateTime<Utc>>) -> String {
+    match timestamp {
+        Some(timestamp) => timestamp
+            .with_timezone(&chrono::Local)
+            .format("%d/%m/%Y %H:%M")
+            .to_string(),
+        None => i18n::tr("timestamp-unknown"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v1_entries_migrate_to_the_current_schema() {
+        let value = serde_json::json!({
+            "service_URL": "https://www.auchan.fr",
+            "service_name": "auchan.fr",
+            "service_password": "hunter2",
+            "email": "alice@exemple.fr",
+        });
+        let entry = Entry::from_value("https://www.auchan.fr", value).unwrap();
+        assert_eq!(entry.id, "https://www.auchan.fr");
+        assert_eq!(entry.schema_version, ENTRY_SCHEMA_VERSION);
+        // L'email de la v1 servait d'identifiant
+        assert_eq!(entry.username, "alice@exemple.fr");
+        assert_eq!(&*entry.service_password, "hunter2");
+        assert!(entry.tags.is_empty() && entry.custom_fields.is_empty());
+    }
+
+    #[test]
+    fn migration_keeps_an_explicit_username() {
+        let value = serde_json::json!({ "email": "alice@exemple.fr", "username": "alice" });
+        let entry = Entry::from_value("id", value).unwrap();
+        assert_eq!(entry.username, "alice");
+    }
+
+    #[test]
+    fn serialization_keeps_the_server_field_names() {
+        let entry = Entry::new("https://exemple.fr", "exemple");
+        let value = serde_json::to_value(&entry).unwrap();
+        assert_eq!(value["service_URL"], "https://exemple.fr");
+        // L'identifiant est la clé de l'entrée, pas un champ
+        assert!(value.get("id").is_none());
+        assert!(value.get("encryption").is_none());
+    }
+
+    #[test]
+    fn tags_are_trimmed_and_deduplicated() {
+        assert_eq!(parse_tags("perso, banque ,, Perso,travail"), vec!["perso", "banque", "travail"]);
+        let mut entry = Entry::new("u", "n");
+        entry.set_tags_from_text(" a ,b");
+        assert_eq!(entry.tags_text(), "a, b");
+    }
+
+    #[test]
+    fn changed_fields_ignore_timestamps() {
+        let a = Entry::new("https://exemple.fr", "exemple");
+        let mut b = a.clone();
+        b.touch_used();
+        b.touch_modified();
+        assert!(changed_fields(&a, &b).is_empty());
+
+        b.service_password = "nouveau".into();
+        b.folder = String::from("Perso");
+        assert_eq!(changed_fields(&a, &b), vec![EntryField::Password, EntryField::Folder]);
+
+        let mut c = a.clone();
+        for field in changed_fields(&a, &b) {
+            field.copy(&b, &mut c);
+        }
+        assert!(changed_fields(&b, &c).is_empty());
+    }
+
+    #[test]
+    fn service_name_follows_the_server_rule() {
+        assert_eq!(service_name_from_url("https://www.compte.auchan.fr/login"), "compte.auchan");
+        assert_eq!(service_name_from_url("http://exemple.fr"), "exemple.fr");
+        assert_eq!(service_name_from_url("localhost"), "localhost");
+    }
+}
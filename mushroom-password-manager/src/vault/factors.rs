@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+use crate::i18n;
+
+// Facteurs exigés pour déverrouiller le coffre, enregistrés dans son en-tête
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct UnlockFactors {
+    // La clé du coffre mêle le mot de passe maître et l'empreinte d'un fichier clé
+    #[serde(default)]
+    pub key_file: bool,
+    // Secret TOTP (base32) chiffré avec la clé du coffre
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub totp: Option<String>,
+    // Valeur connue chiffrée avec la clé du coffre : signale un mauvais fichier clé
+    // avant que les entrées ne soient illisibles
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check: Option<String>,
+    // Kit de récupération en cours : une autre façon d'obtenir la clé des entrées
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery: Option<RecoveryFactor>,
+}
+
+// La clé de récupération, aléatoire, n'existe qu'en parts (voir recovery.rs) : elle emballe
+// la clé des entrées sans mot de passe ni fichier clé, et survit donc à leur changement
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecoveryFactor {
+    // Clé des entrées chiffrée par la clé de récupération
+    pub wrapped_key: String,
+    // Clé de récupération chiffrée par la clé des entrées : un appareil déverrouillé
+    // la retrouve pour sceller son cache local, même après un changement de mot de passe
+    pub sealed_key: String,
+    // Empreinte du jeton dérivé de la clé de récupération, que le serveur exige
+    // pour remplacer le mot de passe maître sans session
+    pub token_digest: String,
+}
+
+impl UnlockFactors {
+    // Rien à enregistrer dans l'en-tête, pas même la valeur de contrôle
+    pub fn is_empty(&self) -> bool {
+        *self == UnlockFactors::default()
+    }
+
+    pub fn describe(&self) -> String {
+        let mut factors = vec![i18n::tr("factor-master-password")];
+        if self.key_file {
+            factors.push(i18n::tr("factor-key-file"));
+        }
+        if self.totp.is_some() {
+            factors.push(i18n::tr("factor-totp"));
+        }
+        factors.join(" + ")
+    }
+}
+
+// Fichier clé sur un support amovible : seule son empreinte est gardée en mémoire.
+// Comme KeePass, n'importe quel fichier convient, tant qu'il ne change plus
+#[derive(Clone)]
+pub struct KeyFile {
+    digest: [u8; 32],
+}
+
+impl std::fmt::Debug for KeyFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("KeyFile")
+    }
+}
+
+impl KeyFile {
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let data = std::fs::read(path)
+            .map_err(|e| format!("Lecture du fichier clé '{}' impossible: {}", path.display(), e))?;
+        if data.is_empty() {
+            return Err(format!("Le fichier clé '{}' est vide", path.display()));
+        }
+        Ok(KeyFile { digest: Sha256::digest(&data).into() })
+    }
+
+    // Crée un fichier clé aléatoire, à copier sur un support amovible
+    pub fn generate(path: &Path) -> Result<Self, String> {
+        if path.exists() {
+            return Err(format!("Le fichier '{}' existe déjà", path.display()));
+        }
+        let data = crate::vault::crypto::random_bytes::<64>();
+        crate::export::write_private(path, &data)?;
+        Ok(KeyFile { digest: Sha256::digest(data).into() })
+    }
+}
+
+// Secret passé à Argon2id : le mot de passe seul, ou l'empreinte composée
+// SHA-256(SHA-256(mot de passe) ‖ SHA-256(fichier clé))
+pub fn composite_secret(password: &str, key_file: Option<&KeyFile>) -> String {
+    match key_file {
+        None => password.to_string(),
+        Some(key_file) => {
+            let mut hasher = Sha256::new();
+            hasher.update(Sha256::digest(password.as_bytes()));
+            hasher.update(key_file.digest);
+            hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestDir;
+
+    #[test]
+    fn check_value_alone_is_not_empty() {
+        assert!(UnlockFactors::default().is_empty());
+        let factors = UnlockFactors { check: Some(String::from("e2e1:AAAA")), ..UnlockFactors::default() };
+        assert!(!factors.is_empty());
+        assert_eq!(factors.describe(), "mot de passe maître");
+    }
+
+    #[test]
+    fn key_file_changes_the_composite_secret() {
+        let dir = TestDir::new("composite");
+        let first = KeyFile::generate(&dir.0.join("a.key")).unwrap();
+        let second = KeyFile::generate(&dir.0.join("b.key")).unwrap();
+
+        assert_eq!(composite_secret("motdepasse", None), "motdepasse");
+        let with_first = composite_secret("motdepasse", Some(&first));
+        assert_eq!(with_first.len(), 64);
+        assert_ne!(with_first, composite_secret("motdepasse", Some(&second)));
+        assert_ne!(with_first, composite_secret("autre", Some(&first)));
+        // Relu depuis le support, le fichier donne la même empreinte
+        let reread = KeyFile::read(&dir.0.join("a.key")).unwrap();
+        assert_eq!(composite_secret("motdepasse", Some(&reread)), with_first);
+    }
+
+    #[test]
+    fn rejects_empty_or_existing_key_files() {
+        let dir = TestDir::new("invalid");
+        let empty = dir.0.join("vide.key");
+        std::fs::write(&empty, b"").unwrap();
+        assert!(KeyFile::read(&empty).is_err());
+        assert!(KeyFile::generate(&empty).is_err());
+        assert!(KeyFile::read(&dir.0.join("absent.key")).is_err());
+    }
+}
@@ -0,0 +1,10 @@
+pub mod audit;
+pub mod cache;
+pub mod crypto;
+pub mod e2e;
+pub mod entry;
+pub mod factors;
+pub mod recovery;
+pub mod secure;
+pub mod shared;
+pub mod totp;
\ No newline at end of file
@@ -0,0 +1,254 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use qrcode::render::unicode::Dense1x2;
+use qrcode::QrCode;
+use sha2::{Digest, Sha256};
+use sharks::{Share, Sharks};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use zeroize::Zeroizing;
+
+use crate::i18n;
+use crate::vault::crypto::{self, KEY_LENGTH};
+use crate::vault::secure::SecretKey;
+
+// Préfixe des parts, pour les reconnaître une fois recopiées ou scannées
+const SHARE_PREFIX: &str = "mrk1";
+const CHECK_LENGTH: usize = 4;
+
+// Part d'un kit de récupération : sans le seuil de parts réunies, elle ne révèle rien de la clé
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryShare {
+    pub kit: String,
+    pub threshold: u8,
+    pub count: u8,
+    data: Vec<u8>,
+}
+
+impl RecoveryShare {
+    // Numéro de la part dans le kit (1 à n)
+    pub fn index(&self) -> u8 {
+        self.data[0]
+    }
+
+    pub fn qr_code(&self) -> Result<QrCode, String> {
+        QrCode::new(self.to_string()).map_err(|e| format!("QR code impossible: {}", e))
+    }
+}
+
+impl fmt::Display for RecoveryShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}:{}",
+            SHARE_PREFIX,
+            self.kit,
+            self.threshold,
+            self.count,
+            URL_SAFE_NO_PAD.encode(&self.data)
+        )
+    }
+}
+
+impl FromStr for RecoveryShare {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        let invalid = || format!("Part de récupération invalide: '{}'", text.trim());
+        let parts: Vec<&str> = text.trim().split(':').collect();
+        let [prefix, kit, threshold, count, data] = parts[..] else {
+            return Err(invalid());
+        };
+        if prefix != SHARE_PREFIX {
+            return Err(invalid());
+        }
+        let threshold: u8 = threshold.parse().map_err(|_| invalid())?;
+        let count: u8 = count.parse().map_err(|_| invalid())?;
+        let data = URL_SAFE_NO_PAD.decode(data).map_err(|_| invalid())?;
+        if data.len() < 2 || threshold < 2 || threshold > count {
+            return Err(invalid());
+        }
+        Ok(RecoveryShare { kit: kit.to_string(), threshold, count, data })
+    }
+}
+
+// Kit de récupération d'un profil, à distribuer part par part : ses parts réunies
+// reconstituent la clé de récupération inscrite dans l'en-tête du coffre
+#[derive(Debug, Clone)]
+pub struct RecoveryKit {
+    pub profile: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub shares: Vec<RecoveryShare>,
+}
+
+// Clé puis empreinte : une mauvaise combinaison de parts est détectée
+fn encode_key(key: &SecretKey<KEY_LENGTH>) -> Zeroizing<Vec<u8>> {
+    let mut encoded = Zeroizing::new(key.bytes().to_vec());
+    encoded.extend_from_slice(&Sha256::digest(key.bytes())[..CHECK_LENGTH]);
+    encoded
+}
+
+fn decode_key(encoded: &[u8]) -> Option<SecretKey<KEY_LENGTH>> {
+    let (key, check) = encoded.split_at(encoded.len().checked_sub(CHECK_LENGTH)?);
+    if Sha256::digest(key)[..CHECK_LENGTH] != *check {
+        return None;
+    }
+    Some(SecretKey::new(key.try_into().ok()?))
+}
+
+// Découpe la clé de récupération en `count` parts dont `threshold` suffisent à la reconstituer
+pub fn split(key: &SecretKey<KEY_LENGTH>, profile: &str, threshold: u8, count: u8) -> Result<RecoveryKit, String> {
+    if threshold < 2 || threshold > count {
+        return Err(String::from("Le seuil doit être compris entre 2 et le nombre de parts"));
+    }
+    let kit = crypto::random_bytes::<4>().iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+    let shares = Sharks(threshold)
+        .dealer(&encode_key(key))
+        .take(count as usize)
+        .map(|share| RecoveryShare { kit: kit.clone(), threshold, count, data: Vec::from(&share) })
+        .collect();
+    Ok(RecoveryKit { profile: profile.to_string(), created_at: chrono::Utc::now(), shares })
+}
+
+// Reconstitue la clé de récupération à partir d'au moins `threshold` parts d'un même kit
+pub fn recover(shares: &[RecoveryShare]) -> Result<SecretKey<KEY_LENGTH>, String> {
+    let first = shares.first().ok_or_else(|| String::from("Aucune part saisie"))?;
+    if shares.iter().any(|share| share.kit != first.kit) {
+        return Err(String::from("Les parts proviennent de kits différents"));
+    }
+    // Une même part saisie deux fois ne compte qu'une fois
+    let distinct: BTreeMap<u8, Share> = shares
+        .iter()
+        .map(|share| Ok((share.index(), Share::try_from(share.data.as_slice())?)))
+        .collect::<Result<_, &str>>()
+        .map_err(|e| format!("Part invalide: {}", e))?;
+    if distinct.len() < first.threshold as usize {
+        return Err(format!(
+            "{} part(s) distincte(s) sur les {} nécessaires",
+            distinct.len(),
+            first.threshold
+        ));
+    }
+    let encoded = Zeroizing::new(
+        Sharks(first.threshold)
+            .recover(distinct.values())
+            .map_err(|e| format!("Reconstitution impossible: {}", e))?,
+    );
+    decode_key(&encoded).ok_or_else(|| String::from("Parts altérées ou mal recopiées"))
+}
+
+impl RecoveryKit {
+    // Texte à imprimer : une page par part, avec son QR code
+    pub fn printable(&self) -> Result<String, String> {
+        let mut text = String::new();
+        for share in &self.shares {
+            let code = share.qr_code()?;
+            let qr = code.render::<Dense1x2>().quiet_zone(true).build();
+            let title = i18n::tr_args("recovery-page-title", &[("profile", self.profile.as_str().into())]);
+            let summary = i18n::tr_args(
+                "recovery-page-share",
+                &[
+                    ("date", self.created_at.format("%d/%m/%Y %H:%M").to_string().into()),
+                    ("index", share.index().into()),
+                    ("count", share.count.into()),
+                    ("threshold", share.threshold.into()),
+                ],
+            );
+            text.push_str(&format!(
+                "{}\n{}\n\n{}\n\n{}\n\n{}\n\x0c\n",
+                title,
+                summary,
+                share,
+                qr,
+                i18n::tr("recovery-page-hint")
+            ));
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> SecretKey<KEY_LENGTH> {
+        SecretKey::new(crypto::random_bytes::<KEY_LENGTH>())
+    }
+
+    #[test]
+    fn any_threshold_subset_recovers_the_key() {
+        let key = key();
+        let kit = split(&key, "Travail", 3, 5).unwrap();
+        assert_eq!(kit.shares.len(), 5);
+        let indexes: Vec<u8> = kit.shares.iter().map(RecoveryShare::index).collect();
+        assert_eq!(indexes, vec![1, 2, 3, 4, 5]);
+
+        for subset in [[0, 1, 2], [0, 2, 4], [1, 3, 4], [2, 3, 4]] {
+            let shares: Vec<RecoveryShare> = subset.iter().map(|&index| kit.shares[index].clone()).collect();
+            assert!(recover(&shares).unwrap() == key);
+        }
+        assert!(recover(&kit.shares).unwrap() == key);
+    }
+
+    #[test]
+    fn fewer_than_threshold_shares_are_refused() {
+        let kit = split(&key(), "Perso", 3, 5).unwrap();
+        let error = recover(&kit.shares[..2]).err().unwrap();
+        assert_eq!(error, "2 part(s) distincte(s) sur les 3 nécessaires");
+
+        // Une part saisie deux fois ne compte qu'une fois
+        let repeated = vec![kit.shares[0].clone(), kit.shares[0].clone(), kit.shares[1].clone()];
+        assert!(recover(&repeated).is_err());
+        assert!(recover(&[]).is_err());
+    }
+
+    #[test]
+    fn shares_from_different_kits_are_refused() {
+        let key = key();
+        let first = split(&key, "Perso", 2, 3).unwrap();
+        let second = split(&key, "Perso", 2, 3).unwrap();
+        let mixed = vec![first.shares[0].clone(), second.shares[1].clone()];
+        assert_eq!(recover(&mixed).err().unwrap(), "Les parts proviennent de kits différents");
+    }
+
+    #[test]
+    fn altered_shares_are_detected() {
+        let kit = split(&key(), "Perso", 2, 3).unwrap();
+        let mut altered = kit.shares[1].clone();
+        altered.data[5] ^= 0x01;
+        assert_eq!(
+            recover(&[kit.shares[0].clone(), altered]).err().unwrap(),
+            "Parts altérées ou mal recopiées"
+        );
+    }
+
+    #[test]
+    fn shares_round_trip_through_text() {
+        let kit = split(&key(), "Perso", 2, 3).unwrap();
+        let share = &kit.shares[2];
+        let text = share.to_string();
+        assert!(text.starts_with("mrk1:"));
+        assert_eq!(format!("  {}\n", text).parse::<RecoveryShare>().unwrap(), *share);
+
+        for invalid in ["", "mrk1:abcd:2:3", "mrk2:abcd:2:3:AQI", "mrk1:abcd:1:3:AQI", "mrk1:abcd:4:3:AQI", "mrk1:abcd:2:3:!!"] {
+            assert!(invalid.parse::<RecoveryShare>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn split_validates_its_parameters() {
+        let key = key();
+        assert!(split(&key, "Perso", 1, 3).is_err());
+        assert!(split(&key, "Perso", 4, 3).is_err());
+        assert!(split(&key, "Perso", 3, 3).is_ok());
+    }
+
+    #[test]
+    fn printable_kit_has_one_page_per_share() {
+        let kit = split(&key(), "Perso", 2, 3).unwrap();
+        let text = kit.printable().unwrap();
+        assert_eq!(text.matches('\x0c').count(), 3);
+        assert!(text.contains("part 1 sur 3"));
+        assert!(text.contains(&kit.shares[2].to_string()));
+    }
+}
@@ -0,0 +1,492 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
+use zeroize::Zeroize;
+
+use crate::vault::crypto;
+
+// Une zone n'a pas pu être verrouillée en mémoire (limite RLIMIT_MEMLOCK trop basse)
+static LOCK_FAILED: AtomicBool = AtomicBool::new(false);
+
+// Avertissement à afficher une fois quand des secrets n'ont pas pu être verrouillés en mémoire
+pub fn take_lock_warning() -> Option<String> {
+    LOCK_FAILED.swap(false, Ordering::Relaxed).then(|| {
+        String::from("Mémoire verrouillée insuffisante (ulimit -l) : des secrets peuvent être écrits dans le swap")
+    })
+}
+
+// Zone mémoire pour les secrets : sous Linux, un emplacement dans des pages partagées
+// verrouillées (mlock), exclues des core dumps ; effacée à la libération. Les pages de
+// garde encadrent la zone partagée, pas chaque secret : un débordement vers un emplacement
+// voisin de la même zone n'est pas détecté (voir pool)
+pub struct LockedBuffer {
+    storage: Storage,
+    len: usize,
+}
+
+enum Storage {
+    #[cfg(target_os = "linux")]
+    Pooled(pool::Slot),
+    // Hors Linux, ou quand la réserve ne peut plus grandir : simple allocation
+    Heap(Box<[u8]>),
+}
+
+// L'emplacement appartient au seul LockedBuffer qui le possède
+unsafe impl Send for LockedBuffer {}
+unsafe impl Sync for LockedBuffer {}
+
+impl LockedBuffer {
+    pub fn new(len: usize) -> Self {
+        LockedBuffer { storage: Storage::allocate(len), len }
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        let mut buffer = LockedBuffer::new(bytes.len());
+        buffer.as_mut().copy_from_slice(bytes);
+        buffer
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        match &self.storage {
+            #[cfg(target_os = "linux")]
+            Storage::Pooled(slot) => unsafe { std::slice::from_raw_parts(slot.data(), self.len) },
+            Storage::Heap(data) => data,
+        }
+    }
+
+    pub fn as_mut(&mut self) -> &mut [u8] {
+        match &mut self.storage {
+            #[cfg(target_os = "linux")]
+            Storage::Pooled(slot) => unsafe { std::slice::from_raw_parts_mut(slot.data(), self.len) },
+            Storage::Heap(data) => data,
+        }
+    }
+}
+
+impl Storage {
+    #[cfg(target_os = "linux")]
+    fn allocate(len: usize) -> Self {
+        if len == 0 {
+            return Storage::Heap(Box::default());
+        }
+        match pool::allocate(len) {
+            Ok(slot) => Storage::Pooled(slot),
+            Err(e) => {
+                log::warn!("{}", e);
+                LOCK_FAILED.store(true, Ordering::Relaxed);
+                Storage::Heap(vec![0u8; len].into_boxed_slice())
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn allocate(len: usize) -> Self {
+        Storage::Heap(vec![0u8; len].into_boxed_slice())
+    }
+}
+
+impl Drop for LockedBuffer {
+    fn drop(&mut self) {
+        // Effacé avant que l'emplacement ne retourne à la réserve
+        self.as_mut().zeroize();
+    }
+}
+
+impl Clone for LockedBuffer {
+    fn clone(&self) -> Self {
+        LockedBuffer::from_slice(self.as_slice())
+    }
+}
+
+// Clé symétrique gardée en mémoire verrouillée
+#[derive(Clone)]
+pub struct SecretKey<const N: usize> {
+    buffer: LockedBuffer,
+}
+
+impl<const N: usize> SecretKey<N> {
+    // Le tableau d'origine, copié sur la pile, est effacé
+    pub fn new(mut key: [u8; N]) -> Self {
+        let buffer = LockedBuffer::from_slice(&key);
+        key.zeroize();
+        SecretKey { buffer }
+    }
+
+    pub fn bytes(&self) -> &[u8; N] {
+        self.buffer.as_slice().try_into().expect("taille de clé fixe")
+    }
+}
+
+impl<const N: usize> PartialEq for SecretKey<N> {
+    fn eq(&self, other: &Self) -> bool {
+        crypto::constant_time_eq(self.bytes(), other.bytes())
+    }
+}
+
+// Texte secret (mot de passe, notes) en mémoire verrouillée ; se lit comme un &str
+#[derive(Clone, Default)]
+pub struct SecretString {
+    buffer: Option<LockedBuffer>,
+}
+
+impl SecretString {
+    pub fn new(text: &str) -> Self {
+        SecretString { buffer: (!text.is_empty()).then(|| LockedBuffer::from_slice(text.as_bytes())) }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.buffer {
+            // Toujours rempli à partir d'un &str
+            Some(buffer) => unsafe { std::str::from_utf8_unchecked(buffer.as_slice()) },
+            None => "",
+        }
+    }
+}
+
+impl Deref for SecretString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<String> for SecretString {
+    // La chaîne d'origine est effacée une fois copiée
+    fn from(mut text: String) -> Self {
+        let secret = SecretString::new(&text);
+        text.zeroize();
+        secret
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(text: &str) -> Self {
+        SecretString::new(text)
+    }
+}
+
+// Comparaisons en temps constant, comme pour les clés : la durée ne dépend que des longueurs
+impl PartialEq for SecretString {
+    fn eq(&self, other: &Self) -> bool {
+        crypto::constant_time_eq(self.as_bytes(), other.as_bytes())
+    }
+}
+
+impl Eq for SecretString {}
+
+impl PartialEq<str> for SecretString {
+    fn eq(&self, other: &str) -> bool {
+        crypto::constant_time_eq(self.as_bytes(), other.as_bytes())
+    }
+}
+
+// Jamais affiché dans les traces de débogage
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretString(***)")
+    }
+}
+
+impl Serialize for SecretString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(SecretString::from)
+    }
+}
+
+// Core dumps désactivés tant que le coffre est ouvert ; l'état précédent est rétabli
+// quand la garde est libérée
+pub struct CoreDumpGuard {
+    #[cfg(target_os = "linux")]
+    previous: libc::rlimit,
+    #[cfg(target_os = "linux")]
+    dumpable: libc::c_int,
+}
+
+impl CoreDumpGuard {
+    #[cfg(target_os = "linux")]
+    pub fn acquire() -> Result<Self, String> {
+        let mut previous = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+        unsafe {
+            if libc::getrlimit(libc::RLIMIT_CORE, &mut previous) != 0 {
+                return Err(format!("Lecture de RLIMIT_CORE impossible: {}", std::io::Error::last_os_error()));
+            }
+            let dumpable = libc::prctl(libc::PR_GET_DUMPABLE, 0, 0, 0, 0);
+            if dumpable < 0 {
+                return Err(format!("PR_GET_DUMPABLE refusé: {}", std::io::Error::last_os_error()));
+            }
+            // Seule la limite souple est abaissée : elle pourra être rétablie
+            let disabled = libc::rlimit { rlim_cur: 0, rlim_max: previous.rlim_max };
+            if libc::setrlimit(libc::RLIMIT_CORE, &disabled) != 0 {
+                return Err(format!("Désactivation des core dumps impossible: {}", std::io::Error::last_os_error()));
+            }
+            if libc::prctl(libc::PR_SET_DUMPABLE, 0, 0, 0, 0) != 0 {
+                let error = std::io::Error::last_os_error();
+                libc::setrlimit(libc::RLIMIT_CORE, &previous);
+                return Err(format!("PR_SET_DUMPABLE refusé: {}", error));
+            }
+            Ok(CoreDumpGuard { previous, dumpable })
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn acquire() -> Result<Self, String> {
+        Ok(CoreDumpGuard {})
+    }
+}
+
+impl Drop for CoreDumpGuard {
+    fn drop(&mut self) {
+        #[cfg(target_os = "linux")]
+        unsafe {
+            libc::setrlimit(libc::RLIMIT_CORE, &self.previous);
+            libc::prctl(libc::PR_SET_DUMPABLE, self.dumpable as libc::c_ulong, 0, 0, 0);
+        }
+    }
+}
+
+// Réserve de pages verrouillées partagées entre les secrets : une zone de quelques pages
+// accueille des centaines de secrets courts, sans épuiser RLIMIT_MEMLOCK ni vm.max_map_count
+#[cfg(target_os = "linux")]
+mod pool {
+    use std::ptr;
+    use std::sync::atomic::Ordering;
+    use std::sync::{Mutex, PoisonError};
+
+    use super::LOCK_FAILED;
+
+    // Granularité des emplacements
+    const BLOCK: usize = 32;
+    // Pages de données d'une zone, sauf pour un secret plus grand. Une zone par secret
+    // épuiserait vite RLIMIT_MEMLOCK (souvent 64 Kio, soit 16 pages) avec un coffre de
+    // quelques centaines d'entrées : les secrets se partagent donc les zones
+    const ARENA_PAGES: usize = 4;
+
+    // Une zone vidée est rendue au système ; sa place dans la liste est réutilisée
+    static ARENAS: Mutex<Vec<Option<Arena>>> = Mutex::new(Vec::new());
+
+    // Pages de données précédées et suivies d'une page inaccessible : un débordement
+    // hors de la zone provoque une erreur au lieu de lire ou d'écraser la mémoire voisine.
+    // À l'intérieur de la zone, rien ne sépare deux emplacements : un débordement d'un
+    // secret sur le suivant passe inaperçu, seule la sortie de la zone est interceptée
+    struct Arena {
+        base: *mut u8,
+        total: usize,
+        data_len: usize,
+        locked: bool,
+        used: Vec<bool>,
+    }
+
+    // La zone n'est manipulée que sous le verrou de la réserve
+    unsafe impl Send for Arena {}
+
+    // Blocs réservés dans une zone ; rendus à la libération
+    pub struct Slot {
+        arena: usize,
+        first: usize,
+        blocks: usize,
+        data: *mut u8,
+    }
+
+    impl Slot {
+        pub fn data(&self) -> *mut u8 {
+            self.data
+        }
+    }
+
+    fn page_size() -> usize {
+        unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+    }
+
+    fn lock_arenas() -> std::sync::MutexGuard<'static, Vec<Option<Arena>>> {
+        ARENAS.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn allocate(len: usize) -> Result<Slot, String> {
+        let blocks = len.div_ceil(BLOCK);
+        let mut arenas = lock_arenas();
+        for (index, arena) in arenas.iter_mut().enumerate() {
+            if let Some(slot) = arena.as_mut().and_then(|arena| arena.reserve(index, blocks)) {
+                return Ok(slot);
+            }
+        }
+        let index = arenas.iter().position(Option::is_none).unwrap_or(arenas.len());
+        let mut arena = Arena::map(blocks * BLOCK)?;
+        let slot = arena.reserve(index, blocks).ok_or("Zone mémoire protégée trop petite")?;
+        if index == arenas.len() {
+            arenas.push(Some(arena));
+        } else {
+            arenas[index] = Some(arena);
+        }
+        Ok(slot)
+    }
+
+    // Nombre de zones en service
+    #[cfg(test)]
+    pub fn arena_count() -> usize {
+        lock_arenas().iter().filter(|arena| arena.is_some()).count()
+    }
+
+    impl Arena {
+        fn map(min_len: usize) -> Result<Self, String> {
+            let page = page_size();
+            let data_len = (ARENA_PAGES * page).max(min_len.div_ceil(page) * page);
+            let total = data_len + 2 * page;
+            unsafe {
+                let base = libc::mmap(
+                    ptr::null_mut(),
+                    total,
+                    libc::PROT_NONE,
+                    libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                    -1,
+                    0,
+                );
+                if base == libc::MAP_FAILED {
+                    return Err(format!("Allocation de mémoire protégée impossible: {}", std::io::Error::last_os_error()));
+                }
+                let data = (base as *mut u8).add(page) as *mut libc::c_void;
+                if libc::mprotect(data, data_len, libc::PROT_READ | libc::PROT_WRITE) != 0 {
+                    let error = std::io::Error::last_os_error();
+                    libc::munmap(base, total);
+                    return Err(format!("Protection de la mémoire impossible: {}", error));
+                }
+                libc::madvise(data, data_len, libc::MADV_DONTDUMP);
+                let locked = libc::mlock(data, data_len) == 0;
+                if !locked {
+                    LOCK_FAILED.store(true, Ordering::Relaxed);
+                }
+                Ok(Arena { base: base as *mut u8, total, data_len, locked, used: vec![false; data_len / BLOCK] })
+            }
+        }
+
+        fn data(&self) -> *mut u8 {
+            unsafe { self.base.add(page_size()) }
+        }
+
+        // Premier intervalle de `blocks` blocs libres
+        fn reserve(&mut self, arena: usize, blocks: usize) -> Option<Slot> {
+            let mut run = 0;
+            for index in 0..self.used.len() {
+                run = if self.used[index] { 0 } else { run + 1 };
+                if run == blocks {
+                    let first = index + 1 - blocks;
+                    self.used[first..=index].fill(true);
+                    let data = unsafe { self.data().add(first * BLOCK) };
+                    return Some(Slot { arena, first, blocks, data });
+                }
+            }
+            None
+        }
+    }
+
+    impl Drop for Arena {
+        fn drop(&mut self) {
+            unsafe {
+                if self.locked {
+                    libc::munlock(self.data() as *const libc::c_void, self.data_len);
+                }
+                libc::munmap(self.base as *mut libc::c_void, self.total);
+            }
+        }
+    }
+
+    impl Drop for Slot {
+        fn drop(&mut self) {
+            let mut arenas = lock_arenas();
+            if let Some(entry) = arenas.get_mut(self.arena) {
+                if let Some(arena) = entry {
+                    arena.used[self.first..self.first + self.blocks].fill(false);
+                    if !arena.used.contains(&true) {
+                        *entry = None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffer_round_trip_and_zero_length() {
+        let buffer = LockedBuffer::from_slice(b"secret");
+        assert_eq!(buffer.as_slice(), b"secret");
+        assert!(LockedBuffer::new(0).as_slice().is_empty());
+        assert_eq!(SecretString::new("").as_str(), "");
+    }
+
+    #[test]
+    fn new_buffers_are_zeroed() {
+        // Des emplacements libérés sont réutilisés : ils doivent revenir effacés
+        for _ in 0..50 {
+            let mut buffer = LockedBuffer::new(100);
+            assert!(buffer.as_slice().iter().all(|byte| *byte == 0));
+            buffer.as_mut().fill(0xAA);
+        }
+    }
+
+    #[test]
+    fn large_buffers_get_their_own_zone() {
+        let text = "n".repeat(100_000);
+        let secret = SecretString::new(&text);
+        assert_eq!(secret.as_str(), text);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn many_secrets_share_pages() {
+        let secrets: Vec<SecretString> = (0..2000).map(|i| SecretString::from(format!("mot-de-passe-{}", i))).collect();
+        // Une zone par secret en donnerait 2000 ; d'autres tests en parallèle peuvent en ajouter quelques-unes
+        assert!(pool::arena_count() < 50);
+        for (i, secret) in secrets.iter().enumerate() {
+            assert_eq!(secret.as_str(), format!("mot-de-passe-{}", i));
+        }
+    }
+
+    #[test]
+    fn clones_are_independent() {
+        let original = SecretString::from("secret");
+        let copy = original.clone();
+        drop(original);
+        assert_eq!(copy.as_str(), "secret");
+
+        let key = SecretKey::new([7u8; 32]);
+        assert!(key.clone() == key);
+        assert!(SecretKey::new([8u8; 32]) != key);
+    }
+
+    #[test]
+    fn secret_string_compares_and_serializes() {
+        let secret = SecretString::from("mot de passe");
+        assert_eq!(secret, SecretString::from(String::from("mot de passe")));
+        assert!(secret == *"mot de passe");
+        assert_ne!(secret, SecretString::from("mot de passf"));
+        assert!(secret != *"mot de pass");
+        assert_eq!(SecretString::default(), SecretString::from(""));
+        assert_eq!(format!("{:?}", secret), "SecretString(***)");
+
+        let json = serde_json::to_string(&secret).unwrap();
+        assert_eq!(json, "\"mot de passe\"");
+        let parsed: SecretString = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, secret);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn core_dump_guard_restores_previous_state() {
+        let dumpable = unsafe { libc::prctl(libc::PR_GET_DUMPABLE, 0, 0, 0, 0) };
+        let guard = CoreDumpGuard::acquire().unwrap();
+        assert_eq!(unsafe { libc::prctl(libc::PR_GET_DUMPABLE, 0, 0, 0, 0) }, 0);
+        drop(guard);
+        assert_eq!(unsafe { libc::prctl(libc::PR_GET_DUMPABLE, 0, 0, 0, 0) }, dumpable);
+    }
+}
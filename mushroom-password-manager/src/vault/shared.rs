@@ -0,0 +1,680 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::vault::crypto::{self, base64_bytes, KEY_LENGTH, NONCE_LENGTH};
+use crate::vault::e2e::EntryCipher;
+use crate::vault::entry::Entry;
+
+pub const SHARED_FORMAT: &str = "mushroom-shared/v1";
+// Identifiant des entrées partagées dans la liste : préfixe, collection, entrée
+const SHARED_ID_PREFIX: &str = "shared:";
+const IDENTITY_FILE: &str = "identity.json";
+// Identifiant sous lequel la clé de la liste des membres est chiffrée, distinct des
+// identifiants d'entrée (hexadécimaux)
+const MEMBERSHIP_ID: &str = "\0members";
+
+// Paire de clés X25519 de l'utilisateur pour ce profil ; la clé publique est
+// donnée aux équipes, la clé privée reste chiffrée avec la clé du coffre
+pub struct Identity {
+    secret: StaticSecret,
+}
+
+impl std::fmt::Debug for Identity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Identity").field("public_key", &self.public_key_text()).finish()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct IdentityFile {
+    #[serde(with = "base64_bytes")]
+    public_key: Vec<u8>,
+    // Clé privée chiffrée avec la clé du coffre
+    secret: String,
+}
+
+impl Identity {
+    pub fn public_key(&self) -> [u8; 32] {
+        PublicKey::from(&self.secret).to_bytes()
+    }
+
+    pub fn public_key_text(&self) -> String {
+        BASE64.encode(self.public_key())
+    }
+
+    // Identité du profil dont `dir` est le dossier de données, créée à la première utilisation
+    pub fn load_or_create(dir: &Path, cipher: &EntryCipher) -> Result<Self, String> {
+        let path = dir.join(IDENTITY_FILE);
+        match std::fs::read(&path) {
+            Ok(data) => {
+                let file: IdentityFile =
+                    serde_json::from_slice(&data).map_err(|e| format!("Identité illisible: {}", e))?;
+                let secret = cipher.open(IDENTITY_FILE, "x25519", &file.secret)?;
+                let bytes: [u8; 32] = BASE64
+                    .decode(secret)
+                    .ok()
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .ok_or_else(|| String::from("Identité invalide"))?;
+                Ok(Identity { secret: StaticSecret::from(bytes) })
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let bytes = crypto::random_bytes::<32>();
+                let identity = Identity { secret: StaticSecret::from(bytes) };
+                let file = IdentityFile {
+                    public_key: identity.public_key().to_vec(),
+                    secret: cipher.seal(IDENTITY_FILE, "x25519", &BASE64.encode(bytes))?,
+                };
+                let data = serde_json::to_vec_pretty(&file).map_err(|e| format!("Erreur d'encodage JSON: {}", e))?;
+                crate::export::write_private(&path, &data)?;
+                Ok(identity)
+            }
+            Err(e) => Err(format!("Lecture de '{}' impossible: {}", path.display(), e)),
+        }
+    }
+}
+
+pub fn parse_public_key(text: &str) -> Result<[u8; 32], String> {
+    BASE64
+        .decode(text.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| String::from("Clé publique invalide : 32 octets en base64 attendus"))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub name: String,
+    #[serde(with = "base64_bytes")]
+    pub public_key: Vec<u8>,
+}
+
+impl Member {
+    pub fn key_text(&self) -> String {
+        BASE64.encode(&self.public_key)
+    }
+}
+
+// Clé d'une entrée chiffrée pour un membre : échange X25519 avec une clé éphémère
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct WrappedKey {
+    #[serde(with = "base64_bytes")]
+    ephemeral: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    nonce: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    ciphertext: Vec<u8>,
+}
+
+// Entrée chiffrée avec sa propre clé, elle-même chiffrée pour chaque membre
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SharedEntry {
+    // Indexées par clé publique (base64) du membre
+    keys: BTreeMap<String, WrappedKey>,
+    #[serde(with = "base64_bytes")]
+    nonce: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    ciphertext: Vec<u8>,
+}
+
+// Collection partagée, conservée dans un fichier commun à l'équipe (dossier partagé, dépôt)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SharedCollection {
+    pub format: String,
+    pub id: String,
+    pub name: String,
+    pub members: Vec<Member>,
+    // Clé qui authentifie la liste des membres, chiffrée pour chacun d'eux : quiconque peut
+    // écrire le fichier sans en être membre ne peut pas s'y ajouter pour recevoir les clés
+    membership_keys: BTreeMap<String, WrappedKey>,
+    #[serde(with = "base64_bytes")]
+    membership_tag: Vec<u8>,
+    entries: BTreeMap<String, SharedEntry>,
+}
+
+// Contenu authentifié par l'étiquette de la liste des membres
+#[derive(Serialize)]
+struct MembershipData<'a> {
+    format: &'a str,
+    id: &'a str,
+    name: &'a str,
+    members: &'a [Member],
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Clé de chiffrement de la clé d'entrée, dérivée du secret partagé X25519
+fn wrapping_key(shared: &[u8; 32], ephemeral: &[u8; 32], recipient: &[u8]) -> Result<[u8; KEY_LENGTH], String> {
+    let mut salt = ephemeral.to_vec();
+    salt.extend_from_slice(recipient);
+    let mut key = [0u8; KEY_LENGTH];
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(SHARED_FORMAT.as_bytes(), &mut key)
+        .map_err(|_| String::from("Dérivation de la clé d'enveloppe impossible"))?;
+    Ok(key)
+}
+
+fn wrap(entry_key: &[u8; KEY_LENGTH], entry_id: &str, recipient: &[u8]) -> Result<WrappedKey, String> {
+    let recipient_key: [u8; 32] = recipient.try_into().map_err(|_| String::from("Clé publique invalide"))?;
+    let ephemeral = StaticSecret::from(crypto::random_bytes::<32>());
+    let ephemeral_public = PublicKey::from(&ephemeral).to_bytes();
+    let shared = ephemeral.diffie_hellman(&PublicKey::from(recipient_key));
+    if !shared.was_contributory() {
+        return Err(String::from("Clé publique de membre invalide"));
+    }
+    let key = wrapping_key(shared.as_bytes(), &ephemeral_public, recipient)?;
+    let nonce = crypto::random_bytes::<NONCE_LENGTH>();
+    Ok(WrappedKey {
+        ephemeral: ephemeral_public.to_vec(),
+        nonce: nonce.to_vec(),
+        ciphertext: crypto::seal(&key, &nonce, entry_id.as_bytes(), entry_key)?,
+    })
+}
+
+fn unwrap(wrapped: &WrappedKey, entry_id: &str, identity: &Identity) -> Result<[u8; KEY_LENGTH], String> {
+    let ephemeral: [u8; 32] = wrapped
+        .ephemeral
+        .as_slice()
+        .try_into()
+        .map_err(|_| String::from("Clé éphémère invalide"))?;
+    let shared = identity.secret.diffie_hellman(&PublicKey::from(ephemeral));
+    let key = wrapping_key(shared.as_bytes(), &ephemeral, &identity.public_key())?;
+    crypto::open(&key, &wrapped.nonce, entry_id.as_bytes(), &wrapped.ciphertext)?
+        .try_into()
+        .map_err(|_| String::from("Clé d'entrée invalide"))
+}
+
+impl SharedCollection {
+    // Nouvelle collection dont le créateur est le premier membre
+    pub fn create(name: &str, owner_name: &str, owner: &Identity) -> Result<Self, String> {
+        let mut collection = SharedCollection {
+            format: SHARED_FORMAT.to_string(),
+            id: hex(&crypto::random_bytes::<8>()),
+            name: name.trim().to_string(),
+            members: vec![Member { name: owner_name.trim().to_string(), public_key: owner.public_key().to_vec() }],
+            membership_keys: BTreeMap::new(),
+            membership_tag: Vec::new(),
+            entries: BTreeMap::new(),
+        };
+        collection.seal_members(&crypto::random_bytes::<KEY_LENGTH>())?;
+        Ok(collection)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = std::fs::read(path).map_err(|e| format!("Lecture de '{}' impossible: {}", path.display(), e))?;
+        let collection: SharedCollection =
+            serde_json::from_slice(&data).map_err(|e| format!("Collection partagée illisible: {}", e))?;
+        if collection.format != SHARED_FORMAT {
+            return Err(format!("Format de collection '{}' non pris en charge", collection.format));
+        }
+        Ok(collection)
+    }
+
+    // Fichier lisible par l'équipe : seul le contenu des entrées est secret
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let data = serde_json::to_vec_pretty(self).map_err(|e| format!("Erreur d'encodage JSON: {}", e))?;
+        let temporary = path.with_extension("tmp");
+        std::fs::write(&temporary, data)
+            .map_err(|e| format!("Écriture de '{}' impossible: {}", temporary.display(), e))?;
+        std::fs::rename(&temporary, path).map_err(|e| format!("Écriture de '{}' impossible: {}", path.display(), e))
+    }
+
+    fn membership_tag(&self, key: &[u8; KEY_LENGTH]) -> Result<Vec<u8>, String> {
+        let data = MembershipData { format: &self.format, id: &self.id, name: &self.name, members: &self.members };
+        let data = serde_json::to_vec(&data).map_err(|e| format!("Erreur d'encodage JSON: {}", e))?;
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).map_err(|_| String::from("Clé de la liste des membres invalide"))?;
+        mac.update(&data);
+        Ok(mac.finalize().into_bytes().to_vec())
+    }
+
+    // Chiffre `key` pour chaque membre et authentifie la liste actuelle avec elle
+    fn seal_members(&mut self, key: &[u8; KEY_LENGTH]) -> Result<(), String> {
+        self.membership_keys = self.wrap_for_members(MEMBERSHIP_ID, key)?;
+        self.membership_tag = self.membership_tag(key)?;
+        Ok(())
+    }
+
+    // Clé de la liste des membres, après vérification de la liste : une liste modifiée hors
+    // de l'application est refusée avant que la moindre clé ne soit chiffrée pour ses membres
+    fn membership_key(&self, identity: &Identity) -> Result<[u8; KEY_LENGTH], String> {
+        let wrapped = self
+            .membership_keys
+            .get(&identity.public_key_text())
+            .ok_or_else(|| format!("La collection '{}' n'est pas partagée avec vous", self.name))?;
+        let key = unwrap(wrapped, MEMBERSHIP_ID, identity)?;
+        if !crypto::constant_time_eq(&self.membership_tag(&key)?, &self.membership_tag) {
+            return Err(format!(
+                "La liste des membres de la collection '{}' a été modifiée hors de l'application",
+                self.name
+            ));
+        }
+        Ok(key)
+    }
+
+    fn entry_key(&self, id: &str, identity: &Identity) -> Result<[u8; KEY_LENGTH], String> {
+        let entry = self.entries.get(id).ok_or_else(|| format!("Entrée partagée '{}' introuvable", id))?;
+        let wrapped = entry
+            .keys
+            .get(&identity.public_key_text())
+            .ok_or_else(|| format!("La collection '{}' n'est pas partagée avec vous", self.name))?;
+        unwrap(wrapped, id, identity)
+    }
+
+    fn associated_data(&self, id: &str) -> String {
+        format!("{}\0{}\0{}", SHARED_FORMAT, self.id, id)
+    }
+
+    fn seal_entry(&self, id: &str, key: &[u8; KEY_LENGTH], entry: &Entry) -> Result<(Vec<u8>, Vec<u8>), String> {
+        let plaintext = serde_json::to_vec(entry).map_err(|e| format!("Erreur d'encodage JSON: {}", e))?;
+        let nonce = crypto::random_bytes::<NONCE_LENGTH>();
+        let ciphertext = crypto::seal(key, &nonce, self.associated_data(id).as_bytes(), &plaintext)?;
+        Ok((nonce.to_vec(), ciphertext))
+    }
+
+    fn open_entry(&self, id: &str, key: &[u8; KEY_LENGTH]) -> Result<Entry, String> {
+        let sealed = &self.entries[id];
+        let plaintext = crypto::open(key, &sealed.nonce, self.associated_data(id).as_bytes(), &sealed.ciphertext)?;
+        let value = serde_json::from_slice(&plaintext).map_err(|e| format!("Entrée partagée illisible: {}", e))?;
+        Entry::from_value(&shared_id(&self.id, id), value)
+    }
+
+    // Entrées déchiffrées, identifiées pour la liste principale
+    pub fn entries(&self, identity: &Identity) -> Result<Vec<Entry>, String> {
+        self.membership_key(identity)?;
+        self.entries
+            .keys()
+            .map(|id| self.open_entry(id, &self.entry_key(id, identity)?))
+            .collect()
+    }
+
+    // Ajoute ou remplace une entrée ; une nouvelle entrée reçoit sa propre clé, chiffrée pour chaque membre
+    pub fn upsert(&mut self, identity: &Identity, id: Option<&str>, entry: &Entry) -> Result<String, String> {
+        self.membership_key(identity)?;
+        let (id, key) = match id.filter(|id| self.entries.contains_key(*id)) {
+            Some(id) => (id.to_string(), self.entry_key(id, identity)?),
+            None => (hex(&crypto::random_bytes::<12>()), crypto::random_bytes::<KEY_LENGTH>()),
+        };
+        let keys = match self.entries.get(&id) {
+            Some(existing) => existing.keys.clone(),
+            None => self.wrap_for_members(&id, &key)?,
+        };
+        let (nonce, ciphertext) = self.seal_entry(&id, &key, entry)?;
+        self.entries.insert(id.clone(), SharedEntry { keys, nonce, ciphertext });
+        Ok(id)
+    }
+
+    pub fn remove_entry(&mut self, id: &str) {
+        self.entries.remove(id);
+    }
+
+    fn wrap_for_members(&self, id: &str, key: &[u8; KEY_LENGTH]) -> Result<BTreeMap<String, WrappedKey>, String> {
+        self.members
+            .iter()
+            .map(|member| Ok((member.key_text(), wrap(key, id, &member.public_key)?)))
+            .collect()
+    }
+
+    // Le nouveau membre reçoit la clé de chaque entrée
+    pub fn add_member(&mut self, identity: &Identity, name: &str, public_key: [u8; 32]) -> Result<(), String> {
+        if self.members.iter().any(|member| member.public_key == public_key) {
+            return Err(String::from("Cette clé publique est déjà membre de la collection"));
+        }
+        let membership_key = self.membership_key(identity)?;
+        let member = Member { name: name.trim().to_string(), public_key: public_key.to_vec() };
+        let ids: Vec<String> = self.entries.keys().cloned().collect();
+        for id in ids {
+            let key = self.entry_key(&id, identity)?;
+            let wrapped = wrap(&key, &id, &member.public_key)?;
+            if let Some(entry) = self.entries.get_mut(&id) {
+                entry.keys.insert(member.key_text(), wrapped);
+            }
+        }
+        self.membership_keys.insert(member.key_text(), wrap(&membership_key, MEMBERSHIP_ID, &member.public_key)?);
+        self.members.push(member);
+        self.membership_tag = self.membership_tag(&membership_key)?;
+        Ok(())
+    }
+
+    // Retire un membre et change la clé de chaque entrée : les clés qu'il a pu
+    // conserver ne déchiffrent plus les versions suivantes
+    pub fn remove_member(&mut self, identity: &Identity, public_key: &[u8]) -> Result<(), String> {
+        if public_key == identity.public_key() {
+            return Err(String::from("Vous ne pouvez pas vous retirer vous-même de la collection"));
+        }
+        if !self.members.iter().any(|member| member.public_key == public_key) {
+            return Err(String::from("Membre introuvable"));
+        }
+        self.membership_key(identity)?;
+        let ids: Vec<String> = self.entries.keys().cloned().collect();
+        let mut rotated = BTreeMap::new();
+        for id in &ids {
+            let entry = self.open_entry(id, &self.entry_key(id, identity)?)?;
+            rotated.insert(id.clone(), entry);
+        }
+
+        self.members.retain(|member| member.public_key != public_key);
+        // Nouvelle clé de liste : l'ancien membre ne peut plus authentifier une liste modifiée
+        self.seal_members(&crypto::random_bytes::<KEY_LENGTH>())?;
+        for (id, entry) in rotated {
+            let key = crypto::random_bytes::<KEY_LENGTH>();
+            let keys = self.wrap_for_members(&id, &key)?;
+            let (nonce, ciphertext) = self.seal_entry(&id, &key, &entry)?;
+            self.entries.insert(id, SharedEntry { keys, nonce, ciphertext });
+        }
+        Ok(())
+    }
+}
+
+pub fn shared_id(collection: &str, entry: &str) -> String {
+    format!("{}{}:{}", SHARED_ID_PREFIX, collection, entry)
+}
+
+// Collection et entrée désignées par l'identifiant d'une entrée partagée
+pub fn parse_shared_id(id: &str) -> Option<(&str, &str)> {
+    id.strip_prefix(SHARED_ID_PREFIX)?.split_once(':')
+}
+
+// Collection rattachée au profil et son fichier
+#[derive(Debug, Clone)]
+pub struct Attached {
+    pub path: PathBuf,
+    pub collection: SharedCollection,
+}
+
+// Collections partagées du profil ouvert, entre l'interface et les tâches d'écriture
+#[derive(Clone)]
+pub struct TeamHandle {
+    identity: Arc<Identity>,
+    collections: Arc<Mutex<Vec<Attached>>>,
+}
+
+impl std::fmt::Debug for TeamHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TeamHandle")
+    }
+}
+
+// Entrées partagées et nom de leur collection
+#[derive(Debug, Clone, Default)]
+pub struct TeamSnapshot {
+    pub public_key: String,
+    pub collections: Vec<Attached>,
+    pub entries: Vec<(Entry, String)>,
+    // Collections illisibles, avec la raison
+    pub errors: Vec<String>,
+}
+
+impl TeamHandle {
+    // Charge l'identité du profil et les collections rattachées
+    pub async fn open(dir: PathBuf, cipher: Arc<EntryCipher>, paths: Vec<PathBuf>) -> Result<Self, String> {
+        let identity = Identity::load_or_create(&dir, &cipher)?;
+        let mut collections = Vec::new();
+        for path in paths {
+            // Une collection illisible reste rattachée : elle est signalée à chaque lecture
+            if let Ok(collection) = SharedCollection::load(&path) {
+                collections.push(Attached { path, collection });
+            }
+        }
+        Ok(TeamHandle { identity: Arc::new(identity), collections: Arc::new(Mutex::new(collections)) })
+    }
+
+    // Relit les fichiers, modifiés par les autres membres, et déchiffre les entrées
+    pub async fn snapshot(&self, paths: Vec<PathBuf>) -> TeamSnapshot {
+        let mut collections = self.collections.lock().await;
+        let mut snapshot = TeamSnapshot { public_key: self.identity.public_key_text(), ..TeamSnapshot::default() };
+        collections.clear();
+        for path in paths {
+            match SharedCollection::load(&path) {
+                Ok(collection) => {
+                    match collection.entries(&self.identity) {
+                        Ok(entries) => snapshot
+                            .entries
+                            .extend(entries.into_iter().map(|entry| (entry, collection.name.clone()))),
+                        Err(e) => snapshot.errors.push(format!("{}: {}", collection.name, e)),
+                    }
+                    collections.push(Attached { path, collection });
+                }
+                Err(e) => snapshot.errors.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+        snapshot.collections = collections.clone();
+        snapshot
+    }
+
+    // Applique une modification à une collection puis réécrit son fichier
+    async fn modify<T>(
+        &self,
+        collection_id: &str,
+        change: impl FnOnce(&mut SharedCollection, &Identity) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut collections = self.collections.lock().await;
+        let attached = collections
+            .iter_mut()
+            .find(|attached| attached.collection.id == collection_id)
+            .ok_or_else(|| String::from("Collection partagée introuvable"))?;
+        // Relue juste avant l'écriture pour ne pas écraser les ajouts des autres membres
+        let mut collection = SharedCollection::load(&attached.path)?;
+        let result = change(&mut collection, &self.identity)?;
+        collection.save(&attached.path)?;
+        attached.collection = collection;
+        Ok(result)
+    }
+
+    pub async fn create(&self, path: PathBuf, name: &str, owner_name: &str) -> Result<(), String> {
+        if path.exists() {
+            return Err(format!("Le fichier '{}' existe déjà", path.display()));
+        }
+        let collection = SharedCollection::create(name, owner_name, &self.identity)?;
+        collection.save(&path)?;
+        self.collections.lock().await.push(Attached { path, collection });
+        Ok(())
+    }
+
+    // Enregistre une entrée partagée modifiée
+    pub async fn save_entry(&self, entry: Entry) -> Result<(), String> {
+        let (collection_id, id) =
+            parse_shared_id(&entry.id).ok_or_else(|| String::from("Entrée non partagée"))?;
+        let id = id.to_string();
+        self.modify(collection_id, |collection, identity| collection.upsert(identity, Some(&id), &entry).map(|_| ()))
+            .await
+    }
+
+    // Copie une entrée personnelle dans une collection
+    pub async fn share(&self, collection_id: &str, mut entry: Entry) -> Result<(), String> {
+        entry.id = String::new();
+        entry.encryption = None;
+        self.modify(collection_id, |collection, identity| collection.upsert(identity, None, &entry).map(|_| ()))
+            .await
+    }
+
+    pub async fn remove_entry(&self, shared_id: &str) -> Result<(), String> {
+        let (collection_id, id) = parse_shared_id(shared_id).ok_or_else(|| String::from("Entrée non partagée"))?;
+        let id = id.to_string();
+        self.modify(collection_id, |collection, _| {
+            collection.remove_entry(&id);
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn add_member(&self, collection_id: &str, name: String, public_key: [u8; 32]) -> Result<(), String> {
+        self.modify(collection_id, |collection, identity| collection.add_member(identity, &name, public_key))
+            .await
+    }
+
+    pub async fn remove_member(&self, collection_id: &str, public_key: Vec<u8>) -> Result<(), String> {
+        self.modify(collection_id, |collection, identity| collection.remove_member(identity, &public_key))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::e2e;
+    use crate::test_support::{FAST, TestDir};
+
+    fn identity() -> Identity {
+        Identity { secret: StaticSecret::from(crypto::random_bytes::<32>()) }
+    }
+
+    fn entry(password: &str) -> Entry {
+        let mut entry = Entry::new("forge.example", "Forge");
+        entry.service_password = password.into();
+        entry
+    }
+
+    #[test]
+    fn identity_is_created_once_and_sealed_with_the_vault_key() {
+        let dir = TestDir::new("identity");
+        let cipher = EntryCipher::derive("motdepasse", &e2e::initial_params(FAST, "motdepasse", None).unwrap(), None).unwrap();
+        let created = Identity::load_or_create(&dir.0, &cipher).unwrap();
+        let loaded = Identity::load_or_create(&dir.0, &cipher).unwrap();
+        assert_eq!(created.public_key(), loaded.public_key());
+        assert_eq!(parse_public_key(&created.public_key_text()).unwrap(), created.public_key());
+
+        let other = EntryCipher::derive("motdepasse", &e2e::initial_params(FAST, "motdepasse", None).unwrap(), None).unwrap();
+        assert!(Identity::load_or_create(&dir.0, &other).is_err());
+        assert!(parse_public_key("trop court").is_err());
+    }
+
+    #[test]
+    fn members_read_entries_and_outsiders_do_not() {
+        let owner = identity();
+        let member = identity();
+        let outsider = identity();
+        let mut collection = SharedCollection::create(" Équipe ", "Alice", &owner).unwrap();
+        assert_eq!(collection.name, "Équipe");
+
+        let id = collection.upsert(&owner, None, &entry("premier")).unwrap();
+        // Un membre ajouté ensuite reçoit la clé des entrées existantes
+        collection.add_member(&owner, "Bob", member.public_key()).unwrap();
+        assert!(collection.add_member(&owner, "Bob", member.public_key()).is_err());
+
+        let entries = collection.entries(&member).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(&*entries[0].service_password, "premier");
+        assert_eq!(parse_shared_id(&entries[0].id), Some((collection.id.as_str(), id.as_str())));
+        assert!(collection.entries(&outsider).is_err());
+
+        // Une modification garde l'identifiant et la clé de l'entrée
+        assert_eq!(collection.upsert(&member, Some(&id), &entry("second")).unwrap(), id);
+        assert_eq!(&*collection.entries(&owner).unwrap()[0].service_password, "second");
+    }
+
+    #[test]
+    fn removed_members_lose_access_to_new_versions() {
+        let owner = identity();
+        let member = identity();
+        let mut collection = SharedCollection::create("Équipe", "Alice", &owner).unwrap();
+        collection.add_member(&owner, "Bob", member.public_key()).unwrap();
+        let id = collection.upsert(&owner, None, &entry("secret")).unwrap();
+        let old_key = collection.entry_key(&id, &member).unwrap();
+
+        assert!(collection.remove_member(&owner, &owner.public_key()).is_err());
+        collection.remove_member(&owner, &member.public_key()).unwrap();
+        assert!(collection.remove_member(&owner, &member.public_key()).is_err());
+
+        assert!(collection.entries(&member).is_err());
+        // La clé conservée par l'ancien membre ne déchiffre plus l'entrée
+        assert!(collection.open_entry(&id, &old_key).is_err());
+        assert_eq!(&*collection.entries(&owner).unwrap()[0].service_password, "secret");
+    }
+
+    #[test]
+    fn members_added_outside_the_application_are_refused() {
+        let owner = identity();
+        let intruder = identity();
+        let mut collection = SharedCollection::create("Équipe", "Alice", &owner).unwrap();
+        collection.upsert(&owner, None, &entry("secret")).unwrap();
+
+        // Ajouté directement dans le fichier, l'intrus ne reçoit aucune clé
+        let mut tampered = collection.clone();
+        tampered.members.push(Member { name: String::from("Mallory"), public_key: intruder.public_key().to_vec() });
+        assert!(tampered.entries(&owner).is_err());
+        assert!(tampered.upsert(&owner, None, &entry("nouveau")).is_err());
+        assert!(tampered.add_member(&owner, "Bob", identity().public_key()).is_err());
+        // Un nom de collection changé invalide aussi la liste
+        tampered = SharedCollection { name: String::from("Autre"), ..collection.clone() };
+        assert!(tampered.entries(&owner).is_err());
+    }
+
+    #[test]
+    fn removed_members_cannot_authenticate_a_new_member_list() {
+        let owner = identity();
+        let member = identity();
+        let mut collection = SharedCollection::create("Équipe", "Alice", &owner).unwrap();
+        collection.add_member(&owner, "Bob", member.public_key()).unwrap();
+        let old_key = collection.membership_key(&member).unwrap();
+        collection.remove_member(&owner, &member.public_key()).unwrap();
+
+        // L'ancienne clé de liste n'authentifie plus rien
+        collection.members.push(Member { name: String::from("Bob"), public_key: member.public_key().to_vec() });
+        collection.membership_tag = collection.membership_tag(&old_key).unwrap();
+        assert!(collection.entries(&owner).is_err());
+    }
+
+    #[test]
+    fn entries_are_bound_to_their_collection_and_id() {
+        let owner = identity();
+        let mut collection = SharedCollection::create("Équipe", "Alice", &owner).unwrap();
+        let first = collection.upsert(&owner, None, &entry("un")).unwrap();
+        let second = collection.upsert(&owner, None, &entry("deux")).unwrap();
+
+        let key = collection.entry_key(&first, &owner).unwrap();
+        let sealed = collection.entries[&first].clone();
+        collection.entries.insert(second.clone(), sealed);
+        assert!(collection.open_entry(&second, &key).is_err());
+    }
+
+    #[test]
+    fn collections_round_trip_through_their_file() {
+        let dir = TestDir::new("file");
+        let owner = identity();
+        let mut collection = SharedCollection::create("Équipe", "Alice", &owner).unwrap();
+        collection.upsert(&owner, None, &entry("secret")).unwrap();
+        let path = dir.0.join("equipe.json");
+        collection.save(&path).unwrap();
+
+        let loaded = SharedCollection::load(&path).unwrap();
+        assert_eq!(loaded.members, collection.members);
+        assert_eq!(&*loaded.entries(&owner).unwrap()[0].service_password, "secret");
+        // Le mot de passe n'apparaît pas en clair dans le fichier partagé
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("secret"));
+
+        std::fs::write(&path, serde_json::to_vec(&SharedCollection { format: String::from("autre"), ..loaded }).unwrap()).unwrap();
+        assert!(SharedCollection::load(&path).is_err());
+    }
+
+    #[tokio::test]
+    async fn team_handle_shares_entries_through_collection_files() {
+        let dir = TestDir::new("handle");
+        let cipher = Arc::new(EntryCipher::derive("motdepasse", &e2e::initial_params(FAST, "motdepasse", None).unwrap(), None).unwrap());
+        let path = dir.0.join("equipe.json");
+        let team = TeamHandle::open(dir.0.clone(), cipher, Vec::new()).await.unwrap();
+        team.create(path.clone(), "Équipe", "Alice").await.unwrap();
+        assert!(team.create(path.clone(), "Équipe", "Alice").await.is_err());
+
+        let snapshot = team.snapshot(vec![path.clone()]).await;
+        let collection_id = snapshot.collections[0].collection.id.clone();
+        team.share(&collection_id, entry("secret")).await.unwrap();
+
+        let snapshot = team.snapshot(vec![path.clone(), dir.0.join("absente.json")]).await;
+        assert_eq!(snapshot.entries.len(), 1);
+        assert_eq!(snapshot.entries[0].1, "Équipe");
+        assert_eq!(snapshot.errors.len(), 1);
+
+        let shared = snapshot.entries[0].0.clone();
+        team.remove_entry(&shared.id).await.unwrap();
+        assert!(team.snapshot(vec![path]).await.entries.is_empty());
+    }
+}
@@ -0,0 +1,329 @@
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use url::Url;
+
+use crate::vault::entry::{Entry, FieldKind};
+
+const DEFAULT_DIGITS: u32 = 6;
+const DEFAULT_PERIOD: u64 = 30;
+
+// Algorithmes HMAC autorisés par la RFC 6238
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl Algorithm {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.to_ascii_uppercase().as_str() {
+            "SHA1" => Ok(Algorithm::Sha1),
+            "SHA256" => Ok(Algorithm::Sha256),
+            "SHA512" => Ok(Algorithm::Sha512),
+            other => Err(format!("Algorithme TOTP non supporté: {}", other)),
+        }
+    }
+}
+
+// Paramètres d'un générateur TOTP (secret déjà décodé)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Totp {
+    pub secret: Vec<u8>,
+    pub algorithm: Algorithm,
+    pub digits: u32,
+    pub period: u64,
+    pub issuer: Option<String>,
+    pub account: Option<String>,
+}
+
+impl Totp {
+    // Accepte une URI otpauth://totp/... ou un secret base32 seul
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        if input.to_ascii_lowercase().starts_with("otpauth://") {
+            Self::from_uri(input)
+        } else {
+            Ok(Totp {
+                secret: decode_base32(input)?,
+                algorithm: Algorithm::Sha1,
+                digits: DEFAULT_DIGITS,
+                period: DEFAULT_PERIOD,
+                issuer: None,
+                account: None,
+            })
+        }
+    }
+
+    fn from_uri(uri: &str) -> Result<Self, String> {
+        let url = Url::parse(uri).map_err(|e| format!("URI otpauth invalide: {}", e))?;
+
+        if !url.host_str().is_some_and(|host| host.eq_ignore_ascii_case("totp")) {
+            return Err(String::from("Seules les URI otpauth://totp sont supportées"));
+        }
+
+        // Libellé "Émetteur:compte" ou "compte"
+        let label = url.path().trim_start_matches('/');
+        let label = percent_decode(label);
+        let (label_issuer, account) = match label.split_once(':') {
+            Some((issuer, account)) => (Some(issuer.trim().to_string()), account.trim().to_string()),
+            None => (None, label.trim().to_string()),
+        };
+
+        let mut totp = Totp {
+            secret: Vec::new(),
+            algorithm: Algorithm::Sha1,
+            digits: DEFAULT_DIGITS,
+            period: DEFAULT_PERIOD,
+            issuer: label_issuer,
+            account: (!account.is_empty()).then_some(account),
+        };
+
+        let mut has_secret = false;
+        for (key, value) in url.query_pairs() {
+            match key.to_ascii_lowercase().as_str() {
+                "secret" => {
+                    totp.secret = decode_base32(&value)?;
+                    has_secret = true;
+                }
+                "algorithm" => totp.algorithm = Algorithm::parse(&value)?,
+                "digits" => {
+                    totp.digits = value
+                        .parse()
+                        .ok()
+                        .filter(|digits| (6..=8).contains(digits))
+                        .ok_or_else(|| format!("Nombre de chiffres TOTP invalide: {}", value))?;
+                }
+                "period" => {
+                    totp.period = value
+                        .parse()
+                        .ok()
+                        .filter(|period| *period > 0)
+                        .ok_or_else(|| format!("Période TOTP invalide: {}", value))?;
+                }
+                "issuer" => totp.issuer = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        if !has_secret {
+            return Err(String::from("URI otpauth sans paramètre 'secret'"));
+        }
+        Ok(totp)
+    }
+
+    // Code pour un instant donné (secondes depuis l'époque Unix)
+    pub fn generate(&self, unix_time: u64) -> String {
+        let counter = unix_time / self.period;
+        let digest = self.hmac(&counter.to_be_bytes());
+
+        // Troncature dynamique (RFC 4226, section 5.3)
+        let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+        let binary = ((digest[offset] as u32 & 0x7f) << 24)
+            | ((digest[offset + 1] as u32) << 16)
+            | ((digest[offset + 2] as u32) << 8)
+            | (digest[offset + 3] as u32);
+
+        let code = binary % 10u32.pow(self.digits);
+        format!("{:0width$}", code, width = self.digits as usize)
+    }
+
+    pub fn seconds_remaining(&self, unix_time: u64) -> u64 {
+        self.period - unix_time % self.period
+    }
+
+    fn hmac(&self, message: &[u8]) -> Vec<u8> {
+        // Les implémentations HMAC acceptent des clés de toute longueur
+        match self.algorithm {
+            Algorithm::Sha1 => {
+                let mut mac = Hmac::<Sha1>::new_from_slice(&self.secret).expect("clé HMAC");
+                mac.update(message);
+                mac.finalize().into_bytes().to_vec()
+            }
+            Algorithm::Sha256 => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).expect("clé HMAC");
+                mac.update(message);
+                mac.finalize().into_bytes().to_vec()
+            }
+            Algorithm::Sha512 => {
+                let mut mac = Hmac::<Sha512>::new_from_slice(&self.secret).expect("clé HMAC");
+                mac.update(message);
+                mac.finalize().into_bytes().to_vec()
+            }
+        }
+    }
+}
+
+// Premier champ TOTP de l'entrée, s'il existe et n'est pas vide
+pub fn entry_totp(entry: &Entry) -> Option<Result<Totp, String>> {
+    entry
+        .custom_fields
+        .iter()
+        .find(|field| field.kind == FieldKind::Totp && !field.value.trim().is_empty())
+        .map(|field| Totp::parse(&field.value))
+}
+
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+// Base32 RFC 4648 sans bourrage, la forme attendue par les applications d'authentification
+pub fn encode_base32(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+    let mut output = String::new();
+    let mut buffer: u64 = 0;
+    let mut bits = 0;
+    for byte in data {
+        buffer = (buffer << 8) | *byte as u64;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        output.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    output
+}
+
+// Base32 RFC 4648, insensible à la casse, espaces et bourrage ignorés
+pub fn decode_base32(input: &str) -> Result<Vec<u8>, String> {
+    let mut output = Vec::new();
+    let mut buffer: u64 = 0;
+    let mut bits = 0;
+
+    for c in input.chars().filter(|c| !c.is_whitespace() && *c != '-' && *c != '=') {
+        let value = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u64 - 'A' as u64,
+            c @ '2'..='7' => c as u64 - '2' as u64 + 26,
+            _ => return Err(format!("Caractère base32 invalide: '{}'", c)),
+        };
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    if output.is_empty() {
+        return Err(String::from("Secret TOTP vide"));
+    }
+    Ok(output)
+}
+
+fn percent_decode(input: &str) -> String {
+    url::form_urlencoded::parse(format!("x={}", input.replace('+', "%2B")).as_bytes())
+        .next()
+        .map(|(_, value)| value.into_owned())
+        .unwrap_or_else(|| input.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Graines et valeurs de référence de la RFC 6238, annexe B
+    const TIMES: [u64; 6] = [59, 1111111109, 1111111111, 1234567890, 2000000000, 20000000000];
+
+    fn rfc_totp(algorithm: Algorithm, seed: &[u8]) -> Totp {
+        Totp { secret: seed.to_vec(), algorithm, digits: 8, period: 30, issuer: None, account: None }
+    }
+
+    fn assert_vectors(totp: &Totp, expected: [&str; 6]) {
+        for (time, code) in TIMES.iter().zip(expected) {
+            assert_eq!(totp.generate(*time), code, "T = {}", time);
+        }
+    }
+
+    #[test]
+    fn rfc6238_sha1_vectors() {
+        let totp = rfc_totp(Algorithm::Sha1, b"12345678901234567890");
+        assert_vectors(&totp, ["94287082", "07081804", "14050471", "89005924", "69279037", "65353130"]);
+    }
+
+    #[test]
+    fn rfc6238_sha256_vectors() {
+        let totp = rfc_totp(Algorithm::Sha256, b"12345678901234567890123456789012");
+        assert_vectors(&totp, ["46119246", "68084774", "67062674", "91819424", "90698825", "77737706"]);
+    }
+
+    #[test]
+    fn rfc6238_sha512_vectors() {
+        let seed = b"1234567890123456789012345678901234567890123456789012345678901234";
+        let totp = rfc_totp(Algorithm::Sha512, seed);
+        assert_vectors(&totp, ["90693936", "25091201", "99943326", "93441116", "38618901", "47863826"]);
+    }
+
+    #[test]
+    fn seconds_remaining_counts_down_to_the_next_period() {
+        let totp = rfc_totp(Algorithm::Sha1, b"12345678901234567890");
+        assert_eq!(totp.seconds_remaining(0), 30);
+        assert_eq!(totp.seconds_remaining(59), 1);
+    }
+
+    #[test]
+    fn parses_a_full_otpauth_uri() {
+        let totp = Totp::parse(
+            "otpauth://totp/Ma%20Banque:alice@exemple.fr?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ\
+             &algorithm=SHA256&digits=8&period=60&issuer=Banque",
+        )
+        .unwrap();
+        assert_eq!(totp.secret, b"12345678901234567890");
+        assert_eq!(totp.algorithm, Algorithm::Sha256);
+        assert_eq!(totp.digits, 8);
+        assert_eq!(totp.period, 60);
+        // Le paramètre issuer l'emporte sur le préfixe du libellé
+        assert_eq!(totp.issuer.as_deref(), Some("Banque"));
+        assert_eq!(totp.account.as_deref(), Some("alice@exemple.fr"));
+    }
+
+    #[test]
+    fn otpauth_uri_defaults_missing_parameters() {
+        let totp = Totp::parse("otpauth://totp/alice?secret=gezdgnbvgy3tqojq").unwrap();
+        assert_eq!(totp.algorithm, Algorithm::Sha1);
+        assert_eq!(totp.digits, DEFAULT_DIGITS);
+        assert_eq!(totp.period, DEFAULT_PERIOD);
+        assert_eq!(totp.issuer, None);
+        assert_eq!(totp.account.as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn rejects_invalid_otpauth_uris() {
+        let invalid = [
+            "otpauth://totp/alice",
+            "otpauth://totp/alice?secret=",
+            "otpauth://totp/alice?secret=ABC1",
+            "otpauth://hotp/alice?secret=GEZDGNBV&counter=1",
+            "otpauth://totp/alice?secret=GEZDGNBV&algorithm=MD5",
+            "otpauth://totp/alice?secret=GEZDGNBV&digits=5",
+            "otpauth://totp/alice?secret=GEZDGNBV&digits=9",
+            "otpauth://totp/alice?secret=GEZDGNBV&digits=six",
+            "otpauth://totp/alice?secret=GEZDGNBV&period=0",
+            "otpauth://totp/alice?secret=GEZDGNBV&period=-30",
+            "otpauth://",
+        ];
+        for uri in invalid {
+            assert!(Totp::parse(uri).is_err(), "{} accepté", uri);
+        }
+    }
+
+    #[test]
+    fn accepts_a_bare_base32_secret() {
+        let totp = Totp::parse(" gezd gnbv-gy3t qojq ").unwrap();
+        assert_eq!(totp.secret, b"1234567890");
+        assert!(Totp::parse("").is_err());
+    }
+
+    #[test]
+    fn base32_round_trips() {
+        let data = b"\x00\xffmushroom\x10";
+        assert_eq!(decode_base32(&encode_base32(data)).unwrap(), data);
+    }
+}